@@ -0,0 +1,66 @@
+use criterion::{ Criterion, criterion_group, criterion_main };
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+
+use angular_module_analyzer::AngularAnalyzer;
+
+const FEATURES: usize = 300;
+
+/// Core/Sharedと、次の機能モジュールを1つずつimportする多数の機能モジュールを持つプロジェクトを用意する
+fn write_project() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("angular-analyzer-bench-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let write = |path: String, imports: String, module: String| {
+        let file = root.join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(
+            &file,
+            format!(
+                "import {{ NgModule }} from '@angular/core';\n\
+                 import {{ CommonModule }} from '@angular/common';\n\
+                 import {{ RouterModule }} from '@angular/router';\n\n\
+                 @NgModule({{\n  \
+                   declarations: [ListComponent, DetailComponent],\n  \
+                   imports: [{}],\n  \
+                   exports: [ListComponent],\n  \
+                   providers: [DataService],\n\
+                 }})\n\
+                 export class {} {{}}\n",
+                imports,
+                module
+            )
+        ).unwrap();
+    };
+    write("src/app/core/core.module.ts".into(), "CommonModule".into(), "CoreModule".into());
+    write("src/app/shared/shared.module.ts".into(), "CommonModule".into(), "SharedModule".into());
+    for i in 0..FEATURES {
+        write(
+            format!("src/app/features/f{i}/f{i}.module.ts"),
+            format!("CommonModule, SharedModule, RouterModule.forChild(routes), F{}Module", i + 1),
+            format!("F{i}Module")
+        );
+    }
+    root
+}
+
+fn analyze(c: &mut Criterion) {
+    let root = write_project();
+    let analyzer = AngularAnalyzer::new(root.to_str().unwrap());
+    c.bench_function("analyze 302 modules", |b| b.iter(|| analyzer.analyze().unwrap()));
+    // 識別子のinternがスレッド間で詰まらないかを、スレッド数を変えて比べる
+    let mut group = c.benchmark_group("analyze 302 modules by jobs");
+    for jobs in [1, 4, 8] {
+        let analyzer = AngularAnalyzer::builder()
+            .path(&root)
+            .jobs(NonZeroUsize::new(jobs).unwrap())
+            .build()
+            .unwrap();
+        group.bench_function(format!("{} jobs", jobs), |b| b.iter(|| analyzer.analyze().unwrap()));
+    }
+    group.finish();
+    let _ = fs::remove_dir_all(&root);
+}
+
+criterion_group!(benches, analyze);
+criterion_main!(benches);
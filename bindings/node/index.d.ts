@@ -0,0 +1,179 @@
+// `angular-analyzer analyze -f json`と同じ構造
+
+export type ModuleType = 'Core' | 'Shared' | 'Feature' | 'Unknown'
+
+export type ViolationType =
+  | 'CoreDependsOnFeature'
+  | 'SharedDependsOnFeature'
+  | 'FeatureToFeatureDirect'
+  | 'CircularDependency'
+  | 'NxModuleBoundary'
+  | 'DepcruiseRule'
+
+export interface ModuleInfo {
+  path: string
+  name: string
+  export_style: ExportStyle
+  module_type: ModuleType
+  /** `module_type`を分類ルール・パスのどちらで決めたか、どちらにも当たらなかったか */
+  classified_by: ClassificationSource
+  /** `@NgModule`のメタデータから推定したレイヤー */
+  suggested_type: ModuleType | null
+  imports: string[]
+  exports: string[]
+  /** `exports`の各要素が再exportしているモジュールか宣言か */
+  export_kinds: ExportRef[]
+  providers: string[]
+  declarations: string[]
+  /** import文のモジュール指定子（重複なし、パッケージとパスエイリアスを区別しない） */
+  dependencies: string[]
+  /** 複数のimport文で読み込んでいる指定子と、その数 */
+  dependency_occurrences?: Record<string, number>
+  external_dependencies: PackageRef[]
+  module_dependencies: ModuleRef[]
+  lazy_imports: string[]
+  locations: Record<string, Position>
+  /** テスト用のモジュール（`*.spec.ts`・`*.stories.ts`・`testing/`の中、`includeTests`を指定した場合だけ含まれる） */
+  test: boolean
+}
+
+export interface PackageRef {
+  specifier: string
+  package: string
+}
+
+export interface ExportRef {
+  name: string
+  kind: ExportKind
+}
+
+export type ExportKind = 'module' | 'declarable' | 'unknown'
+
+export interface ModuleRef {
+  name: string
+  specifier: string | null
+  resolution: Resolution
+  /** 解決できた参照先のモジュールのファイル */
+  path?: string
+}
+
+export type Resolution = 'resolved' | 'external' | 'unresolved'
+
+export type ClassificationSource = 'rule' | 'path' | 'fallback'
+
+export type ExportStyle = 'named' | 'default' | 'list' | 'equals' | 're_export' | 'not_exported'
+
+export interface Position {
+  line: number
+  column: number
+}
+
+export interface SourceLocation {
+  file: string
+  line: number
+  column: number
+}
+
+export interface DependencyViolation {
+  from_module: string
+  to_module: string
+  violation_type: ViolationType
+  description: string
+  location: SourceLocation | null
+  /** `[rules]`で設定したルールの重大度 */
+  severity: 'warn' | 'error'
+}
+
+export interface ArchitectureMetrics {
+  total_modules: number
+  core_modules: number
+  shared_modules: number
+  feature_modules: number
+  average_dependencies_per_module: number
+  max_dependency_depth: number
+  coupling_factor: number
+  health_score: number
+}
+
+export type DeclarableKind = 'Component' | 'Directive' | 'Pipe'
+
+export interface Declarable {
+  name: string
+  kind: DeclarableKind
+  /** コンポーネント・ディレクティブのセレクター、パイプの名前 */
+  selector: string | null
+  file: string
+  /** 宣言しているNgModule（スタンドアロンなら`null`） */
+  module: string | null
+}
+
+export interface DeclarableEdge {
+  from: string
+  to: string
+  usage: 'template' | 'import'
+}
+
+export interface ComponentGraph {
+  declarables: Declarable[]
+  edges: DeclarableEdge[]
+}
+
+export interface PluginFinding {
+  plugin: string
+  rule: string
+  severity: 'warn' | 'error'
+  module: string
+  target: string | null
+  message: string
+  location: SourceLocation | null
+}
+
+export interface AnalysisWarning {
+  file: string
+  cause: string
+}
+
+/** 同じクラス名のモジュール（`modules`は`path#クラス名`で修飾した名前） */
+export interface NameCollision {
+  name: string
+  modules: string[]
+}
+
+export interface AnalysisResult {
+  modules: ModuleInfo[]
+  dependency_violations: DependencyViolation[]
+  circular_dependencies: string[][]
+  circular_dependency_locations: (SourceLocation | null)[][]
+  /** 循環の列挙が上限で打ち切られた場合はtrue */
+  cycles_truncated: boolean
+  /** `[rules]`で設定した循環依存（AA004）の重大度 */
+  cycle_severity: 'warn' | 'error'
+  metrics: ArchitectureMetrics
+  /** `components: true`を指定した場合のみ */
+  components?: ComponentGraph
+  /** `.angular-analyzer.toml`の`[[plugins]]`が報告した問題 */
+  plugin_findings: PluginFinding[]
+  /** 読み込めずに結果から除いたファイル */
+  warnings: AnalysisWarning[]
+  /** 複数のファイルで宣言されているモジュールのクラス名 */
+  name_collisions: NameCollision[]
+}
+
+export interface AnalyzeOptions {
+  /** ディレクトリ走査でシンボリックリンクをたどるか（既定はfalse） */
+  followSymlinks?: boolean
+  /** `*.spec.ts`・`*.stories.ts`・`testing/`の中のテスト用モジュールも解析するか（既定はfalse） */
+  includeTests?: boolean
+  /** `.angular-analyzer.toml`を読まずに既定の分類ルールで解析するか（既定はfalse） */
+  ignoreConfig?: boolean
+  /** コンポーネント・ディレクティブ・パイプの依存グラフも作るか（既定はfalse） */
+  components?: boolean
+  /** 結果のファイルパスをプロジェクトルートからの相対パスにするか、絶対パスにするか（既定は`'relative'`） */
+  pathStyle?: 'relative' | 'absolute'
+}
+
+/** プロジェクトを解析する（解析はlibuvのスレッドプールで実行される） */
+export function analyze(path: string, options?: AnalyzeOptions): Promise<AnalysisResult>
+
+/** `analyze`の同期版 */
+export function analyzeSync(path: string, options?: AnalyzeOptions): AnalysisResult
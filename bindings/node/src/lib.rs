@@ -0,0 +1,89 @@
+//! Node.js向けバインディング（napi-rs）
+//!
+//! `analyze(path, options)`は`angular-analyzer analyze -f json`と同じ構造のオブジェクトを返す。
+
+use napi::bindgen_prelude::AsyncTask;
+use std::path::Path;
+use napi::{ Env, JsUnknown, Task };
+use napi_derive::napi;
+
+use angular_module_analyzer::config::Config;
+use angular_module_analyzer::paths::{ PathStyle, PresentPaths };
+use angular_module_analyzer::{ AnalyzerError, AngularAnalyzer };
+
+/// `analyze`のオプション（すべて省略可能）
+#[napi(object)]
+#[derive(Default)]
+pub struct AnalyzeOptions {
+    /// ディレクトリ走査でシンボリックリンクをたどるか（既定はfalse）
+    pub follow_symlinks: Option<bool>,
+    /// `*.spec.ts`・`*.stories.ts`・`testing/`の中のテスト用モジュールも解析するか（既定はfalse）
+    pub include_tests: Option<bool>,
+    /// `.angular-analyzer.toml`を読まずに既定の分類ルールで解析するか（既定はfalse）
+    pub ignore_config: Option<bool>,
+    /// コンポーネント・ディレクティブ・パイプの依存グラフも作るか（既定はfalse）
+    pub components: Option<bool>,
+    /// 結果のファイルパスの形式（`relative`か`absolute`、既定は`relative`）
+    pub path_style: Option<String>,
+}
+
+fn run(path: &str, options: &AnalyzeOptions) -> Result<serde_json::Value, AnalyzerError> {
+    let path_style = match options.path_style.as_deref() {
+        None | Some("relative") => PathStyle::Relative,
+        Some("absolute") => PathStyle::Absolute,
+        Some(other) => {
+            return Err(
+                AnalyzerError::UnsupportedFormat(
+                    format!("Unknown pathStyle '{}' (expected 'relative' or 'absolute')", other)
+                )
+            );
+        }
+    };
+    let mut builder = AngularAnalyzer::builder().path(path);
+    if !options.ignore_config.unwrap_or(false) {
+        let config = Config::load(Path::new(path))?;
+        builder = builder.config(config);
+    }
+    let analyzer = builder
+        .follow_symlinks(options.follow_symlinks.unwrap_or(false))
+        .include_tests(options.include_tests.unwrap_or(false))
+        .components(options.components.unwrap_or(false))
+        .build()?;
+    let mut result = analyzer.analyze()?;
+    result.present_paths(Path::new(path), path_style);
+    Ok(serde_json::to_value(result)?)
+}
+
+fn to_napi_error(err: AnalyzerError) -> napi::Error {
+    napi::Error::from_reason(err.to_string())
+}
+
+pub struct AnalyzeTask {
+    path: String,
+    options: AnalyzeOptions,
+}
+
+impl Task for AnalyzeTask {
+    type Output = serde_json::Value;
+    type JsValue = JsUnknown;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        run(&self.path, &self.options).map_err(to_napi_error)
+    }
+
+    fn resolve(&mut self, env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        env.to_js_value(&output)
+    }
+}
+
+/// プロジェクトを解析し、結果をPromiseで返す（解析はlibuvのスレッドプールで実行される）
+#[napi(ts_return_type = "Promise<AnalysisResult>")]
+pub fn analyze(path: String, options: Option<AnalyzeOptions>) -> AsyncTask<AnalyzeTask> {
+    AsyncTask::new(AnalyzeTask { path, options: options.unwrap_or_default() })
+}
+
+/// `analyze`の同期版
+#[napi(ts_return_type = "AnalysisResult")]
+pub fn analyze_sync(path: String, options: Option<AnalyzeOptions>) -> napi::Result<serde_json::Value> {
+    run(&path, &options.unwrap_or_default()).map_err(to_napi_error)
+}
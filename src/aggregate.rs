@@ -0,0 +1,195 @@
+use serde::{ Deserialize, Serialize };
+use std::collections::{ BTreeMap, BTreeSet };
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::doctor::package_version;
+use crate::{ AnalysisResult, AnalyzerError, Result };
+
+/// 集計するリポジトリ（マニフェストの`[[repos]]`）
+#[derive(Debug, Clone, Deserialize)]
+pub struct Repo {
+    pub name: String,
+    /// Angularプロジェクトのパス（マニフェストからの相対パス）
+    pub path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    repos: Vec<Repo>,
+}
+
+/// リポジトリの一覧を書いたTOMLを読み込む（パスはマニフェストのディレクトリを基準にする）
+pub fn load_manifest(path: &Path) -> Result<Vec<Repo>> {
+    let content = fs::read_to_string(path).map_err(|err| AnalyzerError::io(path, err))?;
+    let manifest: Manifest = toml::from_str(&content).map_err(|err| AnalyzerError::parse(path, err.message().trim()))?;
+    let base = path.parent().unwrap_or(Path::new(""));
+    Ok(
+        manifest.repos
+            .into_iter()
+            .map(|repo| Repo { path: base.join(&repo.path), ..repo })
+            .collect()
+    )
+}
+
+/// リポジトリ1つの解析結果
+#[derive(Debug, Serialize)]
+pub struct RepoReport {
+    pub name: String,
+    pub path: PathBuf,
+    pub result: AnalysisResult,
+}
+
+/// 外部パッケージのリポジトリごとの使用状況
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageUsage {
+    pub package: String,
+    /// リポジトリ名 -> 使用状況（使っていないリポジトリは含まない）
+    pub repos: BTreeMap<String, RepoUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoUsage {
+    /// パッケージに依存しているモジュールの数
+    pub modules: usize,
+    /// `package.json`のバージョン指定
+    pub version: Option<String>,
+}
+
+impl PackageUsage {
+    /// リポジトリによってバージョン指定が異なるか
+    pub fn versions_differ(&self) -> bool {
+        let versions: BTreeSet<&str> = self.repos
+            .values()
+            .filter_map(|usage| usage.version.as_deref())
+            .collect();
+        versions.len() > 1
+    }
+}
+
+/// 複数のリポジトリをまとめたレポート
+#[derive(Debug, Serialize)]
+pub struct AggregateReport {
+    pub repos: Vec<RepoReport>,
+    /// 使っているリポジトリの多い順
+    pub packages: Vec<PackageUsage>,
+}
+
+impl AggregateReport {
+    /// 各リポジトリの解析結果と`package.json`から、外部パッケージの使用状況を比べる
+    pub fn new(repos: Vec<RepoReport>) -> Self {
+        let mut packages: BTreeMap<String, BTreeMap<String, RepoUsage>> = BTreeMap::new();
+        for repo in &repos {
+            let package_json = fs
+                ::read_to_string(repo.path.join("package.json"))
+                .ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+            let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+            for module in &repo.result.modules {
+                let used: BTreeSet<&str> = module.external_dependencies
+                    .iter()
+                    .map(|dep| dep.package.as_ref())
+                    .collect();
+                for package in used {
+                    *counts.entry(package).or_insert(0) += 1;
+                }
+            }
+            for (package, modules) in counts {
+                let version = package_json.as_ref().and_then(|json| package_version(json, package));
+                packages
+                    .entry(package.to_string())
+                    .or_default()
+                    .insert(repo.name.clone(), RepoUsage { modules, version });
+            }
+        }
+
+        let mut packages: Vec<PackageUsage> = packages
+            .into_iter()
+            .map(|(package, repos)| PackageUsage { package, repos })
+            .collect();
+        packages.sort_by(|a, b| b.repos.len().cmp(&a.repos.len()).then_with(|| a.package.cmp(&b.package)));
+        Self { repos, packages }
+    }
+
+    pub fn render_markdown(&self) -> String {
+        let mut md = format!("# Angular Architecture Across {} Repositories\n\n", self.repos.len());
+
+        md.push_str("## Summary\n\n");
+        md.push_str(
+            "| Repository | Modules | Violations | Cycles | Coupling Factor | Health Score |\n|---|---:|---:|---:|---:|---:|\n"
+        );
+        for repo in &self.repos {
+            let metrics = &repo.result.metrics;
+            md.push_str(
+                &format!(
+                    "| {} | {} | {} | {} | {:.2} | {:.1} |\n",
+                    repo.name,
+                    metrics.total_modules,
+                    repo.result.dependency_violations.len(),
+                    repo.result.circular_dependencies.len(),
+                    metrics.coupling_factor,
+                    metrics.health_score
+                )
+            );
+        }
+        md.push('\n');
+
+        md.push_str("## External Packages\n\n");
+        if self.packages.is_empty() {
+            md.push_str("No external packages found.\n\n");
+        } else {
+            // 各セルは依存しているモジュールの数と、package.jsonのバージョン指定
+            md.push_str("| Package |");
+            for repo in &self.repos {
+                md.push_str(&format!(" {} |", repo.name));
+            }
+            md.push_str("\n|---|");
+            md.push_str(&"---|".repeat(self.repos.len()));
+            md.push('\n');
+            for package in &self.packages {
+                let marker = if package.versions_differ() { " ⚠️" } else { "" };
+                md.push_str(&format!("| {}{} |", package.package, marker));
+                for repo in &self.repos {
+                    let cell = match package.repos.get(&repo.name) {
+                        Some(RepoUsage { modules, version: Some(version) }) => format!("{} ({})", modules, version),
+                        Some(RepoUsage { modules, version: None }) => modules.to_string(),
+                        None => "–".to_string(),
+                    };
+                    md.push_str(&format!(" {} |", cell));
+                }
+                md.push('\n');
+            }
+            md.push('\n');
+            if self.packages.iter().any(PackageUsage::versions_differ) {
+                md.push_str("⚠️ marks packages whose version ranges differ between repositories.\n\n");
+            }
+        }
+
+        for repo in &self.repos {
+            let result = &repo.result;
+            md.push_str(&format!("## {}\n\n`{}`\n\n", repo.name, repo.path.display()));
+            md.push_str(
+                &format!(
+                    "{} Core · {} Shared · {} Feature modules\n\n",
+                    result.metrics.core_modules,
+                    result.metrics.shared_modules,
+                    result.metrics.feature_modules
+                )
+            );
+            if result.dependency_violations.is_empty() && result.circular_dependencies.is_empty() {
+                md.push_str("No dependency violations or circular dependencies found.\n\n");
+                continue;
+            }
+            for violation in &result.dependency_violations {
+                md.push_str(
+                    &format!("- {} -> {}: {}\n", violation.from_module, violation.to_module, violation.description)
+                );
+            }
+            for cycle in &result.circular_dependencies {
+                md.push_str(&format!("- Circular dependency: {} -> {}\n", cycle.join(" -> "), cycle[0]));
+            }
+            md.push('\n');
+        }
+        md
+    }
+}
@@ -0,0 +1,138 @@
+use flate2::read::GzDecoder;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::{ Component, Path, PathBuf };
+
+use crate::config::CONFIG_FILE_NAME;
+use crate::{ AnalyzerError, DEFAULT_IGNORED_DIRS, IGNORE_FILE_NAME, Result, is_module_file, source };
+
+/// アーカイブから読み出した解析対象のファイル
+#[derive(Debug, Default)]
+pub struct ArchiveSources {
+    /// プロジェクトルートからの相対パスと内容（[`AngularAnalyzer::analyze_sources`](crate::AngularAnalyzer::analyze_sources)に渡す）
+    pub files: Vec<(PathBuf, String)>,
+    /// プロジェクトルートの設定ファイルの内容
+    pub config: Option<String>,
+    /// プロジェクトルートの除外ファイル（[`IGNORE_FILE_NAME`]）の内容（[`AnalyzerBuilder::ignore_file`](crate::AnalyzerBuilder::ignore_file)に渡す）
+    pub ignore_file: Option<String>,
+}
+
+/// `.tar.gz`・`.tgz`・`.tar`・`.zip`から、モジュールファイルと設定ファイル・除外ファイルだけをメモリに読み込む（ディスクには展開しない）
+///
+/// 全エントリに共通する最上位のディレクトリ（GitHubのソースアーカイブの`app-main/`など。なければアーカイブのルート）、
+/// `root`を指定したときはその中の`root`をプロジェクトルートとする。
+/// `node_modules`などの[`DEFAULT_IGNORED_DIRS`]の中と、`max_file_size`を超えるファイル、UTF-8・UTF-16（BOM付き）でないファイルは読まない。
+pub fn read(archive: &Path, root: Option<&Path>, max_file_size: Option<u64>) -> Result<ArchiveSources> {
+    let name = archive.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let file = File::open(archive).map_err(|err| AnalyzerError::io(archive, err))?;
+    let mut reader = Reader { max_file_size, top_level: BTreeSet::new(), entries: Vec::new() };
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        reader.read_tar(archive, GzDecoder::new(file))?;
+    } else if name.ends_with(".tar") {
+        reader.read_tar(archive, file)?;
+    } else if name.ends_with(".zip") {
+        reader.read_zip(archive, file)?;
+    } else {
+        return Err(
+            AnalyzerError::UnsupportedFormat(
+                format!("Unsupported archive {} (expected .tar.gz, .tgz, .tar or .zip)", archive.display())
+            )
+        );
+    }
+
+    let root = reader.common_root().join(root.unwrap_or(Path::new("")));
+    let root: PathBuf = root
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect();
+    let mut sources = ArchiveSources::default();
+    for (path, content) in reader.entries {
+        let Ok(relative) = path.strip_prefix(&root) else {
+            continue;
+        };
+        if relative == Path::new(CONFIG_FILE_NAME) {
+            sources.config = Some(content);
+        } else if relative == Path::new(IGNORE_FILE_NAME) {
+            sources.ignore_file = Some(content);
+        } else if is_module_file(relative) {
+            sources.files.push((relative.to_path_buf(), content));
+        }
+    }
+    // エントリの順序はアーカイブの作り方で変わるため、ディレクトリ走査と同じくパス順にする
+    sources.files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(sources)
+}
+
+struct Reader {
+    max_file_size: Option<u64>,
+    /// すべてのエントリの最上位の要素（ファイルなら`None`）
+    top_level: BTreeSet<Option<PathBuf>>,
+    /// 読み込んだファイル（アーカイブ内のパス）
+    entries: Vec<(PathBuf, String)>,
+}
+
+impl Reader {
+    fn read_tar(&mut self, archive: &Path, reader: impl Read) -> Result<()> {
+        let mut tar = tar::Archive::new(reader);
+        let entries = tar.entries().map_err(|err| AnalyzerError::io(archive, err))?;
+        for entry in entries {
+            let mut entry = entry.map_err(|err| AnalyzerError::io(archive, err))?;
+            let path = entry
+                .path()
+                .map_err(|err| AnalyzerError::io(archive, err))?
+                .into_owned();
+            let is_file = entry.header().entry_type().is_file();
+            let size = entry.size();
+            self.add(path, is_file, size, &mut entry);
+        }
+        Ok(())
+    }
+
+    fn read_zip(&mut self, archive: &Path, file: File) -> Result<()> {
+        let mut zip = zip::ZipArchive::new(file).map_err(|err| AnalyzerError::parse(archive, err))?;
+        for index in 0..zip.len() {
+            let mut entry = zip.by_index(index).map_err(|err| AnalyzerError::parse(archive, err))?;
+            // `../`などでアーカイブの外を指すパスは読まない
+            let Some(path) = entry.enclosed_name() else {
+                continue;
+            };
+            let is_file = entry.is_file();
+            let size = entry.size();
+            self.add(path, is_file, size, &mut entry);
+        }
+        Ok(())
+    }
+
+    fn add(&mut self, path: PathBuf, is_file: bool, size: u64, content: &mut impl Read) {
+        let path: PathBuf = path.components().filter(|c| matches!(c, Component::Normal(_))).collect();
+        let mut components = path.components();
+        let first = components.next().map(|c| PathBuf::from(c.as_os_str()));
+        let nested = components.next().is_some();
+        self.top_level.insert(first.filter(|_| nested || !is_file));
+
+        let wanted = is_file &&
+            (is_module_file(&path) ||
+                path.file_name().is_some_and(|name| name == CONFIG_FILE_NAME || name == IGNORE_FILE_NAME)) &&
+            !path.components().any(|c| DEFAULT_IGNORED_DIRS.iter().any(|dir| c.as_os_str() == *dir)) &&
+            self.max_file_size.is_none_or(|limit| size <= limit);
+        if !wanted {
+            return;
+        }
+        let mut bytes = Vec::new();
+        if
+            content.read_to_end(&mut bytes).is_ok() &&
+            let Ok(text) = source::decode(&path, bytes)
+        {
+            self.entries.push((path, text.to_string()));
+        }
+    }
+
+    /// すべてのエントリが1つのディレクトリの中にあればそのディレクトリ
+    fn common_root(&self) -> PathBuf {
+        match self.top_level.iter().collect::<Vec<_>>().as_slice() {
+            [Some(dir)] => dir.clone(),
+            _ => PathBuf::new(),
+        }
+    }
+}
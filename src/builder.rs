@@ -0,0 +1,206 @@
+use rayon::ThreadPoolBuilder;
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::intern::Interner;
+use crate::progress::ProgressHandler;
+use crate::source::{ DEFAULT_MAX_FILE_SIZE, DEFAULT_PARSE_TIMEOUT };
+use crate::{ AngularAnalyzer, CancellationToken, Result };
+use crate::config::{ ClassificationConfig, Classifier, Config, RuleLevel, Thresholds };
+
+/// [`AngularAnalyzer`]の設定を組み立てるビルダー
+///
+/// CLIも同じビルダーを使うため、ライブラリ利用者とCLIで設定項目が揃う。
+///
+/// ```no_run
+/// use angular_module_analyzer::AngularAnalyzer;
+/// use angular_module_analyzer::config::ClassificationConfig;
+///
+/// let analyzer = AngularAnalyzer::builder()
+///     .path("path/to/angular/project")
+///     .follow_symlinks(false)
+///     .classification(ClassificationConfig {
+///         core: vec!["src/app/core/**".to_string()],
+///         ..Default::default()
+///     })
+///     .build()?;
+/// # Ok::<(), angular_module_analyzer::AnalyzerError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzerBuilder {
+    path: PathBuf,
+    config: Config,
+    follow_symlinks: bool,
+    respect_ignore: bool,
+    include_tests: bool,
+    ignore_file: Option<String>,
+    parse_timeout: Option<Duration>,
+    cache_dir: Option<PathBuf>,
+    max_file_size: Option<u64>,
+    low_memory: bool,
+    components: bool,
+    progress: Vec<Arc<dyn ProgressHandler>>,
+    cancellation: CancellationToken,
+}
+
+impl AnalyzerBuilder {
+    pub fn new() -> Self {
+        Self {
+            path: PathBuf::from("."),
+            respect_ignore: true,
+            max_file_size: Some(DEFAULT_MAX_FILE_SIZE),
+            parse_timeout: Some(DEFAULT_PARSE_TIMEOUT),
+            ..Default::default()
+        }
+    }
+
+    /// 解析するプロジェクトのルート
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// 設定ファイル全体を指定する（個別の設定メソッドはこの後に呼ぶと上書きになる）
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// ディレクトリ走査でシンボリックリンクをたどるか（既定はfalse）
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// `.gitignore`と既定の除外ディレクトリ（node_modules, dist, .angular）に従うか（既定はtrue）
+    pub fn respect_ignore(mut self, respect: bool) -> Self {
+        self.respect_ignore = respect;
+        self
+    }
+
+    /// `*.spec.ts`・`*.stories.ts`・`testing/`の中のテスト用モジュールも解析するか（既定はfalse）
+    ///
+    /// 解析したテスト用モジュールは[`ModuleInfo::test`](crate::ModuleInfo::test)がtrueになる。
+    pub fn include_tests(mut self, include: bool) -> Self {
+        self.include_tests = include;
+        self
+    }
+
+    /// 除外ファイル（`.gitignore`と同じ書式）の内容（既定は`None`でプロジェクトルートの`.analyzerignore`を読む）
+    ///
+    /// [`AngularAnalyzer::analyze_sources`]で解析するアーカイブのように、ディスク上にないプロジェクトで使う。
+    pub fn ignore_file(mut self, content: impl Into<String>) -> Self {
+        self.ignore_file = Some(content.into());
+        self
+    }
+
+    /// 解析結果をキャッシュするディレクトリ（既定は`None`でキャッシュしない）
+    ///
+    /// 内容が前回から変わっていないファイルは再解析せず、キャッシュした`ModuleInfo`を使う。
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// これより大きいファイルは解析せずに読み飛ばす（既定は5MiB、`None`で無制限）
+    ///
+    /// 読み飛ばしたファイルは[`ProgressHandler::on_file_skipped`]に通知される。
+    pub fn max_file_size(mut self, bytes: Option<u64>) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    /// 1ファイルの解析にかけてよい時間（既定は5秒、`None`で無制限）
+    ///
+    /// 超えたファイルは結果の`warnings`に入れて読み飛ばし、[`ProgressHandler::on_file_skipped`]に通知する。
+    pub fn parse_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.parse_timeout = timeout;
+        self
+    }
+
+    /// 省メモリモード（既定はfalse）
+    ///
+    /// 各モジュールを解析した直後に依存グラフの構築に必要な参照だけへ縮約するため、
+    /// 数千モジュール規模のモノレポでも全ファイルのメタデータ文字列を同時に保持しない。
+    /// 結果の`ModuleInfo`は`exports`・`providers`・`declarations`が空になり、`imports`は識別子名だけになる。
+    pub fn low_memory(mut self, enabled: bool) -> Self {
+        self.low_memory = enabled;
+        self
+    }
+
+    /// コンポーネント・ディレクティブ・パイプの依存グラフも作るか（既定はfalse）
+    ///
+    /// モジュールファイル以外のTypeScriptファイルとテンプレートもすべて読むため、解析に時間がかかる。
+    /// 結果は[`AnalysisResult::components`](crate::AnalysisResult::components)に入る。
+    pub fn components(mut self, enabled: bool) -> Self {
+        self.components = enabled;
+        self
+    }
+
+    /// 解析の進捗を受け取るハンドラー（複数回呼ぶと登録順にすべてのハンドラーへ通知する）
+    pub fn progress(mut self, handler: impl ProgressHandler + 'static) -> Self {
+        self.progress.push(Arc::new(handler));
+        self
+    }
+
+    /// 解析を中断するためのトークン（呼び出し側でクローンを保持して`cancel`する）
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// モジュール分類ルール
+    pub fn classification(mut self, classification: ClassificationConfig) -> Self {
+        self.config.classification = classification;
+        self
+    }
+
+    /// ルールコードごとの重大度
+    pub fn rules(mut self, rules: BTreeMap<String, RuleLevel>) -> Self {
+        self.config.rules = rules;
+        self
+    }
+
+    /// ファイル解析に使うスレッド数（未指定ならCPUコア数）
+    pub fn jobs(mut self, jobs: NonZeroUsize) -> Self {
+        self.config.analysis.jobs = Some(jobs);
+        self
+    }
+
+    /// `check`で評価する閾値
+    pub fn thresholds(mut self, thresholds: Thresholds) -> Self {
+        self.config.thresholds = thresholds;
+        self
+    }
+
+    /// 設定を検証してアナライザーを作成する（分類globが不正な場合はエラー）
+    pub fn build(self) -> Result<AngularAnalyzer> {
+        let classifier = Classifier::new(&self.config.classification)?;
+        let pool = match self.config.analysis.jobs {
+            Some(jobs) => Some(Arc::new(ThreadPoolBuilder::new().num_threads(jobs.get()).build()?)),
+            None => None,
+        };
+        let exclusions = crate::exclusions(&self.path, self.ignore_file.as_deref(), &self.config.analysis.exclude);
+        Ok(AngularAnalyzer {
+            project_path: self.path,
+            config: self.config,
+            classifier,
+            follow_symlinks: self.follow_symlinks,
+            respect_ignore: self.respect_ignore,
+            include_tests: self.include_tests,
+            parse_timeout: self.parse_timeout,
+            exclusions,
+            cache_dir: self.cache_dir,
+            max_file_size: self.max_file_size,
+            low_memory: self.low_memory,
+            components: self.components,
+            progress: self.progress,
+            cancellation: self.cancellation,
+            interner: Interner::default(),
+            pool,
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use clap::ValueEnum;
+use std::collections::{ BTreeMap, HashMap, HashSet };
+use std::path::Path;
+
+use crate::graph::EdgeKind;
+use crate::nx::{ self, NxProject };
+use crate::paths::{ PathStyle, presenter };
+use crate::{ AnalysisResult, ModuleInfo, Result };
+
+/// モジュール（C4のコンポーネント）をまとめるコンテナの単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ContainerGrouping {
+    /// Core・Shared・Featureのレイヤーごと
+    #[default]
+    Layer,
+    /// `project.json`のNxプロジェクトごと（プロジェクト外のモジュールはレイヤーごと）
+    NxProject,
+}
+
+/// モジュールをC4モデルのコンポーネントとしてStructurizr DSLで出力する
+///
+/// 識別子はモジュール名から作るため、再生成しても同じモジュールは同じ識別子になる。
+/// 違反のある依存には`Violation`タグを付け、赤で表示する。コンポーネントのファイルは`path_style`の形式で書く。
+pub fn render_structurizr(
+    result: &AnalysisResult,
+    name: &str,
+    project_path: &Path,
+    grouping: ContainerGrouping,
+    path_style: PathStyle
+) -> Result<String> {
+    let present = presenter(project_path, path_style);
+    let projects = match grouping {
+        ContainerGrouping::Layer => Vec::new(),
+        ContainerGrouping::NxProject => nx::projects(project_path)?,
+    };
+    let container_of = |module: &ModuleInfo| -> (String, Vec<String>) {
+        match nx::project_of(&projects, &module.path) {
+            Some(NxProject { name, tags, .. }) => (name.clone(), tags.clone()),
+            None => (module.module_type.to_string(), Vec::new()),
+        }
+    };
+
+    let mut identifiers = Identifiers::default();
+    identifiers.unique("system");
+    let mut containers: BTreeMap<String, (Vec<String>, Vec<&ModuleInfo>)> = BTreeMap::new();
+    for module in &result.modules {
+        let (container, tags) = container_of(module);
+        containers.entry(container).or_insert_with(|| (tags, Vec::new())).1.push(module);
+    }
+    let container_ids: BTreeMap<&str, String> = containers
+        .keys()
+        .map(|container| (container.as_str(), identifiers.unique(&format!("container_{}", container))))
+        .collect();
+    let component_ids: HashMap<&str, String> = result.modules
+        .iter()
+        .map(|module| (module.name.as_ref(), identifiers.unique(&module.name)))
+        .collect();
+
+    let mut dsl = String::new();
+    dsl.push_str(&format!("workspace {} {{\n\n    model {{\n", quote(name)));
+    dsl.push_str(&format!("        system = softwareSystem {} {{\n", quote(name)));
+    for (container, (tags, modules)) in &containers {
+        dsl.push_str(
+            &format!("            {} = container {} {{\n", container_ids[container.as_str()], quote(container))
+        );
+        if !tags.is_empty() {
+            dsl.push_str(&format!("                tags {}\n", quote(&tags.join(","))));
+        }
+        for module in modules {
+            dsl.push_str(
+                &format!(
+                    "                {} = component {} {} \"NgModule\" {{\n                    tags {}\n                }}\n",
+                    component_ids[module.name.as_ref()],
+                    quote(&module.name),
+                    quote(&present(&module.path).to_string_lossy()),
+                    quote(&module.module_type.to_string())
+                )
+            );
+        }
+        dsl.push_str("            }\n");
+    }
+    dsl.push_str("        }\n\n");
+
+    let violations: HashSet<(&str, &str)> = result.dependency_violations
+        .iter()
+        .map(|v| (v.from_module.as_ref(), v.to_module.as_ref()))
+        .collect();
+    let graph = result.graph();
+    for (from, to, kind) in graph.edges() {
+        let description = match kind {
+            EdgeKind::Lazy => "lazy loads",
+            EdgeKind::Eager => "imports",
+        };
+        dsl.push_str(&format!("        {} -> {} \"{}\"", component_ids[from], component_ids[to], description));
+        if violations.contains(&(from, to)) {
+            dsl.push_str(" {\n            tags \"Violation\"\n        }");
+        }
+        dsl.push('\n');
+    }
+    dsl.push_str("    }\n\n");
+
+    dsl.push_str("    views {\n");
+    dsl.push_str("        container system \"Containers\" {\n            include *\n            autoLayout\n        }\n");
+    for container in containers.keys() {
+        dsl.push_str(
+            &format!(
+                "        component {} {} {{\n            include *\n            autoLayout\n        }}\n",
+                container_ids[container.as_str()],
+                quote(&format!("Components-{}", container))
+            )
+        );
+    }
+    dsl.push_str("        styles {\n            relationship \"Violation\" {\n                color #d62728\n            }\n        }\n");
+    dsl.push_str("    }\n}\n");
+    Ok(dsl)
+}
+
+/// DSLの識別子（英数字と`_`のみで、大文字小文字を区別せずに一意）
+#[derive(Default)]
+struct Identifiers {
+    used: HashSet<String>,
+}
+
+impl Identifiers {
+    fn unique(&mut self, name: &str) -> String {
+        let base: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let mut identifier = base.clone();
+        let mut n = 2;
+        while !self.used.insert(identifier.to_lowercase()) {
+            identifier = format!("{}_{}", base, n);
+            n += 1;
+        }
+        identifier
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
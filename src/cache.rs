@@ -0,0 +1,165 @@
+use serde::{ Deserialize, Serialize };
+use std::collections::HashMap;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::config::Config;
+use crate::paths::relative_path;
+use crate::{ AnalyzerError, ModuleInfo, Result };
+
+/// プロジェクトルートに作る既定のキャッシュディレクトリ名
+pub const DEFAULT_CACHE_DIR: &str = ".angular-analyzer-cache";
+
+const CACHE_FILE: &str = "modules.json";
+
+/// キャッシュの状態（`cache stats`用）
+#[derive(Debug, Clone)]
+pub struct CacheStats {
+    pub file: PathBuf,
+    /// キャッシュされているモジュール数
+    pub entries: usize,
+    /// キャッシュファイルのバイト数
+    pub size: u64,
+    /// キャッシュを作成した解析器のバージョン（読み取れない場合は空）
+    pub version: String,
+    /// 現在のバージョンと設定でそのまま使えるか（使えない場合は次回の解析で作り直される）
+    pub up_to_date: bool,
+}
+
+/// キャッシュディレクトリ内のキャッシュファイル
+pub fn cache_file(dir: &Path) -> PathBuf {
+    dir.join(CACHE_FILE)
+}
+
+/// キャッシュの状態を調べる（キャッシュがなければ`None`）
+pub fn stats(dir: &Path, config: &Config, low_memory: bool) -> Result<Option<CacheStats>> {
+    let file = cache_file(dir);
+    let Ok(metadata) = fs::metadata(&file) else {
+        return Ok(None);
+    };
+    let content = fs::read_to_string(&file).map_err(|err| AnalyzerError::io(&file, err))?;
+    // 壊れたキャッシュは次回の解析で作り直されるので、エラーにせず古いものとして扱う
+    let cache: ModuleCache = serde_json::from_str(&content).unwrap_or_default();
+    Ok(
+        Some(CacheStats {
+            up_to_date: cache.is_valid(&fingerprint(config, low_memory)),
+            entries: cache.entries.len(),
+            size: metadata.len(),
+            version: cache.version,
+            file,
+        })
+    )
+}
+
+/// キャッシュにあるモジュール名（シェル補完用、名前順）
+///
+/// 補完のたびに解析すると遅すぎるので、前回の解析で保存したキャッシュだけを読む。
+/// バージョンや設定が変わった古いキャッシュでも名前の候補としては使えるので、そのまま読む。
+/// キャッシュがない、または壊れている場合は空。
+pub fn module_names(dir: &Path) -> Vec<String> {
+    let cache: ModuleCache = fs
+        ::read_to_string(cache_file(dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let mut names: Vec<String> = cache.entries
+        .into_values()
+        .map(|entry| entry.module.name.to_string())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// キャッシュファイルを削除する（削除した場合はtrue）
+///
+/// ディレクトリはキャッシュファイルを消して空になった場合だけ削除する。
+pub fn clear(dir: &Path) -> Result<bool> {
+    let file = cache_file(dir);
+    if !file.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(&file).map_err(|err| AnalyzerError::io(&file, err))?;
+    let _ = fs::remove_dir(dir);
+    Ok(true)
+}
+
+/// 解析結果に影響する設定のハッシュ
+///
+/// 分類ルールが変わるとモジュール種別も変わり、省メモリモードでは保持する情報が変わるので、
+/// それぞれの組み合わせごとにキャッシュを分ける。
+pub(crate) fn fingerprint(config: &Config, low_memory: bool) -> String {
+    let classification = serde_json::to_string(&config.classification).unwrap_or_default();
+    content_hash(&format!("{}{}", classification, if low_memory { ":low-memory" } else { "" }))
+}
+
+/// 解析済みの`ModuleInfo`を(パス, 内容のハッシュ)で保持するキャッシュ
+///
+/// 解析器のバージョンか分類ルールが変わった場合は全体を無効にする。
+/// パスはプロジェクトルートからの相対パスで保存するため、チェックアウト先の異なるCIのエージェント間でも共有できる。
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ModuleCache {
+    version: String,
+    fingerprint: String,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    hash: String,
+    module: ModuleInfo,
+}
+
+impl ModuleCache {
+    /// キャッシュを読み込む（存在しない、壊れている、前提が変わった場合は空）
+    pub(crate) fn load(dir: &Path, fingerprint: &str) -> Self {
+        let cache: Option<Self> = fs
+            ::read_to_string(dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+        match cache {
+            Some(cache) if cache.is_valid(fingerprint) => cache,
+            _ =>
+                Self {
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    fingerprint: fingerprint.to_string(),
+                    entries: HashMap::new(),
+                },
+        }
+    }
+
+    fn is_valid(&self, fingerprint: &str) -> bool {
+        self.version == env!("CARGO_PKG_VERSION") && self.fingerprint == fingerprint
+    }
+
+    /// `path`のキャッシュが内容のハッシュと一致すれば、パスを`project_path`の下に戻したモジュール
+    pub(crate) fn get(&self, project_path: &Path, path: &Path, hash: &str) -> Option<ModuleInfo> {
+        let entry = self.entries.get(&relative_path(path, project_path)).filter(|entry| entry.hash == hash)?;
+        let mut module = entry.module.clone();
+        module.map_paths(|relative| project_path.join(relative));
+        Some(module)
+    }
+
+    /// 今回解析したモジュールだけでキャッシュを作り直して保存する（削除されたファイルは消える）
+    pub(crate) fn save(
+        mut self,
+        dir: &Path,
+        project_path: &Path,
+        parsed: &[(String, ModuleInfo)]
+    ) -> std::io::Result<()> {
+        self.entries = parsed
+            .iter()
+            .map(|(hash, module)| {
+                let mut module = module.clone();
+                module.map_paths(|path| relative_path(path, project_path));
+                (module.path.clone(), CacheEntry { hash: hash.clone(), module })
+            })
+            .collect();
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join(CACHE_FILE), serde_json::to_string(&self)?)
+    }
+}
+
+pub(crate) fn content_hash(content: &str) -> String {
+    blake3::hash(content.as_bytes()).to_hex().to_string()
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+use std::sync::atomic::{ AtomicBool, Ordering };
+
+/// 実行中の解析を中断するためのトークン
+///
+/// クローンはすべて同じ状態を共有する。[`AnalyzerBuilder::cancellation`](crate::AnalyzerBuilder::cancellation)で
+/// 登録すると、ファイル1件ごとと各段階の間で確認され、中断されていれば[`AnalyzerError::Cancelled`](crate::AnalyzerError::Cancelled)を返す。
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 解析の中断を要求する
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::config::{ RuleLevel, Thresholds };
+use crate::i18n::{ Lang, tr };
+use crate::{ AnalysisResult, AnalyzerError, DependencyViolation, Result, ViolationType };
+
+/// 品質ゲート1件の評価結果
+pub struct GateOutcome {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// ベースラインにない違反を返す（`[rules]`で`warn`にしたルールの違反は除く）
+pub fn new_violations<'a>(
+    result: &'a AnalysisResult,
+    baseline: &AnalysisResult
+) -> Vec<&'a DependencyViolation> {
+    let known: HashSet<(&ViolationType, &str, &str)> = baseline.dependency_violations
+        .iter()
+        .map(|v| (&v.violation_type, v.from_module.as_ref(), v.to_module.as_ref()))
+        .collect();
+    result.dependency_violations
+        .iter()
+        .filter(|v| v.severity == RuleLevel::Error)
+        .filter(|v| !known.contains(&(&v.violation_type, v.from_module.as_ref(), v.to_module.as_ref())))
+        .collect()
+}
+
+/// `analyze -f json`で保存した解析結果を読み込む
+pub fn load_baseline(path: &Path) -> Result<AnalysisResult> {
+    let content = fs::read_to_string(path).map_err(|err| AnalyzerError::io(path, err))?;
+    serde_json::from_str(&content).map_err(|err| AnalyzerError::parse(path, err))
+}
+
+/// 設定されたゲートを評価する（未設定のゲートは評価しない）
+pub fn evaluate(
+    result: &AnalysisResult,
+    thresholds: &Thresholds,
+    baseline: Option<&AnalysisResult>
+) -> Vec<GateOutcome> {
+    let metrics = &result.metrics;
+    let mut outcomes = Vec::new();
+
+    if let Some(max) = thresholds.max_coupling_factor {
+        outcomes.push(GateOutcome {
+            name: tr("coupling factor", "結合度").to_string(),
+            passed: metrics.coupling_factor <= max,
+            detail: format!("{:.2} ({} {:.2})", metrics.coupling_factor, tr("max", "上限"), max),
+        });
+    }
+    if let Some(max) = thresholds.max_cycles {
+        let cycles = result.circular_dependencies.len();
+        outcomes.push(GateOutcome {
+            name: tr("circular dependencies", "循環依存").to_string(),
+            // AA004を`warn`にしていれば、循環があっても不合格にしない
+            passed: cycles <= max || result.cycle_severity == RuleLevel::Warn,
+            detail: format!("{} ({} {})", cycles, tr("max", "上限"), max),
+        });
+    }
+    if let Some(min) = thresholds.min_health_score {
+        outcomes.push(GateOutcome {
+            name: tr("health score", "ヘルススコア").to_string(),
+            passed: metrics.health_score >= min,
+            detail: format!("{:.1} ({} {:.1})", metrics.health_score, tr("min", "下限"), min),
+        });
+    }
+
+    match baseline {
+        // ベースラインがあれば既存の違反は許容し、新規の違反のみを不合格にする
+        Some(baseline) => {
+            let new = new_violations(result, baseline);
+            outcomes.push(GateOutcome {
+                name: tr("new violations", "新しい違反").to_string(),
+                passed: new.is_empty(),
+                detail: if new.is_empty() {
+                    format!("0 ({})", tr("vs baseline", "ベースライン比"))
+                } else {
+                    let list: Vec<String> = new
+                        .iter()
+                        .map(|v| format!("{} -> {}", v.from_module, v.to_module))
+                        .collect();
+                    format!("{} ({}): {}", new.len(), tr("vs baseline", "ベースライン比"), list.join(", "))
+                },
+            });
+        }
+        None => {
+            if let Some(max) = thresholds.max_violations {
+                let violations = result.dependency_violations
+                    .iter()
+                    .filter(|v| v.severity == RuleLevel::Error)
+                    .count();
+                outcomes.push(GateOutcome {
+                    name: tr("violations", "違反").to_string(),
+                    passed: violations <= max,
+                    detail: format!("{} ({} {})", violations, tr("max", "上限"), max),
+                });
+            }
+        }
+    }
+
+    outcomes
+}
+
+/// プラグインが`error`として報告した問題がないか（`[[plugins]]`を設定している場合のみ評価する）
+pub fn plugin_gate(result: &AnalysisResult) -> GateOutcome {
+    let errors = result.plugin_findings
+        .iter()
+        .filter(|f| f.severity == RuleLevel::Error)
+        .count();
+    GateOutcome {
+        name: tr("plugin findings", "プラグインが報告した問題").to_string(),
+        passed: errors == 0,
+        detail: match Lang::current() {
+            Lang::En => format!("{} errors, {} warnings", errors, result.plugin_findings.len() - errors),
+            Lang::Ja => format!("エラー{}件、警告{}件", errors, result.plugin_findings.len() - errors),
+        },
+    }
+}
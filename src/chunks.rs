@@ -0,0 +1,230 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{ BTreeMap, HashMap, HashSet };
+use std::fs;
+use std::path::Path;
+
+use crate::ownership::FileOwnership;
+use crate::{ AnalysisResult, AnalyzerError, Name, Result };
+
+/// 統計ファイルを出力したバンドラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bundler {
+    /// `ng build --stats-json`（browserビルダー）の`stats.json`
+    Webpack,
+    /// applicationビルダーの`stats.json`（esbuildのmetafile）
+    Esbuild,
+}
+
+/// ビルドが出力したチャンク
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub name: String,
+    pub bytes: u64,
+    /// 初期ロードで読み込まれるチャンク（遅延ロードのチャンクではない）
+    pub initial: bool,
+    /// チャンクに含まれるソースファイルと、そのチャンク内でのバイト数
+    pub inputs: Vec<(String, u64)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BuildStats {
+    pub bundler: Bundler,
+    pub chunks: Vec<Chunk>,
+}
+
+/// webpackの`stats.json`かesbuildのmetafileを読み込む（`outputs`があればesbuild）
+pub fn load_stats(path: &Path) -> Result<BuildStats> {
+    let content = fs::read_to_string(path).map_err(|err| AnalyzerError::io(path, err))?;
+    let stats: Value = serde_json::from_str(&content).map_err(|err| AnalyzerError::parse(path, err))?;
+    if let Some(outputs) = stats.get("outputs").and_then(Value::as_object) {
+        return Ok(BuildStats { bundler: Bundler::Esbuild, chunks: esbuild_chunks(outputs) });
+    }
+    if stats.get("chunks").is_some_and(Value::is_array) {
+        return Ok(BuildStats { bundler: Bundler::Webpack, chunks: webpack_chunks(&stats) });
+    }
+    Err(AnalyzerError::parse(path, "neither a webpack stats.json nor an esbuild metafile"))
+}
+
+fn esbuild_chunks(outputs: &serde_json::Map<String, Value>) -> Vec<Chunk> {
+    // エントリーポイントから静的なimportでたどれる出力が初期ロードされる
+    let mut initial: HashSet<&str> = HashSet::new();
+    let mut stack: Vec<&str> = outputs
+        .iter()
+        .filter(|(_, output)| output.get("entryPoint").is_some())
+        .map(|(file, _)| file.as_str())
+        .collect();
+    while let Some(file) = stack.pop() {
+        if !initial.insert(file) {
+            continue;
+        }
+        let imports = outputs
+            .get(file)
+            .and_then(|output| output.get("imports"))
+            .and_then(Value::as_array);
+        for import in imports.into_iter().flatten() {
+            if import.get("kind").and_then(Value::as_str) == Some("import-statement") {
+                stack.extend(import.get("path").and_then(Value::as_str));
+            }
+        }
+    }
+
+    outputs
+        .iter()
+        .filter(|(file, _)| !file.ends_with(".map"))
+        .map(|(file, output)| {
+            let inputs = output
+                .get("inputs")
+                .and_then(Value::as_object)
+                .map(|inputs| {
+                    inputs
+                        .iter()
+                        .map(|(input, info)| {
+                            (input.clone(), info.get("bytesInOutput").and_then(Value::as_u64).unwrap_or(0))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            Chunk {
+                name: file.clone(),
+                bytes: output.get("bytes").and_then(Value::as_u64).unwrap_or(0),
+                initial: initial.contains(file.as_str()),
+                inputs,
+            }
+        })
+        .collect()
+}
+
+fn webpack_chunks(stats: &Value) -> Vec<Chunk> {
+    let mut chunks: BTreeMap<String, Chunk> = BTreeMap::new();
+    for chunk in stats["chunks"].as_array().into_iter().flatten() {
+        let id = chunk["id"].to_string();
+        let name = chunk["names"]
+            .get(0)
+            .or_else(|| chunk["files"].get(0))
+            .and_then(Value::as_str)
+            .map_or_else(|| id.clone(), str::to_string);
+        chunks.insert(id, Chunk {
+            name,
+            bytes: chunk["size"].as_u64().unwrap_or(0),
+            initial: chunk["initial"].as_bool().unwrap_or(false),
+            inputs: Vec::new(),
+        });
+    }
+
+    // 結合されたモジュール（`./src/x.ts + 5 modules`）は中身のモジュールを数える
+    fn add(module: &Value, chunk_ids: &[String], chunks: &mut BTreeMap<String, Chunk>) {
+        if let Some(nested) = module["modules"].as_array() {
+            for module in nested {
+                add(module, chunk_ids, chunks);
+            }
+            return;
+        }
+        let Some(name) = module["name"].as_str() else {
+            return;
+        };
+        for id in chunk_ids {
+            if let Some(chunk) = chunks.get_mut(id) {
+                chunk.inputs.push((name.to_string(), module["size"].as_u64().unwrap_or(0)));
+            }
+        }
+    }
+    for module in stats["modules"].as_array().into_iter().flatten() {
+        let chunk_ids: Vec<String> = module["chunks"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(Value::to_string)
+            .collect();
+        add(module, &chunk_ids, &mut chunks);
+    }
+    chunks.into_values().collect()
+}
+
+/// モジュールが1つのチャンクに占めるバイト数
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleContribution {
+    pub module: Name,
+    pub bytes: u64,
+    /// ルートから遅延ロードを経由しないと到達できないモジュール
+    pub behind_lazy_route: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkContents {
+    pub chunk: String,
+    pub bytes: u64,
+    pub initial: bool,
+    /// バイト数の大きい順
+    pub modules: Vec<ModuleContribution>,
+}
+
+/// 遅延ロードの境界と実際のチャンクの対応
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkReport {
+    pub bundler: Bundler,
+    pub root: String,
+    /// 初期ロードのチャンクを先に、バイト数の大きい順
+    pub chunks: Vec<ChunkContents>,
+    /// 遅延ロードの先にあるのに初期ロードのチャンクに含まれているモジュール
+    pub misplaced: Vec<MisplacedModule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct MisplacedModule {
+    pub module: Name,
+    pub chunk: String,
+    pub bytes: u64,
+}
+
+/// チャンクのソースファイルをモジュールに振り分け、遅延ロードの境界と照合する
+///
+/// `root`（通常は`AppModule`）から遅延ロードを経由せずに到達できるモジュールは初期ロードされるべきもの、
+/// 遅延ロードを経由しないと到達できないモジュールは遅延チャンクにあるべきものとみなす。
+pub fn correlate(result: &AnalysisResult, project_path: &Path, stats: &BuildStats, root: &str) -> ChunkReport {
+    let graph = result.graph();
+    let ownership = FileOwnership::new(project_path, &result.modules);
+    let names: HashMap<&str, &Name> = result.modules
+        .iter()
+        .map(|m| (m.name.as_ref(), &m.name))
+        .collect();
+    let behind_lazy_route = |module: &str| {
+        module != root &&
+            graph.eager_shortest_paths(root, module).is_empty() &&
+            !graph.shortest_paths(root, module).is_empty()
+    };
+
+    let mut chunks = Vec::new();
+    let mut misplaced = Vec::new();
+    for chunk in &stats.chunks {
+        let mut bytes: BTreeMap<&str, u64> = BTreeMap::new();
+        for (input, size) in &chunk.inputs {
+            if let Some(owner) = ownership.owner(input) {
+                *bytes.entry(owner).or_default() += size;
+            }
+        }
+        let mut modules: Vec<ModuleContribution> = bytes
+            .into_iter()
+            .map(|(module, bytes)| ModuleContribution {
+                module: names[module].clone(),
+                bytes,
+                behind_lazy_route: behind_lazy_route(module),
+            })
+            .collect();
+        modules.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.module.cmp(&b.module)));
+        if chunk.initial {
+            misplaced.extend(
+                modules
+                    .iter()
+                    .filter(|m| m.behind_lazy_route)
+                    .map(|m| MisplacedModule { module: m.module.clone(), chunk: chunk.name.clone(), bytes: m.bytes })
+            );
+        }
+        chunks.push(ChunkContents { chunk: chunk.name.clone(), bytes: chunk.bytes, initial: chunk.initial, modules });
+    }
+    chunks.sort_by(|a, b| b.initial.cmp(&a.initial).then(b.bytes.cmp(&a.bytes)).then_with(|| a.chunk.cmp(&b.chunk)));
+    misplaced.sort();
+
+    ChunkReport { bundler: stats.bundler, root: root.to_string(), chunks, misplaced }
+}
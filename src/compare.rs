@@ -0,0 +1,150 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{ Component, Path };
+
+use crate::{ AnalysisResult, AnalyzerError, Result };
+
+/// 比較対象の依存グラフを出力したツール
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Tool {
+    /// `madge --json`（ファイルごとの依存先の配列）
+    Madge,
+    /// `depcruise --output-type json`
+    DependencyCruiser,
+}
+
+impl Tool {
+    pub fn label(self) -> &'static str {
+        match self {
+            Tool::Madge => "madge",
+            Tool::DependencyCruiser => "dependency-cruiser",
+        }
+    }
+}
+
+/// 他のツールが出力したファイル単位の依存グラフ
+pub struct ForeignGraph {
+    pub tool: Tool,
+    /// （依存元, 依存先）のファイルパス
+    pub edges: Vec<(String, String)>,
+}
+
+/// madgeまたはdependency-cruiserのJSONを読み込む（形式は内容から判定する）
+pub fn load(path: &Path) -> Result<ForeignGraph> {
+    let content = fs::read_to_string(path).map_err(|err| AnalyzerError::io(path, err))?;
+    let json: serde_json::Value = serde_json::from_str(&content).map_err(|err| AnalyzerError::parse(path, err))?;
+
+    if let Some(modules) = json.get("modules").and_then(|modules| modules.as_array()) {
+        let edges = modules
+            .iter()
+            .filter_map(|module| {
+                let source = module.get("source")?.as_str()?;
+                let dependencies = module.get("dependencies")?.as_array()?;
+                Some(
+                    dependencies
+                        .iter()
+                        .filter_map(move |dependency| {
+                            Some((source.to_string(), dependency.get("resolved")?.as_str()?.to_string()))
+                        })
+                )
+            })
+            .flatten()
+            .collect();
+        return Ok(ForeignGraph { tool: Tool::DependencyCruiser, edges });
+    }
+
+    let Some(files) = json.as_object() else {
+        return Err(AnalyzerError::parse(path, "expected madge or dependency-cruiser JSON"));
+    };
+    let mut edges = Vec::new();
+    for (source, targets) in files {
+        let targets = targets
+            .as_array()
+            .ok_or_else(|| AnalyzerError::parse(path, format!("dependencies of {} are not an array", source)))?;
+        for target in targets.iter().filter_map(|target| target.as_str()) {
+            edges.push((source.clone(), target.to_string()));
+        }
+    }
+    Ok(ForeignGraph { tool: Tool::Madge, edges })
+}
+
+/// モジュール間の依存エッジ（モジュール名）
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ModuleEdge {
+    pub from: String,
+    pub to: String,
+}
+
+/// 解析結果と他のツールのエッジの比較
+#[derive(Debug, Serialize)]
+pub struct Comparison {
+    pub tool: Tool,
+    /// 両方が検出したエッジ
+    pub common: Vec<ModuleEdge>,
+    /// 解析器だけが検出したエッジ
+    pub only_analyzer: Vec<ModuleEdge>,
+    /// 他のツールだけが検出したエッジ
+    pub only_other: Vec<ModuleEdge>,
+}
+
+impl Comparison {
+    pub fn is_identical(&self) -> bool {
+        self.only_analyzer.is_empty() && self.only_other.is_empty()
+    }
+}
+
+/// 解析結果のモジュール間エッジを他のツールのファイル単位のエッジと比較する
+///
+/// 他のツールのエッジは、両端がどちらも解析器の見つけたモジュールファイルであるものだけを対象にする。
+/// ツールごとに基準ディレクトリや拡張子の有無が違うため、ファイルパスは末尾のパス要素で対応付ける。
+pub fn compare(result: &AnalysisResult, foreign: &ForeignGraph) -> Comparison {
+    let files: Vec<(Vec<String>, &str)> = result.modules
+        .iter()
+        .map(|module| {
+            let components = module.path
+                .components()
+                .filter_map(|component| match component {
+                    Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+                    _ => None,
+                })
+                .collect();
+            (components, module.name.as_ref())
+        })
+        .collect();
+    let module_at = |file: &str| -> Option<&str> {
+        let file = file.replace('\\', "/");
+        let file = if file.ends_with(".ts") { file } else { format!("{}.ts", file) };
+        let parts: Vec<String> = file
+            .split('/')
+            .filter(|part| !part.is_empty() && *part != ".")
+            .map(str::to_string)
+            .collect();
+        files
+            .iter()
+            .find(|(components, _)| components.ends_with(&parts))
+            .map(|(_, name)| *name)
+    };
+
+    let ours: BTreeSet<ModuleEdge> = result
+        .graph()
+        .edges()
+        .into_iter()
+        .map(|(from, to, _)| ModuleEdge { from: from.to_string(), to: to.to_string() })
+        .collect();
+    let theirs: BTreeSet<ModuleEdge> = foreign.edges
+        .iter()
+        .filter_map(|(from, to)| {
+            let (from, to) = (module_at(from)?, module_at(to)?);
+            (from != to).then(|| ModuleEdge { from: from.to_string(), to: to.to_string() })
+        })
+        .collect();
+
+    Comparison {
+        tool: foreign.tool,
+        common: ours.intersection(&theirs).cloned().collect(),
+        only_analyzer: ours.difference(&theirs).cloned().collect(),
+        only_other: theirs.difference(&ours).cloned().collect(),
+    }
+}
@@ -0,0 +1,152 @@
+use serde::{ Deserialize, Serialize };
+use std::collections::{ BTreeMap, BTreeSet };
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::graph::reference_name;
+use crate::{ AnalysisResult, AnalyzerError, ModuleInfo, Result };
+
+/// compodocの`documentation.json`のうち照合に使う部分
+#[derive(Debug, Deserialize)]
+pub struct Documentation {
+    #[serde(default)]
+    pub modules: Vec<DocumentedModule>,
+}
+
+/// compodocが認識したNgModule
+#[derive(Debug, Deserialize)]
+pub struct DocumentedModule {
+    pub name: String,
+    #[serde(default)]
+    pub file: PathBuf,
+    /// `imports`・`exports`・`declarations`などの種類ごとの要素
+    #[serde(default)]
+    children: Vec<DocumentedChildren>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DocumentedChildren {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    elements: Vec<DocumentedElement>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DocumentedElement {
+    name: String,
+}
+
+impl DocumentedModule {
+    fn elements(&self, kind: &str) -> BTreeSet<&str> {
+        self.children
+            .iter()
+            .filter(|children| children.kind == kind)
+            .flat_map(|children| children.elements.iter().map(|element| element.name.as_str()))
+            .collect()
+    }
+}
+
+/// compodocを実行して生成した`documentation.json`を読み込む
+pub fn load_documentation(path: &Path) -> Result<Documentation> {
+    let content = fs::read_to_string(path).map_err(|err| AnalyzerError::io(path, err))?;
+    serde_json::from_str(&content).map_err(|err| AnalyzerError::parse(path, err))
+}
+
+/// 片方だけが認識したモジュール
+#[derive(Debug, Serialize)]
+pub struct UnmatchedModule {
+    pub name: String,
+    pub file: PathBuf,
+}
+
+/// 両方が認識したモジュールで、メタデータの要素が食い違っているもの
+#[derive(Debug, Serialize)]
+pub struct MetadataMismatch {
+    pub module: String,
+    /// `imports`・`exports`・`declarations`のいずれか
+    pub field: &'static str,
+    /// compodocにだけある要素（解析器が読み落としたもの）
+    pub missing: Vec<String>,
+    /// 解析器にだけある要素
+    pub extra: Vec<String>,
+}
+
+/// 解析結果とcompodocの照合結果
+#[derive(Debug, Serialize)]
+pub struct Reconciliation {
+    /// 両方が認識したモジュールの数
+    pub matched: usize,
+    /// compodocだけが認識したモジュール（解析器の抽出漏れ）
+    pub missing_modules: Vec<UnmatchedModule>,
+    /// 解析器だけが認識したモジュール
+    pub undocumented_modules: Vec<UnmatchedModule>,
+    pub mismatches: Vec<MetadataMismatch>,
+}
+
+impl Reconciliation {
+    /// 食い違いが一つもない
+    pub fn is_consistent(&self) -> bool {
+        self.missing_modules.is_empty() && self.undocumented_modules.is_empty() && self.mismatches.is_empty()
+    }
+}
+
+/// 解析結果をcompodocのドキュメントと突き合わせる
+///
+/// モジュールは名前で対応付け、`RouterModule.forRoot(...)`のような要素は識別子部分で比較する。
+pub fn reconcile(result: &AnalysisResult, documentation: &Documentation) -> Reconciliation {
+    let analyzed: BTreeMap<&str, &ModuleInfo> = result.modules
+        .iter()
+        .map(|module| (module.name.as_ref(), module))
+        .collect();
+    let documented: BTreeMap<&str, &DocumentedModule> = documentation.modules
+        .iter()
+        .map(|module| (module.name.as_str(), module))
+        .collect();
+
+    let missing_modules = documented
+        .iter()
+        .filter(|(name, _)| !analyzed.contains_key(*name))
+        .map(|(name, module)| UnmatchedModule { name: name.to_string(), file: module.file.clone() })
+        .collect();
+    let undocumented_modules = analyzed
+        .iter()
+        .filter(|(name, _)| !documented.contains_key(*name))
+        .map(|(name, module)| UnmatchedModule { name: name.to_string(), file: module.path.clone() })
+        .collect();
+
+    let mut matched = 0;
+    let mut mismatches = Vec::new();
+    for (name, module) in &analyzed {
+        let Some(documented) = documented.get(name) else {
+            continue;
+        };
+        matched += 1;
+
+        let fields = [
+            ("imports", &module.imports),
+            ("exports", &module.exports),
+            ("declarations", &module.declarations),
+        ];
+        for (field, entries) in fields {
+            let ours: BTreeSet<&str> = entries
+                .iter()
+                .map(|entry| reference_name(entry))
+                .collect();
+            let theirs = documented.elements(field);
+            let missing: Vec<String> = theirs
+                .difference(&ours)
+                .map(|name| name.to_string())
+                .collect();
+            let extra: Vec<String> = ours
+                .difference(&theirs)
+                .map(|name| name.to_string())
+                .collect();
+            if !missing.is_empty() || !extra.is_empty() {
+                mismatches.push(MetadataMismatch { module: name.to_string(), field, missing, extra });
+            }
+        }
+    }
+
+    Reconciliation { matched, missing_modules, undocumented_modules, mismatches }
+}
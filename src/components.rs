@@ -0,0 +1,330 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use std::collections::{ BTreeSet, HashMap, HashSet };
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::{ ModuleInfo, Name, normalize_path, source };
+
+static DECORATOR_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"@(Component|Directive|Pipe)\s*\(\s*\{").unwrap());
+static CLASS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"class\s+(\w+)").unwrap());
+static SELECTOR_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"selector\s*:\s*["'`]([^"'`]*)["'`]"#).unwrap());
+static PIPE_NAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\bname\s*:\s*["'`]([^"'`]*)["'`]"#).unwrap());
+static TEMPLATE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\btemplate\s*:\s*(?:`([^`]*)`|"([^"]*)"|'([^']*)')"#).unwrap());
+static TEMPLATE_URL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"templateUrl\s*:\s*["'`]([^"'`]*)["'`]"#).unwrap());
+pub(crate) static NAMED_IMPORT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"import\s*(?:type\s*)?\{([^}]*)\}\s*from\s*["']([^"']*)["']"#).unwrap()
+});
+static ELEMENT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<([a-zA-Z][\w-]*)([^>]*?)/?>").unwrap());
+static ATTRIBUTE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([^\s=/>"']+)(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s>]+))?"#).unwrap());
+// `a || b`の`|`はパイプではない
+static PIPE_USAGE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|[^|])\|\s*([A-Za-z_]\w*)").unwrap());
+
+/// NgModuleが宣言できるクラスの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DeclarableKind {
+    Component,
+    Directive,
+    Pipe,
+}
+
+/// `@Component`・`@Directive`・`@Pipe`を付けたクラス
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Declarable {
+    pub name: String,
+    pub kind: DeclarableKind,
+    /// コンポーネント・ディレクティブのセレクター、パイプの名前
+    pub selector: Option<String>,
+    pub file: PathBuf,
+    /// `declarations`に含めているNgModule（スタンドアロンか、どのモジュールにも宣言されていなければ`None`）
+    pub module: Option<Name>,
+}
+
+/// 依存の根拠
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Usage {
+    /// テンプレート内でセレクターやパイプ名を使っている
+    Template,
+    /// TypeScriptのimport文でクラスを読み込んでいる（スタンドアロンコンポーネントの`imports`など）
+    Import,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct DeclarableEdge {
+    pub from: String,
+    pub to: String,
+    pub usage: Usage,
+}
+
+/// コンポーネント・ディレクティブ・パイプの依存グラフ
+///
+/// モジュール単位のグラフでは`SharedModule`にまとめられて見えなくなる、個々のUI部品の結合を表す。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentGraph {
+    /// 名前順
+    pub declarables: Vec<Declarable>,
+    /// 依存元・依存先・根拠の順
+    pub edges: Vec<DeclarableEdge>,
+}
+
+/// 1つのファイルから読み取ったクラスと、そのファイルのテンプレート・import文
+struct ParsedFile {
+    declarables: Vec<(Declarable, Option<String>)>,
+    /// （読み込んだ名前, モジュール指定子）
+    imports: Vec<(String, String)>,
+}
+
+impl ComponentGraph {
+    /// `files`のうち`@Component`・`@Directive`・`@Pipe`を含むものを読み、テンプレートとimport文から依存を集める
+    pub(crate) fn build(
+        files: impl Iterator<Item = PathBuf>,
+        modules: &[ModuleInfo],
+        max_file_size: Option<u64>
+    ) -> Self {
+        let declared_in: HashMap<&str, &Name> = modules
+            .iter()
+            .flat_map(|module| module.declarations.iter().map(move |name| (name.as_ref(), &module.name)))
+            .collect();
+
+        let parsed: Vec<ParsedFile> = files
+            .filter_map(|path| {
+                let content = source::read(&path, max_file_size).ok()?;
+                DECORATOR_REGEX.is_match(&content).then(|| parse_file(&path, &content, &declared_in))
+            })
+            .collect();
+
+        let mut declarables: Vec<Declarable> = parsed
+            .iter()
+            .flat_map(|file| file.declarables.iter().map(|(declarable, _)| declarable.clone()))
+            .collect();
+        declarables.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.file.cmp(&b.file)));
+
+        let selectors: Vec<(&Declarable, Vec<Selector>)> = declarables
+            .iter()
+            .filter(|d| d.kind != DeclarableKind::Pipe)
+            .filter_map(|d| Some((d, Selector::parse_list(d.selector.as_deref()?))))
+            .collect();
+        let pipes: HashMap<&str, &Declarable> = declarables
+            .iter()
+            .filter(|d| d.kind == DeclarableKind::Pipe)
+            .filter_map(|d| Some((d.selector.as_deref()?, d)))
+            .collect();
+        let mut by_name: HashMap<&str, Vec<&Declarable>> = HashMap::new();
+        for declarable in &declarables {
+            by_name.entry(declarable.name.as_str()).or_default().push(declarable);
+        }
+
+        let mut edges: BTreeSet<DeclarableEdge> = BTreeSet::new();
+        let mut add = |from: &Declarable, to: &Declarable, usage: Usage| {
+            if from.name != to.name {
+                edges.insert(DeclarableEdge { from: from.name.clone(), to: to.name.clone(), usage });
+            }
+        };
+        for file in &parsed {
+            for (from, template) in &file.declarables {
+                if let Some(template) = template {
+                    for element in elements(template) {
+                        for (to, _) in selectors.iter().filter(|(_, s)| s.iter().any(|s| s.matches(&element))) {
+                            add(from, to, Usage::Template);
+                        }
+                    }
+                    for cap in PIPE_USAGE_REGEX.captures_iter(template) {
+                        if let Some(to) = pipes.get(&cap[1]) {
+                            add(from, to, Usage::Template);
+                        }
+                    }
+                }
+
+                for (name, specifier) in &file.imports {
+                    let Some(candidates) = by_name.get(name.as_str()) else {
+                        continue;
+                    };
+                    // 同名のクラスが複数あれば、相対パスで指しているファイルのものに絞る
+                    let target = match candidates.as_slice() {
+                        [only] => Some(*only),
+                        _ => candidates.iter().copied().find(|d| points_to(&from.file, specifier, &d.file)),
+                    };
+                    if let Some(to) = target {
+                        add(from, to, Usage::Import);
+                    }
+                }
+            }
+        }
+
+        ComponentGraph { declarables, edges: edges.into_iter().collect() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.declarables.is_empty()
+    }
+}
+
+fn parse_file(path: &Path, content: &str, declared_in: &HashMap<&str, &Name>) -> ParsedFile {
+    let mut declarables = Vec::new();
+    for cap in DECORATOR_REGEX.captures_iter(content) {
+        let whole = cap.get(0).unwrap();
+        let Some(body) = object_body(content, whole.end() - 1) else {
+            continue;
+        };
+        let after = &content[whole.end() - 1 + body.len()..];
+        let Some(class) = CLASS_REGEX.captures(after) else {
+            continue;
+        };
+        let name = class[1].to_string();
+        let kind = match &cap[1] {
+            "Component" => DeclarableKind::Component,
+            "Directive" => DeclarableKind::Directive,
+            _ => DeclarableKind::Pipe,
+        };
+        let selector_regex = if kind == DeclarableKind::Pipe { &PIPE_NAME_REGEX } else { &SELECTOR_REGEX };
+        let selector = selector_regex.captures(body).map(|c| c[1].trim().to_string());
+        let template = (kind == DeclarableKind::Component).then(|| template(path, body)).flatten();
+        declarables.push((
+            Declarable {
+                module: declared_in.get(name.as_str()).map(|module| (*module).clone()),
+                name,
+                kind,
+                selector,
+                file: path.to_path_buf(),
+            },
+            template,
+        ));
+    }
+
+    let imports = NAMED_IMPORT_REGEX.captures_iter(content)
+        .flat_map(|cap| {
+            let specifier = cap[2].to_string();
+            cap[1]
+                .split(',')
+                .filter_map(|name| {
+                    // `Foo as Bar`は元の名前で照合する
+                    let name = name.split_whitespace().next()?;
+                    Some((name.to_string(), specifier.clone()))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    ParsedFile { declarables, imports }
+}
+
+/// `open`の`{`から対応する`}`までの文字列（文字列リテラル内の括弧は数えない）
+fn object_body(content: &str, open: usize) -> Option<&str> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in content[open..].char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => {
+                quote = Some(c);
+            }
+            '{' => {
+                depth += 1;
+            }
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&content[open..=open + i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// インラインの`template`か、`templateUrl`のファイルの内容
+fn template(path: &Path, body: &str) -> Option<String> {
+    if let Some(cap) = TEMPLATE_REGEX.captures(body) {
+        return cap
+            .iter()
+            .skip(1)
+            .flatten()
+            .next()
+            .map(|m| m.as_str().to_string());
+    }
+    let url = TEMPLATE_URL_REGEX.captures(body)?;
+    fs::read_to_string(path.parent()?.join(&url[1])).ok()
+}
+
+/// テンプレート中の要素（タグ名と属性名）
+struct Element {
+    tag: String,
+    attributes: HashSet<String>,
+}
+
+fn elements(template: &str) -> Vec<Element> {
+    ELEMENT_REGEX.captures_iter(template)
+        .map(|cap| {
+            let attributes = ATTRIBUTE_REGEX.captures_iter(&cap[2])
+                // `[input]`・`(output)`・`[(ngModel)]`・`*ngIf`は束縛の記号を除いた名前で照合する
+                .map(|attr| attr[1].trim_matches(|c| matches!(c, '[' | ']' | '(' | ')' | '*')).to_string())
+                .map(|attr| attr.split('.').next().unwrap_or_default().to_string())
+                .filter(|attr| !attr.is_empty())
+                .collect();
+            Element { tag: cap[1].to_lowercase(), attributes }
+        })
+        .collect()
+}
+
+/// セレクターのうち、カンマで区切られた1つ（`button[mat-button]`ならタグ`button`と属性`mat-button`）
+struct Selector {
+    tag: Option<String>,
+    attributes: Vec<String>,
+}
+
+impl Selector {
+    fn parse_list(selector: &str) -> Vec<Selector> {
+        selector
+            .split(',')
+            .map(str::trim)
+            // `:not(...)`やクラスセレクターの条件は無視する
+            .map(|part| part.split(":not(").next().unwrap_or(part))
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let tag_end = part.find(['[', '.', ':']).unwrap_or(part.len());
+                let tag = Some(part[..tag_end].to_lowercase()).filter(|tag| !tag.is_empty());
+                let attributes = part[tag_end..]
+                    .split('[')
+                    .skip(1)
+                    .filter_map(|attr| {
+                        let attr = attr.split(']').next()?;
+                        Some(attr.split('=').next()?.trim().to_string())
+                    })
+                    .collect();
+                Selector { tag, attributes }
+            })
+            .filter(|selector| selector.tag.is_some() || !selector.attributes.is_empty())
+            .collect()
+    }
+
+    fn matches(&self, element: &Element) -> bool {
+        self.tag.as_ref().is_none_or(|tag| *tag == element.tag) &&
+            self.attributes.iter().all(|attr| element.attributes.contains(attr))
+    }
+}
+
+/// 相対パスの指定子`specifier`が`target`（拡張子・`index`の省略を含む）を指しているか
+fn points_to(from: &Path, specifier: &str, target: &Path) -> bool {
+    if !specifier.starts_with('.') {
+        return false;
+    }
+    let Some(directory) = from.parent() else {
+        return false;
+    };
+    let resolved = normalize_path(&directory.join(specifier));
+    let target = normalize_path(target);
+    target.with_extension("") == resolved ||
+        (target.parent() == Some(&resolved) && target.file_stem() == Some("index".as_ref()))
+}
@@ -0,0 +1,397 @@
+use globset::{ Glob, GlobSet, GlobSetBuilder };
+use ignore::gitignore::GitignoreBuilder;
+use serde::{ Deserialize, Serialize };
+use std::collections::{ BTreeMap, HashMap };
+use std::fmt;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::path::{ Path, PathBuf };
+use toml::Spanned;
+use toml::de::{ DeTable, DeValue };
+
+use crate::graph::CycleLimits;
+use crate::{ AnalyzerError, ModuleType, Result, ViolationType };
+
+/// プロジェクトルートに置く設定ファイル名
+pub const CONFIG_FILE_NAME: &str = ".angular-analyzer.toml";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub classification: ClassificationConfig,
+    pub thresholds: Thresholds,
+    /// ルールコード（AA001など）ごとの重大度
+    pub rules: BTreeMap<String, RuleLevel>,
+    pub analysis: AnalysisConfig,
+    pub notify: NotifyConfig,
+    /// 独自ルールを実装したWASMプラグイン（`[[plugins]]`、書いた順に実行する）
+    pub plugins: Vec<PluginConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    Off,
+    /// 報告するが、`check`のゲートでは不合格にしない
+    Warn,
+    #[default]
+    Error,
+}
+
+/// モジュール分類ルール（プロジェクトルートからの相対パスに対するglob）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClassificationConfig {
+    pub core: Vec<String>,
+    pub shared: Vec<String>,
+    pub feature: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    pub max_coupling_factor: Option<f32>,
+    pub max_cycles: Option<usize>,
+    pub max_violations: Option<usize>,
+    pub min_health_score: Option<f32>,
+}
+
+/// 解析の実行方法
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    /// ファイル解析に使うスレッド数（未指定ならCPUコア数）
+    pub jobs: Option<NonZeroUsize>,
+    /// 報告する循環の最大数（未指定なら[`DEFAULT_CYCLE_LIMIT`]）
+    pub cycle_limit: Option<usize>,
+    /// 列挙する循環の最大の長さ（モジュール数、未指定なら無制限）
+    pub max_cycle_length: Option<usize>,
+    /// 解析しないファイル（プロジェクトルートからの相対、`.gitignore`と同じ書式で`.analyzerignore`の後に適用する）
+    pub exclude: Vec<String>,
+}
+
+/// `check`の結果を通知するWebhook
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Slack/Teams互換のIncoming Webhook URL（`$NAME`なら環境変数`NAME`の値）
+    pub webhook: Option<String>,
+    /// 通知本文のテンプレート（未指定なら[`notify::DEFAULT_TEMPLATE`](crate::notify::DEFAULT_TEMPLATE)）
+    pub template: Option<String>,
+}
+
+impl NotifyConfig {
+    /// 環境変数を展開したWebhook URL（未設定、または環境変数が空なら`None`）
+    pub fn webhook_url(&self) -> Option<String> {
+        let webhook = self.webhook.as_deref()?;
+        let url = match webhook.strip_prefix('$') {
+            Some(name) => std::env::var(name.trim_start_matches('{').trim_end_matches('}')).ok()?,
+            None => webhook.to_string(),
+        };
+        Some(url).filter(|url| !url.is_empty())
+    }
+}
+
+/// `[[plugins]]`に書いたWASMプラグインまたはRhaiスクリプト（ABIは[`plugins`](crate::plugins)を参照）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginConfig {
+    /// `.wasm`・`.wat`・`.rhai`ファイルのプロジェクトルートからの相対パス
+    pub path: PathBuf,
+    /// 設定ファイルに直接書いたRhaiスクリプト（`path`の代わり）
+    pub script: Option<String>,
+    /// 結果に表示する名前（未指定ならファイル名から拡張子を除いたもの、直接書いたスクリプトは`script`）
+    pub name: Option<String>,
+    /// プラグインにそのまま渡す設定
+    pub options: toml::Table,
+}
+
+impl PluginConfig {
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            self.path
+                .file_stem()
+                .filter(|_| self.script.is_none())
+                .map_or_else(|| "script".to_string(), |stem| stem.to_string_lossy().into_owned())
+        })
+    }
+}
+
+/// 循環の列挙の既定の上限（これを超える規模の循環は個別に列挙しても読み切れない）
+pub const DEFAULT_CYCLE_LIMIT: usize = 1000;
+
+impl AnalysisConfig {
+    pub fn cycle_limits(&self) -> CycleLimits {
+        CycleLimits {
+            max_cycles: Some(self.cycle_limit.unwrap_or(DEFAULT_CYCLE_LIMIT)),
+            max_length: self.max_cycle_length,
+        }
+    }
+}
+
+impl Config {
+    /// プロジェクトルートの設定ファイルを読み込む（存在しない場合は既定値）
+    pub fn load(project_path: &Path) -> Result<Self> {
+        let path = Self::path_for(project_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).map_err(|err| AnalyzerError::io(&path, err))?;
+        Self::parse(&path, &content)
+    }
+
+    /// 設定ファイルの内容を検証して読み込む（`path`はエラーの表示にだけ使う）
+    pub fn parse(path: &Path, content: &str) -> Result<Self> {
+        let issues = validate(content);
+        if !issues.is_empty() {
+            return Err(AnalyzerError::InvalidConfig { path: path.to_path_buf(), issues });
+        }
+        toml::from_str(content).map_err(|err| AnalyzerError::parse(path, err.message().trim()))
+    }
+
+    pub fn path_for(project_path: &Path) -> PathBuf {
+        project_path.join(CONFIG_FILE_NAME)
+    }
+
+    pub fn rule_enabled(&self, violation_type: &ViolationType) -> bool {
+        self.rules.get(violation_type.code()) != Some(&RuleLevel::Off)
+    }
+
+    /// ルールの重大度（`[rules]`に書いていなければ`error`）
+    pub fn rule_level(&self, violation_type: &ViolationType) -> RuleLevel {
+        self.rules.get(violation_type.code()).copied().unwrap_or_default()
+    }
+}
+
+/// 設定ファイル中の問題箇所（1始まりの行・列）
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+const SECTIONS: [&str; 6] = ["classification", "thresholds", "rules", "analysis", "notify", "plugins"];
+const LAYERS: [&str; 3] = ["core", "shared", "feature"];
+const ANALYSIS: [&str; 4] = ["jobs", "cycle_limit", "max_cycle_length", "exclude"];
+const NOTIFY: [&str; 2] = ["webhook", "template"];
+const PLUGIN: [&str; 4] = ["path", "script", "name", "options"];
+
+const THRESHOLDS: [&str; 4] = ["max_coupling_factor", "max_cycles", "max_violations", "min_health_score"];
+
+/// 設定ファイルの内容を検証し、見つかった問題をすべて返す
+pub fn validate(content: &str) -> Vec<ConfigIssue> {
+    let mut validator = Validator { content, issues: Vec::new() };
+
+    let table = match DeTable::parse(content) {
+        Ok(table) => table,
+        Err(err) => {
+            validator.push(err.span().unwrap_or(0..0), err.message().trim().to_string());
+            return validator.issues;
+        }
+    };
+
+    for (key, value) in table.get_ref() {
+        match key.get_ref().as_ref() {
+            "classification" => validator.check_classification(value),
+            "thresholds" => validator.check_keys(value, "thresholds", &THRESHOLDS),
+            "rules" => validator.check_rules(value),
+            "analysis" => validator.check_analysis(value),
+            "notify" => validator.check_keys(value, "notify", &NOTIFY),
+            "plugins" => validator.check_plugins(value),
+            other =>
+                validator.push(
+                    key.span(),
+                    format!("unknown section `{}` (expected one of: {})", other, SECTIONS.join(", "))
+                ),
+        }
+    }
+
+    // 型の誤りなどはserdeに任せる（未知のキーは上で報告済み）
+    if validator.issues.is_empty() && let Err(err) = toml::from_str::<Config>(content) {
+        validator.push(err.span().unwrap_or(0..0), err.message().trim().to_string());
+    }
+
+    validator.issues.sort_by_key(|issue| (issue.line, issue.column));
+    validator.issues
+}
+
+struct Validator<'a> {
+    content: &'a str,
+    issues: Vec<ConfigIssue>,
+}
+
+impl Validator<'_> {
+    fn push(&mut self, span: Range<usize>, message: String) {
+        let before = &self.content[..span.start.min(self.content.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+        self.issues.push(ConfigIssue { line, column, message });
+    }
+
+    fn table<'t, 'i>(&mut self, value: &'t Spanned<DeValue<'i>>, section: &str) -> Option<&'t DeTable<'i>> {
+        match value.get_ref() {
+            DeValue::Table(table) => Some(table),
+            _ => {
+                self.push(value.span(), format!("`{}` must be a table", section));
+                None
+            }
+        }
+    }
+
+    fn check_keys(&mut self, value: &Spanned<DeValue>, section: &str, known: &[&str]) {
+        let Some(table) = self.table(value, section) else {
+            return;
+        };
+        for key in table.keys() {
+            if !known.contains(&key.get_ref().as_ref()) {
+                self.push(
+                    key.span(),
+                    format!("unknown key `{}` in [{}] (expected one of: {})", key.get_ref(), section, known.join(", "))
+                );
+            }
+        }
+    }
+
+    fn check_classification(&mut self, value: &Spanned<DeValue>) {
+        self.check_keys(value, "classification", &LAYERS);
+        let Some(table) = self.table(value, "classification") else {
+            return;
+        };
+
+        // 同じglobが複数のレイヤーに書かれていると分類が曖昧になる
+        let mut seen: HashMap<String, String> = HashMap::new();
+        for (key, patterns) in table {
+            let layer = key.get_ref().to_string();
+            if !LAYERS.contains(&layer.as_str()) {
+                continue;
+            }
+            let DeValue::Array(patterns) = patterns.get_ref() else {
+                self.push(patterns.span(), format!("`classification.{}` must be an array of glob strings", layer));
+                continue;
+            };
+            for pattern in patterns {
+                let DeValue::String(glob) = pattern.get_ref() else {
+                    self.push(pattern.span(), "classification globs must be strings".to_string());
+                    continue;
+                };
+                if let Err(err) = Glob::new(glob) {
+                    self.push(pattern.span(), format!("invalid glob `{}`: {}", glob, err.kind()));
+                }
+                match seen.get(glob.as_ref()) {
+                    Some(other) if *other != layer =>
+                        self.push(
+                            pattern.span(),
+                            format!("glob `{}` is assigned to both `{}` and `{}`", glob, other, layer)
+                        ),
+                    Some(_) => {}
+                    None => {
+                        seen.insert(glob.to_string(), layer.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_analysis(&mut self, value: &Spanned<DeValue>) {
+        self.check_keys(value, "analysis", &ANALYSIS);
+        let Some(table) = self.table(value, "analysis") else {
+            return;
+        };
+        // 型の誤りはserdeが報告する
+        let Some(DeValue::Array(patterns)) = table.get("exclude").map(|patterns| patterns.get_ref()) else {
+            return;
+        };
+        for pattern in patterns {
+            if
+                let DeValue::String(line) = pattern.get_ref() &&
+                let Err(err) = GitignoreBuilder::new("").add_line(None, line)
+            {
+                self.push(pattern.span(), format!("invalid exclude pattern `{}`: {}", line, err));
+            }
+        }
+    }
+
+    fn check_plugins(&mut self, value: &Spanned<DeValue>) {
+        let DeValue::Array(plugins) = value.get_ref() else {
+            self.push(value.span(), "`plugins` must be an array of tables (`[[plugins]]`)".to_string());
+            return;
+        };
+        for plugin in plugins {
+            self.check_keys(plugin, "[plugins]", &PLUGIN);
+            if let DeValue::Table(table) = plugin.get_ref() {
+                match (table.contains_key("path"), table.contains_key("script")) {
+                    (false, false) => self.push(plugin.span(), "[[plugins]] needs either `path` or `script`".to_string()),
+                    (true, true) => self.push(plugin.span(), "[[plugins]] cannot have both `path` and `script`".to_string()),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn check_rules(&mut self, value: &Spanned<DeValue>) {
+        let Some(table) = self.table(value, "rules") else {
+            return;
+        };
+        let codes: Vec<&str> = ViolationType::ALL.iter()
+            .map(|v| v.code())
+            .collect();
+        for key in table.keys() {
+            if !codes.contains(&key.get_ref().as_ref()) {
+                self.push(
+                    key.span(),
+                    format!("unknown rule code `{}` (known rules: {})", key.get_ref(), codes.join(", "))
+                );
+            }
+        }
+    }
+}
+
+/// 設定された分類ルールをコンパイルしたもの
+pub struct Classifier {
+    rules: Vec<(ModuleType, GlobSet)>,
+}
+
+impl Classifier {
+    pub fn new(config: &ClassificationConfig) -> Result<Self> {
+        let mut rules = Vec::new();
+        for (module_type, patterns) in [
+            (ModuleType::Core, &config.core),
+            (ModuleType::Shared, &config.shared),
+            (ModuleType::Feature, &config.feature),
+        ] {
+            if patterns.is_empty() {
+                continue;
+            }
+            let mut builder = GlobSetBuilder::new();
+            for pattern in patterns {
+                let glob = Glob::new(pattern).map_err(|source| AnalyzerError::InvalidGlob {
+                    pattern: pattern.clone(),
+                    source,
+                })?;
+                builder.add(glob);
+            }
+            let globs = builder.build().map_err(|source| AnalyzerError::InvalidGlob {
+                pattern: patterns.join(", "),
+                source,
+            })?;
+            rules.push((module_type, globs));
+        }
+        Ok(Self { rules })
+    }
+
+    /// 相対パスに最初にマッチしたルールの分類を返す
+    pub fn classify(&self, relative_path: &Path) -> Option<ModuleType> {
+        self.rules
+            .iter()
+            .find(|(_, globs)| globs.is_match(relative_path))
+            .map(|(module_type, _)| module_type.clone())
+    }
+}
@@ -0,0 +1,1472 @@
+use angular_module_analyzer::cache::CacheStats;
+use angular_module_analyzer::check::GateOutcome;
+use angular_module_analyzer::chunks::ChunkReport;
+use angular_module_analyzer::compare::Comparison;
+use angular_module_analyzer::compodoc::Reconciliation;
+use angular_module_analyzer::config::RuleLevel;
+use angular_module_analyzer::deadcode::{ Confidence, DeadCodeReport };
+use angular_module_analyzer::doctor::{ Diagnosis, Severity };
+use angular_module_analyzer::drift::DriftReport;
+use angular_module_analyzer::fix::{ FileFix, FixKind };
+use angular_module_analyzer::graph::{ EdgeKind, ModuleGraph };
+use angular_module_analyzer::i18n::{ Lang, tr };
+use angular_module_analyzer::impact::ImpactReport;
+use angular_module_analyzer::list::{ self, ModuleRow };
+use angular_module_analyzer::merge::MergeCandidate;
+use angular_module_analyzer::progress::{ Phase, ProgressHandler };
+use angular_module_analyzer::services::{ MultiProviders, ProviderScopes, ServiceTree };
+use angular_module_analyzer::source;
+use angular_module_analyzer::split::SplitPlan;
+use angular_module_analyzer::standalone::MigrationPlan;
+use angular_module_analyzer::table::{ Align, Table };
+use angular_module_analyzer::workspaces::WorkspaceReport;
+use angular_module_analyzer::{ AnalysisResult, AnalyzerError, ModuleInfo, ModuleType, SourceLocation };
+use anyhow::{ Context, Result };
+use colored::*;
+use std::collections::{ BTreeMap, HashSet };
+use std::fs;
+use std::io::{ IsTerminal, Write };
+use std::path::{ Path, PathBuf };
+use std::process::{ Command, Stdio };
+use std::sync::Mutex;
+use std::sync::atomic::{ AtomicBool, AtomicUsize, Ordering };
+use std::time::Instant;
+use unicode_width::UnicodeWidthStr;
+
+/// 絵文字と罫線の代わりにASCIIの記号を使うか（`--ascii`）
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// 以降のコンソール出力で絵文字と罫線をASCIIの記号に置き換えるか
+pub fn use_ascii(enabled: bool) {
+    ASCII.store(enabled, Ordering::Relaxed);
+}
+
+/// 絵文字や罫線、`--ascii`ではその代わりのASCIIの記号
+fn glyph(unicode: &'static str, ascii: &'static str) -> &'static str {
+    if ASCII.load(Ordering::Relaxed) { ascii } else { unicode }
+}
+
+/// 絵文字付きの見出し（`--ascii`では絵文字を付けない）
+fn heading(emoji: &str, title: &str) -> String {
+    if ASCII.load(Ordering::Relaxed) { title.to_string() } else { format!("{} {}", emoji, title) }
+}
+
+pub fn mark_ok() -> ColoredString {
+    glyph("✔", "[ok]").green()
+}
+
+pub fn mark_warning() -> ColoredString {
+    glyph("⚠", "[!]").yellow()
+}
+
+pub fn mark_error() -> ColoredString {
+    glyph("✖", "[x]").red()
+}
+
+/// `--out-file`が指定されていればファイルへ、そうでなければ標準出力へ書き出す
+pub fn emit(content: &str, out_file: Option<&Path>) -> Result<()> {
+    match out_file {
+        Some(path) => {
+            fs::write(path, content).with_context(|| format!("Failed to write file: {:?}", path))?;
+            eprintln!("Report written to: {}", path.display());
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// 標準エラー出力に1行で解析の進捗を表示する（読み飛ばしたファイルの警告は端末でなくても表示する）
+pub struct ProgressLine {
+    interactive: bool,
+    discovered: AtomicUsize,
+    parsed: AtomicUsize,
+}
+
+impl ProgressLine {
+    pub fn new(interactive: bool) -> Self {
+        Self { interactive, discovered: AtomicUsize::new(0), parsed: AtomicUsize::new(0) }
+    }
+
+    fn show(&self, message: &str) {
+        if !self.interactive {
+            return;
+        }
+        let mut stderr = std::io::stderr().lock();
+        let _ = write!(stderr, "\r\x1b[2K{}", message);
+        let _ = stderr.flush();
+    }
+}
+
+impl ProgressHandler for ProgressLine {
+    fn on_file_discovered(&self, _path: &Path) {
+        let discovered = self.discovered.fetch_add(1, Ordering::Relaxed) + 1;
+        if discovered.is_multiple_of(50) {
+            self.show(&format!("Discovering modules... {}", discovered));
+        }
+    }
+
+    fn on_module_parsed(&self, module: &ModuleInfo) {
+        let parsed = self.parsed.fetch_add(1, Ordering::Relaxed) + 1;
+        let total = self.discovered.load(Ordering::Relaxed);
+        self.show(&format!("Parsing modules {}/{} {}", parsed, total, module.name));
+    }
+
+    fn on_file_skipped(&self, reason: &AnalyzerError) {
+        self.show("");
+        eprintln!("{} {}", "warning:".yellow().bold(), reason);
+    }
+
+    fn on_phase_complete(&self, phase: Phase) {
+        match phase {
+            Phase::Parse => self.show("Checking dependency rules..."),
+            Phase::Violations => self.show("Detecting circular dependencies..."),
+            Phase::Cycles => self.show("Calculating metrics..."),
+            Phase::Metrics => self.show(""),
+            _ => {}
+        }
+    }
+}
+
+/// `--profile`用に段階ごとの所要時間とファイル数を記録する
+pub struct Profiler {
+    started: Instant,
+    marks: Mutex<Vec<(Phase, Instant)>>,
+    discovered: AtomicUsize,
+    parsed: AtomicUsize,
+    skipped: AtomicUsize,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            marks: Mutex::new(Vec::new()),
+            discovered: AtomicUsize::new(0),
+            parsed: AtomicUsize::new(0),
+            skipped: AtomicUsize::new(0),
+        }
+    }
+}
+
+/// 読み込めずに解析から外したファイルを数える（`--strict`の判定に使う）
+#[derive(Default)]
+pub struct SkippedFiles(AtomicUsize);
+
+impl SkippedFiles {
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl ProgressHandler for SkippedFiles {
+    fn on_file_skipped(&self, _reason: &AnalyzerError) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// 分類があいまいなモジュールを集める（`--strict-classification`の判定に使う）
+#[derive(Default)]
+pub struct AmbiguousClassifications(Mutex<Vec<(PathBuf, String, String)>>);
+
+impl AmbiguousClassifications {
+    /// 見つかったモジュールを標準エラー出力に表示し、その数を返す
+    pub fn report(&self) -> usize {
+        let mut modules = self.0.lock().unwrap();
+        modules.sort();
+        for (path, name, issue) in modules.iter() {
+            eprintln!("{} {} ({}): {}", "ambiguous classification:".red().bold(), name, path.display(), issue);
+        }
+        modules.len()
+    }
+}
+
+impl ProgressHandler for AmbiguousClassifications {
+    fn on_module_parsed(&self, module: &ModuleInfo) {
+        // テスト用のモジュールは別に一覧するので分類しなくてよい
+        if
+            !module.test &&
+            let Some(issue) = module.classification_issue()
+        {
+            self.0
+                .lock()
+                .unwrap()
+                .push((module.path.clone(), module.name.to_string(), issue));
+        }
+    }
+}
+
+impl ProgressHandler for Profiler {
+    fn on_file_discovered(&self, _path: &Path) {
+        self.discovered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_module_parsed(&self, _module: &ModuleInfo) {
+        self.parsed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_file_skipped(&self, _reason: &AnalyzerError) {
+        self.skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_phase_complete(&self, phase: Phase) {
+        self.marks
+            .lock()
+            .unwrap()
+            .push((phase, Instant::now()));
+    }
+}
+
+impl Profiler {
+    /// 段階ごとの所要時間を標準エラー出力に表示する（最後の段階から現在までを出力の時間とする）
+    pub fn print(&self) {
+        let marks = self.marks.lock().unwrap();
+        let now = Instant::now();
+        let mut previous = self.started;
+        let mut rows = Vec::new();
+        for (phase, at) in marks.iter() {
+            let label = match phase {
+                Phase::Discover => "Discovery",
+                Phase::Parse => "Parsing",
+                Phase::Violations => "Dependency rules",
+                Phase::Graph => "Graph build",
+                Phase::Cycles => "Cycle detection",
+                Phase::Metrics => "Metrics",
+                _ => "Other",
+            };
+            rows.push((label, *at - previous));
+            previous = *at;
+        }
+        rows.push(("Rendering", now - previous));
+
+        eprintln!();
+        eprintln!("{}", "=== Profile ===".bold().cyan());
+        for (label, elapsed) in rows {
+            eprintln!("  {:<18} {:>10.1} ms", label, elapsed.as_secs_f64() * 1000.0);
+        }
+        eprintln!("  {:<18} {:>10.1} ms", "Total".bold(), (now - self.started).as_secs_f64() * 1000.0);
+        eprintln!(
+            "  Files scanned: {}, parsed: {}, skipped: {}",
+            self.discovered.load(Ordering::Relaxed),
+            self.parsed.load(Ordering::Relaxed),
+            self.skipped.load(Ordering::Relaxed)
+        );
+    }
+}
+
+fn format_chain(graph: &ModuleGraph, chain: &[&str]) -> String {
+    let mut text = chain[0].to_string();
+    for edge in chain.windows(2) {
+        match graph.edge_kind(edge[0], edge[1]) {
+            Some(EdgeKind::Lazy) => text.push_str(&format!(" {} ", "-(lazy)->".yellow())),
+            _ => text.push_str(" -> "),
+        }
+        text.push_str(edge[1]);
+    }
+    text
+}
+
+pub fn print_reachability(graph: &ModuleGraph, from: &str, to: &str) {
+    let paths = graph.shortest_paths(from, to);
+    if paths.is_empty() {
+        match Lang::current() {
+            Lang::En => println!("{} is {} reachable from {}", to.bold(), "not".green(), from.bold()),
+            Lang::Ja => println!("{}は{}から{}", to.bold(), from.bold(), "到達できません".green()),
+        }
+        return;
+    }
+
+    match Lang::current() {
+        Lang::En => println!("{} is reachable from {}", to.bold(), from.bold()),
+        Lang::Ja => println!("{}は{}から到達できます", to.bold(), from.bold()),
+    }
+    for chain in &paths {
+        println!("  {}", format_chain(graph, chain));
+    }
+    println!();
+
+    let eager_paths = graph.eager_shortest_paths(from, to);
+    if eager_paths.is_empty() {
+        let text = match Lang::current() {
+            Lang::En => format!("Only reachable through lazy routes: {} is not loaded eagerly with {}", to, from),
+            Lang::Ja => format!("遅延ロードのルート経由でのみ到達できます: {}は{}と一緒には読み込まれません", to, from),
+        };
+        println!("{}", text.green());
+    } else {
+        let mark = glyph("⚠️ ", "[!]");
+        let text = match Lang::current() {
+            Lang::En => format!("{} Eagerly reachable: {} is loaded together with {} through", mark, to, from),
+            Lang::Ja => format!("{} 即時に到達できます: {}は次の経路で{}と一緒に読み込まれます", mark, to, from),
+        };
+        println!("{}", text.yellow());
+        for chain in &eager_paths {
+            println!("  {}", format_chain(graph, chain));
+        }
+    }
+}
+
+/// 影響を受けるモジュールの、変更されたモジュールからの距離の表示
+fn depth_label(depth: usize) -> String {
+    match (Lang::current(), depth) {
+        (Lang::En, 0) => "changed".to_string(),
+        (Lang::En, 1) => "direct".to_string(),
+        (Lang::En, _) => format!("depth {}", depth),
+        (Lang::Ja, 0) => "変更".to_string(),
+        (Lang::Ja, 1) => "直接".to_string(),
+        (Lang::Ja, _) => format!("深さ{}", depth),
+    }
+}
+
+pub fn print_impact(module: &str, dependents: &[(&str, usize)]) {
+    let title = match Lang::current() {
+        Lang::En => format!("=== Impact of changing {} ===", module),
+        Lang::Ja => format!("=== {}を変更した場合の影響 ===", module),
+    };
+    println!("{}", title.bold().cyan());
+    println!();
+
+    if dependents.is_empty() {
+        println!("{}", tr("No modules depend on this module.", "このモジュールに依存しているモジュールはありません").green());
+        return;
+    }
+
+    let direct = dependents
+        .iter()
+        .filter(|(_, depth)| *depth == 1)
+        .count();
+    match Lang::current() {
+        Lang::En =>
+            println!(
+                "Affected Modules: {} ({} direct, {} transitive)",
+                dependents.len(),
+                direct,
+                dependents.len() - direct
+            ),
+        Lang::Ja =>
+            println!("影響を受けるモジュール: {}（直接{}、推移的{}）", dependents.len(), direct, dependents.len() - direct),
+    }
+    println!();
+
+    for (name, depth) in dependents {
+        println!("  {}{} ({})", "  ".repeat(depth - 1), name, depth_label(*depth).dimmed());
+    }
+}
+
+/// 変更されたファイルの影響範囲を表示する
+pub fn print_change_impact(report: &ImpactReport) {
+    println!("{}", format!("=== {} ===", tr("Impact of the changed files", "変更されたファイルの影響")).bold().cyan());
+    println!();
+
+    for file in &report.changed_files {
+        match &file.module {
+            Some(module) => println!("  {} {}", file.file.display(), format!("({})", module).dimmed()),
+            None => println!("  {} {}", file.file.display(), tr("(no module)", "（モジュールなし）").dimmed()),
+        }
+    }
+    println!();
+
+    if report.affected.is_empty() {
+        println!(
+            "{}",
+            tr("No module is affected by these changes.", "これらの変更の影響を受けるモジュールはありません").green()
+        );
+        return;
+    }
+    match Lang::current() {
+        Lang::En => println!("Affected Modules: {} ({} changed)", report.affected.len(), report.changed_modules.len()),
+        Lang::Ja => println!("影響を受けるモジュール: {}（変更{}）", report.affected.len(), report.changed_modules.len()),
+    }
+    for affected in &report.affected {
+        println!("  {}{} ({})", "  ".repeat(affected.depth), affected.module, depth_label(affected.depth).dimmed());
+    }
+
+    println!();
+    if report.initial_chunk {
+        match Lang::current() {
+            Lang::En => println!("{} Modules loaded at startup by {} changed", mark_warning(), report.root),
+            Lang::Ja => println!("{} {}が起動時に読み込むモジュールが変更されています", mark_warning(), report.root),
+        }
+    }
+    if report.lazy_chunks.is_empty() {
+        println!("{}", tr("No lazy-loaded chunk is affected", "影響を受ける遅延ロードのチャンクはありません"));
+    } else {
+        println!("{}", tr("Affected lazy-loaded chunks:", "影響を受ける遅延ロードのチャンク:"));
+        for chunk in &report.lazy_chunks {
+            let routes = if chunk.routes.is_empty() { String::new() } else { format!(" {}", chunk.routes.join(", ")) };
+            println!("  {}{}", chunk.module, routes.cyan());
+        }
+    }
+}
+
+pub fn print_cycles(result: &AnalysisResult) {
+    println!("{}", format!("=== {} ===", tr("Circular Dependencies", "循環依存")).bold().cyan());
+    println!();
+
+    if result.circular_dependencies.is_empty() {
+        println!("{}", heading("✅", tr("No circular dependencies found!", "循環依存はありません")).green());
+        return;
+    }
+
+    let graph = result.graph();
+    let breaking_edges: HashSet<(&str, &str)> = graph.feedback_arc_set().into_iter().collect();
+    for (i, cycle) in result.circular_dependencies.iter().enumerate() {
+        let mut members: Vec<&str> = cycle.iter().map(|name| name.as_ref()).collect();
+        members.push(&cycle[0]);
+        println!("{} {}", format!("{} {}:", tr("Cycle", "循環"), i + 1).bold().red(), members.join(" -> "));
+
+        let locations = result.circular_dependency_locations.get(i);
+        for (j, edge) in members.windows(2).enumerate() {
+            let (from, to) = (edge[0], edge[1]);
+            let marker = if breaking_edges.contains(&(from, to)) {
+                format!(" {}", glyph("✂", "[cut]")).yellow()
+            } else {
+                "".normal()
+            };
+            match locations.and_then(|locations| locations.get(j)).and_then(Option::as_ref) {
+                Some(location) => {
+                    println!("  {}{}", location, marker);
+                    if let Some(text) = source_line(location) {
+                        println!("      {}", text.dimmed());
+                    }
+                }
+                None => println!("  {} -> {}{}", from, to, marker),
+            }
+        }
+        println!();
+    }
+
+    if result.cycles_truncated {
+        println!("{}", truncation_note(result).yellow());
+        println!();
+    }
+
+    println!(
+        "{}",
+        heading("✂", tr("Suggested edges to remove (breaks all cycles)", "削除を提案する依存（すべての循環を解消する）"))
+            .bold()
+            .yellow()
+    );
+    let mut edges: Vec<&(&str, &str)> = breaking_edges.iter().collect();
+    edges.sort();
+    for (from, to) in edges {
+        println!("  {} -> {}", from, to);
+    }
+}
+
+fn truncation_note(result: &AnalysisResult) -> String {
+    let shown = result.circular_dependencies.len();
+    match Lang::current() {
+        Lang::En =>
+            format!(
+                "Showing the first {} cycles; more exist (raise [analysis] cycle_limit or max_cycle_length to list them)",
+                shown
+            ),
+        Lang::Ja =>
+            format!(
+                "最初の{}件の循環だけを表示しています（すべて表示するには[analysis]のcycle_limitかmax_cycle_lengthを増やしてください）",
+                shown
+            ),
+    }
+}
+
+/// 参照箇所の行をファイルから読み出す
+fn source_line(location: &SourceLocation) -> Option<String> {
+    let content = source::read_to_string(&location.file).ok()?;
+    source
+        ::lines(&content)
+        .nth(location.line - 1)
+        .map(|line| line.trim().to_string())
+}
+
+/// コンソールのレポートの表示方法（`--max-items`・`--full`・`--pager`）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReportView {
+    /// セクションごとに表示する項目の上限（`None`なら全件）
+    pub max_items: Option<usize>,
+    /// 標準出力が端末ならページャーで表示する
+    pub pager: bool,
+    /// 違反・モジュールなどを表にする（`--format table`、`false`なら`--format plain`の一覧）
+    pub table: bool,
+}
+
+impl ReportView {
+    /// セクションの項目のうち表示するもの
+    fn limit<'a, T>(&self, items: &'a [T]) -> &'a [T] {
+        &items[..self.max_items.unwrap_or(items.len()).min(items.len())]
+    }
+
+    /// 表示しきれなかった項目の件数の行（すべて表示した場合は空）
+    fn more(&self, indent: &str, total: usize) -> String {
+        let more = total - self.max_items.unwrap_or(total).min(total);
+        if more == 0 {
+            return String::new();
+        }
+        let text = match Lang::current() {
+            Lang::En => format!("{} and {} more (--full to show all)", glyph("…", "..."), more),
+            Lang::Ja => format!("{}ほか{}件（すべて表示するには--full）", glyph("…", "..."), more),
+        };
+        format!("{}{}\n", indent, text.dimmed())
+    }
+}
+
+/// `view.pager`で標準出力が端末なら`$PAGER`（未設定なら`less -FRX`）に渡し、それ以外はそのまま表示する
+pub fn page(text: &str, view: &ReportView) {
+    if view.pager && std::io::stdout().is_terminal() {
+        let command = std::env
+            ::var("PAGER")
+            .ok()
+            .filter(|pager| !pager.trim().is_empty())
+            .unwrap_or_else(|| "less -FRX".to_string());
+        let mut parts = command.split_whitespace();
+        let pager = parts
+            .next()
+            .and_then(|program| Command::new(program).args(parts).stdin(Stdio::piped()).spawn().ok());
+        // ページャーを起動できなければそのまま表示する
+        if let Some(mut pager) = pager {
+            if let Some(mut stdin) = pager.stdin.take() {
+                // ページャーを途中で閉じた場合の書き込みエラーは無視する
+                let _ = stdin.write_all(text.as_bytes());
+            }
+            let _ = pager.wait();
+            return;
+        }
+    }
+    print!("{}", text);
+}
+
+pub fn print_analysis_result(result: &AnalysisResult, view: &ReportView) {
+    page(&render_analysis_result(result, view), view);
+}
+
+/// 解析結果のコンソール向けのレポート（各セクションは`view.max_items`件まで）
+fn render_analysis_result(result: &AnalysisResult, view: &ReportView) -> String {
+    let title = format!("=== {} ===", tr("Angular Module Analysis Report", "Angularモジュール解析レポート"));
+    let mut out = format!("{}\n\n", title.bold().cyan());
+
+    // メトリクス表示
+    out.push_str(&format!("{}\n", heading("📊", tr("Architecture Metrics", "アーキテクチャメトリクス")).bold().green()));
+    let metrics = &result.metrics;
+    out.push_str(&format!("{}: {}\n", tr("Total Modules", "モジュール数"), metrics.total_modules));
+    out.push_str(&format!("{}: {}\n", tr("Core Modules", "Coreモジュール"), metrics.core_modules));
+    out.push_str(&format!("{}: {}\n", tr("Shared Modules", "Sharedモジュール"), metrics.shared_modules));
+    out.push_str(&format!("{}: {}\n", tr("Feature Modules", "Featureモジュール"), metrics.feature_modules));
+    out.push_str(
+        &format!(
+            "{}: {:.2}\n",
+            tr("Average Dependencies per Module", "モジュールあたりの平均依存数"),
+            metrics.average_dependencies_per_module
+        )
+    );
+    out.push_str(&format!("{}: {:.2}\n", tr("Coupling Factor", "結合度"), metrics.coupling_factor));
+    out.push_str(&format!("{}: {:.1}\n\n", tr("Health Score", "ヘルススコア"), metrics.health_score));
+
+    // 依存関係違反
+    if !result.dependency_violations.is_empty() {
+        out.push_str(&format!("{}\n", heading("⚠️ ", tr("Dependency Violations", "依存関係の違反")).bold().red()));
+        if view.table {
+            let mut table = Table::new([
+                (tr("FROM", "依存元"), Align::Left),
+                (tr("TO", "依存先"), Align::Left),
+                (tr("VIOLATION", "違反"), Align::Left),
+                (tr("LOCATION", "場所"), Align::Left),
+            ]);
+            for violation in view.limit(&result.dependency_violations) {
+                table.row(
+                    vec![
+                        violation.from_module.to_string(),
+                        violation.to_module.to_string(),
+                        violation.localized_description().to_string(),
+                        violation.location.as_ref().map_or_else(String::new, |location| location.to_string())
+                    ]
+                );
+            }
+            out.push_str(&indented_table(&table));
+        } else {
+            for violation in view.limit(&result.dependency_violations) {
+                out.push_str(
+                    &format!(
+                        "  {} -> {}: {}\n",
+                        violation.from_module.red(),
+                        violation.to_module.red(),
+                        violation.localized_description()
+                    )
+                );
+                if let Some(location) = &violation.location {
+                    out.push_str(&format!("      {} {}\n", tr("at", "場所:"), location.to_string().dimmed()));
+                }
+            }
+        }
+        out.push_str(&view.more("  ", result.dependency_violations.len()));
+        out.push('\n');
+    }
+
+    // 循環依存
+    if !result.circular_dependencies.is_empty() {
+        out.push_str(&format!("{}\n", heading("🔄", tr("Circular Dependencies", "循環依存")).bold().red()));
+        for cycle in view.limit(&result.circular_dependencies) {
+            out.push_str(&format!("  {} -> {}\n", cycle.join(" -> ").red(), cycle[0].red()));
+        }
+        out.push_str(&view.more("  ", result.circular_dependencies.len()));
+        if result.cycles_truncated {
+            out.push_str(&format!("{}\n", truncation_note(result).yellow()));
+        }
+        out.push('\n');
+    }
+
+    // プラグインが報告した問題
+    if !result.plugin_findings.is_empty() {
+        out.push_str(&format!("{}\n", heading("🧩", tr("Plugin Findings", "プラグインが報告した問題")).bold().red()));
+        if view.table {
+            let mut table = Table::new([
+                (tr("LEVEL", "レベル"), Align::Left),
+                (tr("RULE", "ルール"), Align::Left),
+                (tr("MODULE", "モジュール"), Align::Left),
+                (tr("MESSAGE", "内容"), Align::Left),
+            ]);
+            for finding in view.limit(&result.plugin_findings) {
+                let level = match finding.severity {
+                    RuleLevel::Error => "error",
+                    _ => "warn",
+                };
+                let message = match &finding.location {
+                    Some(location) => format!("{} ({})", finding.message, location),
+                    None => finding.message.to_string(),
+                };
+                table.row(vec![level.to_string(), finding.rule.to_string(), finding.module.to_string(), message]);
+            }
+            out.push_str(&indented_table(&table));
+        } else {
+            for finding in view.limit(&result.plugin_findings) {
+                let level = match finding.severity {
+                    RuleLevel::Error => "error".red(),
+                    _ => "warn".yellow(),
+                };
+                out.push_str(
+                    &format!("  [{}] {} {}: {}\n", level, finding.rule.bold(), finding.module, finding.message)
+                );
+                if let Some(location) = &finding.location {
+                    out.push_str(&format!("      {} {}\n", tr("at", "場所:"), location.to_string().dimmed()));
+                }
+            }
+        }
+        out.push_str(&view.more("  ", result.plugin_findings.len()));
+        out.push('\n');
+    }
+
+    // 読み込めずに結果から除いたファイル
+    if !result.warnings.is_empty() {
+        out.push_str(
+            &format!("{}\n", heading("⚠️ ", tr("Files Not Analyzed", "解析できなかったファイル")).bold().yellow())
+        );
+        if view.table {
+            let mut table = Table::new([(tr("FILE", "ファイル"), Align::Left), (tr("CAUSE", "原因"), Align::Left)]);
+            for warning in view.limit(&result.warnings) {
+                table.row(vec![warning.file.display().to_string(), warning.cause.to_string()]);
+            }
+            out.push_str(&indented_table(&table));
+        } else {
+            for warning in view.limit(&result.warnings) {
+                out.push_str(&format!("  {}: {}\n", warning.file.display(), warning.cause));
+            }
+        }
+        out.push_str(&view.more("  ", result.warnings.len()));
+        out.push('\n');
+    }
+
+    // 同じクラス名のモジュール（修飾した名前で区別している）
+    if !result.name_collisions.is_empty() {
+        out.push_str(
+            &format!("{}\n", heading("⚠️ ", tr("Duplicate Module Names", "重複したモジュール名")).bold().yellow())
+        );
+        for collision in view.limit(&result.name_collisions) {
+            out.push_str(&format!("  {}:\n", collision.name));
+            for module in &collision.modules {
+                out.push_str(&format!("    - {}\n", module));
+            }
+        }
+        out.push_str(&view.more("  ", result.name_collisions.len()));
+        out.push('\n');
+    }
+
+    // 違反を解消するための提案
+    if !result.suggestions.is_empty() {
+        out.push_str(
+            &format!("{}\n", heading("💡", tr("Suggested Refactorings", "リファクタリングの提案")).bold().yellow())
+        );
+        if view.table {
+            let mut table = Table::new([
+                (tr("FROM", "依存元"), Align::Left),
+                (tr("TO", "依存先"), Align::Left),
+                (tr("SUGGESTION", "提案"), Align::Left),
+            ]);
+            for suggestion in view.limit(&result.suggestions) {
+                table.row(
+                    vec![
+                        suggestion.from_module.to_string(),
+                        suggestion.to_module.to_string(),
+                        suggestion.refactoring.localized()
+                    ]
+                );
+            }
+            out.push_str(&indented_table(&table));
+        } else {
+            for suggestion in view.limit(&result.suggestions) {
+                let description = suggestion.refactoring.localized();
+                out.push_str(&format!("  {} -> {}: {}\n", suggestion.from_module, suggestion.to_module, description));
+            }
+        }
+        out.push_str(&view.more("  ", result.suggestions.len()));
+        out.push('\n');
+    }
+
+    // モジュール一覧
+    if view.table {
+        let rows = list::module_rows(result, None);
+        let (tests, mut modules): (Vec<&ModuleRow>, Vec<&ModuleRow>) = rows.iter().partition(|row| row.module.test);
+        // 種類ごとにまとめる（同じ種類の中は解析した順）
+        modules.sort_by(|a, b| a.module.module_type.cmp(&b.module.module_type));
+        out.push_str(&format!("{}\n", heading("📦", tr("Modules", "モジュール")).bold().blue()));
+        out.push_str(&module_table(&modules, view));
+        out.push('\n');
+        if !tests.is_empty() {
+            out.push_str(&format!("{}\n", heading("🧪", tr("Test Modules", "テスト用モジュール")).bold().blue()));
+            out.push_str(&module_table(&tests, view));
+            out.push('\n');
+        }
+    } else {
+        out.push_str(&format!("{}\n", heading("📦", tr("Modules by Type", "種類別のモジュール")).bold().blue()));
+
+        let mut modules_by_type: BTreeMap<&ModuleType, Vec<&ModuleInfo>> = BTreeMap::new();
+        for module in result.modules.iter().filter(|m| !m.test) {
+            modules_by_type.entry(&module.module_type).or_default().push(module);
+        }
+
+        for (module_type, modules) in modules_by_type {
+            out.push_str(&format!("  {}:\n", module_type.to_string().bold()));
+            for module in view.limit(&modules) {
+                out.push_str(&module_line(module));
+            }
+            out.push_str(&view.more("    ", modules.len()));
+            out.push('\n');
+        }
+
+        // `--include-tests`で解析したテスト用モジュール
+        let test_modules: Vec<&ModuleInfo> = result.modules
+            .iter()
+            .filter(|m| m.test)
+            .collect();
+        if !test_modules.is_empty() {
+            out.push_str(&format!("{}\n", heading("🧪", tr("Test Modules", "テスト用モジュール")).bold().blue()));
+            for module in view.limit(&test_modules) {
+                out.push_str(&module_line(module));
+            }
+            out.push_str(&view.more("    ", test_modules.len()));
+            out.push('\n');
+        }
+    }
+
+    if result.dependency_violations.is_empty() {
+        let message = heading("✅", tr("No dependency violations found!", "依存関係の違反はありません"));
+        out.push_str(&format!("{}\n", message.green()));
+    }
+    out
+}
+
+/// モジュール・種類・依存数・被依存数・違反数の表（`view.max_items`件まで）
+fn module_table(rows: &[&ModuleRow], view: &ReportView) -> String {
+    let mut table = Table::new([
+        (tr("MODULE", "モジュール"), Align::Left),
+        (tr("TYPE", "種類"), Align::Left),
+        (tr("DEPS", "依存"), Align::Right),
+        (tr("FAN-IN", "被依存"), Align::Right),
+        (tr("VIOLATIONS", "違反"), Align::Right),
+    ]);
+    for row in view.limit(rows) {
+        table.row(
+            vec![
+                row.module.name.to_string(),
+                row.module.module_type.to_string(),
+                row.module.internal_dependencies().count().to_string(),
+                row.fan_in.to_string(),
+                row.violations.to_string()
+            ]
+        );
+    }
+    let mut out = indented_table(&table);
+    out.push_str(&view.more("  ", rows.len()));
+    out
+}
+
+/// 2文字字下げし、見出しを太字にした表
+fn indented_table(table: &Table) -> String {
+    let (header, rows) = table.lines();
+    let mut out = format!("  {}\n", header.bold());
+    for row in rows {
+        out.push_str(&format!("  {}\n", row));
+    }
+    out
+}
+
+fn module_line(module: &ModuleInfo) -> String {
+    let dependencies = module.internal_dependencies().count();
+    match Lang::current() {
+        Lang::En => format!("    - {} ({} dependencies)\n", module.name, dependencies),
+        Lang::Ja => format!("    - {}（依存 {}）\n", module.name, dependencies),
+    }
+}
+
+/// ゲートごとの結果を表示し、すべて合格したかを返す
+pub fn print_outcomes(outcomes: &[GateOutcome]) -> bool {
+    println!("{}", format!("=== {} ===", tr("Architecture Quality Gates", "アーキテクチャの品質ゲート")).bold().cyan());
+    println!();
+
+    // フィットネス関数の名前は長いことがあるので、最も長い名前に揃える
+    let width = outcomes
+        .iter()
+        .map(|o| o.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(22);
+    for outcome in outcomes {
+        let status = if outcome.passed { "PASS".green().bold() } else { "FAIL".red().bold() };
+        println!("  {}  {:<width$} {}", status, outcome.name, outcome.detail, width = width);
+    }
+    println!();
+
+    let failed = outcomes
+        .iter()
+        .filter(|o| !o.passed)
+        .count();
+    let summary = match (Lang::current(), failed) {
+        (Lang::En, 0) => format!("Result: PASS ({} gates)", outcomes.len()),
+        (Lang::En, _) => format!("Result: FAIL ({} of {} gates failed)", failed, outcomes.len()),
+        (Lang::Ja, 0) => format!("結果: PASS（{}件のゲート）", outcomes.len()),
+        (Lang::Ja, _) => format!("結果: FAIL（{}件中{}件のゲートが不合格）", outcomes.len(), failed),
+    };
+    if failed == 0 {
+        println!("{}", summary.green().bold());
+    } else {
+        println!("{}", summary.red().bold());
+    }
+    failed == 0
+}
+
+pub fn print_cache_stats(stats: &CacheStats) {
+    let status = if stats.up_to_date {
+        tr("up to date", "最新").green()
+    } else {
+        tr("stale (rebuilt on the next --cache run)", "古い（次に--cacheで実行したときに作り直す）").yellow()
+    };
+    let version = if stats.version.is_empty() { tr("unknown", "不明") } else { &stats.version };
+    let rows = [
+        (tr("Cache file", "キャッシュファイル"), stats.file.display().to_string()),
+        (tr("Modules", "モジュール数"), stats.entries.to_string()),
+        (tr("Size", "サイズ"), format!("{:.1} KiB", (stats.size as f64) / 1024.0)),
+        (tr("Version", "バージョン"), version.to_string()),
+        (tr("Status", "状態"), status.to_string()),
+    ];
+    // 見出しの幅は言語で変わるため、表示幅で揃える
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.width())
+        .max()
+        .unwrap_or(0);
+    for (label, value) in &rows {
+        println!("{}:{} {}", label, " ".repeat(width - label.width()), value);
+    }
+}
+
+/// 診断結果を表示し、エラーがなければtrueを返す
+pub fn print_diagnoses(diagnoses: &[Diagnosis]) -> bool {
+    for diagnosis in diagnoses {
+        let marker = match diagnosis.severity {
+            Severity::Ok => mark_ok(),
+            Severity::Warning => mark_warning(),
+            Severity::Error => mark_error(),
+        };
+        println!("{} {}", marker, diagnosis.message);
+    }
+    !diagnoses.iter().any(|d| d.severity == Severity::Error)
+}
+
+/// compodocとの照合結果を表示する
+pub fn print_reconciliation(reconciliation: &Reconciliation) {
+    match Lang::current() {
+        Lang::En => println!("{} modules found by both the analyzer and compodoc", reconciliation.matched),
+        Lang::Ja => println!("解析器とcompodocの両方が認識したモジュール: {}件", reconciliation.matched),
+    }
+
+    if !reconciliation.missing_modules.is_empty() {
+        println!(
+            "\n{}",
+            tr("Only in compodoc (missed by the analyzer):", "compodocだけが認識したモジュール（解析器の抽出漏れ）:").red().bold()
+        );
+        for module in &reconciliation.missing_modules {
+            println!("  {} {}", module.name, module.file.display().to_string().dimmed());
+        }
+    }
+    if !reconciliation.undocumented_modules.is_empty() {
+        println!(
+            "\n{}",
+            tr("Only in the analyzer (unknown to compodoc):", "解析器だけが認識したモジュール（compodocにはない）:").yellow().bold()
+        );
+        for module in &reconciliation.undocumented_modules {
+            println!("  {} {}", module.name, module.file.display().to_string().dimmed());
+        }
+    }
+    if !reconciliation.mismatches.is_empty() {
+        println!("\n{}", tr("Metadata differences:", "メタデータの食い違い:").yellow().bold());
+        for mismatch in &reconciliation.mismatches {
+            println!("  {} {}", mismatch.module.bold(), mismatch.field);
+            for name in &mismatch.missing {
+                println!("    {} {} {}", "-".red(), name, tr("(only in compodoc)", "（compodocだけ）"));
+            }
+            for name in &mismatch.extra {
+                println!("    {} {} {}", "+".green(), name, tr("(only in the analyzer)", "（解析器だけ）"));
+            }
+        }
+    }
+
+    if reconciliation.is_consistent() {
+        println!("{} {}", mark_ok(), tr("The analyzer and compodoc agree", "解析器とcompodocの結果は一致しています"));
+    }
+}
+
+/// 提供元ごとのサービスと、それを注入しているクラスを木構造で表示する
+pub fn print_service_tree(tree: &ServiceTree) {
+    for (provider, services) in tree.by_provider() {
+        println!("{}", provider.to_string().bold());
+        for (i, service) in services.iter().enumerate() {
+            let last_service = i + 1 == services.len();
+            let module = service.module
+                .as_ref()
+                .map(|module| format!(" ({})", module))
+                .unwrap_or_default();
+            let branch = if last_service { glyph("└─", "`-") } else { glyph("├─", "|-") };
+            println!("{} {}{}", branch, service.name.cyan(), module.dimmed());
+            let indent = if last_service { "   " } else { glyph("│  ", "|  ") };
+            for (j, injection) in service.injected_by.iter().enumerate() {
+                let branch = if j + 1 == service.injected_by.len() { glyph("└─", "`-") } else { glyph("├─", "|-") };
+                let module = injection.module
+                    .as_ref()
+                    .map(|module| format!(" ({})", module))
+                    .unwrap_or_default();
+                if injection.crosses_feature {
+                    println!(
+                        "{}{} {} {}{} {}",
+                        indent,
+                        branch,
+                        mark_warning(),
+                        injection.class.yellow(),
+                        module.yellow(),
+                        tr("crosses a feature boundary", "Featureの境界をまたいでいる").yellow()
+                    );
+                } else {
+                    println!("{}{} {}{}", indent, branch, injection.class, module.dimmed());
+                }
+            }
+        }
+        println!();
+    }
+
+    let crossings = tree.cross_feature_injections();
+    match (Lang::current(), crossings) {
+        (Lang::En, 0) => println!("{} No service is injected across a feature boundary", mark_ok()),
+        (Lang::En, _) => println!("{} {} injections cross a feature boundary", mark_warning(), crossings),
+        (Lang::Ja, 0) => println!("{} Featureの境界をまたいで注入されているサービスはありません", mark_ok()),
+        (Lang::Ja, _) => println!("{} {}件の注入がFeatureの境界をまたいでいます", mark_warning(), crossings),
+    }
+}
+
+/// トークンごとの登録場所を表示する（問題のあるトークンには警告を付ける）
+pub fn print_provider_scopes(scopes: &ProviderScopes, project_path: &Path) {
+    for token in &scopes.tokens {
+        let name = if token.conflicts.is_empty() { token.token.cyan() } else { token.token.yellow() };
+        println!("{}", name.bold());
+        for registration in &token.registrations {
+            let file = registration.location.file.strip_prefix(project_path).unwrap_or(&registration.location.file);
+            let multi = if registration.multi { " multi" } else { "" };
+            println!(
+                "  {}{} {}",
+                registration.scope,
+                multi.dimmed(),
+                format!("{}:{}", file.display(), registration.location.line).dimmed()
+            );
+        }
+        for conflict in &token.conflicts {
+            println!("  {} {}", mark_warning(), conflict.description().yellow());
+        }
+    }
+
+    let conflicting = scopes.conflicting().count();
+    match (Lang::current(), conflicting) {
+        (Lang::En, 0) => println!("{} No token is provided in conflicting scopes", mark_ok()),
+        (Lang::En, _) => println!("{} {} tokens are provided in conflicting scopes", mark_warning(), conflicting),
+        (Lang::Ja, 0) => println!("{} 食い違うスコープで提供されているトークンはありません", mark_ok()),
+        (Lang::Ja, _) => println!("{} {}件のトークンが食い違うスコープで提供されています", mark_warning(), conflicting),
+    }
+}
+
+/// マルチプロバイダーをトークンごとに登録される順で表示する（働かない登録には警告を付ける）
+pub fn print_multi_providers(providers: &MultiProviders, project_path: &Path) {
+    if providers.tokens.is_empty() {
+        println!("{} {}", mark_ok(), tr("No multi-providers found", "マルチプロバイダーはありません"));
+        return;
+    }
+
+    for token in &providers.tokens {
+        println!("{}", token.token.cyan().bold());
+        for registration in &token.registrations {
+            let order = registration.order.map_or_else(|| " -".to_string(), |order| format!("{:>2}.", order));
+            let using = registration.using.as_deref().unwrap_or(tr("(inline)", "（インライン）"));
+            let file = registration.location.file.strip_prefix(project_path).unwrap_or(&registration.location.file);
+            let location = format!("{}:{}", file.display(), registration.location.line);
+            match Lang::current() {
+                Lang::En => println!("  {} {} in {} {}", order, using, registration.scope, location.dimmed()),
+                Lang::Ja => println!("  {} {}（{}） {}", order, using, registration.scope, location.dimmed()),
+            }
+            if let Some(issue) = registration.issue {
+                println!("      {} {}", mark_warning(), issue.description().yellow());
+            }
+        }
+    }
+
+    let issues = providers.issues();
+    match (Lang::current(), issues) {
+        (Lang::En, 0) => println!("{} Every multi-provider is registered in the root injector", mark_ok()),
+        (Lang::En, _) =>
+            println!(
+                "{} {} multi-providers are registered where the root injector cannot see them",
+                mark_warning(),
+                issues
+            ),
+        (Lang::Ja, 0) => println!("{} すべてのマルチプロバイダーがルートインジェクターに登録されています", mark_ok()),
+        (Lang::Ja, _) =>
+            println!("{} {}件のマルチプロバイダーがルートインジェクターから見えない場所に登録されています", mark_warning(), issues),
+    }
+}
+
+/// パッケージごとのモジュールと、パッケージをまたぐ依存・エントリーポイントの迂回を表示する
+pub fn print_workspace_report(report: &WorkspaceReport) {
+    match Lang::current() {
+        Lang::En => println!("{} workspace at {}", report.manager.label(), report.root.display()),
+        Lang::Ja => println!("{}の{}ワークスペース", report.root.display(), report.manager.label()),
+    }
+    let package_label = |package: &Option<String>| {
+        package
+            .as_deref()
+            .unwrap_or(tr("(no package)", "（パッケージなし）"))
+            .to_string()
+    };
+
+    println!("\n{}", tr("Packages:", "パッケージ:").bold());
+    for package in &report.packages {
+        let names: Vec<&str> = package.modules
+            .iter()
+            .map(|m| m.as_ref())
+            .collect();
+        let count = match Lang::current() {
+            Lang::En => format!("({} modules)", names.len()),
+            Lang::Ja => format!("（{}モジュール）", names.len()),
+        };
+        println!("  {} {}", package_label(&package.package).cyan(), count.dimmed());
+        if !names.is_empty() {
+            println!("    {}", names.join(", "));
+        }
+    }
+
+    println!("\n{} {}", tr("Intra-package dependencies:", "パッケージ内の依存:").bold(), report.intra_package.len());
+    println!("{} {}", tr("Cross-package dependencies:", "パッケージをまたぐ依存:").bold(), report.cross_package.len());
+    for edge in &report.cross_package {
+        println!(
+            "  {} {} {} {} {}",
+            edge.from,
+            format!("({})", package_label(&edge.from_package)).dimmed(),
+            if edge.lazy { "-(lazy)->" } else { "->" },
+            edge.to,
+            format!("({})", package_label(&edge.to_package)).dimmed()
+        );
+    }
+
+    if report.bypasses.is_empty() {
+        println!(
+            "\n{} {}",
+            mark_ok(),
+            tr(
+                "Every cross-package import goes through a published entry point",
+                "パッケージをまたぐimportはすべて公開されたエントリーポイントを通っています"
+            )
+        );
+    } else {
+        println!(
+            "\n{}",
+            tr("Imports bypassing a package entry point:", "パッケージのエントリーポイントを迂回しているimport:").yellow().bold()
+        );
+        for bypass in &report.bypasses {
+            let location = bypass.location.as_ref().map(|l| format!("{} ", l)).unwrap_or_default();
+            match Lang::current() {
+                Lang::En =>
+                    println!(
+                        "  {} {}{} imports '{}' from inside {}",
+                        mark_warning(),
+                        location.dimmed(),
+                        bypass.module,
+                        bypass.specifier,
+                        bypass.package
+                    ),
+                Lang::Ja =>
+                    println!(
+                        "  {} {}{}が{}の内部の'{}'をimportしています",
+                        mark_warning(),
+                        location.dimmed(),
+                        bypass.module,
+                        bypass.package,
+                        bypass.specifier
+                    ),
+            }
+        }
+    }
+}
+
+/// チャンクごとのモジュールの内訳と、初期ロードに紛れ込んだ遅延モジュールを表示する
+pub fn print_chunk_report(report: &ChunkReport) {
+    for chunk in report.chunks.iter().filter(|chunk| !chunk.modules.is_empty()) {
+        let kind = if chunk.initial { tr("initial", "初期ロード").cyan() } else { tr("lazy", "遅延ロード").normal() };
+        println!("{} {} {}", chunk.chunk.bold(), kind, format_bytes(chunk.bytes).dimmed());
+        for module in &chunk.modules {
+            let name = if chunk.initial && module.behind_lazy_route {
+                module.module.to_string().yellow()
+            } else {
+                module.module.to_string().normal()
+            };
+            println!("  {:>10}  {}", format_bytes(module.bytes), name);
+        }
+    }
+
+    if report.misplaced.is_empty() {
+        println!(
+            "{} {}",
+            mark_ok(),
+            tr(
+                "No lazily routed module ends up in an initial chunk",
+                "初期ロードのチャンクに含まれている遅延ロードのモジュールはありません"
+            )
+        );
+    } else {
+        let title = match Lang::current() {
+            Lang::En => format!("Modules behind lazy routes from {} that end up in initial chunks:", report.root),
+            Lang::Ja => format!("{}から遅延ロードするはずなのに初期ロードのチャンクに含まれているモジュール:", report.root),
+        };
+        println!("\n{}", title.yellow().bold());
+        for misplaced in &report.misplaced {
+            match Lang::current() {
+                Lang::En =>
+                    println!(
+                        "  {} {} in {} ({})",
+                        mark_warning(),
+                        misplaced.module,
+                        misplaced.chunk,
+                        format_bytes(misplaced.bytes)
+                    ),
+                Lang::Ja =>
+                    println!(
+                        "  {} {}（{}、{}）",
+                        mark_warning(),
+                        misplaced.module,
+                        misplaced.chunk,
+                        format_bytes(misplaced.bytes)
+                    ),
+            }
+        }
+    }
+}
+
+/// モジュールごとの分割案を表示する
+pub fn print_split_plans(plans: &[SplitPlan], min_declarations: usize) {
+    if plans.is_empty() {
+        match Lang::current() {
+            Lang::En => println!("{} No module has {} or more declarations", mark_ok(), min_declarations),
+            Lang::Ja => println!("{} declarationsが{}件以上のモジュールはありません", mark_ok(), min_declarations),
+        }
+        return;
+    }
+
+    for plan in plans {
+        match Lang::current() {
+            Lang::En =>
+                println!(
+                    "{} ({} declarations) -> {} modules, modularity {:.2}",
+                    plan.module.bold(),
+                    plan.declarations,
+                    plan.groups.len(),
+                    plan.modularity
+                ),
+            Lang::Ja =>
+                println!(
+                    "{}（宣言{}件）-> {}モジュール、モジュラリティ{:.2}",
+                    plan.module.bold(),
+                    plan.declarations,
+                    plan.groups.len(),
+                    plan.modularity
+                ),
+        }
+        for group in &plan.groups {
+            match Lang::current() {
+                Lang::En => println!("  {} ({} declarations)", group.name.cyan().bold(), group.declarables.len()),
+                Lang::Ja => println!("  {}（宣言{}件）", group.name.cyan().bold(), group.declarables.len()),
+            }
+            println!("    declarations: {}", group.declarables.join(", "));
+            if !group.exports.is_empty() {
+                println!("    exports: {}", group.exports.join(", "));
+            }
+            if !group.imports.is_empty() {
+                println!("    imports: {}", group.imports.join(", "));
+            }
+        }
+        if !plan.unclustered.is_empty() {
+            println!(
+                "  {} {}",
+                tr("unrelated (keep or move freely):", "どれとも関係がない（残しても移してもよい）:").dimmed(),
+                plan.unclustered.join(", ")
+            );
+        }
+        if !plan.library_imports.is_empty() {
+            println!(
+                "  {} {}",
+                tr("library imports of the original module:", "元のモジュールがimportしているライブラリ:").dimmed(),
+                plan.library_imports.join(", ")
+            );
+        }
+        println!();
+    }
+}
+
+/// スタンドアロンへの移行計画を、ステップごとに作業の順で表示する
+pub fn print_migration_plan(plan: &MigrationPlan) {
+    if plan.steps.is_empty() {
+        println!("{} {}", mark_ok(), tr("No NgModules to migrate", "移行するNgModuleはありません"));
+        return;
+    }
+
+    for step in &plan.steps {
+        println!("{} {} {}", format!("{}.", step.order).bold(), step.module.bold(), step.path.display().to_string().dimmed());
+        if !step.cycle.is_empty() {
+            let cycle = step.cycle.join(", ");
+            match Lang::current() {
+                Lang::En => println!("  {} circular with {}; break the cycle first", mark_warning(), cycle),
+                Lang::Ja => println!("  {} {}と循環しています。先に循環を解消してください", mark_warning(), cycle),
+            }
+        }
+        if !step.standalone.is_empty() {
+            println!("  {} {}", tr("mark standalone:", "standaloneにする:"), step.standalone.join(", "));
+            if !step.imports.is_empty() {
+                println!("  {} {}", tr("add to their imports:", "それらのimportsに追加する:"), step.imports.join(", "));
+            }
+        }
+        for provider in &step.providers {
+            println!("  {} -> {} ({})", provider.provider, provider.replacement.cyan(), provider.target.label());
+        }
+        if step.bootstrap {
+            println!(
+                "  {}",
+                tr(
+                    "replace bootstrapModule() with bootstrapApplication() of the root component",
+                    "bootstrapModule()をルートコンポーネントのbootstrapApplication()に置き換える"
+                )
+            );
+        }
+        if step.lazy {
+            println!(
+                "  {}",
+                tr(
+                    "change its route to loadComponent, or loadChildren of exported routes",
+                    "ルートをloadComponentか、exportしたルート定義のloadChildrenに変える"
+                )
+            );
+        }
+        if !step.importers.is_empty() {
+            let importers = step.importers.join(", ");
+            let instead = step.replaced_by.join(", ");
+            match (Lang::current(), instead.is_empty()) {
+                (Lang::En, true) => println!("  in {}: import nothing instead", importers),
+                (Lang::En, false) => println!("  in {}: import {} instead", importers, instead),
+                (Lang::Ja, true) => println!("  {}: importから外す", importers),
+                (Lang::Ja, false) => println!("  {}: 代わりに{}をimportする", importers, instead),
+            }
+        }
+        println!("  {} {}", tr("delete", "削除").red(), step.module);
+        println!();
+    }
+}
+
+/// まとめてよいモジュールの集まりを、減らせるimportの多い順に表示する
+pub fn print_merge_candidates(candidates: &[MergeCandidate]) {
+    if candidates.is_empty() {
+        println!("{} No tiny modules that always load together", mark_ok());
+        return;
+    }
+
+    for candidate in candidates {
+        println!(
+            "{} <- {} ({} declarations)",
+            candidate.into.bold(),
+            candidate.modules[1..].join(", "),
+            candidate.declarations
+        );
+        let mut saving = format!("{} imports between them", candidate.internal_imports);
+        if !candidate.duplicated_imports.is_empty() {
+            saving.push_str(&format!(", duplicated {}", candidate.duplicated_imports.join(", ")));
+        }
+        println!(
+            "  merging removes {} modules and {} import entries ({})",
+            candidate.modules.len() - 1,
+            candidate.removed_imports,
+            saving
+        );
+    }
+}
+
+/// 未使用のものを確からしさの高い順に表示する
+pub fn print_dead_code(report: &DeadCodeReport, project_path: &Path) {
+    if report.items.is_empty() {
+        match Lang::current() {
+            Lang::En => println!("{} Everything is reachable from {}", mark_ok(), report.root),
+            Lang::Ja => println!("{} すべて{}から到達できます", mark_ok(), report.root),
+        }
+        return;
+    }
+
+    for item in &report.items {
+        let confidence = match item.confidence {
+            Confidence::High => item.confidence.to_string().red(),
+            Confidence::Medium => item.confidence.to_string().yellow(),
+            Confidence::Low => item.confidence.to_string().dimmed(),
+        };
+        let file = item.file.strip_prefix(project_path).unwrap_or(&item.file);
+        println!("{:<8} {:<10} {} {}", confidence, item.kind.to_string(), item.name.bold(), file.display().to_string().dimmed());
+        println!("{:<19} {}", "", item.reason);
+    }
+    match Lang::current() {
+        Lang::En => println!("\n{} unused items not reachable from {}", report.items.len(), report.root),
+        Lang::Ja => println!("\n{}から到達できない未使用のもの: {}件", report.root, report.items.len()),
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    format!("{:.1} KiB", (bytes as f64) / 1024.0)
+}
+
+/// 目標のアーキテクチャとの差分を表示する
+pub fn print_drift(drift: &DriftReport, target: &Path) {
+    if drift.is_empty() {
+        match Lang::current() {
+            Lang::En => println!("{} The code conforms to {}", mark_ok(), target.display()),
+            Lang::Ja => println!("{} コードは{}に従っています", mark_ok(), target.display()),
+        }
+        return;
+    }
+
+    if !drift.unexpected_edges.is_empty() {
+        let title = match Lang::current() {
+            Lang::En => format!("Dependencies not permitted by {}:", target.display()),
+            Lang::Ja => format!("{}で許可されていない依存:", target.display()),
+        };
+        println!("{}", title.red().bold());
+        for edge in &drift.unexpected_edges {
+            let lazy = if edge.lazy { tr(" (lazy)", "（遅延ロード）") } else { "" };
+            println!("  {} {} -> {}{}", "+".red(), edge.from, edge.to, lazy.dimmed());
+        }
+    }
+    if !drift.missing_edges.is_empty() {
+        let title = match Lang::current() {
+            Lang::En => format!("Dependencies in {} no longer present:", target.display()),
+            Lang::Ja => format!("{}にあるがコードからなくなった依存:", target.display()),
+        };
+        println!("{}", title.yellow().bold());
+        for (from, to) in &drift.missing_edges {
+            println!("  {} {} -> {}", "-".yellow(), from, to);
+        }
+    }
+    if !drift.unexpected_modules.is_empty() {
+        println!("{}", tr("Modules not in the target architecture:", "目標のアーキテクチャにないモジュール:").red().bold());
+        for module in &drift.unexpected_modules {
+            println!("  {} {}", "+".red(), module);
+        }
+    }
+    if !drift.missing_modules.is_empty() {
+        println!(
+            "{}",
+            tr(
+                "Modules in the target architecture no longer present:",
+                "目標のアーキテクチャにあるがコードからなくなったモジュール:"
+            )
+                .yellow()
+                .bold()
+        );
+        for module in &drift.missing_modules {
+            println!("  {} {}", "-".yellow(), module);
+        }
+    }
+}
+
+/// 適用した修正をファイルごとに表示する
+pub fn print_fixes(files: &[FileFix], project_path: &Path) {
+    if files.is_empty() {
+        println!("{} {}", mark_ok(), tr("Nothing to fix", "修正するものはありません"));
+        return;
+    }
+
+    for file in files {
+        println!("{}", file.path.strip_prefix(project_path).unwrap_or(&file.path).display().to_string().bold());
+        for fix in &file.fixes {
+            let kind = match fix.kind {
+                FixKind::UnusedImport => tr("remove unused import", "未使用のimportを削除"),
+                FixKind::UnusedExport => tr("remove unused export", "未使用のexportを削除"),
+                FixKind::Duplicate => tr("remove duplicate", "重複を削除"),
+                FixKind::Unsorted => tr("sort", "並べ替え"),
+            };
+            match (Lang::current(), &fix.entry) {
+                (Lang::En, Some(entry)) => println!("  {} {} {} from {}", mark_ok(), kind, entry, fix.field),
+                (Lang::Ja, Some(entry)) => println!("  {} {}の{}: {}", mark_ok(), fix.field, entry, kind),
+                (_, None) => println!("  {} {} {}", mark_ok(), kind, fix.field),
+            }
+        }
+    }
+    let count: usize = files.iter().map(|file| file.fixes.len()).sum();
+    match Lang::current() {
+        Lang::En => println!("\nApplied {} fixes to {} files", count, files.len()),
+        Lang::Ja => println!("\n{}ファイルに{}件の修正を適用しました", files.len(), count),
+    }
+}
+
+/// 他のツールとのエッジの比較結果を表示する
+pub fn print_comparison(comparison: &Comparison) {
+    let tool = comparison.tool.label();
+    match Lang::current() {
+        Lang::En => println!("{} module edges found by both the analyzer and {}", comparison.common.len(), tool),
+        Lang::Ja => println!("解析器と{}の両方が見つけたモジュール間の依存: {}件", tool, comparison.common.len()),
+    }
+
+    if !comparison.only_analyzer.is_empty() {
+        println!("\n{}", tr("Only in the analyzer:", "解析器だけが見つけた依存:").yellow().bold());
+        for edge in &comparison.only_analyzer {
+            println!("  {} {} -> {}", "+".green(), edge.from, edge.to);
+        }
+    }
+    if !comparison.only_other.is_empty() {
+        let title = match Lang::current() {
+            Lang::En => format!("Only in {}:", tool),
+            Lang::Ja => format!("{}だけが見つけた依存:", tool),
+        };
+        println!("\n{}", title.red().bold());
+        for edge in &comparison.only_other {
+            println!("  {} {} -> {}", "-".red(), edge.from, edge.to);
+        }
+    }
+
+    if comparison.is_identical() {
+        match Lang::current() {
+            Lang::En => println!("{} The analyzer and {} see the same module edges", mark_ok(), tool),
+            Lang::Ja => println!("{} 解析器と{}のモジュール間の依存は一致しています", mark_ok(), tool),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::ownership::FileOwnership;
+use crate::{ AnalyzerError, ModuleInfo, Result };
+
+/// カバーされた数と全体の数（lcovは行、istanbulのJSONは文）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Coverage {
+    pub covered: u64,
+    pub total: u64,
+}
+
+impl Coverage {
+    /// カバー率（%）。対象が1つもなければ`None`
+    pub fn percent(&self) -> Option<f64> {
+        (self.total > 0).then(|| ((self.covered as f64) / (self.total as f64)) * 100.0)
+    }
+
+    fn add(&mut self, other: Coverage) {
+        self.covered += other.covered;
+        self.total += other.total;
+    }
+}
+
+/// lcov（`lcov.info`）かistanbulのJSON（`coverage-final.json`・`coverage-summary.json`）を読み込み、ファイルごとのカバレッジを返す
+pub fn load(path: &Path) -> Result<Vec<(String, Coverage)>> {
+    let content = fs::read_to_string(path).map_err(|err| AnalyzerError::io(path, err))?;
+    if content.trim_start().starts_with('{') {
+        let json: Value = serde_json::from_str(&content).map_err(|err| AnalyzerError::parse(path, err))?;
+        let files = json
+            .as_object()
+            .ok_or_else(|| AnalyzerError::parse(path, "expected an object keyed by file"))?;
+        return Ok(
+            files
+                .iter()
+                .filter(|(file, _)| *file != "total")
+                .filter_map(|(file, entry)| Some((file.clone(), istanbul_coverage(entry)?)))
+                .collect()
+        );
+    }
+    Ok(parse_lcov(&content))
+}
+
+fn istanbul_coverage(entry: &Value) -> Option<Coverage> {
+    // coverage-summary.json
+    if let Some(lines) = entry.get("lines") {
+        return Some(Coverage { covered: lines.get("covered")?.as_u64()?, total: lines.get("total")?.as_u64()? });
+    }
+    // coverage-final.json（文ごとの実行回数）
+    let statements = entry.get("s")?.as_object()?;
+    Some(Coverage {
+        covered: statements
+            .values()
+            .filter(|count| count.as_u64().unwrap_or(0) > 0)
+            .count() as u64,
+        total: statements.len() as u64,
+    })
+}
+
+fn parse_lcov(content: &str) -> Vec<(String, Coverage)> {
+    let mut files = Vec::new();
+    let mut file: Option<String> = None;
+    // `LF`/`LH`がなければ`DA`の行から数える
+    let mut summary: Option<Coverage> = None;
+    let mut lines = Coverage::default();
+    for line in content.lines().map(str::trim) {
+        if let Some(path) = line.strip_prefix("SF:") {
+            file = Some(path.to_string());
+            summary = None;
+            lines = Coverage::default();
+        } else if let Some(count) = line.strip_prefix("LF:") {
+            summary.get_or_insert_default().total = count.parse().unwrap_or(0);
+        } else if let Some(count) = line.strip_prefix("LH:") {
+            summary.get_or_insert_default().covered = count.parse().unwrap_or(0);
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            lines.total += 1;
+            let hits = data.split(',').nth(1).and_then(|hits| hits.parse::<u64>().ok());
+            if hits.is_some_and(|hits| hits > 0) {
+                lines.covered += 1;
+            }
+        } else if line == "end_of_record" && let Some(file) = file.take() {
+            files.push((file, summary.unwrap_or(lines)));
+        }
+    }
+    files
+}
+
+/// ファイルのカバレッジを所有するモジュールごとに合算する（どのモジュールにも属さないファイルは数えない）
+pub fn per_module(
+    project_path: &Path,
+    modules: &[ModuleInfo],
+    files: &[(String, Coverage)]
+) -> HashMap<String, Coverage> {
+    let ownership = FileOwnership::new(project_path, modules);
+    let mut coverage: HashMap<String, Coverage> = HashMap::new();
+    for (file, file_coverage) in files {
+        if let Some(owner) = ownership.owner(file) {
+            coverage.entry(owner.to_string()).or_default().add(*file_coverage);
+        }
+    }
+    coverage
+}
@@ -0,0 +1,286 @@
+use clap::ValueEnum;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{ BTreeSet, HashMap, HashSet, VecDeque };
+use std::fmt;
+use std::path::{ Path, PathBuf };
+
+use crate::components::{ ComponentGraph, DeclarableKind };
+use crate::routes::{ Route, RouteTree };
+use crate::services::{ Provider, ServiceTree };
+use crate::{ AnalysisResult, ExportKind, IMPORT_REGEX, Name, Position, SourceLocation, project_exclusions, source, source_files };
+
+static BOOTSTRAP_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bbootstrap\s*:\s*\[([^\]]*)\]").unwrap());
+static BOOTSTRAP_APPLICATION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bbootstrapApplication\s*\(\s*(\w+)").unwrap());
+
+/// 未使用と判断した確からしさ（順序はHigh < Medium < Low）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// どこからも参照されていない
+    High,
+    /// 未使用のものからしか参照されていない、またはモジュールの外へexportしている
+    Medium,
+    /// 解析できる参照はないが、コード中に名前が出てくる（動的に生成している可能性がある）
+    Low,
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Confidence::High => write!(f, "high"),
+            Confidence::Medium => write!(f, "medium"),
+            Confidence::Low => write!(f, "low"),
+        }
+    }
+}
+
+/// 未使用のものの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeadKind {
+    Module,
+    Component,
+    Directive,
+    Pipe,
+    Service,
+}
+
+impl fmt::Display for DeadKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeadKind::Module => write!(f, "module"),
+            DeadKind::Component => write!(f, "component"),
+            DeadKind::Directive => write!(f, "directive"),
+            DeadKind::Pipe => write!(f, "pipe"),
+            DeadKind::Service => write!(f, "service"),
+        }
+    }
+}
+
+impl From<DeclarableKind> for DeadKind {
+    fn from(kind: DeclarableKind) -> Self {
+        match kind {
+            DeclarableKind::Component => DeadKind::Component,
+            DeclarableKind::Directive => DeadKind::Directive,
+            DeclarableKind::Pipe => DeadKind::Pipe,
+        }
+    }
+}
+
+/// ルートから到達できないモジュール・コンポーネント・ディレクティブ・パイプ・サービス
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeadItem {
+    pub name: String,
+    pub kind: DeadKind,
+    pub file: PathBuf,
+    /// 宣言・提供しているモジュール
+    pub module: Option<Name>,
+    pub confidence: Confidence,
+    /// 未使用と判断した理由
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeadCodeReport {
+    pub root: String,
+    /// 確からしさ・種類・名前の順
+    pub items: Vec<DeadItem>,
+}
+
+impl DeadCodeReport {
+    /// `min`以上の確からしさのものだけを残す
+    pub fn retain_confidence(&mut self, min: Confidence) {
+        self.items.retain(|item| item.confidence <= min);
+    }
+}
+
+/// 起動時のルートモジュールから、NgModuleのimports・遅延ロード・ルート定義・テンプレート・注入をたどって未使用のものを探す
+///
+/// `components`は[`crate::AnalyzerBuilder::components`]で集めたグラフ（なければコンポーネント類は調べない）。
+pub fn detect(project_path: &Path, result: &AnalysisResult, root: &str) -> DeadCodeReport {
+    let graph = result.graph();
+    let empty = ComponentGraph::default();
+    let components = result.components.as_ref().unwrap_or(&empty);
+    let files: Vec<(PathBuf, String)> = source_files(project_path, false, true, false, project_exclusions(project_path))
+        .filter_map(|path| {
+            let content = source::read(&path, Some(source::DEFAULT_MAX_FILE_SIZE)).ok()?;
+            Some((path, content.to_string()))
+        })
+        .collect();
+
+    // NgModuleはimportsと遅延ロードの両方をたどる
+    let mut live_modules: HashSet<&str> = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(module) = queue.pop_front() {
+        for dependency in graph.dependencies_of(module) {
+            if live_modules.insert(dependency) {
+                queue.push_back(dependency);
+            }
+        }
+    }
+
+    // 起動コンポーネントとルートに書かれたコンポーネントから、テンプレートとimport文をたどる
+    let mut entry_points: BTreeSet<String> = BTreeSet::new();
+    for module in result.modules.iter().filter(|m| live_modules.contains(m.name.as_ref())) {
+        if let Some((_, content)) = files.iter().find(|(path, _)| *path == module.path) {
+            for cap in BOOTSTRAP_REGEX.captures_iter(content) {
+                entry_points.extend(cap[1].split(',').map(|name| name.trim().to_string()).filter(|n| !n.is_empty()));
+            }
+        }
+    }
+    for (_, content) in &files {
+        entry_points.extend(BOOTSTRAP_APPLICATION_REGEX.captures_iter(content).map(|cap| cap[1].to_string()));
+    }
+    if graph.contains(root) {
+        collect_route_components(&RouteTree::build(result, root).routes, &mut entry_points);
+    }
+
+    let mut used_by: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for edge in &components.edges {
+        used_by.entry(edge.to.as_str()).or_default().insert(edge.from.as_str());
+    }
+    let mut live_declarables: HashSet<&str> = HashSet::new();
+    let mut queue: VecDeque<&str> = components.declarables
+        .iter()
+        .map(|d| d.name.as_str())
+        .filter(|name| entry_points.contains(*name))
+        .collect();
+    live_declarables.extend(queue.iter().copied());
+    while let Some(name) = queue.pop_front() {
+        for edge in components.edges.iter().filter(|edge| edge.from == name) {
+            if live_declarables.insert(edge.to.as_str()) {
+                queue.push_back(edge.to.as_str());
+            }
+        }
+    }
+
+    let mut items = Vec::new();
+
+    for module in &result.modules {
+        let name = module.name.as_ref();
+        if live_modules.contains(name) {
+            continue;
+        }
+        let dependents = graph.dependents_of(name);
+        let (confidence, reason) = if dependents.is_empty() {
+            match code_reference(project_path, &files, name, &module.path) {
+                Some(location) => (Confidence::Low, format!("not imported by any module, but referenced at {}", location)),
+                None => (Confidence::High, format!("not imported by any module reachable from {}", root)),
+            }
+        } else {
+            (Confidence::Medium, format!("only imported by unreachable {}", dependents.join(", ")))
+        };
+        items.push(DeadItem {
+            name: name.to_string(),
+            kind: DeadKind::Module,
+            file: module.path.clone(),
+            module: None,
+            confidence,
+            reason,
+        });
+    }
+
+    let exported: HashSet<&str> = result.modules
+        .iter()
+        .flat_map(|m| m.export_kinds.iter())
+        .filter(|export| export.kind != ExportKind::Module)
+        .map(|export| export.name.as_ref())
+        .collect();
+    let mut dead_classes: HashSet<&str> = HashSet::new();
+    for declarable in &components.declarables {
+        let name = declarable.name.as_str();
+        if live_declarables.contains(name) {
+            continue;
+        }
+        dead_classes.insert(name);
+        // 未使用のモジュールが宣言しているものは、モジュールとしてまとめて報告する
+        if declarable.module.as_deref().is_some_and(|m| !live_modules.contains(m)) {
+            continue;
+        }
+        let users: Vec<&str> = used_by.get(name).into_iter().flatten().copied().collect();
+        let (confidence, reason) = if !users.is_empty() {
+            (Confidence::Medium, format!("only used by unused {}", users.join(", ")))
+        } else if let Some(location) = code_reference(project_path, &files, name, &declarable.file) {
+            (Confidence::Low, format!("not used in any template or route, but referenced at {}", location))
+        } else if exported.contains(name) {
+            (Confidence::Medium, "exported but not used in any analyzed template or route".to_string())
+        } else {
+            (Confidence::High, "not used in any template or route".to_string())
+        };
+        items.push(DeadItem {
+            name: name.to_string(),
+            kind: declarable.kind.into(),
+            file: declarable.file.clone(),
+            module: declarable.module.clone(),
+            confidence,
+            reason,
+        });
+    }
+
+    let services = ServiceTree::collect(project_path, result);
+    for service in &services.services {
+        let provided_by_dead_module = matches!(&service.provider, Provider::Module(m) if !live_modules.contains(m.as_ref()));
+        if provided_by_dead_module {
+            continue;
+        }
+        let injectors: Vec<&str> = service.injected_by
+            .iter()
+            .map(|injection| injection.class.as_str())
+            .collect();
+        let (confidence, reason) = if injectors.is_empty() {
+            match code_reference(project_path, &files, &service.name, &service.file) {
+                Some(location) => (Confidence::Low, format!("never injected, but referenced at {}", location)),
+                None => (Confidence::High, "never injected".to_string()),
+            }
+        } else if injectors.iter().all(|class| dead_classes.contains(class)) {
+            (Confidence::Medium, format!("only injected by unused {}", injectors.join(", ")))
+        } else {
+            continue;
+        };
+        items.push(DeadItem {
+            name: service.name.clone(),
+            kind: DeadKind::Service,
+            file: service.file.clone(),
+            module: service.module.clone(),
+            confidence,
+            reason,
+        });
+    }
+
+    items.sort_by(|a, b| (a.confidence, a.kind, &a.name).cmp(&(b.confidence, b.kind, &b.name)));
+    DeadCodeReport { root: root.to_string(), items }
+}
+
+fn collect_route_components(routes: &[Route], names: &mut BTreeSet<String>) {
+    for route in routes {
+        names.extend(route.component.iter().chain(&route.load_component).cloned());
+        collect_route_components(&route.children, names);
+        if let Some(boundary) = &route.lazy {
+            collect_route_components(&boundary.routes, names);
+        }
+    }
+}
+
+/// `name`を定義しているファイルとNgModuleのファイル以外で、import文の外に`name`が出てくる最初の場所
+///
+/// `dialog.open(ConfirmComponent)`や`injector.get(SomeService)`のように、解析できない形で使っている可能性がある。
+fn code_reference(project_path: &Path, files: &[(PathBuf, String)], name: &str, defined_in: &Path) -> Option<SourceLocation> {
+    let pattern = Regex::new(&format!(r"\b{}\b", regex::escape(name))).unwrap();
+    for (path, content) in files {
+        if path == defined_in || path.to_string_lossy().ends_with(".module.ts") {
+            continue;
+        }
+        let imports: Vec<(usize, usize)> = IMPORT_REGEX.find_iter(content)
+            .map(|m| (m.start(), m.end()))
+            .collect();
+        let found = pattern.find_iter(content).find(|m| !imports.iter().any(|&(start, end)| start <= m.start() && m.end() <= end));
+        if let Some(found) = found {
+            let position = Position::at(content, found.start());
+            let file = path.strip_prefix(project_path).unwrap_or(path).to_path_buf();
+            return Some(SourceLocation { file, line: position.line, column: position.column });
+        }
+    }
+    None
+}
@@ -0,0 +1,193 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use crate::check::new_violations;
+use crate::compare::ModuleEdge;
+use crate::{ AnalysisResult, DependencyViolation, Name };
+
+/// 基準とした解析結果から変化したメトリクス
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricDelta {
+    pub metric: &'static str,
+    pub before: f32,
+    pub after: f32,
+    /// 値が大きいほど悪いメトリクスか
+    pub higher_is_worse: bool,
+}
+
+impl MetricDelta {
+    /// 悪化したか
+    pub fn is_regression(&self) -> bool {
+        if self.higher_is_worse { self.after > self.before } else { self.after < self.before }
+    }
+}
+
+/// メトリクスを基準の解析結果と並べる（変化していないものも含む）
+pub fn metric_deltas(base: &AnalysisResult, head: &AnalysisResult) -> Vec<MetricDelta> {
+    let (before, after) = (&base.metrics, &head.metrics);
+    let delta = |metric, before, after, higher_is_worse| MetricDelta { metric, before, after, higher_is_worse };
+    vec![
+        delta("modules", before.total_modules as f32, after.total_modules as f32, false),
+        delta(
+            "violations",
+            base.dependency_violations.len() as f32,
+            head.dependency_violations.len() as f32,
+            true
+        ),
+        delta(
+            "circular dependencies",
+            base.circular_dependencies.len() as f32,
+            head.circular_dependencies.len() as f32,
+            true
+        ),
+        delta("coupling factor", before.coupling_factor, after.coupling_factor, true),
+        delta("max dependency depth", before.max_dependency_depth as f32, after.max_dependency_depth as f32, true),
+        delta("health score", before.health_score, after.health_score, false)
+    ]
+}
+
+/// 2つの解析結果の差分（PRのブランチと基準ブランチなど）
+#[derive(Debug, Serialize)]
+pub struct ResultDelta<'a> {
+    pub new_violations: Vec<&'a DependencyViolation>,
+    pub fixed_violations: Vec<&'a DependencyViolation>,
+    pub added_edges: Vec<ModuleEdge>,
+    pub removed_edges: Vec<ModuleEdge>,
+    pub new_cycles: Vec<Vec<Name>>,
+    pub resolved_cycles: Vec<Vec<Name>>,
+    pub metrics: Vec<MetricDelta>,
+}
+
+impl<'a> ResultDelta<'a> {
+    pub fn new(base: &'a AnalysisResult, head: &'a AnalysisResult) -> Self {
+        let edges = |result: &AnalysisResult| -> BTreeSet<ModuleEdge> {
+            result
+                .graph()
+                .edges()
+                .into_iter()
+                .map(|(from, to, _)| ModuleEdge { from: from.to_string(), to: to.to_string() })
+                .collect()
+        };
+        let (base_edges, head_edges) = (edges(base), edges(head));
+        let (base_cycles, head_cycles) = (cycle_set(base), cycle_set(head));
+
+        Self {
+            new_violations: new_violations(head, base),
+            fixed_violations: new_violations(base, head),
+            added_edges: head_edges.difference(&base_edges).cloned().collect(),
+            removed_edges: base_edges.difference(&head_edges).cloned().collect(),
+            new_cycles: head_cycles.difference(&base_cycles).cloned().collect(),
+            resolved_cycles: base_cycles.difference(&head_cycles).cloned().collect(),
+            metrics: metric_deltas(base, head),
+        }
+    }
+
+    /// PRにそのまま投稿できるMarkdownのコメント（詳細は折りたたむ）
+    ///
+    /// 先頭の`<!-- angular-module-analyzer -->`は、既存のコメントを探して更新するためのマーカー。
+    pub fn render_markdown(&self) -> String {
+        let mut md = String::from("<!-- angular-module-analyzer -->\n## Architecture report\n\n");
+
+        let health = self.metrics.iter().find(|m| m.metric == "health score");
+        let mut headline = vec![
+            format!("**{} new violation(s)**", self.new_violations.len()),
+            format!("{} fixed", self.fixed_violations.len()),
+            format!("{} new cycle(s)", self.new_cycles.len()),
+        ];
+        if let Some(health) = health {
+            headline.push(format!("health score {:.1} → {:.1}", health.before, health.after));
+        }
+        md.push_str(&headline.join(" · "));
+        md.push_str("\n\n");
+
+        md.push_str("| Metric | Base | This branch | Δ |\n|---|---:|---:|---:|\n");
+        for delta in &self.metrics {
+            let change = delta.after - delta.before;
+            let marker = if delta.is_regression() { " ⚠️" } else { "" };
+            md.push_str(
+                &format!(
+                    "| {} | {} | {} | {}{} |\n",
+                    delta.metric,
+                    format_value(delta.before),
+                    format_value(delta.after),
+                    if change == 0.0 { "–".to_string() } else { format_change(change) },
+                    marker
+                )
+            );
+        }
+        md.push('\n');
+
+        let violations = |violations: &[&DependencyViolation]| -> Vec<String> {
+            violations
+                .iter()
+                .map(|v| {
+                    let location = v.location
+                        .as_ref()
+                        .map(|location| format!(" (`{}`)", location))
+                        .unwrap_or_default();
+                    format!("**{}** {} → {}: {}{}", v.violation_type.code(), v.from_module, v.to_module, v.description, location)
+                })
+                .collect()
+        };
+        let edges = |edges: &[ModuleEdge], sign: &str| -> Vec<String> {
+            edges
+                .iter()
+                .map(|edge| format!("{} `{} -> {}`", sign, edge.from, edge.to))
+                .collect()
+        };
+        let cycles = |cycles: &[Vec<Name>]| -> Vec<String> {
+            cycles
+                .iter()
+                .map(|cycle| format!("`{} -> {}`", cycle.join(" -> "), cycle[0]))
+                .collect()
+        };
+
+        details(&mut md, "New violations", &violations(&self.new_violations));
+        details(&mut md, "Fixed violations", &violations(&self.fixed_violations));
+        let mut changed = edges(&self.added_edges, "➕");
+        changed.extend(edges(&self.removed_edges, "➖"));
+        details(&mut md, "Changed module edges", &changed);
+        details(&mut md, "New circular dependencies", &cycles(&self.new_cycles));
+        details(&mut md, "Resolved circular dependencies", &cycles(&self.resolved_cycles));
+
+        if self.new_violations.is_empty() && self.fixed_violations.is_empty() && changed.is_empty() &&
+            self.new_cycles.is_empty() && self.resolved_cycles.is_empty()
+        {
+            md.push_str("No changes to the module structure.\n");
+        }
+        md
+    }
+}
+
+/// 折りたたみセクション（項目がなければ出力しない）
+fn details(md: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    md.push_str(&format!("<details>\n<summary>{} ({})</summary>\n\n", title, items.len()));
+    for item in items {
+        md.push_str(&format!("- {}\n", item));
+    }
+    md.push_str("\n</details>\n\n");
+}
+
+fn format_value(value: f32) -> String {
+    if value.fract() == 0.0 { format!("{}", value) } else { format!("{:.2}", value) }
+}
+
+fn format_change(change: f32) -> String {
+    if change.fract() == 0.0 { format!("{:+}", change) } else { format!("{:+.2}", change) }
+}
+
+/// 開始モジュールに依存しない形にそろえた循環の集合
+fn cycle_set(result: &AnalysisResult) -> BTreeSet<Vec<Name>> {
+    result.circular_dependencies
+        .iter()
+        .map(|cycle| {
+            let start = (0..cycle.len()).min_by_key(|&i| &cycle[i]).unwrap_or(0);
+            let mut cycle = cycle.clone();
+            cycle.rotate_left(start);
+            cycle
+        })
+        .collect()
+}
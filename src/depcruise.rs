@@ -0,0 +1,323 @@
+use once_cell::sync::Lazy;
+use regex::{ Captures, Regex };
+use serde::{ Deserialize, Deserializer };
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::config::RuleLevel;
+use crate::graph::{ EdgeKind, ModuleGraph };
+use crate::{ AnalyzerError, DependencyViolation, ModuleInfo, Result, ViolationType };
+
+/// 読み込むdependency-cruiserの設定ファイル（先に見つかったもの）
+pub const RULE_FILE_NAMES: [&str; 3] = [".dependency-cruiser.json", ".dependency-cruiser.js", ".dependency-cruiser.cjs"];
+
+// パターン中の依存元のグループの参照（`$1`〜`$9`）
+static GROUP_REFERENCE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$([1-9])").unwrap());
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RuleFile {
+    #[serde(default)]
+    forbidden: Vec<RawRule>,
+    #[serde(default)]
+    allowed: Vec<RawRule>,
+    allowed_severity: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawRule {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    severity: Option<String>,
+    #[serde(default)]
+    comment: Option<String>,
+    #[serde(default)]
+    from: RawCondition,
+    #[serde(default)]
+    to: RawCondition,
+    /// `module`（orphanなど）や`scope`のような、依存ごとの判定ではないルール
+    #[serde(flatten)]
+    other: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawCondition {
+    #[serde(default, deserialize_with = "one_or_many")]
+    path: Option<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    path_not: Option<String>,
+    circular: Option<bool>,
+    dependency_types: Option<Vec<String>>,
+    dependency_types_not: Option<Vec<String>>,
+    #[serde(flatten)]
+    other: BTreeMap<String, serde_json::Value>,
+}
+
+/// 正規表現は文字列か文字列の配列（配列はいずれかにマッチ）
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(
+        Option::<OneOrMany>::deserialize(deserializer)?.map(|patterns| {
+            match patterns {
+                OneOrMany::One(pattern) => pattern,
+                OneOrMany::Many(patterns) => patterns.join("|"),
+            }
+        })
+    )
+}
+
+/// 依存先のパターン（`$1`などで依存元のパターンのグループを参照する場合は依存ごとにコンパイルする）
+#[derive(Debug, Clone)]
+enum Pattern {
+    Fixed(Regex),
+    Template(String),
+}
+
+impl Pattern {
+    fn new(pattern: &str) -> std::result::Result<Self, regex::Error> {
+        if GROUP_REFERENCE.is_match(pattern) {
+            Regex::new(&GROUP_REFERENCE.replace_all(pattern, "x"))?;
+            Ok(Self::Template(pattern.to_string()))
+        } else {
+            Ok(Self::Fixed(Regex::new(pattern)?))
+        }
+    }
+
+    fn is_match(&self, path: &str, groups: Option<&Captures>) -> bool {
+        match self {
+            Pattern::Fixed(regex) => regex.is_match(path),
+            Pattern::Template(template) => {
+                let pattern = GROUP_REFERENCE.replace_all(template, |reference: &Captures| {
+                    let group = groups.and_then(|groups| groups.get(reference[1].parse().unwrap_or(0)));
+                    regex::escape(group.map_or("", |group| group.as_str()))
+                });
+                Regex::new(&pattern).is_ok_and(|regex| regex.is_match(path))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Condition {
+    path: Option<Pattern>,
+    path_not: Option<Pattern>,
+    circular: Option<bool>,
+    dependency_types: Option<Vec<String>>,
+    dependency_types_not: Option<Vec<String>>,
+}
+
+/// 依存ごとに評価できるルール
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub comment: Option<String>,
+    from: Condition,
+    to: Condition,
+}
+
+/// 評価できないため読み飛ばしたルール
+#[derive(Debug, Clone)]
+pub struct SkippedRule {
+    pub name: String,
+    pub reason: String,
+}
+
+/// 解析器のグラフ上で評価する依存の情報
+struct Dependency<'a> {
+    path: String,
+    types: &'a [&'a str],
+    circular: bool,
+}
+
+/// dependency-cruiserの`forbidden`・`allowed`ルール（宣言的な部分のみ）
+///
+/// `from`・`to`の`path`・`pathNot`（`$1`による依存元のグループの参照も含む）・`circular`・
+/// `dependencyTypes`・`dependencyTypesNot`を、NgModuleの依存グラフに対して評価する。
+/// パスはプロジェクトルートからの相対パスで、外部パッケージは`node_modules/<パッケージ名>`として照合する。
+#[derive(Debug, Clone)]
+pub struct DepcruiseRules {
+    pub file: PathBuf,
+    pub forbidden: Vec<Rule>,
+    /// 空でなければ、いずれかにマッチしない依存を`not-in-allowed`として報告する
+    pub allowed: Vec<Rule>,
+    pub skipped: Vec<SkippedRule>,
+}
+
+impl DepcruiseRules {
+    /// プロジェクトルートの設定ファイルを読み込む（なければ`None`）
+    ///
+    /// `.js`はその中の`module.exports = { ... }`を、関数や正規表現リテラルを含まないJSON5として読む。
+    pub fn load(project_path: &Path) -> Result<Option<Self>> {
+        let Some(path) = RULE_FILE_NAMES.iter()
+            .map(|name| project_path.join(name))
+            .find(|path| path.is_file()) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&path).map_err(|err| AnalyzerError::io(&path, err))?;
+        let literal = if path.extension().is_some_and(|ext| ext == "json") {
+            content.as_str()
+        } else {
+            exported_object(&content).ok_or_else(|| AnalyzerError::parse(&path, "no `module.exports = { ... }` found"))?
+        };
+        let file: RuleFile = json5
+            ::from_str(literal)
+            .map_err(|err| {
+                AnalyzerError::parse(&path, format!("{} (only plain object literals are supported)", err))
+            })?;
+
+        let mut skipped = Vec::new();
+        let mut compile = |rules: Vec<RawRule>, default_name: &str| -> Result<Vec<Rule>> {
+            let mut compiled = Vec::new();
+            for rule in rules {
+                let name = rule.name.clone().unwrap_or_else(|| default_name.to_string());
+                if matches!(rule.severity.as_deref(), Some("ignore" | "off")) {
+                    continue;
+                }
+                let unsupported: Vec<&String> = rule.other
+                    .keys()
+                    .chain(rule.from.other.keys())
+                    .chain(rule.to.other.keys())
+                    .collect();
+                if let Some(key) = unsupported.first() {
+                    skipped.push(SkippedRule { name, reason: format!("`{}` is not supported", key) });
+                    continue;
+                }
+                let condition = |raw: RawCondition| -> Result<Condition> {
+                    let pattern = |pattern: Option<String>| {
+                        pattern
+                            .map(|pattern| Pattern::new(&pattern))
+                            .transpose()
+                            .map_err(|err| AnalyzerError::parse(&path, format!("rule `{}`: {}", name, err)))
+                    };
+                    Ok(Condition {
+                        path: pattern(raw.path)?,
+                        path_not: pattern(raw.path_not)?,
+                        circular: raw.circular,
+                        dependency_types: raw.dependency_types,
+                        dependency_types_not: raw.dependency_types_not,
+                    })
+                };
+                compiled.push(Rule {
+                    from: condition(rule.from)?,
+                    to: condition(rule.to)?,
+                    name,
+                    comment: rule.comment,
+                });
+            }
+            Ok(compiled)
+        };
+        let forbidden = compile(file.forbidden, "forbidden")?;
+        let allowed = if file.allowed_severity.as_deref() == Some("ignore") {
+            Vec::new()
+        } else {
+            compile(file.allowed, "allowed")?
+        };
+
+        Ok(Some(Self { file: path, forbidden, allowed, skipped }))
+    }
+
+    /// ルールに反する依存（モジュール間の依存と、モジュールから外部パッケージへの依存）
+    pub fn check(&self, project_path: &Path, modules: &[ModuleInfo], graph: &ModuleGraph) -> Vec<DependencyViolation> {
+        let relative = |module: &ModuleInfo| {
+            let path = module.path.strip_prefix(project_path).unwrap_or(&module.path);
+            path.to_string_lossy().replace('\\', "/")
+        };
+        let paths: BTreeMap<&str, String> = modules
+            .iter()
+            .map(|module| (module.name.as_ref(), relative(module)))
+            .collect();
+        let mut violations = Vec::new();
+
+        for module in modules {
+            let from = &paths[module.name.as_ref()];
+            let mut dependencies: Vec<(&str, Dependency)> = graph
+                .dependencies_of(&module.name)
+                .into_iter()
+                .map(|target| {
+                    let types: &[&str] = match graph.edge_kind(&module.name, target) {
+                        Some(EdgeKind::Lazy) => &["local", "dynamic-import"],
+                        _ => &["local", "import"],
+                    };
+                    let circular = !graph.shortest_paths(target, &module.name).is_empty();
+                    (target, Dependency { path: paths[target].clone(), types, circular })
+                })
+                .collect();
+            for package in &module.external_dependencies {
+                let path = format!("node_modules/{}", package.package);
+                dependencies.push((&package.specifier, Dependency { path, types: &["npm"], circular: false }));
+            }
+
+            for (target, dependency) in &dependencies {
+                let mut violation = |description: String| {
+                    violations.push(DependencyViolation {
+                        from_module: module.name.clone(),
+                        to_module: (*target).into(),
+                        violation_type: ViolationType::DepcruiseRule,
+                        description,
+                        location: module.location_of(target),
+                        severity: RuleLevel::Error,
+                    });
+                };
+                for rule in &self.forbidden {
+                    if rule.matches(from, dependency) {
+                        violation(match &rule.comment {
+                            Some(comment) => format!("{}: {}", rule.name, comment),
+                            None => rule.name.clone(),
+                        });
+                    }
+                }
+                if !self.allowed.is_empty() && !self.allowed.iter().any(|rule| rule.matches(from, dependency)) {
+                    violation("not-in-allowed: the dependency matches none of the allowed rules".to_string());
+                }
+            }
+        }
+        violations
+    }
+}
+
+impl Rule {
+    fn matches(&self, from: &str, dependency: &Dependency) -> bool {
+        let groups = match &self.from.path {
+            Some(Pattern::Fixed(regex)) => {
+                let Some(groups) = regex.captures(from) else {
+                    return false;
+                };
+                Some(groups)
+            }
+            Some(pattern) if !pattern.is_match(from, None) => {
+                return false;
+            }
+            _ => None,
+        };
+        if self.from.path_not.as_ref().is_some_and(|pattern| pattern.is_match(from, None)) {
+            return false;
+        }
+
+        let to = &self.to;
+        to.path.as_ref().is_none_or(|pattern| pattern.is_match(&dependency.path, groups.as_ref())) &&
+            !to.path_not.as_ref().is_some_and(|pattern| pattern.is_match(&dependency.path, groups.as_ref())) &&
+            to.circular.is_none_or(|circular| circular == dependency.circular) &&
+            to.dependency_types
+                .as_ref()
+                .is_none_or(|types| dependency.types.iter().any(|t| types.iter().any(|allowed| allowed == t))) &&
+            !to.dependency_types_not
+                .as_ref()
+                .is_some_and(|types| dependency.types.iter().any(|t| types.iter().any(|denied| denied == t)))
+    }
+}
+
+/// `module.exports = { ... }`のオブジェクトリテラル部分
+fn exported_object(content: &str) -> Option<&str> {
+    let start = content.find("module.exports")?;
+    let open = start + content[start..].find('{')?;
+    let close = content.rfind('}')?;
+    (close > open).then(|| &content[open..=close])
+}
@@ -0,0 +1,297 @@
+use ignore::gitignore::Gitignore;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::config::{ CONFIG_FILE_NAME, Classifier, Config };
+use crate::depcruise::DepcruiseRules;
+use crate::i18n::{ Lang, tr };
+use crate::init::WorkspaceKind;
+use crate::workspaces::Workspace;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+pub struct Diagnosis {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnosis {
+    fn ok(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Ok, message: message.into() }
+    }
+
+    fn warning(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, message: message.into() }
+    }
+
+    fn error(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, message: message.into() }
+    }
+}
+
+/// package.jsonの依存関係からパッケージのバージョン指定を探す
+pub(crate) fn package_version(package_json: &serde_json::Value, name: &str) -> Option<String> {
+    ["dependencies", "devDependencies"]
+        .iter()
+        .find_map(|section| package_json.get(section)?.get(name)?.as_str())
+        .map(|version| version.to_string())
+}
+
+/// 解析を実行する前にプロジェクトと設定の健全性を確認する
+pub fn diagnose(project_path: &Path) -> Vec<Diagnosis> {
+    let mut diagnoses = Vec::new();
+
+    if !project_path.is_dir() {
+        diagnoses.push(
+            Diagnosis::error(match Lang::current() {
+                Lang::En => format!("{} is not a directory", project_path.display()),
+                Lang::Ja => format!("{}はディレクトリではありません", project_path.display()),
+            })
+        );
+        return diagnoses;
+    }
+
+    // ワークスペースの検出
+    let workspace = WorkspaceKind::detect(project_path);
+    match workspace {
+        WorkspaceKind::Unknown => {
+            let hint = project_path
+                .ancestors()
+                .skip(1)
+                .find(|dir| dir.join("angular.json").exists() || dir.join("nx.json").exists());
+            match hint {
+                Some(root) =>
+                    diagnoses.push(
+                        Diagnosis::warning(match Lang::current() {
+                            Lang::En =>
+                                format!(
+                                    "No angular.json or nx.json here; the workspace root seems to be {}",
+                                    root.display()
+                                ),
+                            Lang::Ja =>
+                                format!("angular.jsonもnx.jsonもありません。ワークスペースのルートは{}のようです", root.display()),
+                        })
+                    ),
+                None =>
+                    diagnoses.push(
+                        Diagnosis::warning(
+                            tr(
+                                "No angular.json or nx.json found; is this an Angular project?",
+                                "angular.jsonもnx.jsonも見つかりません。Angularのプロジェクトですか？"
+                            )
+                        )
+                    ),
+            }
+        }
+        kind =>
+            diagnoses.push(
+                Diagnosis::ok(match Lang::current() {
+                    Lang::En => format!("Detected {}", kind.label()),
+                    Lang::Ja => format!("{}を検出しました", kind.label()),
+                })
+            ),
+    }
+
+    // pnpm・yarn・npmのワークスペース
+    match Workspace::detect(project_path) {
+        Ok(Some(workspace)) =>
+            diagnoses.push(
+                Diagnosis::ok(match Lang::current() {
+                    Lang::En =>
+                        format!(
+                            "Detected {} workspace with {} packages at {}",
+                            workspace.manager.label(),
+                            workspace.packages.len(),
+                            workspace.root.display()
+                        ),
+                    Lang::Ja =>
+                        format!(
+                            "{}に{}パッケージの{}ワークスペースを検出しました",
+                            workspace.root.display(),
+                            workspace.packages.len(),
+                            workspace.manager.label()
+                        ),
+                })
+            ),
+        Ok(None) => {}
+        Err(err) => diagnoses.push(Diagnosis::error(format!("{:#}", err))),
+    }
+
+    let package_json_path = project_path.join("package.json");
+    match fs::read_to_string(&package_json_path) {
+        Ok(content) =>
+            match serde_json::from_str::<serde_json::Value>(&content) {
+                Ok(package_json) => {
+                    match package_version(&package_json, "@angular/core") {
+                        Some(version) => {
+                            let label = tr("Angular version", "Angularのバージョン");
+                            diagnoses.push(Diagnosis::ok(format!("{} {}", label, version)));
+                        }
+                        None =>
+                            diagnoses.push(
+                                Diagnosis::warning(
+                                    tr(
+                                        "@angular/core is not listed in package.json",
+                                        "package.jsonに@angular/coreがありません"
+                                    )
+                                )
+                            ),
+                    }
+                    if let Some(version) = package_version(&package_json, "nx") {
+                        diagnoses.push(Diagnosis::ok(format!("{} {}", tr("Nx version", "Nxのバージョン"), version)));
+                    }
+                }
+                Err(err) => {
+                    let label = tr("package.json is not valid JSON", "package.jsonが正しいJSONではありません");
+                    diagnoses.push(Diagnosis::error(format!("{}: {}", label, err)));
+                }
+            }
+        Err(_) => diagnoses.push(Diagnosis::warning(tr("No package.json found", "package.jsonが見つかりません"))),
+    }
+
+    // 解析対象のモジュールファイル
+    let exclusions = crate::project_exclusions(project_path);
+    let module_files: Vec<_> = crate::module_files(project_path, false, true, false, exclusions.clone()).collect();
+    let unignored = crate::module_files(project_path, false, false, false, exclusions).count();
+    let ignored_module_files = unignored.saturating_sub(module_files.len());
+    let excluded_module_files = crate::module_files(project_path, false, false, false, Arc::new(Gitignore::empty()))
+        .count()
+        .saturating_sub(unignored);
+    if module_files.is_empty() {
+        diagnoses.push(Diagnosis::error(tr("No *.module.ts files found", "*.module.tsファイルが見つかりません")));
+    } else {
+        diagnoses.push(
+            Diagnosis::ok(match Lang::current() {
+                Lang::En => format!("{} candidate module files", module_files.len()),
+                Lang::Ja => format!("解析対象のモジュールファイル: {}件", module_files.len()),
+            })
+        );
+    }
+    if ignored_module_files > 0 {
+        let dirs = crate::DEFAULT_IGNORED_DIRS.join(", ");
+        let message = match Lang::current() {
+            Lang::En =>
+                format!(
+                    "{} *.module.ts files in {} or .gitignore'd paths are skipped (use --no-ignore to include them)",
+                    ignored_module_files,
+                    dirs
+                ),
+            Lang::Ja =>
+                format!(
+                    "{}と.gitignoreで除外したパスの*.module.tsファイル{}件を読み飛ばします（含めるには--no-ignore）",
+                    dirs,
+                    ignored_module_files
+                ),
+        };
+        diagnoses.push(Diagnosis::ok(message));
+    }
+
+    if excluded_module_files > 0 {
+        diagnoses.push(
+            Diagnosis::ok(match Lang::current() {
+                Lang::En =>
+                    format!(
+                        "{} *.module.ts files are excluded by {} or [analysis] exclude",
+                        excluded_module_files,
+                        crate::IGNORE_FILE_NAME
+                    ),
+                Lang::Ja =>
+                    format!(
+                        "{}か[analysis] excludeで*.module.tsファイル{}件を除外しています",
+                        crate::IGNORE_FILE_NAME,
+                        excluded_module_files
+                    ),
+            })
+        );
+    }
+
+    // dependency-cruiserのルールのうち評価できるもの
+    match DepcruiseRules::load(project_path) {
+        Ok(Some(rules)) => {
+            diagnoses.push(
+                Diagnosis::ok(match Lang::current() {
+                    Lang::En =>
+                        format!(
+                            "{} dependency-cruiser rules loaded from {}",
+                            rules.forbidden.len() + rules.allowed.len(),
+                            rules.file.display()
+                        ),
+                    Lang::Ja =>
+                        format!(
+                            "{}からdependency-cruiserのルールを{}件読み込みました",
+                            rules.file.display(),
+                            rules.forbidden.len() + rules.allowed.len()
+                        ),
+                })
+            );
+            for rule in &rules.skipped {
+                diagnoses.push(
+                    Diagnosis::warning(match Lang::current() {
+                        Lang::En => format!("dependency-cruiser rule `{}` is skipped: {}", rule.name, rule.reason),
+                        Lang::Ja => format!("dependency-cruiserのルール`{}`は評価しません: {}", rule.name, rule.reason),
+                    })
+                );
+            }
+        }
+        Ok(None) => {}
+        Err(err) => diagnoses.push(Diagnosis::error(format!("{:#}", err))),
+    }
+
+    // 設定ファイルの検証
+    let config_path = Config::path_for(project_path);
+    if !config_path.exists() {
+        diagnoses.push(
+            Diagnosis::warning(match Lang::current() {
+                Lang::En => format!("No {} found; run `init` to create one", CONFIG_FILE_NAME),
+                Lang::Ja => format!("{}がありません。`init`で作成できます", CONFIG_FILE_NAME),
+            })
+        );
+        return diagnoses;
+    }
+    let config = match Config::load(project_path) {
+        Ok(config) => config,
+        Err(err) => {
+            diagnoses.push(Diagnosis::error(format!("{:#}", err)));
+            return diagnoses;
+        }
+    };
+    let classifier = match Classifier::new(&config.classification) {
+        Ok(classifier) => classifier,
+        Err(err) => {
+            diagnoses.push(Diagnosis::error(format!("{:#}", err)));
+            return diagnoses;
+        }
+    };
+    diagnoses.push(
+        Diagnosis::ok(match Lang::current() {
+            Lang::En => format!("{} is valid", CONFIG_FILE_NAME),
+            Lang::Ja => format!("{}は正しい設定です", CONFIG_FILE_NAME),
+        })
+    );
+
+    let unclassified = module_files
+        .iter()
+        .filter(|path| classifier.classify(path.strip_prefix(project_path).unwrap_or(path)).is_none())
+        .count();
+    if unclassified > 0 {
+        diagnoses.push(
+            Diagnosis::warning(match Lang::current() {
+                Lang::En =>
+                    format!(
+                        "{} module files match no [classification] rule and fall back to path heuristics",
+                        unclassified
+                    ),
+                Lang::Ja =>
+                    format!("{}件のモジュールファイルが[classification]のどのルールにも一致せず、パスから分類しています", unclassified),
+            })
+        );
+    }
+
+    diagnoses
+}
@@ -0,0 +1,205 @@
+use globset::{ Glob, GlobMatcher };
+use serde::{ Deserialize, Serialize };
+use std::collections::{ BTreeSet, HashMap };
+use std::fs;
+use std::path::Path;
+
+use crate::graph::EdgeKind;
+use crate::list::TypeFilter;
+use crate::{ AnalysisResult, AnalyzerError, ModuleType, Result };
+
+/// 目標とするアーキテクチャ（存在してよいモジュールと、許可する依存）
+///
+/// TOMLで書いたものか、以前に承認したグラフ（`drift --bless`や`/api/graph`のJSON、`analyze -f json`の結果）から読み込む。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TargetArchitecture {
+    /// 存在してよいモジュール名（glob、未指定なら制限しない）
+    pub modules: Option<Vec<String>>,
+    pub allow: Vec<AllowedEdge>,
+}
+
+/// 許可する依存（`from`・`to`はモジュール名のglob、`from_type`・`to_type`はモジュールの種類）
+///
+/// globを使わずに両端のモジュール名を書いた依存は、コードから消えると「目標にあるがコードにない依存」として報告する。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AllowedEdge {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub from_type: Option<TypeFilter>,
+    pub to_type: Option<TypeFilter>,
+}
+
+/// `drift --bless`やHTTPサーバーの`/api/graph`が出力するグラフ
+#[derive(Deserialize)]
+struct GraphExport {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+}
+
+#[derive(Deserialize)]
+struct GraphNode {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct GraphEdge {
+    from: String,
+    to: String,
+}
+
+fn is_concrete(pattern: &str) -> bool {
+    !pattern.contains(['*', '?', '[', '{'])
+}
+
+fn matcher(pattern: &str) -> Result<GlobMatcher> {
+    Glob::new(pattern)
+        .map(|glob| glob.compile_matcher())
+        .map_err(|source| AnalyzerError::InvalidGlob { pattern: pattern.to_string(), source })
+}
+
+struct CompiledEdge<'t> {
+    edge: &'t AllowedEdge,
+    from: Option<GlobMatcher>,
+    to: Option<GlobMatcher>,
+}
+
+impl CompiledEdge<'_> {
+    fn permits(&self, from: &str, from_type: &ModuleType, to: &str, to_type: &ModuleType) -> bool {
+        self.from.as_ref().is_none_or(|m| m.is_match(from)) &&
+            self.to.as_ref().is_none_or(|m| m.is_match(to)) &&
+            self.edge.from_type.is_none_or(|t| t.matches(from_type)) &&
+            self.edge.to_type.is_none_or(|t| t.matches(to_type))
+    }
+}
+
+impl TargetArchitecture {
+    /// `.toml`なら目標の定義として、それ以外はグラフのJSONか解析結果のJSONとして読み込む
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|err| AnalyzerError::io(path, err))?;
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            return toml::from_str(&content).map_err(|err| AnalyzerError::parse(path, err.message().trim()));
+        }
+        if let Ok(graph) = serde_json::from_str::<GraphExport>(&content) {
+            return Ok(Self::blessed(graph.nodes.into_iter().map(|n| n.id), graph.edges.into_iter().map(|e| (e.from, e.to))));
+        }
+        let result: AnalysisResult = serde_json::from_str(&content).map_err(|err| AnalyzerError::parse(path, err))?;
+        Ok(Self::from_result(&result))
+    }
+
+    /// 現在の解析結果のモジュールと依存をそのまま目標にする
+    pub fn from_result(result: &AnalysisResult) -> Self {
+        let edges = result
+            .graph()
+            .edges()
+            .into_iter()
+            .map(|(from, to, _)| (from.to_string(), to.to_string()))
+            .collect::<Vec<_>>();
+        Self::blessed(result.modules.iter().map(|m| m.name.to_string()), edges)
+    }
+
+    fn blessed(modules: impl IntoIterator<Item = String>, edges: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            modules: Some(modules.into_iter().collect()),
+            allow: edges
+                .into_iter()
+                .map(|(from, to)| AllowedEdge { from: Some(from), to: Some(to), ..AllowedEdge::default() })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct DriftEdge {
+    pub from: String,
+    pub to: String,
+    pub lazy: bool,
+}
+
+/// コードと目標のアーキテクチャの差分
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DriftReport {
+    /// コードにあるが目標で許可されていない依存
+    pub unexpected_edges: Vec<DriftEdge>,
+    /// 目標に名前で書かれているがコードにない依存（遅延ロードかどうかは問わない）
+    pub missing_edges: Vec<(String, String)>,
+    /// 目標の`modules`のどれにも当てはまらないモジュール
+    pub unexpected_modules: Vec<String>,
+    /// 目標の`modules`に名前で書かれているがコードにないモジュール
+    pub missing_modules: Vec<String>,
+}
+
+impl DriftReport {
+    pub fn detect(result: &AnalysisResult, target: &TargetArchitecture) -> Result<Self> {
+        let graph = result.graph();
+        let types: HashMap<&str, &ModuleType> = result.modules
+            .iter()
+            .map(|m| (m.name.as_ref(), &m.module_type))
+            .collect();
+
+        let allow = target.allow
+            .iter()
+            .map(|edge| {
+                Ok(CompiledEdge {
+                    edge,
+                    from: edge.from.as_deref().map(matcher).transpose()?,
+                    to: edge.to.as_deref().map(matcher).transpose()?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let edges = graph.edges();
+        let unexpected_edges = edges
+            .iter()
+            .filter(|(from, to, _)| !allow.iter().any(|a| a.permits(from, types[from], to, types[to])))
+            .map(|&(from, to, kind)| DriftEdge { from: from.to_string(), to: to.to_string(), lazy: kind == EdgeKind::Lazy })
+            .collect();
+
+        let present: BTreeSet<(&str, &str)> = edges
+            .iter()
+            .map(|&(from, to, _)| (from, to))
+            .collect();
+        let missing_edges: BTreeSet<(String, String)> = target.allow
+            .iter()
+            .filter_map(|edge| Some((edge.from.as_deref()?, edge.to.as_deref()?)))
+            .filter(|(from, to)| is_concrete(from) && is_concrete(to) && !present.contains(&(*from, *to)))
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect();
+
+        let mut unexpected_modules = Vec::new();
+        let mut missing_modules = Vec::new();
+        if let Some(patterns) = &target.modules {
+            let matchers = patterns
+                .iter()
+                .map(|p| matcher(p))
+                .collect::<Result<Vec<_>>>()?;
+            unexpected_modules = result.modules
+                .iter()
+                .map(|m| m.name.to_string())
+                .filter(|name| !matchers.iter().any(|m| m.is_match(name)))
+                .collect();
+            unexpected_modules.sort();
+            missing_modules = patterns
+                .iter()
+                .filter(|p| is_concrete(p) && !graph.contains(p))
+                .cloned()
+                .collect();
+            missing_modules.sort();
+        }
+
+        Ok(DriftReport {
+            unexpected_edges,
+            missing_edges: missing_edges.into_iter().collect(),
+            unexpected_modules,
+            missing_modules,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unexpected_edges.is_empty() &&
+            self.missing_edges.is_empty() &&
+            self.unexpected_modules.is_empty() &&
+            self.missing_modules.is_empty()
+    }
+}
@@ -0,0 +1,117 @@
+use std::io;
+use std::path::{ Path, PathBuf };
+use thiserror::Error;
+
+use crate::config::ConfigIssue;
+
+/// ライブラリの公開APIが返すエラー
+///
+/// CLIはこれを`anyhow`に変換して表示するだけだが、組み込み側は種類ごとに分岐できる。
+#[derive(Debug, Error)]
+pub enum AnalyzerError {
+    /// ファイルの読み書きに失敗した
+    #[error("Failed to access {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// 最大サイズを超えるため解析対象から外したファイル
+    #[error("Skipped {} ({size} bytes exceeds the {limit} byte limit)", path.display())]
+    FileTooLarge {
+        path: PathBuf,
+        size: u64,
+        limit: u64,
+    },
+
+    /// minifyされたファイルや巨大なメタデータなど、1ファイルの解析の上限を超えたため解析対象から外したファイル
+    #[error("Skipped {} ({reason})", path.display())]
+    ExtractionLimit {
+        path: PathBuf,
+        reason: String,
+    },
+
+    /// ファイルの内容を解釈できなかった（ベースライン、履歴、設定ファイルの型エラーなど）
+    #[error("Invalid {file:?}: {cause}")]
+    Parse {
+        file: PathBuf,
+        cause: String,
+    },
+
+    /// 設定ファイルの検証で問題が見つかった
+    #[error("Invalid config {}:\n{}", path.display(), format_issues(path, issues))]
+    InvalidConfig {
+        path: PathBuf,
+        issues: Vec<ConfigIssue>,
+    },
+
+    /// 分類ルールのglobが不正
+    #[error("Invalid classification glob: {pattern}")]
+    InvalidGlob {
+        pattern: String,
+        #[source]
+        source: globset::Error,
+    },
+
+    /// 循環依存があるため依存順に並べられない
+    #[error("Circular dependency involving {module}")]
+    GraphCycle {
+        module: String,
+    },
+
+    /// 出力形式を決定できない、またはその形式では出力できない
+    #[error("{0}")]
+    UnsupportedFormat(String),
+
+    /// 上書きを許可されていない既存ファイル
+    #[error("{} already exists; use --force to overwrite it", .0.display())]
+    AlreadyExists(PathBuf),
+
+    /// [`CancellationToken`](crate::CancellationToken)によって解析が中断された
+    #[error("Analysis was cancelled")]
+    Cancelled,
+
+    /// 解析用のスレッドプールを作成できなかった
+    #[error("Failed to start worker threads: {0}")]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+
+    /// 通知のWebhookへの送信に失敗した（URLは秘密情報のため含めない）
+    #[error("Failed to post the notification: {0}")]
+    Webhook(String),
+
+    /// Prometheus Pushgatewayへの送信に失敗した
+    #[error("Failed to push metrics to the Pushgateway: {0}")]
+    PushGateway(String),
+
+    /// `[[plugins]]`のプラグインを実行できなかった、またはプラグインの結果が不正
+    #[error("Plugin {plugin} failed: {cause}")]
+    Plugin {
+        plugin: String,
+        cause: String,
+    },
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// [`AnalyzerError`]を既定のエラー型とする`Result`
+pub type Result<T, E = AnalyzerError> = std::result::Result<T, E>;
+
+impl AnalyzerError {
+    pub(crate) fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Self::Io { path: path.into(), source }
+    }
+
+    pub(crate) fn parse(file: impl Into<PathBuf>, cause: impl ToString) -> Self {
+        Self::Parse { file: file.into(), cause: cause.to_string() }
+    }
+}
+
+fn format_issues(path: &Path, issues: &[ConfigIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| format!("  {}:{}", path.display(), issue))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
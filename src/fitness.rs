@@ -0,0 +1,209 @@
+use globset::Glob;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::check::GateOutcome;
+use crate::list::TypeFilter;
+use crate::{ AnalysisResult, AnalyzerError, Result };
+
+/// プロジェクトルートに置くフィットネス関数の定義ファイル名
+pub const FITNESS_FILE_NAME: &str = "fitness.toml";
+
+/// フィットネス関数が検査する値
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FitnessMetric {
+    /// モジュール数
+    Modules,
+    Cycles,
+    Violations,
+    CouplingFactor,
+    HealthScore,
+    /// 依存の最大の深さ
+    MaxDepth,
+    /// モジュールごと: 直接依存しているモジュールの数
+    FanOut,
+    /// モジュールごと: 直接依存されているモジュールの数
+    FanIn,
+    /// モジュールごと: `of`の種類のモジュールのうち、直接依存しているものの割合（0〜1）
+    FanInRatio,
+}
+
+impl FitnessMetric {
+    fn per_module(self) -> bool {
+        matches!(self, FitnessMetric::FanOut | FitnessMetric::FanIn | FitnessMetric::FanInRatio)
+    }
+}
+
+/// `fitness.toml`の`[[fitness]]`1件（アーキテクチャの意図を実行できるアサーションとして書いたもの）
+///
+/// モジュールごとの値は、`module`（モジュール名のglob）と`type`で絞ったすべてのモジュールが満たす必要がある。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FitnessFunction {
+    pub name: String,
+    pub metric: FitnessMetric,
+    pub module: Option<String>,
+    #[serde(rename = "type")]
+    pub module_type: Option<TypeFilter>,
+    /// `fan_in_ratio`の分母になるモジュールの種類（未指定ならすべてのモジュール）
+    pub of: Option<TypeFilter>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub equals: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FitnessFile {
+    #[serde(default)]
+    fitness: Vec<FitnessFunction>,
+}
+
+/// 比較の条件を`<= 25`のように表示する
+struct Bounds<'f>(&'f FitnessFunction);
+
+impl fmt::Display for Bounds<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(equals) = self.0.equals {
+            parts.push(format!("== {}", equals));
+        }
+        if let Some(min) = self.0.min {
+            parts.push(format!(">= {}", min));
+        }
+        if let Some(max) = self.0.max {
+            parts.push(format!("<= {}", max));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+impl FitnessFunction {
+    fn holds(&self, value: f64) -> bool {
+        // 割合や結合度の丸め誤差で`equals = 1.0`が外れないようにする
+        self.equals.is_none_or(|equals| (value - equals).abs() < 1e-6) &&
+            self.min.is_none_or(|min| value >= min - 1e-6) &&
+            self.max.is_none_or(|max| value <= max + 1e-6)
+    }
+
+    fn evaluate(&self, result: &AnalysisResult) -> Result<GateOutcome> {
+        let graph = result.graph();
+        let metrics = &result.metrics;
+        let project_value = match self.metric {
+            FitnessMetric::Modules => Some(result.modules.len() as f64),
+            FitnessMetric::Cycles => Some(result.circular_dependencies.len() as f64),
+            FitnessMetric::Violations => Some(result.dependency_violations.len() as f64),
+            FitnessMetric::CouplingFactor => Some(f64::from(metrics.coupling_factor)),
+            FitnessMetric::HealthScore => Some(f64::from(metrics.health_score)),
+            FitnessMetric::MaxDepth => Some(graph.max_depth() as f64),
+            FitnessMetric::FanOut | FitnessMetric::FanIn | FitnessMetric::FanInRatio => None,
+        };
+        if let Some(value) = project_value {
+            return Ok(GateOutcome {
+                name: self.name.clone(),
+                passed: self.holds(value),
+                detail: format!("{} (expected {})", format_value(value), Bounds(self)),
+            });
+        }
+
+        let pattern = match &self.module {
+            Some(pattern) =>
+                Some(
+                    Glob::new(pattern)
+                        .map_err(|source| AnalyzerError::InvalidGlob { pattern: pattern.clone(), source })?
+                        .compile_matcher()
+                ),
+            None => None,
+        };
+        let denominator: Vec<&str> = result.modules
+            .iter()
+            .filter(|m| self.of.is_none_or(|of| of.matches(&m.module_type)))
+            .map(|m| m.name.as_ref())
+            .collect();
+
+        let mut checked = 0;
+        let mut failures = Vec::new();
+        for module in &result.modules {
+            if
+                pattern.as_ref().is_some_and(|p| !p.is_match(module.name.as_ref())) ||
+                self.module_type.is_some_and(|t| !t.matches(&module.module_type))
+            {
+                continue;
+            }
+            checked += 1;
+            let value = match self.metric {
+                FitnessMetric::FanOut => graph.dependencies_of(&module.name).len() as f64,
+                FitnessMetric::FanIn => graph.dependents_of(&module.name).len() as f64,
+                _ => {
+                    // 自分自身は分母に含めない
+                    let dependents = graph.dependents_of(&module.name);
+                    let candidates: Vec<&&str> = denominator
+                        .iter()
+                        .filter(|name| **name != module.name.as_ref())
+                        .collect();
+                    let count = candidates
+                        .iter()
+                        .filter(|name| dependents.contains(name))
+                        .count();
+                    if candidates.is_empty() { 1.0 } else { (count as f64) / (candidates.len() as f64) }
+                }
+            };
+            if !self.holds(value) {
+                failures.push(format!("{} = {}", module.name, format_value(value)));
+            }
+        }
+
+        let detail = if checked == 0 {
+            "no module matched".to_string()
+        } else if failures.is_empty() {
+            format!("{} modules (expected {})", checked, Bounds(self))
+        } else {
+            format!("{} of {} modules (expected {}): {}", failures.len(), checked, Bounds(self), failures.join(", "))
+        };
+        Ok(GateOutcome { name: self.name.clone(), passed: failures.is_empty(), detail })
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 { format!("{}", value) } else { format!("{:.2}", value) }
+}
+
+/// フィットネス関数の定義を読み込む
+pub fn load(path: &Path) -> Result<Vec<FitnessFunction>> {
+    let content = fs::read_to_string(path).map_err(|err| AnalyzerError::io(path, err))?;
+    let file: FitnessFile = toml::from_str(&content).map_err(|err| AnalyzerError::parse(path, err.message().trim()))?;
+    for function in &file.fitness {
+        let problem = if function.min.is_none() && function.max.is_none() && function.equals.is_none() {
+            Some("needs at least one of min, max or equals")
+        } else if !function.metric.per_module() && (function.module.is_some() || function.module_type.is_some()) {
+            Some("module and type only apply to fan_in, fan_out and fan_in_ratio")
+        } else if function.metric != FitnessMetric::FanInRatio && function.of.is_some() {
+            Some("of only applies to fan_in_ratio")
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return Err(AnalyzerError::parse(path, format!("fitness function {:?} {}", function.name, problem)));
+        }
+    }
+    Ok(file.fitness)
+}
+
+/// `--fitness`、なければプロジェクトルートの`fitness.toml`（存在しなければ`None`）
+pub fn default_path(project_path: &Path, explicit: Option<&Path>) -> Option<PathBuf> {
+    match explicit {
+        Some(path) => Some(path.to_path_buf()),
+        None => Some(project_path.join(FITNESS_FILE_NAME)).filter(|path| path.exists()),
+    }
+}
+
+/// フィットネス関数をすべて評価する（定義順）
+pub fn evaluate(result: &AnalysisResult, functions: &[FitnessFunction]) -> Result<Vec<GateOutcome>> {
+    functions
+        .iter()
+        .map(|function| function.evaluate(result))
+        .collect()
+}
@@ -0,0 +1,505 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{ HashMap, HashSet };
+use std::fmt;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use crate::graph::reference_name;
+use crate::source::{ Encoding, blank_comments, ngmodule_decorator, object_arrays, top_level_items };
+use crate::{ AnalysisResult, ExportKind, ModuleInfo, Result, source };
+
+static NAMED_IMPORT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^[ \t]*import\s*\{([^}]*)\}\s*from\s*["'][^"']*["']\s*;?[ \t]*\r?\n?"#).unwrap()
+});
+
+/// 自動修正の対象にするNgModuleメタデータの配列
+const FIELDS: [&str; 4] = ["declarations", "imports", "exports", "providers"];
+/// 並べ替えても意味が変わらない配列（`imports`と`providers`は順序で上書きやルートの照合順が変わる）
+const SORTABLE_FIELDS: [&str; 2] = ["declarations", "exports"];
+
+/// 自動修正の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FixKind {
+    /// 宣言しているコンポーネント類がどれも使っていないモジュールのimport
+    UnusedImport,
+    /// importしているモジュールのどれも使っていない宣言のexport
+    UnusedExport,
+    /// 同じ配列に2回以上書かれた要素
+    Duplicate,
+    /// 名前順に並んでいない配列
+    Unsorted,
+}
+
+impl fmt::Display for FixKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            FixKind::UnusedImport => "remove unused import",
+            FixKind::UnusedExport => "remove unused export",
+            FixKind::Duplicate => "remove duplicate",
+            FixKind::Unsorted => "sort",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// 1件の修正
+#[derive(Debug, Clone, Serialize)]
+pub struct Fix {
+    pub module: String,
+    /// `imports`・`exports`などのメタデータの配列
+    pub field: String,
+    pub kind: FixKind,
+    /// 取り除く要素（並べ替えでは`None`）
+    pub entry: Option<String>,
+}
+
+/// 1つのファイルへの修正（`fixed`を書き込めば適用できる）
+#[derive(Debug, Clone)]
+pub struct FileFix {
+    pub path: PathBuf,
+    pub original: String,
+    pub fixed: String,
+    pub fixes: Vec<Fix>,
+}
+
+impl FileFix {
+    /// 修正前後の差分（`label`はdiffのヘッダーに書くパス）
+    pub fn diff(&self, label: &str) -> String {
+        unified_diff(label, &self.original, &self.fixed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixOptions {
+    /// `declarations`と`exports`を名前順に並べる
+    pub sort: bool,
+}
+
+/// NgModuleのファイルごとに、安全に適用できる修正を求める
+///
+/// 未使用のimport・exportは[`crate::AnalyzerBuilder::components`]で集めたグラフから判断する（なければ取り除かない）。
+/// UTF-16のファイルは元の文字コードで書き戻せないので直さない。
+pub fn plan(result: &AnalysisResult, options: FixOptions) -> Result<Vec<FileFix>> {
+    let mut files = Vec::new();
+    let mut boms = HashSet::new();
+    for module in &result.modules {
+        let content = source::read(&module.path, Some(source::DEFAULT_MAX_FILE_SIZE))?;
+        match content.encoding {
+            // 書き戻すとUTF-8になってしまうので直さない
+            Encoding::Utf16 => continue,
+            Encoding::Utf8Bom => {
+                boms.insert(module.path.as_path());
+            }
+            Encoding::Utf8 => {}
+        }
+        files.push(ModuleFile::new(module, content.to_string()));
+    }
+    let unused = Unused::find(result, &files);
+
+    let mut fixed_files = Vec::new();
+    for file in &files {
+        let module = file.module.name.as_ref();
+        let mut fixes = Vec::new();
+        let mut replacements = Vec::new();
+        for (field, array) in &file.arrays {
+            let inner = &file.content[array.clone()];
+            // コメントを含む配列は要素と一緒に動かせないので触らない
+            if inner != &file.code[array.clone()] {
+                continue;
+            }
+            let items = file.entries(field);
+
+            let mut kept: Vec<&str> = Vec::new();
+            for (index, item) in items.iter().enumerate() {
+                let removed = match *field {
+                    _ if items[..index].iter().any(|earlier| same_entry(earlier, item)) => Some(FixKind::Duplicate),
+                    "imports" if unused.imports.contains(&(module, *item)) => Some(FixKind::UnusedImport),
+                    "exports" if unused.exports.contains(&(module, *item)) => Some(FixKind::UnusedExport),
+                    _ => None,
+                };
+                match removed {
+                    Some(kind) => fixes.push(Fix {
+                        module: module.to_string(),
+                        field: field.to_string(),
+                        kind,
+                        entry: Some(item.to_string()),
+                    }),
+                    None => kept.push(item),
+                }
+            }
+            // 展開（`...COMPONENTS`）を含む配列は並べ替えない
+            if options.sort && SORTABLE_FIELDS.contains(field) && !kept.iter().any(|item| item.starts_with("...")) {
+                let mut sorted = kept.clone();
+                sorted.sort();
+                if sorted != kept {
+                    fixes.push(Fix { module: module.to_string(), field: field.to_string(), kind: FixKind::Unsorted, entry: None });
+                    kept = sorted;
+                }
+            }
+
+            if kept != items {
+                replacements.push((array.clone(), render_array(inner, &kept)));
+            }
+        }
+
+        if !replacements.is_empty() {
+            let mut fixed = file.content.clone();
+            for (range, text) in replacements.into_iter().rev() {
+                fixed.replace_range(range, &text);
+            }
+            for fix in fixes.iter().filter(|fix| fix.kind == FixKind::UnusedImport) {
+                if let Some(entry) = &fix.entry {
+                    fixed = remove_import_statement(&fixed, entry);
+                }
+            }
+            // BOMは解析のために除いているので、書き戻すときに付け直す
+            let bom = if boms.contains(file.module.path.as_path()) { "\u{FEFF}" } else { "" };
+            fixed_files.push(FileFix {
+                path: file.module.path.clone(),
+                original: format!("{}{}", bom, file.content),
+                fixed: format!("{}{}", bom, fixed),
+                fixes,
+            });
+        }
+    }
+    Ok(fixed_files)
+}
+
+/// NgModuleのファイルと、メタデータの配列の位置
+///
+/// 配列を書き換えるため、括弧の対応から配列の範囲を求め直す。
+struct ModuleFile<'r> {
+    module: &'r ModuleInfo,
+    content: String,
+    /// コメントを空白に置き換えた内容（バイト位置は`content`と同じ）
+    code: String,
+    arrays: Vec<(&'static str, Range<usize>)>,
+}
+
+impl<'r> ModuleFile<'r> {
+    fn new(module: &'r ModuleInfo, content: String) -> Self {
+        let code = blank_comments(&content);
+        let arrays = metadata_arrays(&code);
+        ModuleFile { module, content, code, arrays }
+    }
+
+    /// 配列の要素（コメントを除いたもの）
+    fn entries(&self, field: &str) -> Vec<&str> {
+        self.arrays
+            .iter()
+            .filter(|(f, _)| *f == field)
+            .flat_map(|(_, array)| {
+                let inner = &self.code[array.clone()];
+                top_level_items(inner).into_iter().map(move |range| &inner[range])
+            })
+            .collect()
+    }
+
+    /// 配列の要素の参照名（`RouterModule.forChild(routes)`は`RouterModule`）
+    fn names(&self, field: &str) -> HashSet<&str> {
+        self.entries(field).into_iter().map(reference_name).collect()
+    }
+}
+
+/// 未使用と判断したimport・export（モジュール名, 配列の要素）
+#[derive(Default)]
+struct Unused<'f> {
+    imports: HashSet<(&'f str, &'f str)>,
+    exports: HashSet<(&'f str, &'f str)>,
+}
+
+impl<'f> Unused<'f> {
+    fn find(result: &AnalysisResult, files: &'f [ModuleFile<'_>]) -> Self {
+        let mut unused = Unused::default();
+        let Some(components) = &result.components else {
+            return unused;
+        };
+
+        let by_name: HashMap<&str, &ModuleFile> = files
+            .iter()
+            .map(|file| (file.module.name.as_ref(), file))
+            .collect();
+        let known: HashSet<&str> = components.declarables
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        let mut declared_in: HashMap<&str, &str> = HashMap::new();
+        for file in files {
+            for name in file.names("declarations") {
+                declared_in.insert(name, file.module.name.as_ref());
+            }
+        }
+        let mut uses: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for edge in &components.edges {
+            uses.entry(edge.from.as_str()).or_default().insert(edge.to.as_str());
+        }
+
+        for file in files {
+            let module = file.module.name.as_ref();
+            let declarations = file.names("declarations");
+            // 宣言を1つでも読み取れていなければ、テンプレートで何を使っているか分からない
+            if !declarations.iter().all(|d| known.contains(d)) {
+                continue;
+            }
+
+            let exports = file.names("exports");
+            for entry in file.entries("imports") {
+                // `import { SharedModule as UiModule }`のような別名は、元のクラス名でたどる
+                let name = imported_name(&file.code, entry).unwrap_or(entry);
+                let Some(imported) = by_name.get(name).filter(|other| other.module.name.as_ref() != module) else {
+                    continue;
+                };
+                // プロバイダーは、テンプレートからは使われているか分からない
+                if !imported.entries("providers").is_empty() || exports.contains(entry) {
+                    continue;
+                }
+                let Some(offered) = offered_declarables(imported, &by_name, &known, &mut HashSet::new()) else {
+                    continue;
+                };
+                if offered.is_empty() {
+                    continue;
+                }
+                let used = declarations
+                    .iter()
+                    .any(|d| uses.get(d).is_some_and(|targets| targets.iter().any(|t| offered.contains(t))));
+                if !used {
+                    unused.imports.insert((module, entry));
+                }
+            }
+
+            let importers: Vec<&ModuleFile> = files
+                .iter()
+                .filter(|other| other.names("imports").contains(module))
+                .collect();
+            // 再exportされていれば、その先で使われているかもしれない
+            if importers.is_empty() || importers.iter().any(|other| other.names("exports").contains(module)) {
+                continue;
+            }
+            for entry in file.entries("exports") {
+                if !declarations.contains(entry) {
+                    continue;
+                }
+                let used_outside = uses
+                    .iter()
+                    .any(|(from, targets)| targets.contains(entry) && declared_in.get(from) != Some(&module));
+                // ルート定義などで他のモジュールのファイルから参照していれば残す
+                let pattern = Regex::new(&format!(r"\b{}\b", regex::escape(entry))).unwrap();
+                let mentioned = files
+                    .iter()
+                    .any(|other| other.module.path != file.module.path && pattern.is_match(&other.code));
+                if !used_outside && !mentioned {
+                    unused.exports.insert((module, entry));
+                }
+            }
+        }
+        unused
+    }
+}
+
+/// `file`のモジュールをimportすると使えるようになる宣言
+///
+/// 再exportしているプロジェクト内のモジュールは、その`exports`もたどる。
+/// パッケージのモジュールやグラフにない宣言を再exportしていれば、何が使われているか分からないので`None`を返す。
+fn offered_declarables<'f>(
+    file: &'f ModuleFile,
+    by_name: &HashMap<&str, &'f ModuleFile>,
+    known: &HashSet<&str>,
+    visited: &mut HashSet<&'f str>
+) -> Option<HashSet<&'f str>> {
+    let mut offered = HashSet::new();
+    if !visited.insert(file.module.name.as_ref()) {
+        return Some(offered);
+    }
+    let kinds: HashMap<&str, ExportKind> = file.module.export_kinds
+        .iter()
+        .map(|export| (export.name.as_ref(), export.kind))
+        .collect();
+    for name in file.names("exports") {
+        match kinds.get(name) {
+            Some(ExportKind::Module) => {
+                let module = by_name.get(name)?;
+                if !module.entries("providers").is_empty() {
+                    return None;
+                }
+                offered.extend(offered_declarables(module, by_name, known, visited)?);
+            }
+            Some(ExportKind::Declarable) if known.contains(name) => {
+                offered.insert(name);
+            }
+            // 複数行の配列などで解析結果にない要素は、自分で宣言していればコンポーネントなどとみなす
+            None if known.contains(name) && file.names("declarations").contains(name) => {
+                offered.insert(name);
+            }
+            _ => {
+                return None;
+            }
+        }
+    }
+    Some(offered)
+}
+
+/// import文で`Foo as local`と別名を付けていれば、元の名前（`Foo`）
+fn imported_name<'c>(code: &'c str, local: &str) -> Option<&'c str> {
+    NAMED_IMPORT_REGEX.captures_iter(code).find_map(|cap| {
+        let list = cap.get(1)?.as_str();
+        list.split(',').find_map(|specifier| {
+            let mut words = specifier.split_whitespace();
+            match (words.next(), words.next(), words.next()) {
+                (Some(name), Some("as"), Some(alias)) if alias == local => Some(name),
+                _ => None,
+            }
+        })
+    })
+}
+
+/// 取り除いたimportの名前を他で使っていなければ、import文からも取り除く
+fn remove_import_statement(content: &str, name: &str) -> String {
+    let pattern = Regex::new(&format!(r"\b{}\b", regex::escape(name))).unwrap();
+    if pattern.find_iter(content).count() != 1 {
+        return content.to_string();
+    }
+    let Some(cap) = NAMED_IMPORT_REGEX.captures_iter(content).find(|cap| pattern.is_match(&cap[1])) else {
+        return content.to_string();
+    };
+    let statement = cap.get(0).unwrap();
+    let names: Vec<&str> = cap[1]
+        .split(',')
+        .map(str::trim)
+        // `Foo as Bar`は配列に書く名前（`Bar`）で照合する
+        .filter(|n| !n.is_empty() && n.split_whitespace().last() != Some(name))
+        .collect();
+    let replacement = if names.is_empty() {
+        String::new()
+    } else {
+        let list = cap.get(1).unwrap();
+        let padding = if list.as_str().starts_with(' ') { " " } else { "" };
+        format!(
+            "{}{}{}{}{}",
+            &content[statement.start()..list.start()],
+            padding,
+            names.join(", "),
+            padding,
+            &content[list.end()..statement.end()]
+        )
+    };
+    format!("{}{}{}", &content[..statement.start()], replacement, &content[statement.end()..])
+}
+
+/// 空白の違いを無視して同じ要素か
+fn same_entry(a: &str, b: &str) -> bool {
+    a.split_whitespace().eq(b.split_whitespace())
+}
+
+/// `@NgModule({...})`の最上位にある`declarations`・`imports`・`exports`・`providers`の配列の中身の範囲
+///
+/// ルート定義の中の`providers`のように、入れ子のオブジェクトにある同名のプロパティは含めない。
+fn metadata_arrays(content: &str) -> Vec<(&'static str, Range<usize>)> {
+    let Some(decorator) = ngmodule_decorator(content) else {
+        return Vec::new();
+    };
+    object_arrays(content, decorator.metadata, &FIELDS)
+}
+
+/// 配列の中身を`items`で書き直す（複数行なら1行に1要素、1行なら元の余白と区切りを保つ）
+fn render_array(inner: &str, items: &[&str]) -> String {
+    if items.is_empty() {
+        return String::new();
+    }
+    if !inner.contains('\n') {
+        let leading = &inner[..inner.len() - inner.trim_start().len()];
+        let trailing = &inner[inner.trim_end().len()..];
+        return format!("{}{}{}", leading, items.join(", "), trailing);
+    }
+
+    let first = inner.len() - inner.trim_start().len();
+    let line_start = inner[..first].rfind('\n').map_or(0, |i| i + 1);
+    let indent = &inner[line_start..first];
+    let closing_indent = inner.rsplit('\n').next().filter(|last| last.trim().is_empty()).unwrap_or("");
+    let trailing_comma = inner.trim_end().ends_with(',');
+
+    let mut text = String::from("\n");
+    for (i, item) in items.iter().enumerate() {
+        text.push_str(indent);
+        text.push_str(item);
+        if i + 1 < items.len() || trailing_comma {
+            text.push(',');
+        }
+        text.push('\n');
+    }
+    text.push_str(closing_indent);
+    text
+}
+
+/// 行単位のunified diff（前後3行の文脈つき、差分がなければ空文字列）
+pub fn unified_diff(label: &str, old: &str, new: &str) -> String {
+    const CONTEXT: usize = 3;
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // 最長共通部分列の長さの表から、削除・追加・一致の列を組み立てる
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    // （記号, 旧の行番号, 新の行番号）
+    let mut ops: Vec<(char, usize, usize)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((' ', i, j));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', i, j));
+            i += 1;
+        } else {
+            ops.push(('+', i, j));
+            j += 1;
+        }
+    }
+
+    // 変更の前後CONTEXT行ずつを含む範囲をまとめてハンクにする
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _, _))| *op != ' ')
+        .map(|(index, _)| index)
+        .collect();
+    let mut hunks: Vec<Range<usize>> = Vec::new();
+    for index in changed {
+        let start = index.saturating_sub(CONTEXT);
+        let end = (index + CONTEXT + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.end => {
+                last.end = end;
+            }
+            _ => hunks.push(start..end),
+        }
+    }
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", label, label);
+    for hunk in hunks {
+        let ops = &ops[hunk];
+        let old_count = ops.iter().filter(|(op, _, _)| *op != '+').count();
+        let new_count = ops.iter().filter(|(op, _, _)| *op != '-').count();
+        let (_, old_start, new_start) = ops[0];
+        // 行数が0の範囲は直前の行番号で表す
+        let old_start = if old_count == 0 { old_start } else { old_start + 1 };
+        let new_start = if new_count == 0 { new_start } else { new_start + 1 };
+        diff.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start, old_count, new_start, new_count));
+        for &(op, i, j) in ops {
+            let line = if op == '+' { new[j] } else { old[i] };
+            diff.push_str(&format!("{}{}\n", op, line));
+        }
+    }
+    diff
+}
+
@@ -0,0 +1,140 @@
+use anyhow::{ Context, Result };
+use colored::*;
+use std::path::{ Path, PathBuf };
+use std::process::Command;
+
+/// 別のリビジョンを一時的なworktreeに展開したもの（dropで削除する）
+pub struct Checkout {
+    repo: PathBuf,
+    dir: PathBuf,
+    /// worktree内でのプロジェクトのパス
+    pub project: PathBuf,
+}
+
+impl Checkout {
+    /// `project`を含むリポジトリの`revision`をチェックアウトする（作業ツリーには触れない）
+    pub fn new(project: &Path, revision: &str) -> Result<Self> {
+        let project = project.canonicalize().with_context(|| format!("Failed to open project {:?}", project))?;
+        let toplevel = git(&project, &["rev-parse", "--show-toplevel"])
+            .with_context(|| format!("{} is not inside a git repository", project.display()))?;
+        let repo = PathBuf::from(toplevel.trim()).canonicalize()?;
+        let relative = project.strip_prefix(&repo).unwrap_or(Path::new("")).to_path_buf();
+
+        let dir = std::env::temp_dir().join(format!("angular-analyzer-{}-{}", std::process::id(), sanitize(revision)));
+        git(&repo, &["worktree", "add", "--detach", "--quiet", &dir.to_string_lossy(), revision]).with_context(||
+            format!("Failed to check out {}", revision)
+        )?;
+        Ok(Self { project: dir.join(relative), repo, dir })
+    }
+}
+
+impl Drop for Checkout {
+    fn drop(&mut self) {
+        if let Err(err) = git(&self.repo, &["worktree", "remove", "--force", &self.dir.to_string_lossy()]) {
+            eprintln!("{} failed to remove {}: {:#}", "warning:".yellow().bold(), self.dir.display(), err);
+        }
+    }
+}
+
+/// リモートのリポジトリを一時ディレクトリに浅くcloneしたもの（dropで削除する）
+pub struct RemoteClone {
+    pub dir: PathBuf,
+}
+
+impl RemoteClone {
+    /// `url`の`reference`（ブランチ・タグ・コミット、未指定ならリモートのHEAD）だけを取得する
+    pub fn new(url: &str, reference: Option<&str>) -> Result<Self> {
+        let reference = reference.unwrap_or("HEAD");
+        // `--upload-pack=...`などをgitのオプションとして渡さない
+        if url.starts_with('-') || reference.starts_with('-') {
+            anyhow::bail!("Invalid repository URL or reference: {} {}", url, reference);
+        }
+        // ここから先で失敗してもdropで削除されるようにする
+        let clone = Self { dir: create_temp_dir("clone")? };
+        git(&clone.dir, &["init", "--quiet"])?;
+        // `clone --branch`はコミットハッシュを受け付けないため、fetchで1つのリビジョンだけを取得する
+        git(&clone.dir, &["fetch", "--quiet", "--depth", "1", "--end-of-options", url, reference]).with_context(||
+            format!("Failed to fetch {} from {}", reference, url)
+        )?;
+        git(&clone.dir, &["checkout", "--quiet", "--detach", "FETCH_HEAD"])?;
+        Ok(clone)
+    }
+}
+
+impl Drop for RemoteClone {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_dir_all(&self.dir) {
+            eprintln!("{} failed to remove {}: {}", "warning:".yellow().bold(), self.dir.display(), err);
+        }
+    }
+}
+
+/// `range`（`HEAD~1..HEAD`や`main...`、単一のリビジョンなら作業ツリーとの差分）で変更された`project`以下のファイル
+///
+/// パスは`project`からの相対パス。
+pub fn changed_files(project: &Path, range: &str) -> Result<Vec<PathBuf>> {
+    let output = git(project, &["diff", "--name-only", "--relative", range, "--"]).with_context(||
+        format!("Failed to diff {}", range)
+    )?;
+    Ok(output.lines().filter(|line| !line.is_empty()).map(PathBuf::from).collect())
+}
+
+/// `since`（`"6 months ago"`や`2024-01-01`）以降のHEADの第1親をたどったコミットを、古い順に（ハッシュ, コミット日時）で返す
+pub fn commits_since(project: &Path, since: &str) -> Result<Vec<(String, u64)>> {
+    let output = git(project, &["log", "--first-parent", "--reverse", "--format=%H %ct", &format!("--since={}", since), "HEAD"])?;
+    output
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(hash, time)| Ok((hash.to_string(), time.trim().parse().context("Unexpected git log output")?)))
+        .collect()
+}
+
+/// 一時ディレクトリの中に、他のプロセスと重ならない新しいディレクトリを作る（既にあれば別の名前で作り直す）
+fn create_temp_dir(label: &str) -> Result<PathBuf> {
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    let nanos = std::time::SystemTime
+        ::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.subsec_nanos());
+    for attempt in 0..100u32 {
+        let dir = std::env
+            ::temp_dir()
+            .join(format!("angular-analyzer-{}-{}-{:08x}", std::process::id(), label, nanos.wrapping_add(attempt)));
+        match builder.create(&dir) {
+            Ok(()) => {
+                return Ok(dir);
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                continue;
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to create {}", dir.display()));
+            }
+        }
+    }
+    anyhow::bail!("Failed to create a temporary directory in {}", std::env::temp_dir().display())
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command
+        ::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// ディレクトリ名に使えるようにリビジョン名の記号を置き換える
+fn sanitize(revision: &str) -> String {
+    revision
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,566 @@
+use petgraph::graph::{ DiGraph, NodeIndex };
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::hash_map::Entry;
+use rayon::prelude::*;
+use std::collections::{ HashMap, HashSet, VecDeque };
+use std::path::Path;
+
+use crate::{ AnalysisResult, AnalyzerError, ModuleInfo, Result, ViolationType };
+
+/// 依存エッジの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EdgeKind {
+    /// NgModuleのimportsやimport文による依存（起動時に読み込まれる）
+    Eager,
+    /// `loadChildren`による遅延ロード
+    Lazy,
+}
+
+/// [`AnalysisResult::dependency_graph`]のエッジの重み
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyEdge {
+    pub kind: EdgeKind,
+    /// この依存がレイヤールールに違反している場合はその種類
+    pub violation: Option<ViolationType>,
+}
+
+/// 循環の列挙の上限（`None`は無制限）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CycleLimits {
+    /// 報告する循環の最大数
+    pub max_cycles: Option<usize>,
+    /// 列挙する循環の最大の長さ（モジュール数）
+    pub max_length: Option<usize>,
+}
+
+/// [`ModuleGraph::cycles_within`]の結果
+#[derive(Debug, Clone, Default)]
+pub struct CycleSearch<'a> {
+    pub cycles: Vec<Vec<&'a str>>,
+    /// 上限に達して列挙を打ち切った場合はtrue（報告されていない循環がある）
+    pub truncated: bool,
+}
+
+/// 強連結成分内の循環を深さ優先で列挙する
+struct CycleCollector<'g> {
+    adjacency: &'g [Vec<usize>],
+    limits: CycleLimits,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    found: Vec<Vec<usize>>,
+    truncated: bool,
+    /// `max_cycles`を超える循環が見つかり、列挙を止めた
+    exhausted: bool,
+}
+
+impl CycleCollector<'_> {
+    fn collect(&mut self, start: usize) {
+        let current = *self.stack.last().unwrap();
+        for &next in &self.adjacency[current] {
+            if next < start || (next != start && self.on_stack[next]) {
+                continue;
+            }
+            if next == start {
+                // 上限に達した後に実際に次の循環が見つかった場合だけ打ち切る
+                if self.limits.max_cycles.is_some_and(|max| self.found.len() >= max) {
+                    self.truncated = true;
+                    self.exhausted = true;
+                    return;
+                }
+                self.found.push(self.stack.clone());
+            } else if self.limits.max_length.is_some_and(|max| self.stack.len() >= max) {
+                // これ以上伸ばすと上限より長い循環になるので、startへ戻れる場合だけ打ち切りとする
+                if !self.truncated && self.returns_to_start(next, start) {
+                    self.truncated = true;
+                }
+            } else {
+                self.stack.push(next);
+                self.on_stack[next] = true;
+                self.collect(start);
+                self.on_stack[next] = false;
+                self.stack.pop();
+            }
+            if self.exhausted {
+                return;
+            }
+        }
+    }
+
+    /// `from`からスタック上のノードを通らずに`start`へ戻れるか（戻れれば上限より長い循環がある）
+    fn returns_to_start(&self, from: usize, start: usize) -> bool {
+        let mut visited = self.on_stack.clone();
+        visited[from] = true;
+        let mut queue = vec![from];
+        while let Some(current) = queue.pop() {
+            for &next in &self.adjacency[current] {
+                if next == start {
+                    return true;
+                }
+                if next > start && !visited[next] {
+                    visited[next] = true;
+                    queue.push(next);
+                }
+            }
+        }
+        false
+    }
+}
+
+/// モジュール間の有向依存グラフ
+pub struct ModuleGraph<'a> {
+    graph: DiGraph<&'a ModuleInfo, EdgeKind>,
+    node_indices: HashMap<&'a str, NodeIndex>,
+}
+
+impl<'a> ModuleGraph<'a> {
+    pub fn new(modules: &'a [ModuleInfo]) -> Self {
+        let mut graph = DiGraph::new();
+        let mut node_indices = HashMap::new();
+
+        let mut path_indices: HashMap<&Path, NodeIndex> = HashMap::new();
+        for module in modules {
+            let idx = graph.add_node(module);
+            node_indices.insert(module.name.as_ref(), idx);
+            path_indices.insert(module.path.as_path(), idx);
+        }
+
+        for module in modules {
+            let from_idx = node_indices[module.name.as_ref()];
+            let references = module_references(module)
+                .into_iter()
+                .map(|(path, dep)| (path, dep, EdgeKind::Eager))
+                .chain(module.lazy_imports.iter().map(|dep| (None, dep.as_ref(), EdgeKind::Lazy)));
+            // 同じモジュールへのEagerとLazyの両方の参照がある場合はEagerを優先する
+            for (path, dep, kind) in references {
+                // 参照先のファイルが分かっていれば、同じクラス名の別のモジュールと取り違えない
+                let to_idx = match path {
+                    Some(path) => path_indices.get(path),
+                    None => node_indices.get(dep),
+                };
+                let Some(&to_idx) = to_idx else {
+                    continue;
+                };
+                if from_idx == to_idx {
+                    continue;
+                }
+                match graph.find_edge(from_idx, to_idx) {
+                    None => {
+                        graph.add_edge(from_idx, to_idx, kind);
+                    }
+                    Some(edge) if kind == EdgeKind::Eager => {
+                        graph[edge] = EdgeKind::Eager;
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        Self { graph, node_indices }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.node_indices.contains_key(name)
+    }
+
+    fn name(&self, idx: NodeIndex) -> &'a str {
+        &self.graph[idx].name
+    }
+
+    /// 内部のpetgraphを返す（ノードはモジュール、エッジは依存の種類）
+    ///
+    /// 独自のグラフアルゴリズムを適用したい場合に使う。
+    pub fn petgraph(&self) -> &DiGraph<&'a ModuleInfo, EdgeKind> {
+        &self.graph
+    }
+
+    /// モジュール名に対応するノード
+    pub fn node_index(&self, name: &str) -> Option<NodeIndex> {
+        self.node_indices.get(name).copied()
+    }
+
+    /// `from`から`to`への最短経路をすべて返す（同じ長さの経路が複数ある場合も含む）
+    pub fn shortest_paths(&self, from: &str, to: &str) -> Vec<Vec<&'a str>> {
+        self.shortest_paths_via(from, to, |_| true)
+    }
+
+    /// 遅延ロードを経由しない最短経路をすべて返す
+    pub fn eager_shortest_paths(&self, from: &str, to: &str) -> Vec<Vec<&'a str>> {
+        self.shortest_paths_via(from, to, |kind| kind == EdgeKind::Eager)
+    }
+
+    pub fn edge_kind(&self, from: &str, to: &str) -> Option<EdgeKind> {
+        let edge = self.graph.find_edge(*self.node_indices.get(from)?, *self.node_indices.get(to)?)?;
+        Some(self.graph[edge])
+    }
+
+    fn shortest_paths_via(
+        &self,
+        from: &str,
+        to: &str,
+        follow: impl Fn(EdgeKind) -> bool
+    ) -> Vec<Vec<&'a str>> {
+        let (Some(&start), Some(&goal)) = (self.node_indices.get(from), self.node_indices.get(to)) else {
+            return Vec::new();
+        };
+
+        // BFSで各ノードへの最短距離と直前ノードを記録
+        let mut distance: HashMap<NodeIndex, usize> = HashMap::new();
+        let mut parents: HashMap<NodeIndex, Vec<NodeIndex>> = HashMap::new();
+        let mut queue = VecDeque::new();
+        distance.insert(start, 0);
+        queue.push_back(start);
+
+        while let Some(node) = queue.pop_front() {
+            let next_distance = distance[&node] + 1;
+            if distance.get(&goal).is_some_and(|&d| next_distance > d) {
+                break;
+            }
+            for edge in self.graph.edges_directed(node, Direction::Outgoing) {
+                if !follow(*edge.weight()) {
+                    continue;
+                }
+                let next = edge.target();
+                match distance.get(&next) {
+                    None => {
+                        distance.insert(next, next_distance);
+                        parents.entry(next).or_default().push(node);
+                        queue.push_back(next);
+                    }
+                    Some(&d) if d == next_distance => {
+                        parents.entry(next).or_default().push(node);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if start == goal {
+            return vec![vec![self.name(start)]];
+        }
+        if !distance.contains_key(&goal) {
+            return Vec::new();
+        }
+
+        let mut paths = Vec::new();
+        let mut stack = vec![goal];
+        self.collect_paths(start, &parents, &mut stack, &mut paths);
+        paths.sort();
+        paths
+    }
+
+    fn collect_paths(
+        &self,
+        start: NodeIndex,
+        parents: &HashMap<NodeIndex, Vec<NodeIndex>>,
+        stack: &mut Vec<NodeIndex>,
+        paths: &mut Vec<Vec<&'a str>>
+    ) {
+        let current = *stack.last().unwrap();
+        if current == start {
+            paths.push(
+                stack
+                    .iter()
+                    .rev()
+                    .map(|&idx| self.name(idx))
+                    .collect()
+            );
+            return;
+        }
+        for &parent in parents.get(&current).into_iter().flatten() {
+            stack.push(parent);
+            self.collect_paths(start, parents, stack, paths);
+            stack.pop();
+        }
+    }
+
+    /// すべての依存エッジ（依存元, 依存先）を名前順で返す
+    pub fn edges(&self) -> Vec<(&'a str, &'a str, EdgeKind)> {
+        let mut edges: Vec<(&'a str, &'a str, EdgeKind)> = self.graph
+            .edge_references()
+            .map(|edge| (self.name(edge.source()), self.name(edge.target()), *edge.weight()))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    /// `name`と一緒に読み込まれるモジュール（`name`自身と、遅延ロードを経由せずにたどれるモジュール。名前順）
+    pub fn eager_closure(&self, name: &str) -> Vec<&'a str> {
+        let Some(&start) = self.node_indices.get(name) else {
+            return Vec::new();
+        };
+        let mut visited = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for edge in self.graph.edges_directed(node, Direction::Outgoing) {
+                if *edge.weight() == EdgeKind::Eager && visited.insert(edge.target()) {
+                    stack.push(edge.target());
+                }
+            }
+        }
+        let mut names: Vec<&'a str> = visited
+            .into_iter()
+            .map(|n| self.name(n))
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// `name`が直接依存しているモジュール
+    pub fn dependencies_of(&self, name: &str) -> Vec<&'a str> {
+        self.neighbors(name, Direction::Outgoing)
+    }
+
+    /// `name`に直接依存しているモジュール
+    pub fn dependents_of(&self, name: &str) -> Vec<&'a str> {
+        self.neighbors(name, Direction::Incoming)
+    }
+
+    fn neighbors(&self, name: &str, direction: Direction) -> Vec<&'a str> {
+        let Some(&idx) = self.node_indices.get(name) else {
+            return Vec::new();
+        };
+        let mut names: Vec<&'a str> = self.graph
+            .neighbors_directed(idx, direction)
+            .map(|n| self.name(n))
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// `name`に直接・推移的に依存しているモジュールを、依存の深さとともに返す
+    pub fn dependents(&self, name: &str) -> Vec<(&'a str, usize)> {
+        let Some(&start) = self.node_indices.get(name) else {
+            return Vec::new();
+        };
+
+        let mut depths: HashMap<NodeIndex, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        depths.insert(start, 0);
+        queue.push_back(start);
+
+        while let Some(node) = queue.pop_front() {
+            let depth = depths[&node] + 1;
+            for dependent in self.graph.neighbors_directed(node, Direction::Incoming) {
+                if let Entry::Vacant(entry) = depths.entry(dependent) {
+                    entry.insert(depth);
+                    queue.push_back(dependent);
+                }
+            }
+        }
+
+        let mut dependents: Vec<(&'a str, usize)> = depths
+            .into_iter()
+            .filter(|&(idx, _)| idx != start)
+            .map(|(idx, depth)| (self.name(idx), depth))
+            .collect();
+        dependents.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+        dependents
+    }
+
+    /// 依存グラフ中の循環（elementary cycle）をすべて列挙する
+    ///
+    /// 各循環は強連結成分内で最小のノードから始まり、同じ循環が重複して報告されることはない。
+    /// 大きな強連結成分では循環の数が爆発的に増えるため、上限を設ける場合は[`cycles_within`](Self::cycles_within)を使う。
+    pub fn cycles(&self) -> Vec<Vec<&'a str>> {
+        self.cycles_within(CycleLimits::default()).cycles
+    }
+
+    /// 上限付きで循環を列挙する
+    ///
+    /// 強連結成分ごとに独立して（rayonで並列に）列挙し、名前順に並べてから`max_cycles`件に切り詰める。
+    /// 成分ごとの列挙順は決まっているため、上限に達した場合も結果は実行ごとに変わらない。
+    pub fn cycles_within(&self, limits: CycleLimits) -> CycleSearch<'a> {
+        let components: Vec<Vec<NodeIndex>> = petgraph::algo
+            ::tarjan_scc(&self.graph)
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .collect();
+        let found: Vec<(Vec<Vec<&'a str>>, bool)> = components
+            .into_par_iter()
+            .map(|component| self.component_cycles(component, limits))
+            .collect();
+
+        let truncated = found.iter().any(|(_, truncated)| *truncated);
+        let mut cycles: Vec<Vec<&'a str>> = found
+            .into_iter()
+            .flat_map(|(cycles, _)| cycles)
+            .collect();
+        cycles.sort();
+        let truncated = match limits.max_cycles {
+            Some(max) if cycles.len() > max => {
+                cycles.truncate(max);
+                true
+            }
+            _ => truncated,
+        };
+        CycleSearch { cycles, truncated }
+    }
+
+    /// 1つの強連結成分内の循環を列挙する（上限で打ち切った場合はtrueを返す）
+    fn component_cycles(&self, mut members: Vec<NodeIndex>, limits: CycleLimits) -> (Vec<Vec<&'a str>>, bool) {
+        members.sort();
+        // 成分内のノードを0..nの番号で扱い、成分外へのエッジは最初に取り除いておく
+        let local: HashMap<NodeIndex, usize> = members
+            .iter()
+            .enumerate()
+            .map(|(i, &idx)| (idx, i))
+            .collect();
+        let adjacency: Vec<Vec<usize>> = members
+            .iter()
+            .map(|&idx| {
+                let mut next: Vec<usize> = self.graph
+                    .neighbors_directed(idx, Direction::Outgoing)
+                    .filter_map(|n| local.get(&n).copied())
+                    .collect();
+                next.sort();
+                next
+            })
+            .collect();
+
+        let mut search = CycleCollector {
+            adjacency: &adjacency,
+            limits,
+            on_stack: vec![false; members.len()],
+            stack: Vec::new(),
+            found: Vec::new(),
+            truncated: false,
+            exhausted: false,
+        };
+        for start in 0..members.len() {
+            if search.exhausted {
+                break;
+            }
+            // startより小さいノードを含む循環は既に列挙済み
+            search.stack.push(start);
+            search.on_stack[start] = true;
+            search.collect(start);
+            search.on_stack[start] = false;
+            search.stack.pop();
+        }
+
+        let cycles = search.found
+            .into_iter()
+            .map(|cycle|
+                cycle
+                    .into_iter()
+                    .map(|i| self.name(members[i]))
+                    .collect()
+            )
+            .collect();
+        (cycles, search.truncated)
+    }
+
+    /// 最長の依存チェーンの長さ（エッジ数）
+    ///
+    /// 強連結成分を1つのノードに縮約したDAG上で求めるため、循環があっても有限で、ノード数に対して線形に計算できる。
+    pub fn max_depth(&self) -> usize {
+        let condensed = petgraph::algo::condensation(self.graph.map(|_, _| (), |_, _| ()), true);
+        let Ok(order) = petgraph::algo::toposort(&condensed, None) else {
+            return 0;
+        };
+        // 依存先から順に、そこから伸びる最長チェーンの長さを求める
+        let mut depth = vec![0usize; condensed.node_count()];
+        for &node in order.iter().rev() {
+            depth[node.index()] = condensed
+                .neighbors_directed(node, Direction::Outgoing)
+                .map(|next| depth[next.index()] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+        depth.into_iter().max().unwrap_or(0)
+    }
+
+    /// すべての循環を解消するために取り除くべきエッジの候補（greedy feedback arc set）
+    pub fn feedback_arc_set(&self) -> Vec<(&'a str, &'a str)> {
+        let mut edges: Vec<(&'a str, &'a str)> = petgraph::algo
+            ::greedy_feedback_arc_set(&self.graph)
+            .map(|edge| (self.name(edge.source()), self.name(edge.target())))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    /// 依存先が先に来る順序でモジュールを並べる（循環がある場合は`GraphCycle`）
+    pub fn topological_order(&self) -> Result<Vec<&'a str>> {
+        let order = petgraph::algo::toposort(&self.graph, None).map_err(|cycle| AnalyzerError::GraphCycle {
+            module: self.name(cycle.node_id()).to_string(),
+        })?;
+        Ok(
+            order
+                .into_iter()
+                .rev()
+                .map(|idx| self.name(idx))
+                .collect()
+        )
+    }
+
+    /// `from`から`to`への単純経路をすべて返す（経路長は`max_length`エッジまで、0なら空）
+    pub fn all_simple_paths(&self, from: &str, to: &str, max_length: usize) -> Vec<Vec<&'a str>> {
+        let (Some(&start), Some(&goal)) = (self.node_indices.get(from), self.node_indices.get(to)) else {
+            return Vec::new();
+        };
+        // petgraphの上限は途中のノード数なので、エッジ数から1を引いて渡す
+        let Some(max_intermediate) = max_length.checked_sub(1) else {
+            return Vec::new();
+        };
+
+        let mut paths: Vec<Vec<&'a str>> = petgraph::algo
+            ::all_simple_paths::<Vec<_>, _>(&self.graph, start, goal, 0, Some(max_intermediate))
+            .map(|path|
+                path
+                    .into_iter()
+                    .map(|idx| self.name(idx))
+                    .collect()
+            )
+            .collect();
+        paths.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        paths
+    }
+}
+
+impl AnalysisResult {
+    /// 解析済みモジュールの依存グラフを作成する
+    pub fn graph(&self) -> ModuleGraph<'_> {
+        ModuleGraph::new(&self.modules)
+    }
+
+    /// 違反情報付きの依存グラフをpetgraphとして返す
+    pub fn dependency_graph(&self) -> DiGraph<&ModuleInfo, DependencyEdge> {
+        let graph = self.graph();
+        let petgraph = graph.petgraph();
+        petgraph.map(
+            |_, module| *module,
+            |edge, kind| {
+                let (from, to) = petgraph.edge_endpoints(edge).expect("edge exists");
+                let violation = self.dependency_violations
+                    .iter()
+                    .find(|v| v.from_module == petgraph[from].name && v.to_module == petgraph[to].name)
+                    .map(|v| v.violation_type.clone());
+                DependencyEdge { kind: *kind, violation }
+            }
+        )
+    }
+}
+
+/// NgModuleの`imports`配列から参照されているモジュールを（解決できたファイル, 名前）で列挙する
+fn module_references(module: &ModuleInfo) -> Vec<(Option<&Path>, &str)> {
+    // `module_dependencies`のない以前のバージョンのJSONでは`imports`から求める
+    if module.module_dependencies.is_empty() {
+        module.imports
+            .iter()
+            .map(|entry| (None, reference_name(entry)))
+            .collect()
+    } else {
+        module.module_dependencies
+            .iter()
+            .map(|dep| (dep.path.as_deref(), dep.name.as_ref()))
+            .collect()
+    }
+}
+
+/// `RouterModule.forChild(routes)`のようなエントリから識別子部分を取り出す
+pub fn reference_name(entry: &str) -> &str {
+    let end = entry.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$')).unwrap_or(entry.len());
+    &entry[..end]
+}
@@ -0,0 +1,46 @@
+use clap::ValueEnum;
+use std::sync::atomic::{ AtomicU8, Ordering };
+
+/// コンソール・Markdown・HTMLのレポートの言語
+///
+/// JSON・SARIFなど他のツールが読む出力と、JSONに保存する`description`は常に英語。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Lang {
+    /// English
+    #[default]
+    En,
+    /// 日本語
+    Ja,
+}
+
+/// 現在の言語（[`Lang`]の値）
+static CURRENT: AtomicU8 = AtomicU8::new(Lang::En as u8);
+
+impl Lang {
+    /// 環境変数`LC_ALL`・`LC_MESSAGES`・`LANG`のうち最初に設定されているものが`ja`で始まれば日本語
+    pub fn detect() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        if locale.starts_with("ja") { Lang::Ja } else { Lang::En }
+    }
+
+    /// 以降のレポートをこの言語で出力する（プロセス全体に効く）
+    pub fn apply(self) {
+        CURRENT.store(self as u8, Ordering::Relaxed);
+    }
+
+    pub fn current() -> Self {
+        if CURRENT.load(Ordering::Relaxed) == Lang::Ja as u8 { Lang::Ja } else { Lang::En }
+    }
+}
+
+/// 現在の言語に合わせて英語か日本語の文言を選ぶ
+pub fn tr<'a>(en: &'a str, ja: &'a str) -> &'a str {
+    match Lang::current() {
+        Lang::En => en,
+        Lang::Ja => ja,
+    }
+}
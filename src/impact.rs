@@ -0,0 +1,134 @@
+use serde::Serialize;
+use std::collections::{ BTreeMap, BTreeSet };
+use std::path::{ Path, PathBuf };
+
+use crate::graph::EdgeKind;
+use crate::ownership::FileOwnership;
+use crate::routes::{ Route, RouteTree };
+use crate::AnalysisResult;
+
+/// 変更されたファイルと、それを所有するモジュール
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedFile {
+    pub file: PathBuf,
+    /// どのモジュールのディレクトリにも含まれないファイルは`None`
+    pub module: Option<String>,
+}
+
+/// 変更の影響を受けるモジュール
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AffectedModule {
+    pub module: String,
+    /// 変更されたモジュールからの依存の距離（変更されたモジュール自身は0）
+    pub depth: usize,
+}
+
+/// 変更されたモジュールを含む遅延ロードのチャンク
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AffectedChunk {
+    /// `loadChildren`の読み込み先のモジュール
+    pub module: String,
+    /// このチャンクを読み込むルートのURL（e2eテストの対象を選ぶ手がかり）
+    pub routes: Vec<String>,
+}
+
+/// 変更されたファイルから求めた影響範囲
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImpactReport {
+    pub root: String,
+    pub changed_files: Vec<ChangedFile>,
+    pub changed_modules: Vec<String>,
+    /// 変更されたモジュールと、それに直接・推移的に依存しているモジュール（距離・名前の順）
+    pub affected: Vec<AffectedModule>,
+    /// 起動時に読み込まれるモジュールが変更されている
+    pub initial_chunk: bool,
+    pub lazy_chunks: Vec<AffectedChunk>,
+}
+
+impl ImpactReport {
+    /// `changed`（プロジェクトルートからの相対パスか絶対パス）を所有するモジュールから、依存元をたどって影響範囲を求める
+    ///
+    /// 遅延ロードのチャンクは、`loadChildren`の読み込み先と一緒に読み込まれるモジュールのどれかが変更されていれば影響を受ける。
+    pub fn from_changed_files(project_path: &Path, result: &AnalysisResult, changed: &[PathBuf], root: &str) -> Self {
+        let graph = result.graph();
+        let ownership = FileOwnership::new(project_path, &result.modules);
+
+        // モジュールファイル自身は、同じディレクトリの別のモジュールではなくそのモジュールのもの
+        let module_files: BTreeMap<PathBuf, &str> = result.modules
+            .iter()
+            .map(|m| (m.path.strip_prefix(project_path).unwrap_or(&m.path).to_path_buf(), m.name.as_ref()))
+            .collect();
+        let changed_files: Vec<ChangedFile> = changed
+            .iter()
+            .map(|file| {
+                let relative = file.strip_prefix(project_path).unwrap_or(file);
+                let module = module_files
+                    .get(relative)
+                    .copied()
+                    .or_else(|| ownership.owner(&file.to_string_lossy()));
+                ChangedFile { file: file.clone(), module: module.map(str::to_string) }
+            })
+            .collect();
+        let changed_modules: BTreeSet<String> = changed_files
+            .iter()
+            .filter_map(|file| file.module.clone())
+            .collect();
+
+        let mut depths: BTreeMap<&str, usize> = BTreeMap::new();
+        for module in &changed_modules {
+            depths.insert(module.as_str(), 0);
+            for (dependent, depth) in graph.dependents(module) {
+                let entry = depths.entry(dependent).or_insert(depth);
+                *entry = (*entry).min(depth);
+            }
+        }
+        let mut affected: Vec<AffectedModule> = depths
+            .into_iter()
+            .map(|(module, depth)| AffectedModule { module: module.to_string(), depth })
+            .collect();
+        affected.sort_by(|a, b| a.depth.cmp(&b.depth).then_with(|| a.module.cmp(&b.module)));
+
+        let touches = |module: &str| graph.eager_closure(module).iter().any(|m| changed_modules.contains(*m));
+        let lazy_targets: BTreeSet<&str> = graph
+            .edges()
+            .into_iter()
+            .filter(|(_, _, kind)| *kind == EdgeKind::Lazy)
+            .map(|(_, to, _)| to)
+            .collect();
+        let mut routes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        if graph.contains(root) {
+            collect_lazy_routes(&RouteTree::build(result, root).routes, "", &mut routes);
+        }
+        let lazy_chunks = lazy_targets
+            .into_iter()
+            .filter(|target| touches(target))
+            .map(|target| AffectedChunk {
+                module: target.to_string(),
+                routes: routes.remove(target).unwrap_or_default(),
+            })
+            .collect();
+
+        let initial_chunk = graph.contains(root) && touches(root);
+        ImpactReport {
+            root: root.to_string(),
+            changed_files,
+            changed_modules: changed_modules.into_iter().collect(),
+            affected,
+            initial_chunk,
+            lazy_chunks,
+        }
+    }
+}
+
+/// 遅延ロード先ごとに、それを読み込むルートの完全なURLを集める
+fn collect_lazy_routes(routes: &[Route], prefix: &str, found: &mut BTreeMap<String, Vec<String>>) {
+    for route in routes {
+        let url = if route.path.is_empty() { prefix.to_string() } else { format!("{}/{}", prefix, route.path) };
+        collect_lazy_routes(&route.children, &url, found);
+        if let Some(boundary) = &route.lazy {
+            let display = if url.is_empty() { "/".to_string() } else { url.clone() };
+            found.entry(boundary.target.clone()).or_default().push(display);
+            collect_lazy_routes(&boundary.routes, &url, found);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::{ Path, PathBuf };
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::{ AnalyzerError, Result };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceKind {
+    Nx,
+    AngularCli,
+    Unknown,
+}
+
+impl WorkspaceKind {
+    pub fn detect(project_path: &Path) -> Self {
+        if project_path.join("nx.json").exists() {
+            WorkspaceKind::Nx
+        } else if project_path.join("angular.json").exists() {
+            WorkspaceKind::AngularCli
+        } else {
+            WorkspaceKind::Unknown
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WorkspaceKind::Nx => "Nx workspace",
+            WorkspaceKind::AngularCli => "Angular CLI workspace",
+            WorkspaceKind::Unknown => "unknown workspace",
+        }
+    }
+}
+
+/// プロジェクト構成から推測した分類ルール
+pub struct Scaffold {
+    pub workspace: WorkspaceKind,
+    pub core: Vec<String>,
+    pub shared: Vec<String>,
+    pub feature: Vec<String>,
+}
+
+impl Scaffold {
+    pub fn detect(project_path: &Path) -> Self {
+        let workspace = WorkspaceKind::detect(project_path);
+        let mut scaffold = Scaffold {
+            workspace,
+            core: Vec::new(),
+            shared: Vec::new(),
+            feature: Vec::new(),
+        };
+
+        for dir in layer_directories(project_path) {
+            let name = dir.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+            let pattern = format!("{}/**", dir.to_string_lossy().replace('\\', "/"));
+            match name.as_str() {
+                "core" => scaffold.core.push(pattern),
+                "shared" => scaffold.shared.push(pattern),
+                "features" | "feature" => scaffold.feature.push(pattern),
+                _ => {}
+            }
+        }
+
+        if workspace == WorkspaceKind::Nx {
+            // Nxのライブラリ命名規約（feature-*, ui-*, util-*, data-access-*）
+            scaffold.core.push("libs/**/data-access-*/**".to_string());
+            scaffold.shared.push("libs/**/ui-*/**".to_string());
+            scaffold.shared.push("libs/**/util-*/**".to_string());
+            scaffold.feature.push("libs/**/feature-*/**".to_string());
+        }
+
+        if scaffold.core.is_empty() {
+            scaffold.core.push("**/core/**".to_string());
+        }
+        if scaffold.shared.is_empty() {
+            scaffold.shared.push("**/shared/**".to_string());
+        }
+        if scaffold.feature.is_empty() {
+            scaffold.feature.push("**/features/**".to_string());
+        }
+
+        scaffold
+    }
+
+    pub fn render(&self) -> String {
+        let list = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| format!("\"{}\"", p))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        format!(
+            "# Angular Module Analyzer configuration\n\
+             # Generated by `angular-analyzer init` for an {workspace}.\n\
+             \n\
+             # Globs are matched against module file paths relative to this file.\n\
+             # The first matching layer wins; unmatched modules fall back to path heuristics.\n\
+             [classification]\n\
+             core = [{core}]\n\
+             shared = [{shared}]\n\
+             feature = [{feature}]\n\
+             \n\
+             [thresholds]\n\
+             max_coupling_factor = 0.3\n\
+             max_cycles = 0\n\
+             max_violations = 0\n\
+             min_health_score = 70.0\n",
+            workspace = self.workspace.label(),
+            core = list(&self.core),
+            shared = list(&self.shared),
+            feature = list(&self.feature)
+        )
+    }
+}
+
+/// core/shared/features という名前のディレクトリをプロジェクトルートからの相対パスで列挙する
+fn layer_directories(project_path: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = WalkDir::new(project_path)
+        .max_depth(6)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            !matches!(name.as_ref(), "node_modules" | "dist" | ".git" | ".angular")
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_lowercase();
+            matches!(name.as_str(), "core" | "shared" | "features" | "feature")
+        })
+        .filter_map(|e| e.path().strip_prefix(project_path).ok().map(Path::to_path_buf))
+        .collect();
+    dirs.sort();
+
+    // ネストしたディレクトリ（shared/core など）は外側のルールに含まれるので除外
+    let mut outermost: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        if !outermost.iter().any(|parent| dir.starts_with(parent)) {
+            outermost.push(dir);
+        }
+    }
+    outermost
+}
+
+/// 設定ファイルを書き出し、その内容を返す
+pub fn write_config(project_path: &Path, force: bool) -> Result<(PathBuf, Scaffold)> {
+    let path = Config::path_for(project_path);
+    if path.exists() && !force {
+        return Err(AnalyzerError::AlreadyExists(path));
+    }
+    let scaffold = Scaffold::detect(project_path);
+    fs::write(&path, scaffold.render()).map_err(|err| AnalyzerError::io(&path, err))?;
+    Ok((path, scaffold))
+}
@@ -0,0 +1,34 @@
+use std::collections::HashSet;
+use std::hash::{ BuildHasher, RandomState };
+use std::sync::{ Arc, Mutex };
+
+/// モジュール名・依存先・プロバイダーなどの識別子
+///
+/// 同じ名前はモジュール情報、グラフ、違反の間で1つの割り当てを共有する。
+pub type Name = Arc<str>;
+
+/// ロックを分ける数（rayonの各スレッドが同じロックで待たないように、スレッド数より十分多くする）
+const SHARDS: usize = 32;
+
+/// 解析中に現れた識別子を重複なく保持する
+///
+/// 名前のハッシュでシャードを選ぶので、並列に解析するファイルどうしは別々のロックを取ることが多い。
+#[derive(Debug, Default)]
+pub(crate) struct Interner {
+    hasher: RandomState,
+    shards: [Mutex<HashSet<Name>>; SHARDS],
+}
+
+impl Interner {
+    pub(crate) fn intern(&self, name: &str) -> Name {
+        let hash = self.hasher.hash_one(name);
+        let shard = &self.shards[(hash as usize) % SHARDS];
+        let mut names = shard.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(interned) = names.get(name) {
+            return interned.clone();
+        }
+        let interned: Name = Arc::from(name);
+        names.insert(interned.clone());
+        interned
+    }
+}
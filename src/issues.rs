@@ -0,0 +1,113 @@
+use serde::Serialize;
+
+use crate::output::{ findings, report_path };
+use crate::AnalysisResult;
+
+/// 違反1件を課題管理ツールのチケットにしたもの
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub title: String,
+    /// Markdownの本文（末尾に重複登録を避けるためのキーのコメントを含む）
+    pub body: String,
+    /// `architecture`、ルールコード、依存元のレイヤー（`layer:shared`など）
+    pub labels: Vec<String>,
+    pub rule: &'static str,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    /// 同じ違反を実行間で同一視するためのキー
+    pub key: String,
+}
+
+/// 違反と循環依存を1件ずつチケットにする
+///
+/// `link_base`（`https://github.com/org/repo/blob/main`など）を指定すると、本文の場所をファイルへのリンクにする。
+pub fn issues(result: &AnalysisResult, link_base: Option<&str>) -> Vec<Issue> {
+    findings(result)
+        .into_iter()
+        .map(|finding| {
+            let code = finding.rule.code();
+            let layer = result.modules
+                .iter()
+                .find(|m| *m.name == *finding.module)
+                .map(|m| m.module_type.to_string().to_lowercase());
+            let file = finding.file.map(report_path);
+            let line = finding.position.map(|(line, _)| line);
+
+            let location = match (&file, finding.position) {
+                (Some(file), Some((line, column))) => {
+                    let text = format!("{}:{}:{}", file, line, column);
+                    match link_base {
+                        Some(base) => format!("[{}]({}/{}#L{})", text, base.trim_end_matches('/'), file, line),
+                        None => format!("`{}`", text),
+                    }
+                }
+                (Some(file), None) =>
+                    match link_base {
+                        Some(base) => format!("[{}]({}/{})", file, base.trim_end_matches('/'), file),
+                        None => format!("`{}`", file),
+                    }
+                (None, _) => "unknown".to_string(),
+            };
+            let body = format!(
+                "**Rule:** {} ({})\n**Module:** {}{}\n**Location:** {}\n\n{}\n\n<!-- angular-module-analyzer:{} -->\n",
+                code,
+                finding.rule,
+                finding.module,
+                layer
+                    .as_ref()
+                    .map(|layer| format!(" ({})", layer))
+                    .unwrap_or_default(),
+                location,
+                finding.message,
+                finding.key
+            );
+
+            let mut labels = vec!["architecture".to_string(), code.to_string()];
+            labels.extend(layer.map(|layer| format!("layer:{}", layer)));
+            Issue {
+                title: format!("[{}] {}", code, finding.message),
+                body,
+                labels,
+                rule: code,
+                file,
+                line,
+                key: finding.key,
+            }
+        })
+        .collect()
+}
+
+/// 1行に1件のJSON（`jq`で1件ずつ`gh issue create`に渡せる）
+pub fn render_json_lines(issues: &[Issue]) -> crate::Result<String> {
+    let mut lines = String::new();
+    for issue in issues {
+        lines.push_str(&serde_json::to_string(issue)?);
+        lines.push('\n');
+    }
+    Ok(lines)
+}
+
+/// JiraのCSVインポート形式（ラベルは同名の`Labels`列を並べる）
+pub fn render_jira_csv(issues: &[Issue]) -> String {
+    let label_columns = issues
+        .iter()
+        .map(|issue| issue.labels.len())
+        .max()
+        .unwrap_or(1);
+    let mut csv = String::from("Summary,Description");
+    for _ in 0..label_columns {
+        csv.push_str(",Labels");
+    }
+    csv.push('\n');
+    for issue in issues {
+        let mut fields = vec![csv_field(&issue.title), csv_field(&issue.body)];
+        fields.extend((0..label_columns).map(|i| csv_field(issue.labels.get(i).map_or("", String::as_str))));
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) { format!("\"{}\"", value.replace('"', "\"\"")) } else { value.to_string() }
+}
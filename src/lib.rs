@@ -0,0 +1,1774 @@
+//! Angularプロジェクトのモジュール構造（Core/Shared/Feature）を解析するライブラリ
+//!
+//! `angular-analyzer` CLIと同じ解析を、バイナリを呼び出さずに組み込みで利用できる。
+//!
+//! ```no_run
+//! use angular_module_analyzer::AngularAnalyzer;
+//!
+//! let analyzer = AngularAnalyzer::from_project("path/to/angular/project")?;
+//! let result = analyzer.analyze()?;
+//! for violation in &result.dependency_violations {
+//!     println!("[{}] {}", violation.violation_type.code(), violation);
+//! }
+//! # Ok::<(), angular_module_analyzer::AnalyzerError>(())
+//! ```
+//!
+//! - [`AngularAnalyzer`] — モジュールの探索・解析の入口
+//! - [`AnalyzerError`] — ライブラリが返すエラーの種類
+//! - [`graph::ModuleGraph`] — 依存グラフ（経路・循環・影響範囲）。[`AnalysisResult::graph`]で作成する
+//! - [`output`] / [`report`] — JSON・Markdown・HTML・SARIF・DOT・Mermaidなどへの変換
+
+use ignore::gitignore::{ Gitignore, GitignoreBuilder };
+use once_cell::sync::Lazy;
+use rayon::iter::Either;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use std::cmp::Ordering;
+use std::collections::{ BTreeMap, HashMap, HashSet };
+use std::fmt;
+use std::ops::Range;
+use std::path::{ Path, PathBuf };
+use std::sync::Arc;
+use std::time::{ Duration, Instant };
+use tracing::info_span;
+
+pub mod aggregate;
+pub mod archive;
+mod builder;
+pub mod c4;
+pub mod cache;
+mod cancel;
+pub mod check;
+pub mod chunks;
+pub mod components;
+pub mod compare;
+pub mod compodoc;
+pub mod config;
+pub mod coverage;
+pub mod deadcode;
+pub mod delta;
+pub mod depcruise;
+pub mod doctor;
+pub mod drift;
+pub mod fitness;
+pub mod fix;
+mod error;
+pub mod graph;
+pub mod i18n;
+pub mod impact;
+pub mod init;
+pub mod issues;
+mod intern;
+pub mod list;
+pub mod merge;
+pub mod metrics;
+pub mod notify;
+pub mod nx;
+pub mod output;
+pub mod ownership;
+pub mod paths;
+pub mod plugins;
+pub mod progress;
+pub mod report;
+pub mod routes;
+mod scripts;
+pub mod services;
+pub mod snapshot;
+pub mod suggestions;
+pub mod source;
+pub mod split;
+pub mod standalone;
+pub mod table;
+pub mod trend;
+pub mod workspaces;
+
+pub use builder::AnalyzerBuilder;
+pub use cancel::CancellationToken;
+pub use petgraph;
+pub use error::{ AnalyzerError, Result };
+pub use intern::Name;
+use intern::Interner;
+use cache::ModuleCache;
+use config::{ Classifier, Config, RuleLevel };
+use graph::ModuleGraph;
+use progress::{ Phase, ProgressHandler };
+use source::SourceText;
+
+/// `ModuleInfo`に読み込むNgModuleのメタデータの配列
+const METADATA_FIELDS: [&str; 4] = ["imports", "exports", "providers", "declarations"];
+
+// 正規表現のコンパイルは解析より重いので、ファイルごとではなく一度だけ行う
+// export class XModule / export default class XModule / class XModule
+static CLASS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(export\s+(default\s+)?)?(?:abstract\s+)?class\s+(\w+Module)\b").unwrap()
+});
+static ANONYMOUS_DEFAULT_CLASS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"export\s+default\s+class\s*\{").unwrap());
+// export { XModule } / export { XModule as default } / export { XModule } from './x.module'
+static EXPORT_LIST_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"export\s*\{([^}]*)\}(\s*from\b)?").unwrap());
+// export default XModule; / export = XModule;
+static EXPORT_ASSIGNMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"export\s*(default\s+|=\s*)(\w+Module)\s*;").unwrap()
+});
+static IMPORT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"import\s*\{[^}]*\}\s*from\s*["']([^"']*)["']\s*;"#).unwrap()
+});
+// loadChildren: () => import('./x.module').then(m => m.XModule)
+static DYNAMIC_LAZY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"loadChildren\s*:\s*\(\s*\)\s*=>\s*import\([^)]*\)\s*\.then\(\s*\(?\s*(\w+)\s*\)?\s*=>\s*(\w+)\.(\w+)"#
+    ).unwrap()
+});
+// loadChildren: './x.module#XModule'（旧形式）
+static STRING_LAZY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"loadChildren\s*:\s*["'][^"'#]*#(\w+)["']"#).unwrap());
+
+/// 1つの`*.module.ts`ファイルから抽出したNgModuleの情報
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct ModuleInfo {
+    pub path: PathBuf,
+    /// NgModuleのクラス名（同じ名前のモジュールが複数ある場合は`path#クラス名`で修飾する）
+    pub name: Name,
+    pub export_style: ExportStyle,
+    pub module_type: ModuleType,
+    /// `module_type`を何から決めたか
+    pub classified_by: ClassificationSource,
+    /// `@NgModule`のメタデータから推定したレイヤー（手がかりがなければ`None`）
+    pub suggested_type: Option<ModuleType>,
+    pub imports: Vec<Name>,
+    pub exports: Vec<Name>,
+    /// `exports`の各要素が再exportしているモジュールか、宣言か（`exports`と同じ順）
+    pub export_kinds: Vec<ExportRef>,
+    pub providers: Vec<Name>,
+    pub declarations: Vec<Name>,
+    /// import文のモジュール指定子（重複を除いて最初に現れた順）
+    ///
+    /// 外部パッケージとパスエイリアスを区別しない。パッケージは`external_dependencies`、
+    /// プロジェクト内のモジュールへの依存は`module_dependencies`を使う。
+    pub dependencies: Vec<Name>,
+    /// 複数のimport文で読み込んでいる`dependencies`と、そのimport文の数
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependency_occurrences: BTreeMap<Name, usize>,
+    /// 依存している外部パッケージ（プロジェクト内のモジュールをimportしているパスエイリアスを除く）
+    pub external_dependencies: Vec<PackageRef>,
+    /// NgModuleの`imports`で参照しているモジュール（重複を除いて最初に現れた順）
+    pub module_dependencies: Vec<ModuleRef>,
+    /// `loadChildren`で遅延ロードしているモジュール
+    pub lazy_imports: Vec<Name>,
+    /// 参照名（NgModuleメタデータの識別子、import文のモジュール指定子、遅延ロード先）ごとの最初の出現位置
+    pub locations: BTreeMap<Name, Position>,
+    /// テスト用のモジュール（[`is_test_file`]、[`AnalyzerBuilder::include_tests`]を指定した場合だけ解析する）
+    pub test: bool,
+}
+
+impl ModuleInfo {
+    /// このモジュールのファイル内で`name`を参照している位置
+    pub fn location_of(&self, name: &str) -> Option<SourceLocation> {
+        // 修飾したモジュール名は、ソース上ではクラス名で参照されている
+        let class_name = name.rsplit_once('#').map_or(name, |(_, class_name)| class_name);
+        self.locations.get(name).or_else(|| self.locations.get(class_name)).map(|position| SourceLocation {
+            file: self.path.clone(),
+            line: position.line,
+            column: position.column,
+        })
+    }
+
+    /// 修飾していないクラス名
+    pub fn class_name(&self) -> &str {
+        self.name.rsplit_once('#').map_or(&self.name, |(_, class_name)| class_name)
+    }
+
+    /// `exports`で再exportしているモジュール（プロジェクト内のモジュールとパッケージのモジュール）
+    pub fn exported_modules(&self) -> impl Iterator<Item = &Name> {
+        self.export_kinds
+            .iter()
+            .filter(|export| export.kind == ExportKind::Module)
+            .map(|export| &export.name)
+    }
+
+    /// 分類ルールに当たらず、パスとメタデータからも分類を確かめられない場合はその理由（`--strict-classification`）
+    ///
+    /// 分類ルールで決めたモジュールは対象外。パスから決めた分類がメタデータからの推定と食い違う場合と、
+    /// どの手がかりにも当たらずFeatureにしたモジュールのうちメタデータもFeatureらしくない場合を返す。
+    pub fn classification_issue(&self) -> Option<String> {
+        if self.classified_by == ClassificationSource::Rule {
+            return None;
+        }
+        if self.module_type == ModuleType::Unknown {
+            return Some("could not be classified".to_string());
+        }
+        match (&self.suggested_type, self.classified_by) {
+            (Some(suggested), _) if *suggested != self.module_type =>
+                Some(
+                    format!(
+                        "its path says {} but its @NgModule metadata looks like a {} module",
+                        self.module_type,
+                        suggested
+                    )
+                ),
+            (None, ClassificationSource::Fallback) =>
+                Some(
+                    format!(
+                        "classified as {} only because its path matches no rule or core/shared/feature directory",
+                        self.module_type
+                    )
+                ),
+            _ => None,
+        }
+    }
+
+    /// プロジェクト内のモジュールへの依存（[`Resolution::Resolved`]の`module_dependencies`）
+    pub fn internal_dependencies(&self) -> impl Iterator<Item = &ModuleRef> {
+        self.module_dependencies.iter().filter(|dep| dep.resolution == Resolution::Resolved)
+    }
+}
+
+/// import文で読み込んでいる外部パッケージ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageRef {
+    /// import文のモジュール指定子（`@ngrx/store/testing`など）
+    pub specifier: Name,
+    /// 指定子のパッケージ名部分（`@ngrx/store`）
+    pub package: Name,
+}
+
+/// NgModuleの`exports`の要素
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportRef {
+    /// 識別子（`RouterModule.forChild(routes)`は`RouterModule`）
+    pub name: Name,
+    #[serde(default)]
+    pub kind: ExportKind,
+}
+
+/// [`ExportRef`]が何をexportしているか
+///
+/// ファイル単位の解析では自分で宣言したものしか`Declarable`にできず、
+/// 他のモジュールとの照合は[`AngularAnalyzer::analyze`]が行う。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportKind {
+    /// importしたモジュールの再export（importする側ではそのモジュールの`exports`が使える）
+    Module,
+    /// コンポーネント・ディレクティブ・パイプ（このモジュールか他のモジュールで宣言したもの、スタンドアロンのもの）
+    Declarable,
+    /// どちらか分からない（展開（`...COMPONENTS`）や、パッケージからimportした`Module`で終わらない名前など）
+    #[default]
+    Unknown,
+}
+
+/// NgModuleの`imports`で参照しているモジュール
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleRef {
+    /// 識別子（`RouterModule.forRoot(routes)`は`RouterModule`）
+    pub name: Name,
+    /// 識別子を読み込んでいるimport文のモジュール指定子（同じファイルで宣言している場合などは`None`）
+    #[serde(default)]
+    pub specifier: Option<Name>,
+    #[serde(default)]
+    pub resolution: Resolution,
+    /// 解決できた場合は参照先のモジュールのファイル
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+/// [`ModuleRef`]の参照先を解決できたか
+///
+/// ファイル単位の解析では`External`か`Unresolved`までしか決まらず、
+/// プロジェクト内のモジュールとの照合は[`AngularAnalyzer::analyze`]が行う。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resolution {
+    /// プロジェクト内のNgModule（依存グラフ・違反・メトリクスの対象）
+    Resolved,
+    /// パッケージからimportしたモジュール（`@angular/router`の`RouterModule`など）
+    External,
+    /// どちらでもない（スタンドアロンの宣言や、解析できなかったファイルのモジュール、
+    /// import文からたどれず同じクラス名のモジュールが複数あるものなど）
+    #[default]
+    Unresolved,
+}
+
+/// NgModuleのクラスをファイルからどのようにexportしているか
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStyle {
+    /// `export class XModule`
+    #[default]
+    Named,
+    /// `export default class XModule`・`export default XModule;`・`export { XModule as default }`
+    Default,
+    /// `class XModule`を宣言して`export { XModule }`
+    List,
+    /// `export = XModule;`
+    Equals,
+    /// `export { XModule } from './x.module'`（クラスは別のファイルで宣言されている）
+    ReExport,
+    /// exportしていないか、クラスが見つからない
+    NotExported,
+}
+
+/// `@NgModule`のメタデータから推定したレイヤー
+///
+/// `RouterModule.forChild()`でルートを持つものはFeature、`forRoot()`で設定するモジュールをimportするものや
+/// 再importを防ぐ`@SkipSelf()`のあるものはCore、`providers`もルートもなく何かを`exports`しているものはShared。
+/// `BrowserModule`や`RouterModule.forRoot()`をimportしているルートモジュールは、どのレイヤーとも推定しない。
+fn suggested_module_type(
+    code: &str,
+    imports: &[(usize, String)],
+    exports: &[(usize, String)],
+    providers: &[(usize, String)]
+) -> Option<ModuleType> {
+    let calls = |call: &str| imports.iter().any(|(_, entry)| entry.starts_with(call));
+    if calls("RouterModule.forRoot") || imports.iter().any(|(_, entry)| entry == "BrowserModule") {
+        None
+    } else if calls("RouterModule.forChild") {
+        Some(ModuleType::Feature)
+    } else if code.contains("@SkipSelf()") || imports.iter().any(|(_, entry)| entry.contains(".forRoot(")) {
+        Some(ModuleType::Core)
+    } else if providers.is_empty() && !exports.is_empty() {
+        Some(ModuleType::Shared)
+    } else {
+        None
+    }
+}
+
+/// モジュールの分類を何から決めたか
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassificationSource {
+    /// 設定ファイルの`[classification]`のルール
+    Rule,
+    /// `core/`・`shared/`・`feature(s)/`ディレクトリか、`core.module`・`shared.module`というファイル名
+    Path,
+    /// どの手がかりにも当たらなかった（Featureとする）
+    #[default]
+    Fallback,
+}
+
+/// ファイル内の位置（1始まりの行・列）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// バイトオフセットを行・列（列は文字単位）に変換する
+    ///
+    /// 改行は`\r\n`・`\n`・`\r`のどれでもよく、`\r\n`は1つの改行として数える。
+    fn at(content: &str, offset: usize) -> Self {
+        let before = &content[..offset];
+        let line_start = before.rfind(['\r', '\n']).map_or(0, |i| i + 1);
+        Self {
+            line: source::lines(before).count(),
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// 検出結果の原因となった参照の場所
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// `file:line:column`の形式（エディタやCIのログでそのまま開ける）
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file.display(), self.line, self.column)
+    }
+}
+
+/// モジュールのレイヤー分類（順序はCore < Shared < Feature < Unknown）
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ModuleType {
+    Core,
+    Shared,
+    Feature,
+    /// 分類できなかったモジュール（新しいバージョンが出力した未知の分類もこれとして読み込む）
+    #[default]
+    Unknown,
+}
+
+impl<'de> Deserialize<'de> for ModuleType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "Core" => ModuleType::Core,
+            "Shared" => ModuleType::Shared,
+            "Feature" => ModuleType::Feature,
+            _ => ModuleType::Unknown,
+        })
+    }
+}
+
+/// [`AngularAnalyzer::analyze`]の結果（`--output json`と同じ構造）
+///
+/// 結果の型はフィールドの追加のみで変更する。欠けているフィールドは既定値で、
+/// 未知のフィールドは無視して読み込むため、古い・新しいバージョンのJSONもそのまま扱える。
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct AnalysisResult {
+    pub modules: Vec<ModuleInfo>,
+    pub dependency_violations: Vec<DependencyViolation>,
+    pub circular_dependencies: Vec<Vec<Name>>,
+    /// `circular_dependencies`の各循環について、i番目のモジュールが次のモジュールを参照している場所
+    pub circular_dependency_locations: Vec<Vec<Option<SourceLocation>>>,
+    /// 循環の列挙が`[analysis]`の上限で打ち切られ、報告されていない循環がありうる場合はtrue
+    pub cycles_truncated: bool,
+    /// `[rules]`で設定した循環依存（AA004）の重大度
+    pub cycle_severity: RuleLevel,
+    pub metrics: ArchitectureMetrics,
+    /// コンポーネント・ディレクティブ・パイプの依存グラフ（[`AnalyzerBuilder::components`]で有効にした場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<components::ComponentGraph>,
+    /// 違反と循環を解消するためのリファクタリングの提案（`--low-memory`では空）
+    pub suggestions: Vec<suggestions::Suggestion>,
+    /// `[[plugins]]`のプラグインが報告した問題
+    pub plugin_findings: Vec<plugins::PluginFinding>,
+    /// 読み込めずに結果から除いたファイル（パス順）
+    pub warnings: Vec<AnalysisWarning>,
+    /// 複数のファイルで宣言されているモジュールのクラス名（名前順）
+    pub name_collisions: Vec<NameCollision>,
+}
+
+/// 同じクラス名のモジュールが複数あること
+///
+/// 依存グラフや違反で取り違えないよう、それぞれの`name`は`path#クラス名`で修飾する。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameCollision {
+    pub name: Name,
+    /// 修飾したモジュール名（パス順）
+    pub modules: Vec<Name>,
+}
+
+/// 解析できずに結果から除いたファイル
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalysisWarning {
+    pub file: PathBuf,
+    pub cause: String,
+}
+
+impl AnalysisWarning {
+    fn new(file: &Path, err: &AnalyzerError) -> Self {
+        // エラーの表示に含まれるパスは`file`と重複するので、原因だけを残す
+        let cause = match err {
+            AnalyzerError::Io { source, .. } => source.to_string(),
+            AnalyzerError::FileTooLarge { size, limit, .. } =>
+                format!("{} bytes exceeds the {} byte limit", size, limit),
+            AnalyzerError::Parse { cause, .. } => cause.clone(),
+            AnalyzerError::ExtractionLimit { reason, .. } => reason.clone(),
+            other => other.to_string(),
+        };
+        Self { file: file.to_path_buf(), cause }
+    }
+}
+
+/// レイヤー間の依存ルール違反（順序は種類、依存元、依存先の順で比較する）
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DependencyViolation {
+    pub from_module: Name,
+    pub to_module: Name,
+    pub violation_type: ViolationType,
+    #[serde(default)]
+    pub description: String,
+    /// 依存元のファイル内で依存先を参照している場所
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
+    /// `[rules]`で設定したルールの重大度（`warn`の違反は`check`のゲートで数えない）
+    #[serde(default)]
+    pub severity: RuleLevel,
+}
+
+/// 違反したルールの種類（順序はルールコード順）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ViolationType {
+    CoreDependsOnFeature,
+    SharedDependsOnFeature,
+    FeatureToFeatureDirect,
+    CircularDependency,
+    /// `@nx/enforce-module-boundaries`のタグのルールに反する依存
+    NxModuleBoundary,
+    /// dependency-cruiserの`forbidden`・`allowed`ルールに反する依存
+    DepcruiseRule,
+}
+
+impl ViolationType {
+    pub const ALL: [ViolationType; 6] = [
+        ViolationType::CoreDependsOnFeature,
+        ViolationType::SharedDependsOnFeature,
+        ViolationType::FeatureToFeatureDirect,
+        ViolationType::CircularDependency,
+        ViolationType::NxModuleBoundary,
+        ViolationType::DepcruiseRule,
+    ];
+
+    /// 設定ファイルや外部ツール連携で使うルールコード
+    pub fn code(&self) -> &'static str {
+        match self {
+            ViolationType::CoreDependsOnFeature => "AA001",
+            ViolationType::SharedDependsOnFeature => "AA002",
+            ViolationType::FeatureToFeatureDirect => "AA003",
+            ViolationType::CircularDependency => "AA004",
+            ViolationType::NxModuleBoundary => "AA005",
+            ViolationType::DepcruiseRule => "AA006",
+        }
+    }
+}
+
+impl ViolationType {
+    /// ルールの説明（[`i18n::Lang`]で選んだ言語、英語なら`Display`と同じ）
+    pub fn title(&self) -> &'static str {
+        match self {
+            ViolationType::CoreDependsOnFeature =>
+                i18n::tr("Core module depends on Feature module", "CoreモジュールがFeatureモジュールに依存しています"),
+            ViolationType::SharedDependsOnFeature =>
+                i18n::tr("Shared module depends on Feature module", "SharedモジュールがFeatureモジュールに依存しています"),
+            ViolationType::FeatureToFeatureDirect =>
+                i18n::tr(
+                    "Feature module depends directly on another Feature module",
+                    "Featureモジュールが別のFeatureモジュールに直接依存しています"
+                ),
+            ViolationType::CircularDependency =>
+                i18n::tr("Modules depend on each other in a cycle", "モジュールが循環して依存しています"),
+            ViolationType::NxModuleBoundary =>
+                i18n::tr(
+                    "Module depends on an Nx project its tags do not allow",
+                    "タグで許可されていないNxプロジェクトに依存しています"
+                ),
+            ViolationType::DepcruiseRule =>
+                i18n::tr("Dependency breaks a dependency-cruiser rule", "依存がdependency-cruiserのルールに反しています"),
+        }
+    }
+}
+
+impl fmt::Display for ViolationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ViolationType::CoreDependsOnFeature => "Core module depends on Feature module",
+            ViolationType::SharedDependsOnFeature => "Shared module depends on Feature module",
+            ViolationType::FeatureToFeatureDirect => "Feature module depends directly on another Feature module",
+            ViolationType::CircularDependency => "Modules depend on each other in a cycle",
+            ViolationType::NxModuleBoundary => "Module depends on an Nx project its tags do not allow",
+            ViolationType::DepcruiseRule => "Dependency breaks a dependency-cruiser rule",
+        })
+    }
+}
+
+impl fmt::Display for ModuleType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ModuleType::Core => "Core",
+            ModuleType::Shared => "Shared",
+            ModuleType::Feature => "Feature",
+            ModuleType::Unknown => "Unknown",
+        })
+    }
+}
+
+impl DependencyViolation {
+    /// レポートに表示する説明
+    ///
+    /// 組み込みのルールの説明は[`ViolationType::title`]で翻訳し、Nxやdependency-cruiserのルールが付けた説明はそのまま返す。
+    pub fn localized_description(&self) -> &str {
+        if self.description == self.violation_type.to_string() {
+            self.violation_type.title()
+        } else {
+            &self.description
+        }
+    }
+}
+
+/// `依存元 -> 依存先: 説明`の形式
+impl fmt::Display for DependencyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}: {}", self.from_module, self.to_module, self.description)
+    }
+}
+
+impl Ord for DependencyViolation {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.violation_type, &self.from_module, &self.to_module, &self.description).cmp(
+            &(&other.violation_type, &other.from_module, &other.to_module, &other.description)
+        )
+    }
+}
+
+impl PartialOrd for DependencyViolation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// プロジェクト全体のアーキテクチャ指標
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct ArchitectureMetrics {
+    pub total_modules: usize,
+    pub core_modules: usize,
+    pub shared_modules: usize,
+    pub feature_modules: usize,
+    pub average_dependencies_per_module: f32,
+    pub max_dependency_depth: usize,
+    pub coupling_factor: f32,
+    /// 違反・循環・結合度から算出する0〜100の総合スコア
+    pub health_score: f32,
+}
+
+/// Angularプロジェクトを走査してモジュール構造を解析する
+pub struct AngularAnalyzer {
+    project_path: PathBuf,
+    config: Config,
+    classifier: Classifier,
+    follow_symlinks: bool,
+    respect_ignore: bool,
+    include_tests: bool,
+    parse_timeout: Option<Duration>,
+    /// `.analyzerignore`と`[analysis] exclude`
+    exclusions: Arc<Gitignore>,
+    cache_dir: Option<PathBuf>,
+    max_file_size: Option<u64>,
+    low_memory: bool,
+    components: bool,
+    progress: Vec<Arc<dyn ProgressHandler>>,
+    cancellation: CancellationToken,
+    interner: Interner,
+    /// `jobs`が指定された場合の専用スレッドプール（未指定ならrayonのグローバルプール）
+    pool: Option<Arc<rayon::ThreadPool>>,
+}
+
+impl AngularAnalyzer {
+    /// 設定ファイルを読まずに既定の分類ルールで作成する
+    pub fn new(project_path: &str) -> Self {
+        Self::builder().path(project_path).build().expect("default configuration is valid")
+    }
+
+    pub fn builder() -> AnalyzerBuilder {
+        AnalyzerBuilder::new()
+    }
+
+    /// プロジェクトルートの設定ファイルを読み込んでアナライザーを作成する
+    pub fn from_project(project_path: &str) -> Result<Self> {
+        let config = Config::load(Path::new(project_path))?;
+        Self::builder().path(project_path).config(config).build()
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// モジュールを探索し、違反・循環依存・メトリクスを算出する
+    ///
+    /// モジュールはパス順、違反は(ルールコード, 依存元, 依存先)順に並ぶため、出力は実行ごとに変わらない。
+    /// `*.module.ts`でも`@NgModule`や`standalone:`を含まないファイルは解析しない。
+    /// ファイルの解析はrayonのスレッドプールで並列に行う（スレッド数は[`AnalyzerBuilder::jobs`]で制限できる）。
+    /// [`AnalyzerBuilder::cache_dir`]を指定した場合は、内容が変わったファイルだけを再解析する。
+    pub fn analyze(&self) -> Result<AnalysisResult> {
+        let _span = info_span!("analyze", project = %self.project_path.display()).entered();
+        let (modules, warnings) = self.discover_modules()?;
+        self.analyze_modules(modules, warnings)
+    }
+
+    /// ディスク上にないファイル（アーカイブの中身など）を解析する
+    ///
+    /// パスはプロジェクトルートからの相対パスで渡す。`*.module.ts`以外のファイルと、
+    /// [`AnalyzerBuilder::include_tests`]を指定しなければテスト用のファイル（[`is_test_file`]）は無視する。
+    /// [`IGNORE_FILE_NAME`]（[`AnalyzerBuilder::ignore_file`]）と`[analysis] exclude`に一致するファイルも無視する。
+    /// ディレクトリを走査しないため、`.gitignore`や[`AnalyzerBuilder::cache_dir`]は使われない。
+    pub fn analyze_sources(&self, files: impl IntoIterator<Item = (PathBuf, String)>) -> Result<AnalysisResult> {
+        let _span = info_span!("analyze", project = %self.project_path.display()).entered();
+        let mut modules = Vec::new();
+        let mut warnings = Vec::new();
+        for (relative, content) in files {
+            self.check_cancelled()?;
+            let path = self.project_path.join(&relative);
+            // 除外ルールはプロジェクトの外を指すパスを渡すとpanicする
+            let excluded =
+                path.starts_with(&self.project_path) &&
+                self.exclusions.matched_path_or_any_parents(&path, false).is_ignore();
+            if !is_module_file(&path) || (!self.include_tests && is_test_file(&relative)) || excluded {
+                continue;
+            }
+            self.notify(|progress| progress.on_file_discovered(&path));
+            if source::declares_module(&content) {
+                match self.parse_cached(&path, &content, None) {
+                    Ok((_, module)) => modules.push(module),
+                    Err(err) => warnings.push(AnalysisWarning::new(&path, &err)),
+                }
+            }
+        }
+        self.notify(|progress| progress.on_phase_complete(Phase::Discover));
+        self.notify(|progress| progress.on_phase_complete(Phase::Parse));
+        self.analyze_modules(modules, warnings)
+    }
+
+    /// [`analyze`](Self::analyze)の非同期版（結果は`analyze`と同じ順序になる）
+    ///
+    /// ディレクトリ走査はブロッキングスレッドで行い、モジュールファイルは並行して（同時に[`AnalyzerBuilder::jobs`]件まで）読み込む。
+    /// 読み込みを待つ間や1件解析するごとに制御を返すため、axumなどの非同期サーバーに組み込める。
+    /// 解析自体は呼び出し元のタスクで順に行うので、CPUを使い切りたい場合は`spawn_blocking`内で`analyze`を使う。
+    pub async fn analyze_async(&self) -> Result<AnalysisResult> {
+        // awaitをまたいでspanに入ったままにできないため、親を明示して作成・破棄で計測する
+        let analyze = info_span!("analyze", project = %self.project_path.display());
+        let discover = info_span!(parent: &analyze, "discover", files = tracing::field::Empty);
+        let root = self.project_path.clone();
+        let follow_symlinks = self.follow_symlinks;
+        let respect_ignore = self.respect_ignore;
+        let include_tests = self.include_tests;
+        let exclusions = self.exclusions.clone();
+        let cancellation = self.cancellation.clone();
+        let paths = match
+            tokio::task::spawn_blocking(move || {
+                module_files(&root, follow_symlinks, respect_ignore, include_tests, exclusions)
+                    .take_while(|_| !cancellation.is_cancelled())
+                    .collect::<Vec<_>>()
+            }).await
+        {
+            Ok(paths) => paths,
+            Err(err) => std::panic::resume_unwind(err.into_panic()),
+        };
+        self.check_cancelled()?;
+        for path in &paths {
+            self.notify(|progress| progress.on_file_discovered(path));
+        }
+        discover.record("files", paths.len());
+        drop(discover);
+        self.notify(|progress| progress.on_phase_complete(Phase::Discover));
+        let parse = info_span!(parent: &analyze, "parse", modules = tracing::field::Empty);
+
+        // 大きなファイルはメモリマップで読むため、読み込みはブロッキングスレッドで行う。
+        // ブロッキングスレッドのプールを使い切らず、読み込んだ内容を溜め込まないよう、同時に読むのは`jobs`件まで
+        let concurrency = self.config.analysis.jobs
+            .or_else(|| std::thread::available_parallelism().ok())
+            .map_or(1, |jobs| jobs.get());
+        let max_file_size = self.max_file_size;
+        let mut pending = paths.into_iter();
+        let mut reads = tokio::task::JoinSet::new();
+        let mut spawn_next = |reads: &mut tokio::task::JoinSet<_>| {
+            if let Some(path) = pending.next() {
+                reads.spawn_blocking(move || {
+                    let content = source::read(&path, max_file_size);
+                    (path, content)
+                });
+            }
+        };
+        for _ in 0..concurrency {
+            spawn_next(&mut reads);
+        }
+
+        // 読み込みが終わった順に解析する（読み込めなかったファイルは警告にして読み飛ばす）
+        let cache = self.load_cache();
+        let mut parsed = Vec::new();
+        let mut warnings = Vec::new();
+        while let Some(joined) = reads.join_next().await {
+            let (path, content) = match joined {
+                Ok(read) => read,
+                Err(err) => std::panic::resume_unwind(err.into_panic()),
+            };
+            // JoinSetを破棄すると残りの読み込みも中断される
+            self.check_cancelled()?;
+            spawn_next(&mut reads);
+            match content {
+                Ok(content) if source::declares_module(&content) => {
+                    match self.parse_cached(&path, &content, cache.as_ref()) {
+                        Ok(module) => parsed.push(module),
+                        Err(err) => warnings.push(AnalysisWarning::new(&path, &err)),
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    self.report_skipped(&err);
+                    warnings.push(AnalysisWarning::new(&path, &err));
+                }
+            }
+            tokio::task::yield_now().await;
+        }
+        let modules = self.store_cache(cache, parsed);
+        parse.record("modules", modules.len());
+        drop(parse);
+        self.notify(|progress| progress.on_phase_complete(Phase::Parse));
+
+        analyze.in_scope(|| self.analyze_modules(modules, warnings))
+    }
+
+    fn analyze_modules(&self, mut modules: Vec<ModuleInfo>, mut warnings: Vec<AnalysisWarning>) -> Result<AnalysisResult> {
+        self.check_cancelled()?;
+        // 走査順はファイルシステムに依存するため、出力が実行ごとに変わらないよう並べ替える
+        modules.sort_by(|a, b| a.path.cmp(&b.path));
+        warnings.sort_by(|a, b| a.file.cmp(&b.file));
+        remove_re_exports(&mut modules);
+        resolve_module_dependencies(&mut modules);
+        classify_exports(&mut modules);
+        let name_collisions = self.qualify_duplicate_names(&mut modules);
+        let span = info_span!("violations").entered();
+        let mut dependency_violations = self.check_dependency_violations(&modules);
+        dependency_violations.retain(|v| self.config.rule_enabled(&v.violation_type));
+        dependency_violations.sort();
+        drop(span);
+        self.notify(|progress| progress.on_phase_complete(Phase::Violations));
+        self.check_cancelled()?;
+        let graph = info_span!("graph").in_scope(|| ModuleGraph::new(&modules));
+        // Nxのタグのルールは推移的な依存も見るため、グラフを作ってから検査する
+        if
+            self.config.rule_enabled(&ViolationType::NxModuleBoundary) &&
+            let Some(boundaries) = nx::ModuleBoundaries::load(&self.project_path)?
+        {
+            dependency_violations.extend(info_span!("nx").in_scope(|| boundaries.check(&modules, &graph)));
+            dependency_violations.sort();
+        }
+        if
+            self.config.rule_enabled(&ViolationType::DepcruiseRule) &&
+            let Some(rules) = depcruise::DepcruiseRules::load(&self.project_path)?
+        {
+            let span = info_span!("depcruise");
+            dependency_violations.extend(span.in_scope(|| rules.check(&self.project_path, &modules, &graph)));
+            dependency_violations.sort();
+        }
+        // 同じ依存を複数の経路で検出しても、(ルール, 依存元, 依存先)ごとに1件だけ報告する
+        dependency_violations.dedup_by(|a, b| {
+            a.violation_type == b.violation_type && a.from_module == b.from_module && a.to_module == b.to_module
+        });
+        for violation in &mut dependency_violations {
+            violation.severity = self.config.rule_level(&violation.violation_type);
+        }
+        let plugin_findings = info_span!("plugins").in_scope(|| {
+            plugins::run(&self.project_path, &self.config.plugins, &modules, &graph)
+        })?;
+        self.notify(|progress| progress.on_phase_complete(Phase::Graph));
+        let span = info_span!("cycles").entered();
+        let (circular_dependencies, cycles_truncated) = if
+            self.config.rule_enabled(&ViolationType::CircularDependency)
+        {
+            self.detect_circular_dependencies(&graph)
+        } else {
+            (Vec::new(), false)
+        };
+        drop(span);
+        self.notify(|progress| progress.on_phase_complete(Phase::Cycles));
+        self.check_cancelled()?;
+        let span = info_span!("metrics").entered();
+        let circular_dependency_locations: Vec<Vec<Option<SourceLocation>>> = circular_dependencies
+            .iter()
+            .map(|cycle| cycle_locations(&modules, cycle))
+            .collect();
+        // `--low-memory`では`exports`・`providers`を捨てているので、何を動かせばよいか判断できない
+        let suggestions = if self.low_memory {
+            Vec::new()
+        } else {
+            suggestions::suggest(&modules, &dependency_violations, &circular_dependencies, &circular_dependency_locations)
+        };
+        let mut metrics = self.calculate_metrics(&modules);
+        metrics.max_dependency_depth = graph.max_depth();
+        drop(graph);
+        metrics.health_score = health_score(
+            &metrics,
+            dependency_violations.len(),
+            circular_dependencies.len()
+        );
+        drop(span);
+        self.notify(|progress| progress.on_phase_complete(Phase::Metrics));
+        let components = self.components.then(|| {
+            let files = source_files(
+                &self.project_path,
+                self.follow_symlinks,
+                self.respect_ignore,
+                self.include_tests,
+                self.exclusions.clone()
+            );
+            info_span!("components").in_scope(|| components::ComponentGraph::build(files, &modules, self.max_file_size))
+        });
+
+        Ok(AnalysisResult {
+            modules,
+            dependency_violations,
+            circular_dependencies,
+            circular_dependency_locations,
+            cycles_truncated,
+            cycle_severity: self.config.rule_level(&ViolationType::CircularDependency),
+            metrics,
+            components,
+            suggestions,
+            plugin_findings,
+            warnings,
+            name_collisions,
+        })
+    }
+
+    /// モジュールファイルを探して解析する（読み込めなかったファイルは警告として返す）
+    fn discover_modules(&self) -> Result<(Vec<ModuleInfo>, Vec<AnalysisWarning>)> {
+        let span = info_span!("discover", files = tracing::field::Empty).entered();
+        let mut paths = Vec::new();
+        let files = module_files(
+            &self.project_path,
+            self.follow_symlinks,
+            self.respect_ignore,
+            self.include_tests,
+            self.exclusions.clone()
+        );
+        for path in files {
+            self.check_cancelled()?;
+            self.notify(|progress| progress.on_file_discovered(&path));
+            paths.push(path);
+        }
+        span.record("files", paths.len());
+        drop(span);
+        self.notify(|progress| progress.on_phase_complete(Phase::Discover));
+        let span = info_span!("parse", modules = tracing::field::Empty).entered();
+
+        // ファイルの読み込みと解析は互いに独立しているのでコア数だけ並列に行う
+        let cache = self.load_cache();
+        let parse_all = || {
+            paths
+                .par_iter()
+                .filter(|_| !self.cancellation.is_cancelled())
+                .filter_map(|path| {
+                    match self.read_source(path) {
+                        Ok(content) if source::declares_module(&content) => {
+                            match self.parse_cached(path, &content, cache.as_ref()) {
+                                Ok(module) => Some(Either::Left(module)),
+                                Err(err) => Some(Either::Right(AnalysisWarning::new(path, &err))),
+                            }
+                        }
+                        Ok(_) => None,
+                        Err(err) => Some(Either::Right(AnalysisWarning::new(path, &err))),
+                    }
+                })
+                .partition_map(|parsed| parsed)
+        };
+        let (parsed, warnings): (Vec<_>, Vec<_>) = match &self.pool {
+            Some(pool) => pool.install(parse_all),
+            None => parse_all(),
+        };
+        self.check_cancelled()?;
+        let modules = self.store_cache(cache, parsed);
+        span.record("modules", modules.len());
+        drop(span);
+        self.notify(|progress| progress.on_phase_complete(Phase::Parse));
+        Ok((modules, warnings))
+    }
+
+    fn load_cache(&self) -> Option<ModuleCache> {
+        let dir = self.cache_dir.as_deref()?;
+        Some(ModuleCache::load(dir, &cache::fingerprint(&self.config, self.low_memory)))
+    }
+
+    /// 解析したモジュールでキャッシュを更新し、モジュールだけを返す
+    fn store_cache(&self, cache: Option<ModuleCache>, parsed: Vec<(String, ModuleInfo)>) -> Vec<ModuleInfo> {
+        if let (Some(cache), Some(dir)) = (cache, &self.cache_dir) {
+            // キャッシュは高速化のためだけのものなので、書き込めなくても解析は続ける
+            let _ = cache.save(dir, &self.project_path, &parsed);
+        }
+        parsed
+            .into_iter()
+            .map(|(_, module)| module)
+            .collect()
+    }
+
+    /// 内容のハッシュがキャッシュと一致すれば再解析せずにキャッシュを使う
+    /// 解析の上限を超えたファイルは読み飛ばしとして通知し、`Err`を返す
+    fn parse_cached(&self, path: &Path, content: &str, cache: Option<&ModuleCache>) -> Result<(String, ModuleInfo)> {
+        let hash = cache.map(|_| cache::content_hash(content)).unwrap_or_default();
+        let module = match cache.and_then(|cache| cache.get(&self.project_path, path, &hash)) {
+            Some(module) => module,
+            None => {
+                let module = self.parse_module_source(path, content).inspect_err(|err| self.report_skipped(err))?;
+                if self.low_memory { self.summarize(module) } else { module }
+            }
+        };
+        self.notify(|progress| progress.on_module_parsed(&module));
+        Ok((hash, module))
+    }
+
+    /// 依存グラフの構築に必要な参照だけを残す（省メモリモード）
+    ///
+    /// `imports`は識別子名だけにし、`exports`・`providers`・`declarations`と参照以外の位置情報は捨てる。
+    fn summarize(&self, module: ModuleInfo) -> ModuleInfo {
+        let imports: Vec<Name> = module.imports
+            .iter()
+            .map(|entry| self.interner.intern(graph::reference_name(entry)))
+            .collect();
+        let mut locations = module.locations;
+        locations.retain(|name, _| {
+            imports.contains(name) ||
+                module.dependencies.contains(name) ||
+                module.lazy_imports.contains(name) ||
+                module.module_dependencies.iter().any(|dep| dep.name == *name)
+        });
+        ModuleInfo {
+            imports,
+            exports: Vec::new(),
+            export_kinds: Vec::new(),
+            providers: Vec::new(),
+            declarations: Vec::new(),
+            locations,
+            ..module
+        }
+    }
+
+    fn read_source(&self, path: &Path) -> Result<SourceText> {
+        source::read(path, self.max_file_size).inspect_err(|err| self.report_skipped(err))
+    }
+
+    fn report_skipped(&self, err: &AnalyzerError) {
+        self.notify(|progress| progress.on_file_skipped(err));
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        if self.cancellation.is_cancelled() { Err(AnalyzerError::Cancelled) } else { Ok(()) }
+    }
+
+    fn notify(&self, event: impl Fn(&dyn ProgressHandler)) {
+        for progress in &self.progress {
+            event(progress.as_ref());
+        }
+    }
+
+    /// モジュールファイルを見つけた順に解析して返すイテレーター
+    ///
+    /// 全体の`Vec`を待たずに1件ずつ処理できるため、大規模なワークスペースでもメモリを抑えられる。
+    /// 読み込めなかったファイルと解析できなかったファイルは`Err`として返し、次のファイルに進む。
+    /// `@NgModule`も`standalone:`も含まないファイルは解析せずに飛ばす。
+    /// [`AnalyzerBuilder::cache_dir`]のキャッシュと[`AnalyzerBuilder::low_memory`]は`analyze`と同じく使う（キャッシュは更新しない）。
+    /// 中断された場合は`Err(AnalyzerError::Cancelled)`を1件返して終了する。
+    pub fn modules(&self) -> impl Iterator<Item = Result<ModuleInfo>> + '_ {
+        let mut paths = module_files(
+            &self.project_path,
+            self.follow_symlinks,
+            self.respect_ignore,
+            self.include_tests,
+            self.exclusions.clone()
+        );
+        let cache = self.load_cache();
+        let mut finished = false;
+        std::iter::from_fn(move || {
+            loop {
+                if finished {
+                    return None;
+                }
+                if let Err(err) = self.check_cancelled() {
+                    finished = true;
+                    return Some(Err(err));
+                }
+                let path = paths.next()?;
+                self.notify(|progress| progress.on_file_discovered(&path));
+                match self.read_source(&path) {
+                    Ok(content) if source::declares_module(&content) => {
+                        return Some(self.parse_cached(&path, &content, cache.as_ref()).map(|(_, module)| module));
+                    }
+                    // NgModuleを含まないファイルは結果を返さずに次へ進む
+                    Ok(_) => {}
+                    Err(err) => {
+                        return Some(Err(err));
+                    }
+                }
+            }
+        })
+    }
+
+    /// 解析できたモジュールを1件ずつ`visit`に渡す
+    ///
+    /// 最初のエラー（読み込めない・解析できないファイルや中断）で止まり、そのエラーを返す。
+    /// エラーのファイルを飛ばして続けるには[`modules`](Self::modules)を使う。
+    pub fn visit_modules(&self, mut visit: impl FnMut(ModuleInfo)) -> Result<()> {
+        for module in self.modules() {
+            visit(module?);
+        }
+        Ok(())
+    }
+
+    /// ファイルの内容からモジュールを抽出する
+    ///
+    /// minifyされたファイル（[`source::MAX_LINE_LENGTH`]を超える行）、巨大なメタデータ（[`source::MAX_METADATA_SIZE`]）、
+    /// [`AnalyzerBuilder::parse_timeout`]を超えて時間のかかるファイルは`AnalyzerError::ExtractionLimit`にして、解析全体を止めない。
+    /// 抽出はどれも入力の長さに比例する時間で終わるので、時間は各段階の合間に確かめる。
+    fn parse_module_source(&self, path: &Path, content: &str) -> Result<ModuleInfo> {
+        let limit = |reason: String| AnalyzerError::ExtractionLimit { path: path.to_path_buf(), reason };
+        let longest_line = source::longest_line(content).filter(|&(_, length)| length > source::MAX_LINE_LENGTH);
+        if let Some((line, length)) = longest_line {
+            return Err(limit(format!("line {} is {} bytes long; the file looks minified or generated", line, length)));
+        }
+        let started = Instant::now();
+        let check_time = || match self.parse_timeout {
+            Some(timeout) if started.elapsed() > timeout => {
+                Err(limit(format!("extraction took longer than {:?}", timeout)))
+            }
+            _ => Ok(()),
+        };
+
+        // メタデータは`@NgModule(...)`の引数からだけ読む（他のデコレーターやルート定義の`imports`などと混ぜない）
+        let code = source::blank_comments(content);
+        let decorator = source::ngmodule_decorator(&code);
+        if
+            let Some(decorator) = &decorator &&
+            let Some(size) = source::closing(&code, decorator.metadata).map(|close| close - decorator.metadata) &&
+            size > source::MAX_METADATA_SIZE
+        {
+            return Err(limit(format!("the @NgModule metadata is {} bytes (limit {})", size, source::MAX_METADATA_SIZE)));
+        }
+        let arrays = decorator
+            .as_ref()
+            .map(|decorator| source::object_arrays(&code, decorator.metadata, &METADATA_FIELDS))
+            .unwrap_or_default();
+        let imports = extract_ngmodule_array(&code, &arrays, "imports");
+        let exports = extract_ngmodule_array(&code, &arrays, "exports");
+        let providers = extract_ngmodule_array(&code, &arrays, "providers");
+        let declarations = extract_ngmodule_array(&code, &arrays, "declarations");
+        check_time()?;
+        let (dependencies, dependency_occurrences) = self.extract_dependencies(content);
+        let lazy_imports = self.extract_lazy_imports(content);
+        check_time()?;
+        let module_dependencies = self.extract_module_dependencies(content, &imports);
+        let decorated_class = decorator.and_then(|decorator| decorator.class_name);
+        let (name, export_style) = self.extract_module_declaration(path, &code, decorated_class.as_deref());
+        check_time()?;
+        let (module_type, classified_by) = self.determine_module_type(path);
+        let suggested_type = suggested_module_type(&code, &imports, &exports, &providers);
+        let export_kinds = exports
+            .iter()
+            .map(|(_, entry)| {
+                let name = graph::reference_name(entry);
+                let declared = declarations.iter().any(|(_, d)| graph::reference_name(d) == name);
+                ExportRef {
+                    name: self.interner.intern(name),
+                    kind: if declared { ExportKind::Declarable } else { ExportKind::Unknown },
+                }
+            })
+            .collect();
+
+        // 参照名ごとに最初の出現位置を記録する（`RouterModule.forRoot(...)`は`RouterModule`として）
+        let mut locations = BTreeMap::new();
+        let metadata = imports.iter().chain(&exports).chain(&providers).chain(&declarations);
+        for (offset, entry) in metadata {
+            locations
+                .entry(self.interner.intern(graph::reference_name(entry)))
+                .or_insert_with(|| Position::at(content, *offset));
+        }
+        for (offset, name) in dependencies.iter().chain(&lazy_imports) {
+            locations.entry(self.interner.intern(name)).or_insert_with(|| Position::at(content, *offset));
+        }
+        // `Foo as Bar`は`imports`の`Bar`の位置を`Foo`の位置にもする
+        for (offset, dep) in &module_dependencies {
+            locations.entry(dep.name.clone()).or_insert_with(|| Position::at(content, *offset));
+        }
+
+        let external_dependencies = dependencies
+            .iter()
+            .map(|(_, specifier)| PackageRef {
+                specifier: self.interner.intern(specifier),
+                package: self.interner.intern(output::package_name(specifier)),
+            })
+            .collect();
+        let names = |entries: Vec<(usize, String)>| -> Vec<Name> {
+            entries
+                .into_iter()
+                .map(|(_, name)| self.interner.intern(&name))
+                .collect()
+        };
+        Ok(ModuleInfo {
+            path: path.to_path_buf(),
+            name: self.interner.intern(&name),
+            export_style,
+            module_type,
+            classified_by,
+            suggested_type,
+            imports: names(imports),
+            exports: names(exports),
+            export_kinds,
+            providers: names(providers),
+            declarations: names(declarations),
+            dependencies: names(dependencies),
+            dependency_occurrences: dependency_occurrences
+                .into_iter()
+                .map(|(name, count)| (self.interner.intern(&name), count))
+                .collect(),
+            external_dependencies,
+            module_dependencies: module_dependencies
+                .into_iter()
+                .map(|(_, dep)| dep)
+                .collect(),
+            lazy_imports: names(lazy_imports),
+            locations,
+            test: is_test_file(path.strip_prefix(&self.project_path).unwrap_or(path)),
+        })
+    }
+
+    /// NgModuleのクラス名と、そのexportの仕方
+    ///
+    /// `export { XModule as YModule }`のように別名でexportしている場合は、他のファイルがimportする別名を名前にする。
+    /// `@NgModule`を付けたクラス（`decorated_class`）があればそのクラスを、なければ`Module`で終わるクラスを探す。
+    /// クラス名が見つからなければファイル名を名前にする。
+    fn extract_module_declaration(
+        &self,
+        path: &Path,
+        content: &str,
+        decorated_class: Option<&str>
+    ) -> (String, ExportStyle) {
+        let file_stem = || path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let mut classes: Vec<_> = CLASS_REGEX.captures_iter(content).collect();
+        if
+            let Some(decorated) = decorated_class &&
+            classes.iter().any(|cap| &cap[3] == decorated)
+        {
+            classes.retain(|cap| &cap[3] == decorated);
+        }
+        if let Some(cap) = classes.iter().find(|cap| cap.get(1).is_some()) {
+            let style = if cap.get(2).is_some() { ExportStyle::Default } else { ExportStyle::Named };
+            return (cap[3].to_string(), style);
+        }
+        if ANONYMOUS_DEFAULT_CLASS_REGEX.is_match(content) {
+            return (file_stem(), ExportStyle::Default);
+        }
+
+        // `export { ... }`の要素（元の名前, exportする名前, 別のファイルからのre-exportか）
+        let exported: Vec<(&str, &str, bool)> = EXPORT_LIST_REGEX.captures_iter(content)
+            .flat_map(|cap| {
+                let re_export = cap.get(2).is_some();
+                cap.get(1)
+                    .unwrap()
+                    .as_str()
+                    .split(',')
+                    .filter_map(move |entry| {
+                        let mut words = entry.split_whitespace();
+                        let original = words.next()?;
+                        let name = match (words.next(), words.next()) {
+                            (Some("as"), Some(alias)) => alias,
+                            _ => original,
+                        };
+                        Some((original, name, re_export))
+                    })
+            })
+            .collect();
+
+        // このファイルで宣言し、後からexportしているクラス
+        for cap in &classes {
+            let class_name = cap.get(3).unwrap().as_str();
+            let assignment = EXPORT_ASSIGNMENT_REGEX.captures_iter(content).find(|a| &a[2] == class_name);
+            if let Some(assignment) = assignment {
+                let style = if assignment[1].starts_with('=') { ExportStyle::Equals } else { ExportStyle::Default };
+                return (class_name.to_string(), style);
+            }
+            let local_export = exported.iter().find(|(original, _, re_export)| !re_export && *original == class_name);
+            if let Some(&(_, name, _)) = local_export {
+                return match name {
+                    "default" => (class_name.to_string(), ExportStyle::Default),
+                    name => (name.to_string(), ExportStyle::List),
+                };
+            }
+        }
+        if let Some(cap) = classes.first() {
+            return (cap[3].to_string(), ExportStyle::NotExported);
+        }
+        let re_export = exported.iter().find(|(_, name, re_export)| *re_export && name.ends_with("Module"));
+        if let Some(&(_, name, _)) = re_export {
+            return (name.to_string(), ExportStyle::ReExport);
+        }
+        (file_stem(), ExportStyle::NotExported)
+    }
+
+    /// モジュールのレイヤーと、それを何から決めたか
+    fn determine_module_type(&self, path: &Path) -> (ModuleType, ClassificationSource) {
+        // 設定ファイルの分類ルールを優先する
+        let relative_path = path.strip_prefix(&self.project_path).unwrap_or(path);
+        if let Some(module_type) = self.classifier.classify(relative_path) {
+            return (module_type, ClassificationSource::Rule);
+        }
+
+        let path_str = path.to_string_lossy().to_lowercase();
+
+        if path_str.contains("/core/") || path_str.contains("core.module") {
+            (ModuleType::Core, ClassificationSource::Path)
+        } else if path_str.contains("/shared/") || path_str.contains("shared.module") {
+            (ModuleType::Shared, ClassificationSource::Path)
+        } else if path_str.contains("/feature/") || path_str.contains("/features/") {
+            (ModuleType::Feature, ClassificationSource::Path)
+        } else {
+            (ModuleType::Feature, ClassificationSource::Fallback)
+        }
+    }
+
+    /// import文のモジュール指定子を（バイトオフセット, 指定子）で重複なく返す（2回以上現れたものはその回数も返す）
+    fn extract_dependencies(&self, content: &str) -> (Vec<(usize, String)>, BTreeMap<String, usize>) {
+        let mut dependencies: Vec<(usize, String)> = Vec::new();
+        let mut occurrences: BTreeMap<String, usize> = BTreeMap::new();
+        let imports = IMPORT_REGEX
+            .captures_iter(content)
+            .map(|cap| cap.get(1).unwrap())
+            .filter(|import| !import.as_str().starts_with(".") && !import.as_str().starts_with("@angular/"));
+        for import in imports {
+            let count = occurrences.entry(import.as_str().to_string()).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                dependencies.push((import.start(), import.as_str().to_string()));
+            }
+        }
+        occurrences.retain(|_, count| *count > 1);
+        (dependencies, occurrences)
+    }
+
+    /// `imports`の識別子を、その識別子を読み込んでいるimport文と対応付ける
+    ///
+    /// パッケージからimportしたものは[`Resolution::External`]、それ以外はプロジェクト内のモジュールと
+    /// 照合するまで[`Resolution::Unresolved`]にしておく。
+    fn extract_module_dependencies(&self, content: &str, imports: &[(usize, String)]) -> Vec<(usize, ModuleRef)> {
+        // ローカル名 -> (元の名前, モジュール指定子)
+        let mut imported: HashMap<&str, (&str, &str)> = HashMap::new();
+        for cap in components::NAMED_IMPORT_REGEX.captures_iter(content) {
+            let specifier = cap.get(2).unwrap().as_str();
+            for name in cap.get(1).unwrap().as_str().split(',') {
+                let mut words = name.split_whitespace();
+                let Some(original) = words.next() else {
+                    continue;
+                };
+                // `Foo as Bar`は`imports`に`Bar`と書く
+                let local = match (words.next(), words.next()) {
+                    (Some("as"), Some(alias)) => alias,
+                    _ => original,
+                };
+                imported.insert(local, (original, specifier));
+            }
+        }
+
+        let mut dependencies: Vec<(usize, ModuleRef)> = Vec::new();
+        for (offset, entry) in imports {
+            let local = graph::reference_name(entry);
+            let (name, specifier) = match imported.get(local) {
+                Some(&(name, specifier)) => (name, Some(specifier)),
+                None => (local, None),
+            };
+            if local.is_empty() || dependencies.iter().any(|(_, dep)| *dep.name == *name) {
+                continue;
+            }
+            let external = specifier.is_some_and(|specifier| !specifier.starts_with('.'));
+            dependencies.push((*offset, ModuleRef {
+                name: self.interner.intern(name),
+                specifier: specifier.map(|specifier| self.interner.intern(specifier)),
+                resolution: if external { Resolution::External } else { Resolution::Unresolved },
+                path: None,
+            }));
+        }
+        dependencies
+    }
+
+    fn extract_lazy_imports(&self, content: &str) -> Vec<(usize, String)> {
+        let mut lazy_imports: Vec<(usize, String)> = DYNAMIC_LAZY_REGEX
+            .captures_iter(content)
+            .filter(|cap| cap[1] == cap[2])
+            .map(|cap| cap.get(3).unwrap())
+            .chain(STRING_LAZY_REGEX.captures_iter(content).map(|cap| cap.get(1).unwrap()))
+            .map(|name| (name.start(), name.as_str().to_string()))
+            .collect();
+        // 複数のルートや両方の書き方で遅延ロードしていても1件にする（ファイル内で最初の位置を残す）
+        lazy_imports.sort();
+        let mut seen = HashSet::new();
+        lazy_imports.retain(|(_, name)| seen.insert(name.clone()));
+        lazy_imports
+    }
+
+    /// Core・SharedモジュールからFeatureモジュールへの依存を違反として返す（解決できた参照だけを見る）
+    fn check_dependency_violations(&self, modules: &[ModuleInfo]) -> Vec<DependencyViolation> {
+        let mut violations = Vec::new();
+        // 参照は解決したファイルで照合する（同じクラス名の別のモジュールと取り違えないため）
+        let module_map: HashMap<&Path, &ModuleInfo> = modules
+            .iter()
+            .map(|m| (m.path.as_path(), m))
+            .collect();
+
+        for module in modules {
+            for reference in module.internal_dependencies() {
+                let dep = &reference.name;
+                let target = reference.path.as_deref().and_then(|path| module_map.get(path));
+                if let Some(dep_module) = target {
+                    // Core modules should not depend on Feature modules
+                    if
+                        module.module_type == ModuleType::Core &&
+                        dep_module.module_type == ModuleType::Feature
+                    {
+                        violations.push(DependencyViolation {
+                            from_module: module.name.clone(),
+                            to_module: dep_module.name.clone(),
+                            violation_type: ViolationType::CoreDependsOnFeature,
+                            description: "Core module depends on Feature module".to_string(),
+                            location: module.location_of(dep),
+                            severity: RuleLevel::Error,
+                        });
+                    }
+
+                    // Shared modules should not depend on Feature modules
+                    if
+                        module.module_type == ModuleType::Shared &&
+                        dep_module.module_type == ModuleType::Feature
+                    {
+                        violations.push(DependencyViolation {
+                            from_module: module.name.clone(),
+                            to_module: dep_module.name.clone(),
+                            violation_type: ViolationType::SharedDependsOnFeature,
+                            description: "Shared module depends on Feature module".to_string(),
+                            location: module.location_of(dep),
+                            severity: RuleLevel::Error,
+                        });
+                    }
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// 同じクラス名のモジュールの`name`を`path#クラス名`（プロジェクトルートからの相対パス）にする
+    fn qualify_duplicate_names(&self, modules: &mut [ModuleInfo]) -> Vec<NameCollision> {
+        let mut by_name: BTreeMap<Name, Vec<usize>> = BTreeMap::new();
+        for (i, module) in modules.iter().enumerate() {
+            by_name.entry(module.name.clone()).or_default().push(i);
+        }
+        let mut collisions = Vec::new();
+        for (name, indices) in by_name {
+            if indices.len() < 2 {
+                continue;
+            }
+            let qualified: Vec<Name> = indices
+                .iter()
+                .map(|&i| {
+                    let path = &modules[i].path;
+                    let relative = path.strip_prefix(&self.project_path).unwrap_or(path);
+                    self.interner.intern(&format!("{}#{}", relative.to_string_lossy().replace('\\', "/"), name))
+                })
+                .collect();
+            for (&i, qualified) in indices.iter().zip(&qualified) {
+                modules[i].name = qualified.clone();
+            }
+            collisions.push(NameCollision { name, modules: qualified });
+        }
+        collisions
+    }
+
+    /// 設定の上限まで循環を列挙する（上限で打ち切った場合はtrueも返す）
+    fn detect_circular_dependencies(&self, graph: &ModuleGraph) -> (Vec<Vec<Name>>, bool) {
+        let search = graph.cycles_within(self.config.analysis.cycle_limits());
+        let cycles = search.cycles
+            .into_iter()
+            .map(|cycle|
+                cycle
+                    .into_iter()
+                    .map(|name| self.interner.intern(name))
+                    .collect()
+            )
+            .collect();
+        (cycles, search.truncated)
+    }
+
+    fn calculate_metrics(&self, modules: &[ModuleInfo]) -> ArchitectureMetrics {
+        let total_modules = modules.len();
+        let core_modules = modules
+            .iter()
+            .filter(|m| m.module_type == ModuleType::Core)
+            .count();
+        let shared_modules = modules
+            .iter()
+            .filter(|m| m.module_type == ModuleType::Shared)
+            .count();
+        let feature_modules = modules
+            .iter()
+            .filter(|m| m.module_type == ModuleType::Feature)
+            .count();
+
+        // 外部パッケージは結合度に含めない
+        let total_dependencies: usize = modules
+            .iter()
+            .map(|m| m.internal_dependencies().count())
+            .sum();
+        let average_dependencies_per_module = if total_modules > 0 {
+            (total_dependencies as f32) / (total_modules as f32)
+        } else {
+            0.0
+        };
+
+        // 結合度の計算（依存関係の密度）
+        let possible_connections = if total_modules > 1 {
+            total_modules * (total_modules - 1)
+        } else {
+            1
+        };
+        let coupling_factor = (total_dependencies as f32) / (possible_connections as f32);
+
+        ArchitectureMetrics {
+            total_modules,
+            core_modules,
+            shared_modules,
+            feature_modules,
+            average_dependencies_per_module,
+            max_dependency_depth: 0,
+            coupling_factor,
+            health_score: 100.0,
+        }
+    }
+
+    /// モジュール間の依存関係をDOT形式で出力する
+    pub fn generate_dot_graph(&self, modules: &[ModuleInfo]) -> String {
+        output::render_dot(modules, output::Externals::Hide)
+    }
+}
+
+/// `@NgModule`のメタデータの配列の要素と、その位置（複数行にわたる要素は空白を1つにまとめる）
+fn extract_ngmodule_array(code: &str, arrays: &[(&str, Range<usize>)], field: &str) -> Vec<(usize, String)> {
+    arrays
+        .iter()
+        .filter(|(f, _)| *f == field)
+        .flat_map(|(_, array)| {
+            let inner = &code[array.clone()];
+            source::top_level_items(inner)
+                .into_iter()
+                .map(move |item| {
+                    let entry = inner[item.clone()].split_whitespace().collect::<Vec<_>>().join(" ");
+                    (array.start + item.start, entry)
+                })
+        })
+        .collect()
+}
+
+/// 別のファイルで宣言したモジュールをre-exportしているだけのファイルを除く
+///
+/// 宣言しているファイルも解析していれば同じモジュールが2つになるため。見つからなければre-exportを残す。
+fn remove_re_exports(modules: &mut Vec<ModuleInfo>) {
+    let declared: HashSet<Name> = modules
+        .iter()
+        .filter(|m| m.export_style != ExportStyle::ReExport)
+        .map(|m| m.name.clone())
+        .collect();
+    modules.retain(|m| m.export_style != ExportStyle::ReExport || !declared.contains(&m.name));
+}
+
+/// `module_dependencies`をプロジェクト内のモジュールと照合する
+///
+/// 相対パスのimport文で読み込んでいる識別子は、そのファイルで宣言しているモジュールに解決する。
+/// パスエイリアスやバレル（`index.ts`）経由など、ファイルをたどれない場合はモジュール名で照合する。
+/// プロジェクト内のモジュールをimportしているモジュール指定子はパスエイリアスなので、`external_dependencies`から除く。
+fn resolve_module_dependencies(modules: &mut [ModuleInfo]) {
+    // 拡張子を除いたパス -> (モジュール名, パス)
+    let files: HashMap<PathBuf, (Name, PathBuf)> = modules
+        .iter()
+        .map(|m| (normalize_path(&m.path.with_extension("")), (m.name.clone(), m.path.clone())))
+        .collect();
+    let mut names: HashMap<Name, Option<PathBuf>> = HashMap::new();
+    for module in modules.iter() {
+        // 同じ名前のモジュールが複数あれば、名前だけではどれか決められない（未解決のままにする）
+        names
+            .entry(module.name.clone())
+            .and_modify(|path| *path = None)
+            .or_insert_with(|| Some(module.path.clone()));
+    }
+    for module in modules {
+        let directory = module.path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut aliases = HashSet::new();
+        for dep in &mut module.module_dependencies {
+            let by_file = dep.specifier
+                .as_deref()
+                .filter(|specifier| specifier.starts_with('.'))
+                .and_then(|specifier| files.get(&normalize_path(&directory.join(specifier))))
+                .filter(|(name, _)| *name == dep.name)
+                .map(|(_, path)| path.clone());
+            let Some(path) = by_file.or_else(|| names.get(&dep.name).cloned().flatten()) else {
+                continue;
+            };
+            dep.resolution = Resolution::Resolved;
+            dep.path = Some(path);
+            aliases.extend(dep.specifier.clone());
+        }
+        module.external_dependencies.retain(|package| !aliases.contains(&package.specifier));
+    }
+}
+
+/// `exports`の要素のうちファイル単位では分からなかったものを、importしているモジュールと他のモジュールの宣言から分類する
+///
+/// `imports`で解決できたモジュールとプロジェクト内のモジュールの名前、パッケージからimportした`Module`で終わる名前はモジュール、
+/// 他のモジュールの`declarations`にあればコンポーネントなどの宣言とする。
+fn classify_exports(modules: &mut [ModuleInfo]) {
+    let module_names: HashSet<Name> = modules
+        .iter()
+        .map(|m| m.name.clone())
+        .collect();
+    let declared: HashSet<Name> = modules
+        .iter()
+        .flat_map(|m| m.declarations.iter().map(|d| Name::from(graph::reference_name(d))))
+        .collect();
+    for module in modules {
+        for export in module.export_kinds.iter_mut().filter(|export| export.kind == ExportKind::Unknown) {
+            let imported = module.module_dependencies.iter().find(|dep| dep.name == export.name);
+            let is_module = match imported.map(|dep| dep.resolution) {
+                Some(Resolution::Resolved) => true,
+                Some(Resolution::External) => export.name.ends_with("Module"),
+                _ => module_names.contains(&export.name),
+            };
+            if is_module {
+                export.kind = ExportKind::Module;
+            } else if declared.contains(&export.name) {
+                export.kind = ExportKind::Declarable;
+            }
+        }
+    }
+}
+
+/// 循環の各エッジ（i番目から次のモジュールへの参照）の場所
+fn cycle_locations(modules: &[ModuleInfo], cycle: &[Name]) -> Vec<Option<SourceLocation>> {
+    (0..cycle.len())
+        .map(|i| {
+            let next = &cycle[(i + 1) % cycle.len()];
+            modules
+                .iter()
+                .find(|m| m.name == cycle[i])
+                .and_then(|m| m.location_of(next))
+        })
+        .collect()
+}
+
+/// `.gitignore`の有無にかかわらず既定で走査しないディレクトリ（依存パッケージとビルド成果物）
+pub const DEFAULT_IGNORED_DIRS: [&str; 3] = ["node_modules", "dist", ".angular"];
+
+/// プロジェクトルートに置く除外ファイル名（`.gitignore`と同じ書式）
+pub const IGNORE_FILE_NAME: &str = ".analyzerignore";
+
+/// プロジェクトルートの[`IGNORE_FILE_NAME`]と設定ファイルの`[analysis] exclude`をまとめた除外ルール
+///
+/// パターンはどちらもプロジェクトルートからの相対で、`exclude`は除外ファイルの後ろに書いたものとして扱う。
+/// `ignore_file`（除外ファイルの内容）を渡した場合はディスク上の除外ファイルを読まない。
+/// gitと同じく、解釈できない行は無視する。
+pub(crate) fn exclusions(root: &Path, ignore_file: Option<&str>, exclude: &[String]) -> Arc<Gitignore> {
+    let mut builder = GitignoreBuilder::new(root);
+    let file = root.join(IGNORE_FILE_NAME);
+    if let Some(content) = ignore_file {
+        for line in content.lines() {
+            let _ = builder.add_line(Some(file.clone()), line);
+        }
+    } else if file.is_file() {
+        // 一部の行が不正でも残りの行は追加される
+        let _ = builder.add(file);
+    }
+    for pattern in exclude {
+        let _ = builder.add_line(None, pattern);
+    }
+    Arc::new(builder.build().unwrap_or_else(|_| Gitignore::empty()))
+}
+
+/// 設定ファイルを読み込んで[`exclusions`]を作る（アナライザーを使わない走査用、設定が不正なら除外ファイルだけ）
+pub(crate) fn project_exclusions(root: &Path) -> Arc<Gitignore> {
+    let exclude = Config::load(root)
+        .map(|config| config.analysis.exclude)
+        .unwrap_or_default();
+    exclusions(root, None, &exclude)
+}
+
+/// `root`以下の`*.module.ts`ファイルを走査順に列挙する
+///
+/// `respect_ignore`がtrueなら`.gitignore`などの除外設定と[`DEFAULT_IGNORED_DIRS`]に従う。
+/// `exclusions`（[`IGNORE_FILE_NAME`]と設定ファイルの除外パターン）は`respect_ignore`にかかわらず適用する。
+pub(crate) fn module_files(
+    root: &Path,
+    follow_symlinks: bool,
+    respect_ignore: bool,
+    include_tests: bool,
+    exclusions: Arc<Gitignore>
+) -> impl Iterator<Item = PathBuf> + use<> {
+    source_files(root, follow_symlinks, respect_ignore, include_tests, exclusions).filter(|path| is_module_file(path))
+}
+
+/// テストやStorybookのためのファイルか（`relative_path`はプロジェクトルートからの相対パス）
+///
+/// `*.spec.ts`・`*.stories.ts`（`foo.stories.module.ts`のように途中にあるものも含む）と、`testing/`ディレクトリの中のファイル。
+pub fn is_test_file(relative_path: &Path) -> bool {
+    let in_testing_dir = relative_path
+        .parent()
+        .is_some_and(|dir| dir.components().any(|c| c.as_os_str() == "testing"));
+    in_testing_dir ||
+        relative_path.file_name().is_some_and(|name| {
+            name.to_string_lossy()
+                .split('.')
+                .skip(1)
+                .any(|part| part == "spec" || part == "stories")
+        })
+}
+
+/// NgModuleを探す対象のファイル名（`*.module.ts`）か
+pub(crate) fn is_module_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().ends_with(".module.ts"))
+}
+
+/// ディレクトリ走査の最大の深さ（深すぎる生成物やリンクの連鎖で止まらないようにする）
+pub const MAX_DIRECTORY_DEPTH: usize = 64;
+
+/// `root`以下のTypeScriptファイル（型定義と、`include_tests`がfalseならテスト用のファイルを除く）を走査順に列挙する
+///
+/// `follow_symlinks`がtrueならシンボリックリンクをたどる。祖先を指すリンクの循環はたどらず、
+/// ツリー内の別の場所を指すリンクで同じファイルに2回行き着いた場合は、名前順の走査で先に見つけたパスだけを返す。
+pub(crate) fn source_files(
+    root: &Path,
+    follow_symlinks: bool,
+    respect_ignore: bool,
+    include_tests: bool,
+    exclusions: Arc<Gitignore>
+) -> impl Iterator<Item = PathBuf> + use<> {
+    let root = root.to_path_buf();
+    let mut walker = ignore::WalkBuilder::new(&root);
+    walker
+        .follow_links(follow_symlinks)
+        .standard_filters(respect_ignore)
+        .hidden(false)
+        .max_depth(Some(MAX_DIRECTORY_DEPTH))
+        // Gitリポジトリの外（展開したアーカイブなど）でも.gitignoreを使う
+        .require_git(false);
+    if follow_symlinks {
+        // どちらのパスを残すかが走査順で変わらないよう名前順にたどる
+        walker.sort_by_file_name(|a, b| a.cmp(b));
+    }
+    walker.filter_entry(move |entry| {
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        // 除外したディレクトリの中には入らないので、祖先ディレクトリを照合し直す必要はない
+        let ignored_dir = respect_ignore && is_dir && DEFAULT_IGNORED_DIRS.iter().any(|dir| entry.file_name() == *dir);
+        !ignored_dir && !exclusions.matched(entry.path(), is_dir).is_ignore()
+    });
+    let mut visited = HashSet::new();
+    walker
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(move |entry| {
+            let path = entry.path();
+            path.extension().is_some_and(|ext| ext == "ts") &&
+                !path.file_name().is_some_and(|name| name.to_string_lossy().ends_with(".d.ts")) &&
+                (include_tests || !is_test_file(path.strip_prefix(&root).unwrap_or(path)))
+        })
+        .filter(move |entry| {
+            // リンクを実体のパスで比べて、2回目以降は読み飛ばす
+            !follow_symlinks || entry.path().canonicalize().map_or(true, |real| visited.insert(real))
+        })
+        .map(|entry| entry.into_path())
+}
+
+/// `..`と`.`を字句的に解決する（存在しないファイルも扱えるよう`canonicalize`は使わない）
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            std::path::Component::CurDir => {}
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// 違反1件につき5点、循環1件につき10点、結合度0.1あたり5点を100点から減点する
+fn health_score(metrics: &ArchitectureMetrics, violations: usize, cycles: usize) -> f32 {
+    if metrics.total_modules == 0 {
+        return 100.0;
+    }
+    let penalty = (violations as f32) * 5.0 + (cycles as f32) * 10.0 + metrics.coupling_factor * 50.0;
+    (100.0 - penalty).clamp(0.0, 100.0)
+}
@@ -0,0 +1,183 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::coverage::Coverage;
+use crate::table::{ Align, Table };
+use crate::{ AnalysisResult, ModuleInfo, ModuleType };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TypeFilter {
+    Core,
+    Shared,
+    Feature,
+    Unknown,
+}
+
+impl TypeFilter {
+    pub(crate) fn matches(self, module_type: &ModuleType) -> bool {
+        matches!(
+            (self, module_type),
+            (TypeFilter::Core, ModuleType::Core) |
+                (TypeFilter::Shared, ModuleType::Shared) |
+                (TypeFilter::Feature, ModuleType::Feature) |
+                (TypeFilter::Unknown, ModuleType::Unknown)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Column {
+    Name,
+    Type,
+    Deps,
+    FanIn,
+    FanOut,
+    Imports,
+    Exports,
+    Declarations,
+    Providers,
+    Violations,
+    /// `--coverage`で読み込んだカバー率
+    Coverage,
+    Path,
+}
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::Name => "MODULE",
+            Column::Type => "TYPE",
+            Column::Deps => "DEPS",
+            Column::FanIn => "FAN-IN",
+            Column::FanOut => "FAN-OUT",
+            Column::Imports => "IMPORTS",
+            Column::Exports => "EXPORTS",
+            Column::Declarations => "DECLARATIONS",
+            Column::Providers => "PROVIDERS",
+            Column::Violations => "VIOLATIONS",
+            Column::Coverage => "COVERAGE",
+            Column::Path => "PATH",
+        }
+    }
+
+    fn is_numeric(self) -> bool {
+        !matches!(self, Column::Name | Column::Type | Column::Path)
+    }
+}
+
+/// 一覧表示用に集計したモジュールの指標
+pub struct ModuleRow<'a> {
+    pub module: &'a ModuleInfo,
+    pub fan_in: usize,
+    pub fan_out: usize,
+    pub violations: usize,
+    pub coverage: Option<Coverage>,
+}
+
+impl ModuleRow<'_> {
+    fn numeric(&self, column: Column) -> usize {
+        match column {
+            Column::Deps => self.module.internal_dependencies().count(),
+            Column::FanIn => self.fan_in,
+            Column::FanOut => self.fan_out,
+            Column::Imports => self.module.imports.len(),
+            Column::Exports => self.module.exports.len(),
+            Column::Declarations => self.module.declarations.len(),
+            Column::Providers => self.module.providers.len(),
+            Column::Violations => self.violations,
+            Column::Name | Column::Type | Column::Coverage | Column::Path => 0,
+        }
+    }
+
+    fn cell(&self, column: Column) -> String {
+        match column {
+            Column::Name => self.module.name.to_string(),
+            Column::Type => self.module.module_type.to_string(),
+            Column::Path => self.module.path.display().to_string(),
+            Column::Coverage =>
+                self.coverage
+                    .and_then(|coverage| coverage.percent())
+                    .map_or_else(|| "-".to_string(), |percent| format!("{:.1}%", percent)),
+            _ => self.numeric(column).to_string(),
+        }
+    }
+
+    fn compare(&self, other: &Self, column: Column) -> Ordering {
+        match column {
+            // カバレッジのないモジュールは最も低いものとして扱う
+            Column::Coverage => {
+                let percent = |row: &Self| row.coverage.and_then(|coverage| coverage.percent());
+                percent(self).partial_cmp(&percent(other)).unwrap_or(Ordering::Equal)
+            }
+            column if column.is_numeric() => self.numeric(column).cmp(&other.numeric(column)),
+            column => self.cell(column).cmp(&other.cell(column)),
+        }
+    }
+}
+
+pub fn module_rows<'a>(
+    result: &'a AnalysisResult,
+    coverage: Option<&HashMap<String, Coverage>>
+) -> Vec<ModuleRow<'a>> {
+    let graph = result.graph();
+    let mut violations: HashMap<&str, usize> = HashMap::new();
+    for violation in &result.dependency_violations {
+        *violations.entry(violation.from_module.as_ref()).or_default() += 1;
+    }
+
+    result.modules
+        .iter()
+        .map(|module| ModuleRow {
+            module,
+            fan_in: graph.dependents_of(&module.name).len(),
+            fan_out: graph.dependencies_of(&module.name).len(),
+            violations: violations.get(module.name.as_ref()).copied().unwrap_or(0),
+            coverage: coverage.map(|coverage| coverage.get(module.name.as_ref()).copied().unwrap_or_default()),
+        })
+        .collect()
+}
+
+pub struct ListOptions<'a> {
+    pub module_type: Option<TypeFilter>,
+    pub sort: Column,
+    pub descending: bool,
+    pub limit: Option<usize>,
+    pub columns: &'a [Column],
+    /// モジュールごとのカバレッジ（`coverage::per_module`）
+    pub coverage: Option<&'a HashMap<String, Coverage>>,
+}
+
+/// 条件で絞り込み・並べ替えたモジュールを表形式の文字列にする
+pub fn render_list(result: &AnalysisResult, options: &ListOptions) -> String {
+    let mut rows: Vec<ModuleRow> = module_rows(result, options.coverage)
+        .into_iter()
+        .filter(|row| options.module_type.is_none_or(|t| t.matches(&row.module.module_type)))
+        .collect();
+
+    rows.sort_by(|a, b| {
+        let ordering = a.compare(b, options.sort);
+        let ordering = if options.descending { ordering.reverse() } else { ordering };
+        ordering.then_with(|| a.module.name.cmp(&b.module.name))
+    });
+    if let Some(limit) = options.limit {
+        rows.truncate(limit);
+    }
+
+    let mut table = Table::new(
+        options.columns
+            .iter()
+            .map(|&c| (c.header(), if c.is_numeric() { Align::Right } else { Align::Left }))
+    );
+    for row in &rows {
+        table.row(
+            options.columns
+                .iter()
+                .map(|&c| row.cell(c))
+                .collect()
+        );
+    }
+    table.render()
+}
@@ -0,0 +1,169 @@
+use anyhow::{ Context, Result };
+use lsp_server::{ Connection, Message, Notification };
+use lsp_types::notification::{
+    DidSaveTextDocument,
+    Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::{
+    Diagnostic,
+    DiagnosticSeverity,
+    InitializeParams,
+    NumberOrString,
+    Position,
+    PublishDiagnosticsParams,
+    Range,
+    SaveOptions,
+    ServerCapabilities,
+    TextDocumentSyncCapability,
+    TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions,
+    Url,
+};
+use std::collections::{ BTreeMap, HashSet };
+use std::path::{ Path, PathBuf };
+
+use angular_module_analyzer::config::RuleLevel;
+use angular_module_analyzer::{ AnalysisResult, AngularAnalyzer, SourceLocation, ViolationType };
+
+/// 標準入出力でLanguage Serverとして動作し、モジュールファイルに違反と循環を診断として表示する
+///
+/// `open`は保存のたびに解析器を作り直す（設定ファイルの変更も反映する）。
+/// 解析はキャッシュを使うので、変更されたファイルだけが再解析される。
+pub fn run(default_root: &Path, open: impl Fn(&Path) -> Result<AngularAnalyzer>) -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(
+            TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+                open_close: Some(false),
+                save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions { include_text: Some(false) })),
+                ..Default::default()
+            })
+        ),
+        ..Default::default()
+    };
+    let params: InitializeParams = serde_json::from_value(
+        connection.initialize(serde_json::to_value(capabilities)?)?
+    )?;
+    #[allow(deprecated)]
+    let root = params.root_uri
+        .and_then(|uri| uri.to_file_path().ok())
+        .unwrap_or_else(|| default_root.to_path_buf());
+    let root = root.canonicalize().with_context(|| format!("Failed to open project {:?}", root))?;
+
+    let mut published = HashSet::new();
+    publish(&connection, &open(&root)?, &mut published)?;
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) if connection.handle_shutdown(&request)? => {
+                break;
+            }
+            Message::Notification(notification) if notification.method == DidSaveTextDocument::METHOD => {
+                // 解析に失敗しても（設定ファイルの書きかけなど）サーバーは止めず、次の保存を待つ
+                match open(&root) {
+                    Ok(analyzer) => publish(&connection, &analyzer, &mut published)?,
+                    Err(err) => eprintln!("angular-analyzer: {:#}", err),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 送信側を閉じないと書き込みスレッドが終了しない
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// 解析して診断を送る（前回診断があり今回なくなったファイルは空の診断で消す）
+fn publish(connection: &Connection, analyzer: &AngularAnalyzer, published: &mut HashSet<PathBuf>) -> Result<()> {
+    let result = match analyzer.analyze() {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("angular-analyzer: {}", err);
+            return Ok(());
+        }
+    };
+    let mut diagnostics = diagnostics(&result, |rule| {
+        match analyzer.config().rules.get(rule.code()) {
+            Some(RuleLevel::Warn) => DiagnosticSeverity::WARNING,
+            _ => DiagnosticSeverity::ERROR,
+        }
+    });
+    for file in published.drain() {
+        diagnostics.entry(file).or_default();
+    }
+
+    for (file, diagnostics) in diagnostics {
+        let Ok(uri) = Url::from_file_path(&file) else {
+            continue;
+        };
+        if !diagnostics.is_empty() {
+            published.insert(file);
+        }
+        let params = PublishDiagnosticsParams { uri, diagnostics, version: None };
+        connection.sender.send(Message::Notification(Notification::new(PublishDiagnostics::METHOD.to_string(), params)))?;
+    }
+    Ok(())
+}
+
+/// ファイルごとの診断（違反は依存先を参照している箇所、循環は次のモジュールを参照している箇所に付ける）
+fn diagnostics(
+    result: &AnalysisResult,
+    severity: impl Fn(&ViolationType) -> DiagnosticSeverity
+) -> BTreeMap<PathBuf, Vec<Diagnostic>> {
+    let mut diagnostics: BTreeMap<PathBuf, Vec<Diagnostic>> = BTreeMap::new();
+    let file_of = |name: &str| {
+        result.modules
+            .iter()
+            .find(|m| *m.name == *name)
+            .map(|m| m.path.clone())
+    };
+    let mut add = |rule: &ViolationType, module: &str, target: &str, location: Option<&SourceLocation>, message: String| {
+        let (file, range) = match location {
+            Some(location) => {
+                let start = Position::new((location.line - 1) as u32, (location.column - 1) as u32);
+                let end = Position::new(start.line, start.character + (target.encode_utf16().count() as u32));
+                (location.file.clone(), Range::new(start, end))
+            }
+            None => {
+                let Some(file) = file_of(module) else {
+                    return;
+                };
+                (file, Range::default())
+            }
+        };
+        diagnostics.entry(file).or_default().push(Diagnostic {
+            range,
+            severity: Some(severity(rule)),
+            code: Some(NumberOrString::String(rule.code().to_string())),
+            source: Some("angular-analyzer".to_string()),
+            message,
+            ..Default::default()
+        });
+    };
+
+    for violation in &result.dependency_violations {
+        add(
+            &violation.violation_type,
+            &violation.from_module,
+            &violation.to_module,
+            violation.location.as_ref(),
+            violation.to_string()
+        );
+    }
+    for (i, cycle) in result.circular_dependencies.iter().enumerate() {
+        let message = format!("Circular dependency: {} -> {}", cycle.join(" -> "), cycle[0]);
+        for (j, module) in cycle.iter().enumerate() {
+            let next = &cycle[(j + 1) % cycle.len()];
+            let location = result.circular_dependency_locations
+                .get(i)
+                .and_then(|locations| locations.get(j))
+                .and_then(Option::as_ref);
+            add(&ViolationType::CircularDependency, module, next, location, message.clone());
+        }
+    }
+    diagnostics
+}
@@ -1,476 +1,1602 @@
+use angular_module_analyzer::cache::{ self, DEFAULT_CACHE_DIR };
+use angular_module_analyzer::config::{ self, Config };
+use angular_module_analyzer::output::{ self, OutputFormat };
+use angular_module_analyzer::c4::{ self, ContainerGrouping };
+use angular_module_analyzer::deadcode::{ self, Confidence };
+use angular_module_analyzer::delta::ResultDelta;
+use angular_module_analyzer::drift::{ DriftReport, TargetArchitecture };
+use angular_module_analyzer::fix::{ self, FixOptions };
+use angular_module_analyzer::i18n::Lang;
+use angular_module_analyzer::impact::ImpactReport;
+use angular_module_analyzer::paths::{ PathStyle, PresentPaths };
+use angular_module_analyzer::routes::RouteTree;
+use angular_module_analyzer::services::{ MultiProviders, ProviderScopes, ServiceTree };
+use angular_module_analyzer::split;
+use angular_module_analyzer::trend::{ self, TrendPoint };
+use angular_module_analyzer::workspaces::{ self, Workspace };
+use angular_module_analyzer::{ AnalysisResult, AnalyzerBuilder, AngularAnalyzer, aggregate, archive, check, chunks, compare, coverage, compodoc, doctor, fitness, init, issues, list, merge, metrics, notify, report, snapshot, standalone };
 use anyhow::{ Context, Result };
-use clap::{ Parser, Subcommand };
+use clap::{ CommandFactory, Parser, Subcommand };
+use clap_complete::{ CompleteEnv, Shell };
+use clap_complete::engine::{ ArgValueCompleter, CompletionCandidate };
 use colored::*;
-use petgraph::{ Graph, Undirected };
-use serde::{ Deserialize, Serialize };
-use std::collections::{ HashMap, HashSet };
+use std::ffi::OsStr;
 use std::fs;
+use std::io::IsTerminal;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
 use std::path::{ Path, PathBuf };
-use walkdir::WalkDir;
+
+mod console;
+mod git;
+mod lsp;
+mod serve;
+#[cfg(feature = "otel")]
+mod telemetry;
+mod tui;
+
+use console::*;
 
 #[derive(Parser)]
 #[command(name = "angular-analyzer")]
 #[command(about = "Angular module architecture analyzer")]
 struct Cli {
+    /// Write the report to a file; the format is inferred from the extension
+    /// (.json, .md, .html, .sarif, .dot, .mmd)
+    #[arg(short = 'o', long, global = true)]
+    out_file: Option<PathBuf>,
+    /// When to use colored output
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto, global = true)]
+    color: ColorChoice,
+    /// Print plain ASCII markers instead of emoji and box drawing (the default when TERM is unset or dumb)
+    #[arg(long, visible_alias = "no-emoji", global = true)]
+    ascii: bool,
+    /// Language of console, Markdown and HTML reports (detected from LC_ALL, LC_MESSAGES and LANG by default)
+    #[arg(long, value_enum, global = true)]
+    lang: Option<Lang>,
+    /// Show at most N items per section of the console report (0 shows everything)
+    #[arg(long, value_name = "N", default_value_t = 20, global = true)]
+    max_items: usize,
+    /// Show every item of the console report, overriding --max-items
+    #[arg(long, global = true)]
+    full: bool,
+    /// Page the console report through $PAGER (less -FRX by default) when stdout is a terminal
+    #[arg(long, global = true)]
+    pager: bool,
+    #[command(flatten)]
+    analysis: AnalysisOptions,
     #[command(subcommand)]
     command: Commands,
 }
 
+/// 解析の実行方法に関するオプション（全サブコマンド共通）
+#[derive(clap::Args)]
+struct AnalysisOptions {
+    /// Also analyze files in node_modules, dist, .angular and paths excluded by .gitignore
+    #[arg(long, global = true)]
+    no_ignore: bool,
+    /// Follow symbolic links while walking the project (each real file is analyzed once)
+    #[arg(long, global = true)]
+    follow_symlinks: bool,
+    /// Also analyze *.spec.ts, *.stories.ts and testing/ modules (listed separately as test modules)
+    #[arg(long, global = true)]
+    include_tests: bool,
+    /// Cache parsed modules in <PATH>/.angular-analyzer-cache and re-parse only changed files
+    #[arg(long, global = true)]
+    cache: bool,
+    /// Directory for the parse cache (implies --cache)
+    #[arg(long, global = true, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+    /// Write file paths in the analysis output relative to the project root or as absolute paths
+    #[arg(long, global = true, value_enum, default_value_t = PathStyle::Relative)]
+    path_style: PathStyle,
+    /// Skip module files larger than this (e.g. 512K, 20M; 0 disables the limit)
+    #[arg(long, global = true, value_name = "SIZE", value_parser = parse_size, default_value = "5M")]
+    max_file_size: u64,
+    /// Skip module files whose extraction takes longer than this many seconds (0 disables the limit)
+    #[arg(long, global = true, value_name = "SECS", default_value_t = 5)]
+    parse_timeout: u64,
+    /// Keep only dependency references of each module to bound memory on huge monorepos
+    /// (exports, providers and declarations are not reported)
+    #[arg(long, global = true)]
+    low_memory: bool,
+    /// Also build the component/directive/pipe graph from templates and imports (included in JSON output)
+    #[arg(long, global = true)]
+    components: bool,
+    /// Number of worker threads for parsing (overrides [analysis] jobs; defaults to all cores)
+    #[arg(short, long, global = true, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+    /// Print time spent in each analysis phase and file counts to stderr
+    #[arg(long, global = true)]
+    profile: bool,
+    #[arg(skip)]
+    profiler: Option<Arc<Profiler>>,
+    /// Exit with status 1 when some module files could not be read and are missing from the results
+    #[arg(long, global = true)]
+    strict: bool,
+    #[arg(skip)]
+    skipped: Arc<SkippedFiles>,
+    /// Exit with status 1 when a module matches no [classification] rule and neither its path nor its
+    /// @NgModule metadata settles the layer, or when the two disagree
+    #[arg(long, global = true)]
+    strict_classification: bool,
+    #[arg(skip)]
+    ambiguous: Arc<AmbiguousClassifications>,
+    /// Export analysis phase spans via OTLP/HTTP (configure with the OTEL_EXPORTER_OTLP_* variables)
+    #[cfg(feature = "otel")]
+    #[arg(long, global = true)]
+    otel: bool,
+    #[cfg(feature = "otel")]
+    #[arg(skip)]
+    telemetry: Option<telemetry::Telemetry>,
+}
+
+/// `12h`や`2w`のような単位付きの間隔を秒数に変換する（`m`は30日、`y`は365日）
+fn parse_interval(value: &str) -> std::result::Result<u64, String> {
+    let (digits, unit) = value.split_at(value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len()));
+    let count: u64 = digits.parse().map_err(|_| format!("invalid interval '{}'", value))?;
+    let seconds = match unit {
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        "m" => 30 * 86_400,
+        "y" => 365 * 86_400,
+        _ => {
+            return Err(format!("unknown interval unit '{}' (use h, d, w, m or y)", unit));
+        }
+    };
+    count.checked_mul(seconds).ok_or_else(|| format!("interval '{}' is too large", value))
+}
+
+/// 補完中のコマンドラインの`--path`（`--cache-dir`）のキャッシュにあるモジュール名で補完する
+///
+/// 候補を計算する時点ではコマンドラインは解析されていないので、オプションの値は引数を直接探して読む。
+fn complete_module(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return Vec::new();
+    };
+    let args: Vec<String> = std::env
+        ::args_os()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let option = |names: &[&str]| {
+        args.iter()
+            .enumerate()
+            .rev()
+            .find_map(|(i, arg)| {
+                names.iter().find_map(|name| {
+                    if arg == name {
+                        args.get(i + 1).cloned()
+                    } else {
+                        arg.strip_prefix(&format!("{}=", name)).map(str::to_string)
+                    }
+                })
+            })
+    };
+    let path = option(&["-p", "--path"]).unwrap_or_else(|| ".".to_string());
+    let dir = option(&["--cache-dir"]).map(PathBuf::from).unwrap_or_else(|| Path::new(&path).join(DEFAULT_CACHE_DIR));
+    cache
+        ::module_names(&dir)
+        .into_iter()
+        .filter(|name| name.starts_with(current))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// `512K`や`20M`のような単位付きのサイズをバイト数に変換する
+fn parse_size(value: &str) -> std::result::Result<u64, String> {
+    let (digits, unit) = match value.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => value.split_at(i),
+        None => (value, ""),
+    };
+    let multiplier = match unit.to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1024,
+        "M" | "MB" => 1024 * 1024,
+        "G" | "GB" => 1024 * 1024 * 1024,
+        _ => {
+            return Err(format!("unknown size unit '{}' (use K, M or G)", unit));
+        }
+    };
+    let n: u64 = digits.parse().map_err(|_| format!("invalid size '{}'", value))?;
+    n.checked_mul(multiplier).ok_or_else(|| format!("size '{}' is too large", value))
+}
+
+#[derive(Subcommand)]
+enum SnapshotAction {
+    /// Write the current module graph to the snapshot file
+    Save {
+        /// Path to Angular project
+        #[arg(short, long, default_value = ".")]
+        path: String,
+        /// Snapshot file (default: architecture.snap in the project root)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Fail with a diff when the module graph no longer matches the snapshot file
+    Verify {
+        /// Path to Angular project
+        #[arg(short, long, default_value = ".")]
+        path: String,
+        /// Snapshot file (default: architecture.snap in the project root)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Show how many modules are cached and whether the cache is still valid
+    Stats {
+        /// Path to Angular project
+        #[arg(short, long, default_value = ".")]
+        path: String,
+    },
+    /// Delete the cache
+    Clear {
+        /// Path to Angular project
+        #[arg(short, long, default_value = ".")]
+        path: String,
+    },
+    /// Print the cache directory
+    Path {
+        /// Path to Angular project
+        #[arg(short, long, default_value = ".")]
+        path: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Analyze module dependencies
     Analyze {
+        /// Path to Angular project (with --git or --archive, relative to the repository root)
+        #[arg(short, long, required_unless_present_any = ["git", "archive"])]
+        path: Option<String>,
+        /// Output format (defaults to the --out-file extension, or table)
+        #[arg(short, long, alias = "output", value_enum)]
+        format: Option<OutputFormat>,
+        /// Shallow-clone this git repository into a temporary directory and analyze it
+        #[arg(long, value_name = "URL")]
+        git: Option<String>,
+        /// Branch, tag or commit to analyze with --git (default: the remote HEAD)
+        #[arg(long = "ref", value_name = "REF", requires = "git")]
+        reference: Option<String>,
+        /// Analyze the sources inside a .tar.gz, .tgz, .tar or .zip archive without extracting it
+        #[arg(long, value_name = "FILE", conflicts_with = "git")]
+        archive: Option<PathBuf>,
+    },
+    /// Generate dependency graph
+    Graph {
         /// Path to Angular project
         #[arg(short, long)]
         path: String,
-        /// Output format (json, console)
-        #[arg(short, long, default_value = "console")]
-        output: String,
+        /// Graph format (defaults to the --out-file extension, or dot)
+        #[arg(short, long, value_enum)]
+        format: Option<GraphFormat>,
+        /// How external npm packages appear in the graph
+        #[arg(long, value_enum, default_value = "hide")]
+        externals: output::Externals,
+        /// Draw modules, or components/directives/pipes connected by template usage and imports
+        #[arg(long, value_enum, default_value = "module")]
+        granularity: Granularity,
+        /// Draw the route hierarchy with lazy-loaded boundaries as clusters instead of module imports
+        #[arg(long, conflicts_with = "granularity")]
+        routes: bool,
+        /// Root module whose forRoot routes start the hierarchy (with --routes)
+        #[arg(long, default_value = "AppModule", requires = "routes")]
+        root: String,
     },
-    /// Generate dependency graph
-    Graph {
+    /// Export one ticket per violation for gh issue create or Jira CSV import
+    Export {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Ticket format
+        #[arg(short, long, value_enum, default_value = "issues")]
+        format: ExportFormat,
+        /// URL prefix for file links in the ticket body (e.g. https://github.com/org/repo/blob/main)
+        #[arg(long, value_name = "URL")]
+        link_base: Option<String>,
+    },
+    /// Export modules as C4 components in Structurizr DSL
+    C4 {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Containers the components are grouped into
+        #[arg(long, value_enum, default_value = "layer")]
+        group_by: ContainerGrouping,
+    },
+    /// Show the shortest dependency path(s) between two modules
+    Path {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Module the path starts from
+        #[arg(add = ArgValueCompleter::new(complete_module))]
+        from: String,
+        /// Module the path leads to
+        #[arg(add = ArgValueCompleter::new(complete_module))]
+        to: String,
+        /// Show all simple paths instead of only the shortest ones
+        #[arg(long)]
+        all: bool,
+        /// Maximum path length (in edges) when using --all
+        #[arg(long, default_value = "10")]
+        max_length: NonZeroUsize,
+    },
+    /// List every module that directly or transitively depends on a module
+    #[command(group = clap::ArgGroup::new("change").required(true).args(["module", "diff", "changed_files"]))]
+    Impact {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Module whose dependents should be listed
+        #[arg(add = ArgValueCompleter::new(complete_module))]
+        module: Option<String>,
+        /// Git revision range whose changed files are mapped to modules (e.g. HEAD~1..HEAD)
+        #[arg(long, value_name = "RANGE")]
+        diff: Option<String>,
+        /// File listing changed paths relative to the project, one per line ("-" for stdin)
+        #[arg(long, value_name = "FILE")]
+        changed_files: Option<PathBuf>,
+        /// Module that is loaded eagerly at startup (with --diff or --changed-files)
+        #[arg(long, default_value = "AppModule", add = ArgValueCompleter::new(complete_module))]
+        root: String,
+        /// Print the impact of the changed files as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List dependency cycles with the import statements forming them
+    Circular {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+    },
+    /// Write a multi-page static HTML report site
+    Report {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Directory the site is written to
+        #[arg(short, long, default_value = "architecture-report")]
+        dir: PathBuf,
+        /// Render the result through a Tera template instead of writing the site
+        /// (to stdout, or to --out-file)
+        #[arg(long, conflicts_with = "dir")]
+        template: Option<PathBuf>,
+    },
+    /// Explore modules interactively in the terminal
+    Tui {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+    },
+    /// List modules as a table with filtering and sorting
+    List {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Only list modules of this type
+        #[arg(short = 't', long = "type", value_enum)]
+        module_type: Option<list::TypeFilter>,
+        /// Column to sort by
+        #[arg(short, long, value_enum, default_value = "name")]
+        sort: list::Column,
+        /// Sort in descending order
+        #[arg(long)]
+        desc: bool,
+        /// Maximum number of modules to list
+        #[arg(short, long)]
+        limit: Option<usize>,
+        /// Columns to show
+        #[arg(
+            short,
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_value = "name,type,deps,fan-in,fan-out,violations"
+        )]
+        columns: Vec<list::Column>,
+        /// lcov.info or istanbul coverage JSON; adds a coverage column aggregated per module
+        #[arg(long, value_name = "FILE")]
+        coverage: Option<PathBuf>,
+    },
+    /// Print architecture metrics for monitoring systems
+    Metrics {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Metrics format
+        #[arg(short, long, value_enum, default_value = "prometheus")]
+        format: MetricsFormat,
+        /// Push the metrics to this Prometheus Pushgateway instead of printing them
+        #[arg(long, value_name = "URL")]
+        push_gateway: Option<String>,
+        /// Job name used when pushing to the Pushgateway
+        #[arg(long, default_value = metrics::DEFAULT_JOB)]
+        job: String,
+    },
+    /// Explain whether and how a module is transitively reachable from another
+    Why {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Module to explain
+        #[arg(add = ArgValueCompleter::new(complete_module))]
+        module: String,
+        /// Module the search starts from
+        #[arg(long, default_value = "AppModule", add = ArgValueCompleter::new(complete_module))]
+        reachable_from: String,
+    },
+    /// Evaluate architecture quality gates and exit non-zero if any fails
+    Check {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Previous `analyze -f json` result; only violations not in it fail the check
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Maximum coupling factor (overrides the config file)
+        #[arg(long)]
+        max_coupling: Option<f32>,
+        /// Maximum number of circular dependencies (overrides the config file)
+        #[arg(long)]
+        max_cycles: Option<usize>,
+        /// Maximum number of violations when no baseline is given (overrides the config file)
+        #[arg(long)]
+        max_violations: Option<usize>,
+        /// Minimum health score (overrides the config file)
+        #[arg(long)]
+        min_health: Option<f32>,
+        /// Fitness functions to evaluate (defaults to fitness.toml in the project root, if present)
+        #[arg(long, value_name = "FILE")]
+        fitness: Option<PathBuf>,
+        /// Do not post to the [notify] webhook even if one is configured
+        #[arg(long)]
+        no_notify: bool,
+    },
+    /// Diff the module edges against a madge or dependency-cruiser JSON result
+    Compare {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Output of `madge --json` or `depcruise --output-type json`
+        #[arg(long = "with", value_name = "FILE")]
+        with: PathBuf,
+        /// Print the comparison as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show which services are provided where and which classes inject them
+    Services {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "tree")]
+        format: ServicesFormat,
+    },
+    /// Show where every injection token is provided and report conflicting or shadowing scopes
+    Providers {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// List multi-providers (APP_INITIALIZER, HTTP_INTERCEPTORS, ...) in registration order instead
+        #[arg(long)]
+        multi: bool,
+        /// Print every token's registrations as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Group modules by pnpm/yarn/npm workspace package and report cross-package dependencies
+    Workspaces {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Correlate lazy-loading boundaries with the chunks of a production build
+    Chunks {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// stats.json from `ng build --stats-json` (webpack stats or esbuild metafile)
+        #[arg(long, value_name = "FILE")]
+        stats: PathBuf,
+        /// Module that is loaded eagerly at startup
+        #[arg(long, default_value = "AppModule")]
+        root: String,
+        /// Print the correlation as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Report modules, components, directives, pipes and services unreachable from the bootstrap module
+    DeadCode {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Module that is bootstrapped at startup
+        #[arg(long, default_value = "AppModule")]
+        root: String,
+        /// Only report items at least this certain
+        #[arg(long, value_enum, default_value = "low")]
+        min_confidence: Confidence,
+        /// Print the unused items as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Propose how to split oversized modules by clustering their component graph
+    Split {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Module to split (default: every module with at least --min-declarations declarations)
+        module: Option<String>,
+        /// Declarations a module needs to be considered oversized
+        #[arg(long, default_value_t = 30)]
+        min_declarations: usize,
+        /// Print the split plans as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Suggest merging clusters of tiny modules that only depend on each other and always load together
+    Merge {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Declarations a module may have to count as tiny
+        #[arg(long, default_value_t = 5)]
+        max_declarations: usize,
+        /// Print the merge candidates as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Plan the migration from NgModules to standalone components, leaf modules first
+    Standalone {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Print the migration plan as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Analyze historical revisions and emit a time series of the architecture metrics
+    Trend {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// How far back to go (anything git log --since accepts, e.g. "6 months ago")
+        #[arg(long, default_value = "6 months ago")]
+        since: String,
+        /// Minimum interval between analyzed revisions (e.g. 12h, 3d, 2w, 1m)
+        #[arg(long, default_value = "1w", value_parser = parse_interval)]
+        every: u64,
+        /// Time series format
+        #[arg(short, long, value_enum, default_value = "csv")]
+        format: TrendFormat,
+        /// Also add the revisions to the history of the HTML report site in this directory and rebuild it
+        #[arg(long, value_name = "DIR")]
+        report: Option<PathBuf>,
+    },
+    /// Report drift between the code and a target architecture or a previously blessed graph
+    Drift {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Target architecture (.toml), or a blessed graph / analysis result (.json)
+        #[arg(long, value_name = "FILE")]
+        target: PathBuf,
+        /// Write the current module graph to --target as the new blessed graph instead of checking
+        #[arg(long)]
+        bless: bool,
+        /// Print the drift as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Save the normalized module graph to a committed file, or verify it has not changed
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+    /// Remove unused NgModule imports/exports and duplicate entries, and optionally sort metadata arrays
+    Fix {
+        /// Path to Angular project
+        #[arg(short, long)]
+        path: String,
+        /// Only print the unified diff of the fixes (default)
+        #[arg(long, conflicts_with = "write")]
+        dry_run: bool,
+        /// Apply the fixes to the module files
+        #[arg(long)]
+        write: bool,
+        /// Also sort the declarations and exports arrays by name
+        #[arg(long)]
+        sort: bool,
+    },
+    /// Reconcile the analysis with a compodoc documentation.json to find parser gaps
+    Compodoc {
         /// Path to Angular project
         #[arg(short, long)]
         path: String,
-        /// Output file for graph
-        #[arg(short, long, default_value = "dependency-graph.dot")]
-        output: String,
+        /// documentation.json generated by compodoc (`compodoc --exportFormat json`)
+        #[arg(short, long, default_value = "documentation/documentation.json")]
+        documentation: PathBuf,
+        /// Print the reconciliation as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Analyze several repositories into one report comparing their external package usage
+    Aggregate {
+        /// Paths to Angular projects (repeat or list several)
+        #[arg(short, long = "path", num_args = 1.., required_unless_present = "manifest")]
+        paths: Vec<String>,
+        /// TOML file listing the repositories as [[repos]] with name and path
+        #[arg(long, conflicts_with = "paths")]
+        manifest: Option<PathBuf>,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = DocumentFormat::Markdown)]
+        format: DocumentFormat,
+    },
+    /// Summarize new violations, changed edges and metric deltas against a base branch as a PR comment
+    PrComment {
+        /// Path to Angular project (inside a git repository)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+        /// Branch, tag or commit to compare the working tree against
+        #[arg(long, default_value = "main")]
+        base: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = DocumentFormat::Markdown)]
+        format: DocumentFormat,
+    },
+    /// Serve a continuously refreshed analysis over HTTP (/analysis, /modules/{name}, /graph.json, /graph.svg, /metrics)
+    Serve {
+        /// Path to Angular project
+        #[arg(short, long, default_value = ".")]
+        path: String,
+        /// Port to listen on
+        #[arg(long, default_value_t = 4333)]
+        port: u16,
+        /// Address to bind
+        #[arg(long, default_value = "127.0.0.1")]
+        host: std::net::IpAddr,
+        /// Seconds between background re-analyses
+        #[arg(long, value_name = "SECONDS", default_value_t = 5)]
+        refresh: u64,
+    },
+    /// Run a language server that shows violations and cycles as diagnostics in module files
+    Lsp {
+        /// Project used when the editor does not send a workspace root
+        #[arg(short, long, default_value = ".")]
+        path: String,
+    },
+    /// Check that the path contains an analyzable Angular project
+    Doctor {
+        /// Path to Angular project
+        #[arg(short, long, default_value = ".")]
+        path: String,
+    },
+    /// Validate .angular-analyzer.toml and report every problem with its position
+    ValidateConfig {
+        /// Path to Angular project
+        #[arg(short, long, default_value = ".")]
+        path: String,
+    },
+    /// Inspect or delete the incremental parse cache used by --cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Write a starter .angular-analyzer.toml inferred from the project layout
+    Init {
+        /// Path to Angular project
+        #[arg(short, long, default_value = ".")]
+        path: String,
+        /// Overwrite an existing configuration file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Generate static shell completion scripts
+    ///
+    /// Module names (path, impact and why) are completed only by the dynamic completion,
+    /// enabled with `source <(COMPLETE=bash angular-analyzer)`, from the --cache of the project.
+    Completions {
+        /// Shell to generate completions for (bash, zsh, fish, powershell, elvish)
+        shell: Shell,
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ModuleInfo {
-    pub path: PathBuf,
-    pub name: String,
-    pub module_type: ModuleType,
-    pub imports: Vec<String>,
-    pub exports: Vec<String>,
-    pub providers: Vec<String>,
-    pub declarations: Vec<String>,
-    pub dependencies: Vec<String>,
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ColorChoice {
+    /// Color only when stdout is a terminal and NO_COLOR is not set
+    Auto,
+    Always,
+    Never,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub enum ModuleType {
-    Core,
-    Shared,
-    Feature,
-    Unknown,
+impl ColorChoice {
+    fn apply(self) {
+        let enabled = match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                // https://no-color.org/
+                let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+                !no_color && std::io::stdout().is_terminal()
+            }
+        };
+        colored::control::set_override(enabled);
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AnalysisResult {
-    pub modules: Vec<ModuleInfo>,
-    pub dependency_violations: Vec<DependencyViolation>,
-    pub circular_dependencies: Vec<Vec<String>>,
-    pub metrics: ArchitectureMetrics,
+/// `TERM`が未設定か`dumb`（CIのログなど、絵文字や罫線を表示できないことが多い）
+fn dumb_terminal() -> bool {
+    !cfg!(windows) && std::env::var("TERM").ok().is_none_or(|term| term.is_empty() || term == "dumb")
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DependencyViolation {
-    pub from_module: String,
-    pub to_module: String,
-    pub violation_type: ViolationType,
-    pub description: String,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum MetricsFormat {
+    /// Prometheus text exposition format
+    Prometheus,
+    /// The `metrics` object of the JSON report
+    Json,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub enum ViolationType {
-    CoreDependsOnFeature,
-    SharedDependsOnFeature,
-    FeatureToFeatureDirect,
-    CircularDependency,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ExportFormat {
+    /// One JSON record per line with title, body and labels
+    Issues,
+    /// CSV for Jira's issue import
+    JiraCsv,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ArchitectureMetrics {
-    pub total_modules: usize,
-    pub core_modules: usize,
-    pub shared_modules: usize,
-    pub feature_modules: usize,
-    pub average_dependencies_per_module: f32,
-    pub max_dependency_depth: usize,
-    pub coupling_factor: f32,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum DocumentFormat {
+    /// Markdown, ready to post
+    Markdown,
+    /// The underlying data as JSON
+    Json,
 }
 
-pub struct AngularAnalyzer {
-    project_path: PathBuf,
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum GraphFormat {
+    Dot,
+    Mermaid,
 }
 
-impl AngularAnalyzer {
-    pub fn new(project_path: &str) -> Self {
-        Self {
-            project_path: PathBuf::from(project_path),
-        }
-    }
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ServicesFormat {
+    Tree,
+    Dot,
+    Json,
+}
 
-    pub fn analyze(&self) -> Result<AnalysisResult> {
-        let modules = self.discover_modules()?;
-        let dependency_violations = self.check_dependency_violations(&modules);
-        let circular_dependencies = self.detect_circular_dependencies(&modules);
-        let metrics = self.calculate_metrics(&modules);
-
-        Ok(AnalysisResult {
-            modules,
-            dependency_violations,
-            circular_dependencies,
-            metrics,
-        })
-    }
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum TrendFormat {
+    Csv,
+    Json,
+}
 
-    fn discover_modules(&self) -> Result<Vec<ModuleInfo>> {
-        let mut modules = Vec::new();
-
-        for entry in WalkDir::new(&self.project_path)
-            .into_iter()
-            .filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if
-                path.extension().map_or(false, |ext| ext == "ts") &&
-                path
-                    .file_name()
-                    .map_or(false, |name| name.to_string_lossy().ends_with(".module.ts"))
-            {
-                if let Ok(module_info) = self.parse_module_file(path) {
-                    modules.push(module_info);
-                }
-            }
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Granularity {
+    Module,
+    Component,
+}
 
-        Ok(modules)
+/// 設定ファイルを読み込み、標準エラー出力が端末なら進捗を表示するアナライザーを作成する
+fn open_analyzer(path: &str, options: &AnalysisOptions) -> Result<AngularAnalyzer> {
+    let config = Config::load(Path::new(path))?;
+    let mut builder = analyzer_builder(path, config, options);
+    if options.cache || options.cache_dir.is_some() {
+        builder = builder.cache_dir(cache_dir(path, options));
     }
+    Ok(builder.build()?)
+}
 
-    fn parse_module_file(&self, path: &Path) -> Result<ModuleInfo> {
-        let content = fs
-            ::read_to_string(path)
-            .with_context(|| format!("Failed to read file: {:?}", path))?;
-
-        let name = self.extract_module_name(path, &content);
-        let module_type = self.determine_module_type(path, &content);
-        let imports = self.extract_imports(&content);
-        let exports = self.extract_exports(&content);
-        let providers = self.extract_providers(&content);
-        let declarations = self.extract_declarations(&content);
-        let dependencies = self.extract_dependencies(&content);
-
-        Ok(ModuleInfo {
-            path: path.to_path_buf(),
-            name,
-            module_type,
-            imports,
-            exports,
-            providers,
-            declarations,
-            dependencies,
-        })
+/// `--archive`のアーカイブを解析するAnalyzerと、アーカイブから読み込んだファイル（キャッシュは使わない）
+fn open_archive_analyzer(
+    archive: &Path,
+    root: Option<&Path>,
+    options: &AnalysisOptions
+) -> Result<(AngularAnalyzer, archive::ArchiveSources)> {
+    let max_file_size = Some(options.max_file_size).filter(|size| *size > 0);
+    let mut sources = archive::read(archive, root, max_file_size)?;
+    let config = match sources.config.take() {
+        Some(content) => Config::parse(&archive.join(config::CONFIG_FILE_NAME), &content)?,
+        None => Config::default(),
+    };
+    let mut builder = analyzer_builder(&archive.to_string_lossy(), config, options);
+    if let Some(ignore_file) = sources.ignore_file.take() {
+        builder = builder.ignore_file(ignore_file);
     }
+    Ok((builder.build()?, sources))
+}
 
-    fn extract_module_name(&self, path: &Path, content: &str) -> String {
-        // NgModuleクラス名を抽出
-        let class_regex = regex::Regex::new(r"export\s+class\s+(\w+Module)").unwrap();
-        if let Some(captures) = class_regex.captures(content) {
-            captures.get(1).unwrap().as_str().to_string()
-        } else {
-            path.file_stem().unwrap_or_default().to_string_lossy().to_string()
-        }
+/// キャッシュ以外の共通の設定をしたビルダー
+fn analyzer_builder(path: &str, config: Config, options: &AnalysisOptions) -> AnalyzerBuilder {
+    let mut builder = AngularAnalyzer::builder()
+        .path(path)
+        .config(config)
+        .respect_ignore(!options.no_ignore)
+        .follow_symlinks(options.follow_symlinks)
+        .include_tests(options.include_tests);
+    if let Some(jobs) = options.jobs {
+        builder = builder.jobs(jobs);
     }
-
-    fn determine_module_type(&self, path: &Path, _content: &str) -> ModuleType {
-        let path_str = path.to_string_lossy().to_lowercase();
-
-        if path_str.contains("/core/") || path_str.contains("core.module") {
-            ModuleType::Core
-        } else if path_str.contains("/shared/") || path_str.contains("shared.module") {
-            ModuleType::Shared
-        } else if
-            path_str.contains("/feature/") ||
-            path_str.contains("/features/") ||
-            (!path_str.contains("/core/") && !path_str.contains("/shared/"))
-        {
-            ModuleType::Feature
-        } else {
-            ModuleType::Unknown
-        }
+    let max_file_size = Some(options.max_file_size).filter(|size| *size > 0);
+    builder = builder
+        .max_file_size(max_file_size)
+        .parse_timeout(Some(std::time::Duration::from_secs(options.parse_timeout)).filter(|timeout| !timeout.is_zero()))
+        .low_memory(options.low_memory)
+        .components(options.components)
+        .progress(ProgressLine::new(std::io::stderr().is_terminal()));
+    if let Some(profiler) = &options.profiler {
+        builder = builder.progress(profiler.clone());
     }
-
-    fn extract_imports(&self, content: &str) -> Vec<String> {
-        self.extract_ngmodule_array(content, "imports")
+    if options.strict {
+        builder = builder.progress(options.skipped.clone());
     }
-
-    fn extract_exports(&self, content: &str) -> Vec<String> {
-        self.extract_ngmodule_array(content, "exports")
+    if options.strict_classification {
+        builder = builder.progress(options.ambiguous.clone());
     }
+    builder
+}
 
-    fn extract_providers(&self, content: &str) -> Vec<String> {
-        self.extract_ngmodule_array(content, "providers")
-    }
+/// 通知やドキュメントに使うプロジェクト名（プロジェクトルートのディレクトリ名）
+fn project_name(path: &str) -> String {
+    fs::canonicalize(path)
+        .ok()
+        .and_then(|dir| dir.file_name().map(|name| name.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| path.to_string())
+}
 
-    fn extract_declarations(&self, content: &str) -> Vec<String> {
-        self.extract_ngmodule_array(content, "declarations")
-    }
+/// `--cache-dir`、なければプロジェクトルートの既定のキャッシュディレクトリ
+fn cache_dir(path: &str, options: &AnalysisOptions) -> PathBuf {
+    options.cache_dir.clone().unwrap_or_else(|| Path::new(path).join(DEFAULT_CACHE_DIR))
+}
 
-    fn extract_dependencies(&self, content: &str) -> Vec<String> {
-        let import_regex = regex::Regex
-            ::new(r#"import\s*\{[^}]*\}\s*from\s*["']([^"']*)["']\s*;"#)
-            .unwrap();
-        import_regex
-            .captures_iter(content)
-            .map(|cap| cap.get(1).unwrap().as_str().to_string())
-            .filter(|import| !import.starts_with(".") && !import.starts_with("@angular/"))
+/// `--changed-files`のリスト（1行に1パス、`-`なら標準入力から読む）
+fn read_changed_files(list: &Path) -> Result<Vec<PathBuf>> {
+    let content = if list == Path::new("-") {
+        std::io::read_to_string(std::io::stdin()).context("Failed to read changed files from stdin")?
+    } else {
+        fs::read_to_string(list).with_context(|| format!("Failed to read {}", list.display()))?
+    };
+    Ok(
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
             .collect()
+    )
+}
+
+/// rayonで並列に解析する（ランタイムのワーカーを占有したままにしないようblock_in_placeで実行する）
+fn run_analysis(analyzer: &AngularAnalyzer) -> Result<AnalysisResult> {
+    Ok(tokio::task::block_in_place(|| analyzer.analyze())?)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // `COMPLETE=<shell>`で呼ばれた場合は補完の候補を出力して終了する
+    CompleteEnv::with_factory(Cli::command).complete();
+    let mut cli = Cli::parse();
+    if cli.analysis.profile {
+        cli.analysis.profiler = Some(Arc::default());
+    }
+    #[cfg(feature = "otel")]
+    if cli.analysis.otel {
+        cli.analysis.telemetry = Some(telemetry::Telemetry::init()?);
     }
+    let out_file = cli.out_file.as_deref();
+    cli.color.apply();
+    use_ascii(cli.ascii || dumb_terminal());
+    cli.lang.unwrap_or_else(Lang::detect).apply();
 
-    fn extract_ngmodule_array(&self, content: &str, field: &str) -> Vec<String> {
-        let pattern = format!(r"{}:\s*\[(.*?)\]", field);
-        let regex = regex::Regex::new(&pattern).unwrap();
+    match &cli.command {
+        Commands::Analyze { path, format, git, reference, archive } => {
+            let result = if let Some(archive) = archive {
+                let root = path.as_deref().map(Path::new);
+                let (analyzer, sources) = open_archive_analyzer(archive, root, &cli.analysis)?;
+                let mut result = tokio::task::block_in_place(|| analyzer.analyze_sources(sources.files))?;
+                result.present_paths(archive, cli.analysis.path_style);
+                result
+            } else {
+                // 一時ディレクトリのクローンは解析が終わるまで残しておく
+                let clone = git
+                    .as_deref()
+                    .map(|url| git::RemoteClone::new(url, reference.as_deref()))
+                    .transpose()?;
+                let path = match (&clone, path) {
+                    (Some(clone), Some(path)) => clone.dir.join(path).to_string_lossy().into_owned(),
+                    (Some(clone), None) => clone.dir.to_string_lossy().into_owned(),
+                    (None, path) => path.clone().unwrap_or_default(),
+                };
+                let analyzer = open_analyzer(&path, &cli.analysis)?;
+                let mut result = run_analysis(&analyzer)?;
+                // クローンした一時ディレクトリは絶対パスにするときに必要なので、ここでそろえる
+                result.present_paths(Path::new(&path), cli.analysis.path_style);
+                result
+            };
+            let format = OutputFormat::resolve(*format, out_file, OutputFormat::Table)?;
 
-        if let Some(captures) = regex.captures(content) {
-            let array_content = captures.get(1).unwrap().as_str();
-            array_content
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect()
-        } else {
-            Vec::new()
+            if matches!(format, OutputFormat::Table | OutputFormat::Plain) && out_file.is_none() {
+                let view = ReportView {
+                    max_items: Some(cli.max_items).filter(|&max| max > 0 && !cli.full),
+                    pager: cli.pager,
+                    table: format == OutputFormat::Table,
+                };
+                print_analysis_result(&result, &view);
+            } else {
+                emit(&output::render(&result, format)?, out_file)?;
+            }
         }
-    }
+        Commands::Graph { path, format, externals, granularity, routes, root } => {
+            cli.analysis.components |= *granularity == Granularity::Component;
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let format = match format {
+                Some(GraphFormat::Dot) => OutputFormat::Dot,
+                Some(GraphFormat::Mermaid) => OutputFormat::Mermaid,
+                None => OutputFormat::resolve(None, out_file, OutputFormat::Dot)?,
+            };
+            let graph = if *routes {
+                if !result.graph().contains(root) {
+                    anyhow::bail!("Module not found: {}", root);
+                }
+                let tree = RouteTree::build(&result, root);
+                match format {
+                    OutputFormat::Dot => output::render_route_dot(&tree),
+                    OutputFormat::Mermaid => output::render_route_mermaid(&tree),
+                    _ => anyhow::bail!("Graphs can only be written as .dot or .mmd files"),
+                }
+            } else {
+                let components = result.components.as_ref().filter(|_| *granularity == Granularity::Component);
+                match (format, components) {
+                    (OutputFormat::Dot, Some(components)) => output::render_component_dot(components),
+                    (OutputFormat::Mermaid, Some(components)) => output::render_component_mermaid(components),
+                    (OutputFormat::Dot, None) => output::render_dot(&result.modules, *externals),
+                    (OutputFormat::Mermaid, None) => output::render_mermaid(&result.modules, *externals),
+                    _ => anyhow::bail!("Graphs can only be written as .dot or .mmd files"),
+                }
+            };
 
-    fn check_dependency_violations(&self, modules: &[ModuleInfo]) -> Vec<DependencyViolation> {
-        let mut violations = Vec::new();
-        let module_map: HashMap<String, &ModuleInfo> = modules
-            .iter()
-            .map(|m| (m.name.clone(), m))
-            .collect();
-
-        for module in modules {
-            for dep in &module.dependencies {
-                if let Some(dep_module) = module_map.get(dep) {
-                    // Core modules should not depend on Feature modules
-                    if
-                        module.module_type == ModuleType::Core &&
-                        dep_module.module_type == ModuleType::Feature
-                    {
-                        violations.push(DependencyViolation {
-                            from_module: module.name.clone(),
-                            to_module: dep.clone(),
-                            violation_type: ViolationType::CoreDependsOnFeature,
-                            description: "Core module depends on Feature module".to_string(),
-                        });
-                    }
+            emit(&graph, out_file)?;
+        }
+        Commands::Export { path, format, link_base } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let mut result = run_analysis(&analyzer)?;
+            result.present_paths(Path::new(path), cli.analysis.path_style);
+            let issues = issues::issues(&result, link_base.as_deref());
+            let text = match format {
+                ExportFormat::Issues => issues::render_json_lines(&issues)?,
+                ExportFormat::JiraCsv => issues::render_jira_csv(&issues),
+            };
+            emit(&text, out_file)?;
+        }
+        Commands::C4 { path, group_by } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            emit(&c4::render_structurizr(&result, &project_name(path), Path::new(path), *group_by, cli.analysis.path_style)?, out_file)?;
+        }
+        Commands::Path { path, from, to, all, max_length } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let graph = result.graph();
 
-                    // Shared modules should not depend on Feature modules
-                    if
-                        module.module_type == ModuleType::Shared &&
-                        dep_module.module_type == ModuleType::Feature
-                    {
-                        violations.push(DependencyViolation {
-                            from_module: module.name.clone(),
-                            to_module: dep.clone(),
-                            violation_type: ViolationType::SharedDependsOnFeature,
-                            description: "Shared module depends on Feature module".to_string(),
-                        });
-                    }
+            for name in [from, to] {
+                if !graph.contains(name) {
+                    anyhow::bail!("Module not found: {}", name);
+                }
+            }
+
+            let paths = if *all {
+                graph.all_simple_paths(from, to, max_length.get())
+            } else {
+                graph.shortest_paths(from, to)
+            };
+
+            if paths.is_empty() {
+                println!("No dependency path from {} to {}", from.bold(), to.bold());
+            } else {
+                for dependency_path in &paths {
+                    println!(
+                        "{} ({} hops)",
+                        dependency_path.join(" -> "),
+                        dependency_path.len() - 1
+                    );
                 }
             }
         }
+        Commands::Impact { path, module, diff, changed_files, root, json } => {
+            let changed = match (diff, changed_files) {
+                (Some(range), _) => Some(git::changed_files(Path::new(path), range)?),
+                (None, Some(list)) => Some(read_changed_files(list)?),
+                (None, None) => None,
+            };
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let graph = result.graph();
 
-        violations
-    }
+            if let Some(changed) = changed {
+                let mut report = ImpactReport::from_changed_files(Path::new(path), &result, &changed, root);
+                if *json {
+                    report.present_paths(Path::new(path), cli.analysis.path_style);
+                    emit(&(serde_json::to_string_pretty(&report)? + "\n"), out_file)?;
+                } else {
+                    print_change_impact(&report);
+                }
+            } else if let Some(module) = module {
+                if !graph.contains(module) {
+                    anyhow::bail!("Module not found: {}", module);
+                }
 
-    fn detect_circular_dependencies(&self, modules: &[ModuleInfo]) -> Vec<Vec<String>> {
-        let mut graph = Graph::<String, (), Undirected>::new_undirected();
-        let mut node_indices = HashMap::new();
+                let dependents = graph.dependents(module);
+                print_impact(module, &dependents);
+            }
+        }
+        Commands::Circular { path } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            print_cycles(&result);
+        }
+        Commands::Report { path, dir, template } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            if let Some(template) = template {
+                emit(&report::render_template(&result, template)?, out_file)?;
+            } else {
+                report::write_site(&result, dir)?;
+                println!("Report written to: {}", dir.join("index.html").display());
+            }
+        }
+        Commands::Tui { path } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            tui::run(&result)?;
+        }
+        Commands::List { path, module_type, sort, desc, limit, columns, coverage } => {
+            let files = coverage.as_deref().map(coverage::load).transpose()?;
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let coverage = files.map(|files| coverage::per_module(Path::new(path), &result.modules, &files));
+            let mut columns = columns.clone();
+            if coverage.is_some() && !columns.contains(&list::Column::Coverage) {
+                columns.push(list::Column::Coverage);
+            }
+            let options = list::ListOptions {
+                module_type: *module_type,
+                sort: *sort,
+                descending: *desc,
+                limit: *limit,
+                columns: &columns,
+                coverage: coverage.as_ref(),
+            };
+            emit(&list::render_list(&result, &options), out_file)?;
+        }
+        Commands::Metrics { path, format, push_gateway, job } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
 
-        // グラフのノードを作成
-        for module in modules {
-            let idx = graph.add_node(module.name.clone());
-            node_indices.insert(module.name.clone(), idx);
+            if let Some(gateway) = push_gateway {
+                if *format != MetricsFormat::Prometheus {
+                    anyhow::bail!("--push-gateway requires --format prometheus");
+                }
+                let text = metrics::render_prometheus(&result);
+                tokio::task::block_in_place(|| metrics::push(gateway, job, &text))?;
+                eprintln!("Metrics pushed to: {}", gateway);
+            } else {
+                let text = match format {
+                    MetricsFormat::Prometheus => metrics::render_prometheus(&result),
+                    MetricsFormat::Json => serde_json::to_string_pretty(&result.metrics)? + "\n",
+                };
+                emit(&text, out_file)?;
+            }
         }
+        Commands::Why { path, module, reachable_from } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let graph = result.graph();
 
-        // エッジを追加
-        for module in modules {
-            if let Some(&from_idx) = node_indices.get(&module.name) {
-                for dep in &module.dependencies {
-                    if let Some(&to_idx) = node_indices.get(dep) {
-                        graph.add_edge(from_idx, to_idx, ());
-                    }
+            for name in [module, reachable_from] {
+                if !graph.contains(name) {
+                    anyhow::bail!("Module not found: {}", name);
                 }
             }
+            print_reachability(&graph, reachable_from, module);
         }
+        Commands::Check { path, baseline, max_coupling, max_cycles, max_violations, min_health, fitness, no_notify } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let baseline = baseline.as_deref().map(check::load_baseline).transpose()?;
 
-        // 循環依存の検出（簡易版）
-        Vec::new() // 実装を簡略化
-    }
+            let mut thresholds = analyzer.config().thresholds.clone();
+            thresholds.max_coupling_factor = max_coupling.or(thresholds.max_coupling_factor);
+            thresholds.max_cycles = max_cycles.or(thresholds.max_cycles);
+            thresholds.max_violations = max_violations.or(thresholds.max_violations);
+            thresholds.min_health_score = min_health.or(thresholds.min_health_score);
 
-    fn calculate_metrics(&self, modules: &[ModuleInfo]) -> ArchitectureMetrics {
-        let total_modules = modules.len();
-        let core_modules = modules
-            .iter()
-            .filter(|m| m.module_type == ModuleType::Core)
-            .count();
-        let shared_modules = modules
-            .iter()
-            .filter(|m| m.module_type == ModuleType::Shared)
-            .count();
-        let feature_modules = modules
-            .iter()
-            .filter(|m| m.module_type == ModuleType::Feature)
-            .count();
-
-        let total_dependencies: usize = modules
-            .iter()
-            .map(|m| m.dependencies.len())
-            .sum();
-        let average_dependencies_per_module = if total_modules > 0 {
-            (total_dependencies as f32) / (total_modules as f32)
-        } else {
-            0.0
-        };
+            let mut outcomes = check::evaluate(&result, &thresholds, baseline.as_ref());
+            if let Some(fitness_path) = fitness::default_path(Path::new(path), fitness.as_deref()) {
+                outcomes.extend(fitness::evaluate(&result, &fitness::load(&fitness_path)?)?);
+            }
+            if !analyzer.config().plugins.is_empty() {
+                outcomes.push(check::plugin_gate(&result));
+            }
+            if outcomes.is_empty() {
+                anyhow::bail!(
+                    "No quality gates configured; add [thresholds] to {} (see `init`), write {} or pass --max-* flags",
+                    config::CONFIG_FILE_NAME,
+                    fitness::FITNESS_FILE_NAME
+                );
+            }
+            let notify = &analyzer.config().notify;
+            if !no_notify && let Some(webhook_url) = notify.webhook_url() {
+                let project = project_name(path);
+                let notification = notify::Notification {
+                    project: &project,
+                    result: &result,
+                    violations: match &baseline {
+                        Some(baseline) => check::new_violations(&result, baseline),
+                        None => result.dependency_violations.iter().collect(),
+                    },
+                    regressions: baseline
+                        .as_ref()
+                        .map(|baseline| notify::regressions(&result, baseline))
+                        .unwrap_or_default(),
+                    outcomes: &outcomes,
+                };
+                if notification.is_needed() {
+                    let text = notification.render(notify.template.as_deref());
+                    // 通知の失敗でゲートの結果を変えない
+                    if let Err(err) = tokio::task::block_in_place(|| notify::send(&webhook_url, &text)) {
+                        eprintln!("{} {}", "warning:".yellow(), err);
+                    }
+                }
+            }
+            if !print_outcomes(&outcomes) {
+                finish(&cli.analysis);
+                std::process::exit(1);
+            }
+        }
+        Commands::Compare { path, with, json } => {
+            let foreign = compare::load(with)?;
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let comparison = compare::compare(&result, &foreign);
 
-        // 結合度の計算（依存関係の密度）
-        let possible_connections = if total_modules > 1 {
-            total_modules * (total_modules - 1)
-        } else {
-            1
-        };
-        let coupling_factor = (total_dependencies as f32) / (possible_connections as f32);
+            if *json {
+                emit(&(serde_json::to_string_pretty(&comparison)? + "\n"), out_file)?;
+            } else {
+                print_comparison(&comparison);
+            }
+            if !comparison.is_identical() {
+                finish(&cli.analysis);
+                std::process::exit(1);
+            }
+        }
+        Commands::Services { path, format } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let mut tree = ServiceTree::collect(Path::new(path), &result);
+            match format {
+                ServicesFormat::Tree => print_service_tree(&tree),
+                ServicesFormat::Dot => emit(&output::render_service_dot(&tree), out_file)?,
+                ServicesFormat::Json => {
+                    tree.present_paths(Path::new(path), cli.analysis.path_style);
+                    emit(&(serde_json::to_string_pretty(&tree)? + "\n"), out_file)?;
+                }
+            }
+        }
+        Commands::Providers { path, multi, json } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            if *multi {
+                let mut providers = MultiProviders::collect(Path::new(path), &result);
+                if *json {
+                    providers.present_paths(Path::new(path), cli.analysis.path_style);
+                    emit(&(serde_json::to_string_pretty(&providers)? + "\n"), out_file)?;
+                } else {
+                    print_multi_providers(&providers, Path::new(path));
+                }
+            } else {
+                let mut scopes = ProviderScopes::collect(Path::new(path), &result);
+                if *json {
+                    scopes.present_paths(Path::new(path), cli.analysis.path_style);
+                    emit(&(serde_json::to_string_pretty(&scopes)? + "\n"), out_file)?;
+                } else {
+                    print_provider_scopes(&scopes, Path::new(path));
+                }
+            }
+        }
+        Commands::Workspaces { path, json } => {
+            let Some(workspace) = Workspace::detect(Path::new(path))? else {
+                anyhow::bail!(
+                    "No {} or package.json workspaces found in {} or its parents",
+                    workspaces::PNPM_WORKSPACE_FILE_NAME,
+                    path
+                );
+            };
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let mut report = workspace.report(&result);
 
-        ArchitectureMetrics {
-            total_modules,
-            core_modules,
-            shared_modules,
-            feature_modules,
-            average_dependencies_per_module,
-            max_dependency_depth: 0, // 実装を簡略化
-            coupling_factor,
+            if *json {
+                report.present_paths(Path::new(path), cli.analysis.path_style);
+                emit(&(serde_json::to_string_pretty(&report)? + "\n"), out_file)?;
+            } else {
+                print_workspace_report(&report);
+            }
+            if !report.bypasses.is_empty() {
+                finish(&cli.analysis);
+                std::process::exit(1);
+            }
         }
-    }
+        Commands::Chunks { path, stats, root, json } => {
+            let stats = chunks::load_stats(stats)?;
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            if !result.graph().contains(root) {
+                anyhow::bail!("Module not found: {}", root);
+            }
+            let report = chunks::correlate(&result, Path::new(path), &stats, root);
+
+            if *json {
+                emit(&(serde_json::to_string_pretty(&report)? + "\n"), out_file)?;
+            } else {
+                print_chunk_report(&report);
+            }
+            if !report.misplaced.is_empty() {
+                finish(&cli.analysis);
+                std::process::exit(1);
+            }
+        }
+        Commands::DeadCode { path, root, min_confidence, json } => {
+            cli.analysis.components = true;
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            if !result.graph().contains(root) {
+                anyhow::bail!("Module not found: {}", root);
+            }
+            let mut report = deadcode::detect(Path::new(path), &result, root);
+            report.retain_confidence(*min_confidence);
 
-    pub fn generate_dot_graph(&self, modules: &[ModuleInfo]) -> String {
-        let mut dot = String::from("digraph AngularModules {\n");
-        dot.push_str("  rankdir=TB;\n");
-        dot.push_str("  node [shape=box];\n\n");
-
-        // ノードの定義
-        for module in modules {
-            let color = match module.module_type {
-                ModuleType::Core => "lightblue",
-                ModuleType::Shared => "lightgreen",
-                ModuleType::Feature => "lightyellow",
-                ModuleType::Unknown => "lightgray",
+            if *json {
+                report.present_paths(Path::new(path), cli.analysis.path_style);
+                emit(&(serde_json::to_string_pretty(&report)? + "\n"), out_file)?;
+            } else {
+                print_dead_code(&report, Path::new(path));
+            }
+            if !report.items.is_empty() {
+                finish(&cli.analysis);
+                std::process::exit(1);
+            }
+        }
+        Commands::Split { path, module, min_declarations, json } => {
+            cli.analysis.components = true;
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let targets = match module {
+                Some(name) => {
+                    let module = result.modules
+                        .iter()
+                        .find(|m| m.name.as_ref() == name)
+                        .with_context(|| format!("Module not found: {}", name))?;
+                    vec![module]
+                }
+                None => split::oversized(&result, *min_declarations),
             };
-            dot.push_str(&format!("  \"{}\" [fillcolor={} style=filled];\n", module.name, color));
+            let plans: Vec<split::SplitPlan> = targets
+                .into_iter()
+                .map(|module| split::plan(&result, module))
+                .collect();
+
+            if *json {
+                emit(&(serde_json::to_string_pretty(&plans)? + "\n"), out_file)?;
+            } else {
+                print_split_plans(&plans, *min_declarations);
+            }
         }
+        Commands::Merge { path, max_declarations, json } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let candidates = merge::candidates(&result, *max_declarations);
 
-        dot.push('\n');
+            if *json {
+                emit(&(serde_json::to_string_pretty(&candidates)? + "\n"), out_file)?;
+            } else {
+                print_merge_candidates(&candidates);
+            }
+        }
+        Commands::Standalone { path, json } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let mut plan = standalone::plan(&result, Path::new(path));
 
-        // エッジの定義
-        let module_names: HashSet<String> = modules
-            .iter()
-            .map(|m| m.name.clone())
-            .collect();
+            if *json {
+                plan.present_paths(Path::new(path), cli.analysis.path_style);
+                emit(&(serde_json::to_string_pretty(&plan)? + "\n"), out_file)?;
+            } else {
+                print_migration_plan(&plan);
+            }
+        }
+        Commands::Trend { path, since, every, format, report } => {
+            let commits = git::commits_since(Path::new(path), since)?;
+            if commits.is_empty() {
+                anyhow::bail!("No commits since {}", since);
+            }
+            let mut points = Vec::new();
+            for (revision, timestamp) in trend::sample(&commits, *every) {
+                eprintln!("Analyzing {} ...", &revision[..12]);
+                let checkout = git::Checkout::new(Path::new(path), &revision)?;
+                let analyzer = open_analyzer(&checkout.project.to_string_lossy(), &cli.analysis)
+                    .with_context(|| format!("Failed to analyze {}", revision))?;
+                let result = run_analysis(&analyzer)?;
+                points.push(TrendPoint::new(&result, Some(revision), timestamp));
+            }
 
-        for module in modules {
-            for dep in &module.dependencies {
-                if module_names.contains(dep) {
-                    dot.push_str(&format!("  \"{}\" -> \"{}\";\n", module.name, dep));
+            let text = match format {
+                TrendFormat::Csv => trend::render_csv(&points),
+                TrendFormat::Json => serde_json::to_string_pretty(&points)? + "\n",
+            };
+            emit(&text, out_file)?;
+            if let Some(dir) = report {
+                report::record_history(dir, &points)?;
+                let analyzer = open_analyzer(path, &cli.analysis)?;
+                let result = run_analysis(&analyzer)?;
+                report::write_site(&result, dir)?;
+                eprintln!("Report written to: {}", dir.join("index.html").display());
+            }
+        }
+        Commands::Drift { path, target, bless, json } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            if *bless {
+                let graph = serde_json::to_string_pretty(&output::render_graph_json(&result))? + "\n";
+                fs::write(target, graph).with_context(|| format!("Failed to write {}", target.display()))?;
+                println!("Blessed {} modules into {}", result.modules.len(), target.display());
+            } else {
+                let drift = DriftReport::detect(&result, &TargetArchitecture::load(target)?)?;
+                if *json {
+                    emit(&(serde_json::to_string_pretty(&drift)? + "\n"), out_file)?;
+                } else {
+                    print_drift(&drift, target);
+                }
+                if !drift.is_empty() {
+                    finish(&cli.analysis);
+                    std::process::exit(1);
                 }
             }
         }
+        Commands::Snapshot { action } => {
+            let (SnapshotAction::Save { path, file } | SnapshotAction::Verify { path, file }) = action;
+            let file = file.clone().unwrap_or_else(|| Path::new(path).join(snapshot::SNAPSHOT_FILE_NAME));
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            match action {
+                SnapshotAction::Save { .. } => {
+                    snapshot::save(&result, Path::new(path), &file)?;
+                    println!("Saved {} modules to {}", result.modules.len(), file.display());
+                }
+                SnapshotAction::Verify { .. } => {
+                    if !file.exists() {
+                        anyhow::bail!("No snapshot at {}; create it with `snapshot save`", file.display());
+                    }
+                    match snapshot::verify(&result, Path::new(path), &file)? {
+                        None => println!("{} Module graph matches {}", mark_ok(), file.display()),
+                        Some(diff) => {
+                            emit(&diff, out_file)?;
+                            eprintln!(
+                                "{} the module graph differs from {}; run `snapshot save` if the change is intended",
+                                "error:".red().bold(),
+                                file.display()
+                            );
+                            finish(&cli.analysis);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+            }
+        }
+        Commands::Fix { path, dry_run: _, write, sort } => {
+            cli.analysis.components = true;
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let files = fix::plan(&result, FixOptions { sort: *sort })?;
 
-        dot.push_str("}\n");
-        dot
-    }
-}
-
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+            if *write {
+                for file in &files {
+                    fs::write(&file.path, &file.fixed).with_context(|| format!("Failed to write {}", file.path.display()))?;
+                }
+                print_fixes(&files, Path::new(path));
+            } else {
+                let diff: String = files
+                    .iter()
+                    .map(|file| file.diff(&file.path.strip_prefix(path).unwrap_or(&file.path).to_string_lossy()))
+                    .collect();
+                emit(&diff, out_file)?;
+                // 適用できる修正があればCIで気づけるように失敗させる
+                if !files.is_empty() {
+                    let count: usize = files.iter().map(|file| file.fixes.len()).sum();
+                    eprintln!("{} fixes in {} files (run with --write to apply)", count, files.len());
+                    finish(&cli.analysis);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Compodoc { path, documentation, json } => {
+            if cli.analysis.low_memory {
+                anyhow::bail!("--low-memory drops the module metadata compodoc is compared against");
+            }
+            let documentation = compodoc::load_documentation(documentation)?;
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let result = run_analysis(&analyzer)?;
+            let mut reconciliation = compodoc::reconcile(&result, &documentation);
 
-    match &cli.command {
-        Commands::Analyze { path, output } => {
-            let analyzer = AngularAnalyzer::new(path);
-            let result = analyzer.analyze()?;
-
-            match output.as_str() {
-                "json" => {
-                    let json = serde_json::to_string_pretty(&result)?;
-                    println!("{}", json);
+            if *json {
+                reconciliation.present_paths(Path::new(path), cli.analysis.path_style);
+                emit(&(serde_json::to_string_pretty(&reconciliation)? + "\n"), out_file)?;
+            } else {
+                print_reconciliation(&reconciliation);
+            }
+            if !reconciliation.is_consistent() {
+                finish(&cli.analysis);
+                std::process::exit(1);
+            }
+        }
+        Commands::Aggregate { paths, manifest, format } => {
+            let repos = match manifest {
+                Some(manifest) => aggregate::load_manifest(manifest)?,
+                None => {
+                    paths
+                        .iter()
+                        .map(|path| aggregate::Repo { name: project_name(path), path: PathBuf::from(path) })
+                        .collect()
                 }
-                _ => {
-                    print_analysis_result(&result);
+            };
+            let mut reports: Vec<aggregate::RepoReport> = Vec::new();
+            for repo in repos {
+                if reports.iter().any(|r| r.name == repo.name) {
+                    anyhow::bail!("Two repositories are named {}; list them in a --manifest with distinct names", repo.name);
                 }
+                let analyzer = open_analyzer(&repo.path.to_string_lossy(), &cli.analysis)
+                    .with_context(|| format!("Failed to analyze {}", repo.name))?;
+                let mut result = run_analysis(&analyzer)?;
+                result.present_paths(&repo.path, cli.analysis.path_style);
+                reports.push(aggregate::RepoReport { name: repo.name, path: repo.path, result });
             }
+            let report = aggregate::AggregateReport::new(reports);
+            let text = match format {
+                DocumentFormat::Markdown => report.render_markdown(),
+                DocumentFormat::Json => serde_json::to_string_pretty(&report)? + "\n",
+            };
+            emit(&text, out_file)?;
         }
-        Commands::Graph { path, output } => {
-            let analyzer = AngularAnalyzer::new(path);
-            let result = analyzer.analyze()?;
-            let dot_graph = analyzer.generate_dot_graph(&result.modules);
+        Commands::PrComment { path, base, format } => {
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            let mut result = run_analysis(&analyzer)?;
+            result.present_paths(Path::new(path), cli.analysis.path_style);
+            let checkout = git::Checkout::new(Path::new(path), base)?;
+            // 基準ブランチの解析は基準ブランチ側の設定ファイルで行う
+            let base_analyzer = open_analyzer(&checkout.project.to_string_lossy(), &cli.analysis)
+                .with_context(|| format!("Failed to analyze {}", base))?;
+            let mut base_result = run_analysis(&base_analyzer)?;
+            // 一時ディレクトリのチェックアウトではなく、解析したプロジェクトのファイルとして書く
+            base_result.present_paths(&checkout.project, PathStyle::Relative);
+            base_result.present_paths(Path::new(path), cli.analysis.path_style);
+            drop(checkout);
 
-            fs::write(output, dot_graph)?;
-            println!("Dependency graph written to: {}", output);
+            let delta = ResultDelta::new(&base_result, &result);
+            let text = match format {
+                DocumentFormat::Markdown => delta.render_markdown(),
+                DocumentFormat::Json => serde_json::to_string_pretty(&delta)? + "\n",
+            };
+            emit(&text, out_file)?;
+        }
+        Commands::Serve { path, port, host, refresh } => {
+            // 定期的に再解析するので、変更されたファイルだけを解析し直すようキャッシュを使う
+            cli.analysis.cache = true;
+            let analyzer = open_analyzer(path, &cli.analysis)?;
+            serve::run(analyzer, (*host, *port).into(), std::time::Duration::from_secs(*refresh)).await?;
+        }
+        Commands::Lsp { path } => {
+            // 保存のたびに再解析するので、変更されたファイルだけを解析し直すようキャッシュを使う
+            cli.analysis.cache = true;
+            let options = &cli.analysis;
+            tokio::task::block_in_place(|| {
+                lsp::run(Path::new(path), |root| open_analyzer(&root.to_string_lossy(), options))
+            })?;
+        }
+        Commands::Doctor { path } => {
+            let diagnoses = doctor::diagnose(Path::new(path));
+            if !print_diagnoses(&diagnoses) {
+                std::process::exit(1);
+            }
+        }
+        Commands::ValidateConfig { path } => {
+            let config_path = Config::path_for(Path::new(path));
+            let content = fs
+                ::read_to_string(&config_path)
+                .with_context(|| format!("Failed to read config: {:?}", config_path))?;
+            let issues = config::validate(&content);
+            if issues.is_empty() {
+                println!("{} {} is valid", mark_ok(), config_path.display());
+            } else {
+                for issue in &issues {
+                    println!("{} {}:{}", mark_error(), config_path.display(), issue);
+                }
+                std::process::exit(1);
+            }
+        }
+        Commands::Cache { action } => {
+            match action {
+                CacheAction::Stats { path } => {
+                    let config = Config::load(Path::new(path))?;
+                    match cache::stats(&cache_dir(path, &cli.analysis), &config, cli.analysis.low_memory)? {
+                        Some(stats) => print_cache_stats(&stats),
+                        None => println!("No cache at {}", cache_dir(path, &cli.analysis).display()),
+                    }
+                }
+                CacheAction::Clear { path } => {
+                    let dir = cache_dir(path, &cli.analysis);
+                    if cache::clear(&dir)? {
+                        println!("{} {}", "Cache cleared:".green(), dir.display());
+                    } else {
+                        println!("No cache at {}", dir.display());
+                    }
+                }
+                CacheAction::Path { path } => println!("{}", cache_dir(path, &cli.analysis).display()),
+            }
+        }
+        Commands::Init { path, force } => {
+            let (config_path, scaffold) = init::write_config(Path::new(path), *force)?;
+            println!("Detected {}", scaffold.workspace.label());
+            println!("  core:    {}", scaffold.core.join(", "));
+            println!("  shared:  {}", scaffold.shared.join(", "));
+            println!("  feature: {}", scaffold.feature.join(", "));
+            println!("{} {}", "Configuration written to:".green(), config_path.display());
+        }
+        Commands::Completions { shell } => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            clap_complete::generate(*shell, &mut command, name, &mut std::io::stdout());
         }
     }
 
+    finish(&cli.analysis);
     Ok(())
 }
 
-fn print_analysis_result(result: &AnalysisResult) {
-    println!("{}", "=== Angular Module Analysis Report ===".bold().cyan());
-    println!();
-
-    // メトリクス表示
-    println!("{}", "📊 Architecture Metrics".bold().green());
-    println!("Total Modules: {}", result.metrics.total_modules);
-    println!("Core Modules: {}", result.metrics.core_modules);
-    println!("Shared Modules: {}", result.metrics.shared_modules);
-    println!("Feature Modules: {}", result.metrics.feature_modules);
-    println!(
-        "Average Dependencies per Module: {:.2}",
-        result.metrics.average_dependencies_per_module
-    );
-    println!("Coupling Factor: {:.2}", result.metrics.coupling_factor);
-    println!();
-
-    // 依存関係違反
-    if !result.dependency_violations.is_empty() {
-        println!("{}", "⚠️  Dependency Violations".bold().red());
-        for violation in &result.dependency_violations {
-            println!(
-                "  {} -> {}: {}",
-                violation.from_module.red(),
-                violation.to_module.red(),
-                violation.description
-            );
-        }
-        println!();
+/// 終了前に`--profile`の計測結果を表示し、`--otel`のspanを送り切る
+///
+/// `--strict`で読み込めなかったファイルがあった場合と、`--strict-classification`で分類があいまいなモジュールがあった場合は、
+/// ここで終了コード1で終了する。
+fn finish(options: &AnalysisOptions) {
+    if let Some(profiler) = &options.profiler {
+        profiler.print();
     }
-
-    // モジュール一覧
-    println!("{}", "📦 Modules by Type".bold().blue());
-
-    let mut modules_by_type: HashMap<&ModuleType, Vec<&ModuleInfo>> = HashMap::new();
-    for module in &result.modules {
-        modules_by_type.entry(&module.module_type).or_default().push(module);
+    #[cfg(feature = "otel")]
+    if let Some(telemetry) = &options.telemetry {
+        tokio::task::block_in_place(|| telemetry.shutdown());
     }
-
-    for (module_type, modules) in modules_by_type {
-        let type_name = match module_type {
-            ModuleType::Core => "Core",
-            ModuleType::Shared => "Shared",
-            ModuleType::Feature => "Feature",
-            ModuleType::Unknown => "Unknown",
-        };
-
-        println!("  {}:", type_name.bold());
-        for module in modules {
-            println!("    - {} ({} dependencies)", module.name, module.dependencies.len());
-        }
-        println!();
+    let skipped = if options.strict { options.skipped.count() } else { 0 };
+    if skipped > 0 {
+        eprintln!("{} {} files could not be analyzed (--strict)", "error:".red().bold(), skipped);
     }
-
-    if result.dependency_violations.is_empty() {
-        println!("{}", "✅ No dependency violations found!".green());
+    let ambiguous = if options.strict_classification { options.ambiguous.report() } else { 0 };
+    if ambiguous > 0 {
+        eprintln!(
+            "{} {} modules have an ambiguous classification; add [classification] rules (--strict-classification)",
+            "error:".red().bold(),
+            ambiguous
+        );
+    }
+    if skipped > 0 || ambiguous > 0 {
+        std::process::exit(1);
     }
 }
@@ -0,0 +1,125 @@
+use serde::Serialize;
+use std::collections::{ BTreeMap, BTreeSet, HashMap };
+
+use crate::graph::{ EdgeKind, reference_name };
+use crate::{ AnalysisResult, ModuleInfo };
+
+/// まとめてよい小さなモジュールの集まり
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeCandidate {
+    /// 集まりの外からimportされているモジュール（まとめた先の名前として使う）
+    pub into: String,
+    /// `into`を含む、まとめるモジュール（`into`からたどった順）
+    pub modules: Vec<String>,
+    pub declarations: usize,
+    /// 集まりの中のモジュール同士の`imports`の数（まとめると不要になる）
+    pub internal_imports: usize,
+    /// 複数のモジュールで重複している`imports`（まとめると1つで済む）
+    pub duplicated_imports: Vec<String>,
+    /// まとめることで減る`imports`の要素の数
+    pub removed_imports: usize,
+}
+
+/// 小さなモジュール同士が互いにだけ依存し、常に一緒にロードされている集まりを探す
+///
+/// `declarations`が`max_declarations`以下で同じ種類のモジュールのうち、
+/// 依存先が遅延ロードされておらず、依存元からしかimportされていないものを依存元にまとめる（依存元もどこかからimportされている場合のみ）。
+/// `XxxRoutingModule`はAngular CLIが意図して分けているので対象にしない。
+pub fn candidates(result: &AnalysisResult, max_declarations: usize) -> Vec<MergeCandidate> {
+    let graph = result.graph();
+    let modules: HashMap<&str, &ModuleInfo> = result.modules
+        .iter()
+        .map(|m| (m.name.as_ref(), m))
+        .collect();
+    let tiny = |name: &str| {
+        modules
+            .get(name)
+            .is_some_and(|m| m.declarations.len() <= max_declarations && !name.ends_with("RoutingModule"))
+    };
+
+    let edges = graph.edges();
+    let mut importers: HashMap<&str, Vec<(&str, EdgeKind)>> = HashMap::new();
+    for &(from, to, kind) in &edges {
+        importers.entry(to).or_default().push((from, kind));
+    }
+
+    // 依存先 -> まとめる先の依存元
+    let mut parent: BTreeMap<&str, &str> = BTreeMap::new();
+    for (&child, from) in &importers {
+        // 起動時のルートモジュールのように、どこからもimportされていないモジュールへはまとめない
+        if
+            let [(only, EdgeKind::Eager)] = from.as_slice() &&
+            tiny(child) &&
+            tiny(only) &&
+            *only != child &&
+            importers.contains_key(only) &&
+            modules[child].module_type == modules[*only].module_type
+        {
+            parent.insert(child, only);
+        }
+    }
+
+    let mut clusters: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for &child in parent.keys() {
+        if let Some(root) = root_of(&parent, child) {
+            clusters.entry(root).or_default().push(child);
+        }
+    }
+
+    let mut candidates: Vec<MergeCandidate> = clusters
+        .into_iter()
+        .map(|(root, children)| {
+            // 根から幅優先でたどった順に並べる
+            let mut members = vec![root];
+            let mut i = 0;
+            while i < members.len() {
+                let current = members[i];
+                members.extend(children.iter().copied().filter(|child| parent.get(child) == Some(&current)));
+                i += 1;
+            }
+
+            let internal_imports = edges
+                .iter()
+                .filter(|(from, to, _)| members.contains(from) && members.contains(to))
+                .count();
+            let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+            for member in &members {
+                let imports: BTreeSet<&str> = modules[member].imports
+                    .iter()
+                    .map(|entry| reference_name(entry))
+                    .filter(|name| !members.contains(name))
+                    .collect();
+                for import in imports {
+                    *counts.entry(import).or_insert(0) += 1;
+                }
+            }
+            let duplicated: Vec<(&str, usize)> = counts
+                .into_iter()
+                .filter(|(_, count)| *count > 1)
+                .collect();
+
+            MergeCandidate {
+                into: root.to_string(),
+                declarations: members.iter().map(|m| modules[m].declarations.len()).sum(),
+                internal_imports,
+                removed_imports: internal_imports + duplicated.iter().map(|(_, count)| count - 1).sum::<usize>(),
+                duplicated_imports: duplicated.into_iter().map(|(name, _)| name.to_string()).collect(),
+                modules: members.into_iter().map(str::to_string).collect(),
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.removed_imports.cmp(&a.removed_imports).then_with(|| a.into.cmp(&b.into)));
+    candidates
+}
+
+/// 親をたどって集まりの根を求める（循環していれば`None`）
+fn root_of<'a>(parent: &BTreeMap<&'a str, &'a str>, mut name: &'a str) -> Option<&'a str> {
+    let mut seen = BTreeSet::new();
+    while let Some(&next) = parent.get(name) {
+        if !seen.insert(name) {
+            return None;
+        }
+        name = next;
+    }
+    Some(name)
+}
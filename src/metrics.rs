@@ -0,0 +1,65 @@
+use crate::{ AnalysisResult, AnalyzerError, ModuleType, Result, ViolationType };
+
+/// Pushgatewayに送るときの既定のジョブ名
+pub const DEFAULT_JOB: &str = "angular_module_analyzer";
+
+/// Prometheusのテキスト形式でメトリクスを出力する（すべてgauge）
+pub fn render_prometheus(result: &AnalysisResult) -> String {
+    let metrics = &result.metrics;
+    let mut text = String::new();
+
+    gauge(&mut text, "angular_modules_total", "Number of NgModules by layer");
+    for module_type in [ModuleType::Core, ModuleType::Shared, ModuleType::Feature, ModuleType::Unknown] {
+        let count = result.modules
+            .iter()
+            .filter(|m| m.module_type == module_type)
+            .count();
+        text.push_str(&format!("angular_modules_total{{type=\"{}\"}} {}\n", module_type.to_string().to_lowercase(), count));
+    }
+
+    gauge(&mut text, "angular_violations_total", "Number of architecture rule violations by rule code");
+    for rule in ViolationType::ALL {
+        let count = if rule == ViolationType::CircularDependency {
+            result.circular_dependencies.len()
+        } else {
+            result.dependency_violations
+                .iter()
+                .filter(|v| v.violation_type == rule)
+                .count()
+        };
+        text.push_str(&format!("angular_violations_total{{rule=\"{}\"}} {}\n", rule.code(), count));
+    }
+
+    let values = [
+        ("angular_circular_dependencies", "Number of dependency cycles", result.circular_dependencies.len() as f32),
+        ("angular_coupling_factor", "Ratio of actual to possible module dependencies", metrics.coupling_factor),
+        (
+            "angular_average_dependencies_per_module",
+            "Average number of module dependencies",
+            metrics.average_dependencies_per_module,
+        ),
+        ("angular_max_dependency_depth", "Length of the longest dependency chain", metrics.max_dependency_depth as f32),
+        ("angular_health_score", "Architecture health score (0-100)", metrics.health_score),
+    ];
+    for (name, help, value) in values {
+        gauge(&mut text, name, help);
+        text.push_str(&format!("{} {}\n", name, value));
+    }
+    text
+}
+
+fn gauge(text: &mut String, name: &str, help: &str) {
+    text.push_str(&format!("# HELP {} {}\n", name, help));
+    text.push_str(&format!("# TYPE {} gauge\n", name));
+}
+
+/// Pushgatewayの`/metrics/job/<job>`へメトリクスを送る（同じジョブの以前の値は置き換えられる）
+pub fn push(gateway_url: &str, job: &str, text: &str) -> Result<()> {
+    let url = format!("{}/metrics/job/{}", gateway_url.trim_end_matches('/'), job);
+    ureq
+        ::put(&url)
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .send(text)
+        .map_err(|err| AnalyzerError::PushGateway(err.to_string()))?;
+    Ok(())
+}
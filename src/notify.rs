@@ -0,0 +1,102 @@
+use crate::check::GateOutcome;
+use crate::{ AnalysisResult, AnalyzerError, DependencyViolation, Result };
+
+/// 通知本文の既定のテンプレート
+///
+/// `{project}`・`{summary}`・`{violations}`・`{regressions}`・`{gates}`・`{health_score}`を置き換える。
+pub const DEFAULT_TEMPLATE: &str = "*Architecture check failed for {project}*\n{summary}\n{violations}{regressions}{gates}";
+
+/// ベースラインから悪化したメトリクス
+pub struct Regression {
+    pub metric: &'static str,
+    pub before: f32,
+    pub after: f32,
+}
+
+/// ベースラインと比べて悪化したメトリクスを返す
+pub fn regressions(result: &AnalysisResult, baseline: &AnalysisResult) -> Vec<Regression> {
+    let (before, after) = (&baseline.metrics, &result.metrics);
+    // （名前, 以前, 現在, 大きいほど悪いか）
+    let metrics = [
+        ("coupling factor", before.coupling_factor, after.coupling_factor, true),
+        (
+            "circular dependencies",
+            baseline.circular_dependencies.len() as f32,
+            result.circular_dependencies.len() as f32,
+            true,
+        ),
+        ("max dependency depth", before.max_dependency_depth as f32, after.max_dependency_depth as f32, true),
+        ("health score", before.health_score, after.health_score, false),
+    ];
+    metrics
+        .into_iter()
+        .filter(|&(_, before, after, higher_is_worse)| {
+            if higher_is_worse { after > before } else { after < before }
+        })
+        .map(|(metric, before, after, _)| Regression { metric, before, after })
+        .collect()
+}
+
+/// 通知する内容
+pub struct Notification<'a> {
+    pub project: &'a str,
+    pub result: &'a AnalysisResult,
+    /// 新規の違反（ベースラインがなければすべての違反）
+    pub violations: Vec<&'a DependencyViolation>,
+    pub regressions: Vec<Regression>,
+    pub outcomes: &'a [GateOutcome],
+}
+
+impl Notification<'_> {
+    /// 通知すべき内容があるか（不合格のゲートかメトリクスの悪化がある）
+    pub fn is_needed(&self) -> bool {
+        !self.regressions.is_empty() || self.outcomes.iter().any(|outcome| !outcome.passed)
+    }
+
+    /// テンプレートのプレースホルダーを置き換えて本文を作る
+    pub fn render(&self, template: Option<&str>) -> String {
+        let failed = self.outcomes
+            .iter()
+            .filter(|outcome| !outcome.passed)
+            .count();
+        let summary = format!(
+            "{} new violation(s), {} metric regression(s), {} failed gate(s)",
+            self.violations.len(),
+            self.regressions.len(),
+            failed
+        );
+        let violations: String = self.violations
+            .iter()
+            .map(|v| format!("• [{}] {}\n", v.violation_type.code(), v))
+            .collect();
+        let regressions: String = self.regressions
+            .iter()
+            .map(|r| format!("• {}: {:.2} → {:.2}\n", r.metric, r.before, r.after))
+            .collect();
+        let gates: String = self.outcomes
+            .iter()
+            .filter(|outcome| !outcome.passed)
+            .map(|outcome| format!("• {} gate failed: {}\n", outcome.name, outcome.detail))
+            .collect();
+
+        template
+            .unwrap_or(DEFAULT_TEMPLATE)
+            .replace("{project}", self.project)
+            .replace("{summary}", &summary)
+            .replace("{violations}", &violations)
+            .replace("{regressions}", &regressions)
+            .replace("{gates}", &gates)
+            .replace("{health_score}", &format!("{:.1}", self.result.metrics.health_score))
+    }
+}
+
+/// Slack/Teams互換のIncoming Webhookへ`{"text": ...}`を送る
+pub fn send(webhook_url: &str, text: &str) -> Result<()> {
+    let body = serde_json::json!({ "text": text }).to_string();
+    ureq
+        ::post(webhook_url)
+        .header("Content-Type", "application/json")
+        .send(body)
+        .map_err(|err| AnalyzerError::Webhook(err.to_string()))?;
+    Ok(())
+}
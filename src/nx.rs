@@ -0,0 +1,291 @@
+use globset::Glob;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{ HashMap, VecDeque };
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::config::RuleLevel;
+use crate::graph::ModuleGraph;
+use crate::{ AnalyzerError, DEFAULT_IGNORED_DIRS, DependencyViolation, ModuleInfo, Result, ViolationType };
+
+/// Nxのタグのルールを書く設定ファイル（ワークスペースルートに置く）
+pub const ESLINTRC_FILE_NAME: &str = ".eslintrc.json";
+
+/// ルール名（`@nrwl/nx`は旧スコープ）
+const RULE_NAMES: [&str; 2] = ["@nx/enforce-module-boundaries", "@nrwl/nx/enforce-module-boundaries"];
+
+/// `depConstraints`の1件
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DepConstraint {
+    pub source_tag: String,
+    /// 空でなければ、これらのいずれかのタグを持つプロジェクトにだけ依存できる
+    pub only_depend_on_libs_with_tags: Vec<String>,
+    /// これらのタグを持つプロジェクトには直接にも推移的にも依存できない
+    pub not_depend_on_libs_with_tags: Vec<String>,
+}
+
+/// `project.json`で定義されたNxプロジェクト
+#[derive(Debug, Clone)]
+pub struct NxProject {
+    pub name: String,
+    pub root: PathBuf,
+    pub tags: Vec<String>,
+}
+
+/// `@nx/enforce-module-boundaries`と同じタグのルール
+///
+/// eslintのルールはファイル単位のimportしか見ないが、ここではモジュールの依存グラフ全体に適用するため、
+/// `notDependOnLibsWithTags`に反する推移的な依存も検出する。
+#[derive(Debug, Clone, Default)]
+pub struct ModuleBoundaries {
+    pub constraints: Vec<DepConstraint>,
+    /// ワークスペース内のプロジェクト（ルートが深い順）
+    pub projects: Vec<NxProject>,
+}
+
+#[derive(Deserialize)]
+struct EslintConfig {
+    #[serde(default)]
+    rules: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    overrides: Vec<EslintConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RuleOptions {
+    #[serde(default)]
+    dep_constraints: Vec<DepConstraint>,
+}
+
+#[derive(Deserialize)]
+struct ProjectJson {
+    name: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl ModuleBoundaries {
+    /// ワークスペースの`.eslintrc.json`と`project.json`を読み込む
+    ///
+    /// ワークスペースルートは`project_path`から親へたどって最初に`nx.json`があるディレクトリ
+    /// （なければ`project_path`）。ルールが設定されていなければ`None`。
+    pub fn load(project_path: &Path) -> Result<Option<Self>> {
+        let Some(workspace) = workspace_root(project_path) else {
+            return Ok(None);
+        };
+        let workspace = workspace.as_path();
+
+        let path = workspace.join(ESLINTRC_FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).map_err(|err| AnalyzerError::io(&path, err))?;
+        let config: EslintConfig = serde_json::from_str(&content).map_err(|err| AnalyzerError::parse(&path, err))?;
+        let Some(options) = rule_options(&config) else {
+            return Ok(None);
+        };
+        let options: RuleOptions = serde_json::from_value(options.clone()).map_err(|err| AnalyzerError::parse(&path, err))?;
+        if options.dep_constraints.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self { constraints: options.dep_constraints, projects: load_projects(workspace)? }))
+    }
+
+    /// モジュールのファイルを含むプロジェクト
+    pub fn project_of(&self, file: &Path) -> Option<&NxProject> {
+        project_of(&self.projects, file)
+    }
+
+    /// タグのルールに反するモジュール間の依存（同じプロジェクト内の依存は対象外）
+    pub fn check(&self, modules: &[ModuleInfo], graph: &ModuleGraph) -> Vec<DependencyViolation> {
+        let projects: HashMap<&str, (&ModuleInfo, &NxProject)> = modules
+            .iter()
+            .filter_map(|m| Some((m.name.as_ref(), (m, self.project_of(&m.path)?))))
+            .collect();
+        let mut violations = Vec::new();
+
+        for (&name, &(module, source)) in &projects {
+            let constraints: Vec<&DepConstraint> = self.constraints
+                .iter()
+                .filter(|c| matches_any(std::slice::from_ref(&c.source_tag), &source.tags))
+                .collect();
+            let mut violation = |target: &str, description: String, first_hop: &str| {
+                violations.push(DependencyViolation {
+                    from_module: module.name.clone(),
+                    to_module: projects[target].0.name.clone(),
+                    violation_type: ViolationType::NxModuleBoundary,
+                    description,
+                    location: module.location_of(first_hop),
+                    severity: RuleLevel::Error,
+                });
+            };
+
+            // 経路とともに推移的な依存先を幅優先でたどる
+            let mut paths: HashMap<&str, Vec<&str>> = HashMap::from([(name, Vec::new())]);
+            let mut queue = VecDeque::from([name]);
+            while let Some(current) = queue.pop_front() {
+                for next in graph.dependencies_of(current) {
+                    if paths.contains_key(next) {
+                        continue;
+                    }
+                    let mut path = paths[current].clone();
+                    path.push(next);
+                    paths.insert(next, path);
+                    queue.push_back(next);
+                }
+            }
+
+            for (&target, path) in &paths {
+                let Some(&(_, project)) = projects.get(target) else {
+                    continue;
+                };
+                if path.is_empty() || project.root == source.root {
+                    continue;
+                }
+                let direct = path.len() == 1;
+
+                if direct && constraints.is_empty() {
+                    violation(
+                        target,
+                        format!(
+                            "Nx project `{}` has no tags matching a dependency constraint and cannot depend on `{}`",
+                            source.name,
+                            project.name
+                        ),
+                        path[0]
+                    );
+                    continue;
+                }
+                for constraint in &constraints {
+                    let allowed = &constraint.only_depend_on_libs_with_tags;
+                    if direct && !allowed.is_empty() && !matches_any(allowed, &project.tags) {
+                        violation(
+                            target,
+                            format!(
+                                "Nx project `{}` ({}) may only depend on projects tagged {}, but `{}` has {}",
+                                source.name,
+                                constraint.source_tag,
+                                allowed.join(", "),
+                                project.name,
+                                tag_list(&project.tags)
+                            ),
+                            path[0]
+                        );
+                    }
+                    let denied = &constraint.not_depend_on_libs_with_tags;
+                    if matches_any(denied, &project.tags) {
+                        let via = if direct {
+                            String::new()
+                        } else {
+                            format!(" (via {} -> {})", module.name, path.join(" -> "))
+                        };
+                        violation(
+                            target,
+                            format!(
+                                "Nx project `{}` ({}) must not depend on projects tagged {}, but `{}` has {}{}",
+                                source.name,
+                                constraint.source_tag,
+                                denied.join(", "),
+                                project.name,
+                                tag_list(&project.tags),
+                                via
+                            ),
+                            path[0]
+                        );
+                    }
+                }
+            }
+        }
+        violations
+    }
+}
+
+/// `project_path`から親へたどって最初に`nx.json`があるディレクトリ（なければ`project_path`）
+fn workspace_root(project_path: &Path) -> Option<PathBuf> {
+    let project_path = project_path.canonicalize().ok()?;
+    let workspace = project_path.ancestors().find(|dir| dir.join("nx.json").is_file());
+    Some(workspace.map(Path::to_path_buf).unwrap_or(project_path))
+}
+
+/// ワークスペース内のNxプロジェクト（ルートが深い順、`project.json`がなければ空）
+pub fn projects(project_path: &Path) -> Result<Vec<NxProject>> {
+    match workspace_root(project_path) {
+        Some(workspace) => load_projects(&workspace),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// ファイルを含むプロジェクト（入れ子の場合は内側）
+pub fn project_of<'a>(projects: &'a [NxProject], file: &Path) -> Option<&'a NxProject> {
+    let file = file.canonicalize().ok()?;
+    projects.iter().find(|project| file.starts_with(&project.root))
+}
+
+/// 有効な`@nx/enforce-module-boundaries`のオプション（`overrides`内も探す）
+fn rule_options(config: &EslintConfig) -> Option<&serde_json::Value> {
+    let own = RULE_NAMES.iter().find_map(|name| {
+        // "error"のような重大度だけの指定や"off"はオプションなしとして扱う
+        let setting = config.rules.get(*name)?.as_array()?;
+        let level = setting.first()?;
+        if level == "off" || level == 0 {
+            return None;
+        }
+        setting.get(1)
+    });
+    own.or_else(|| config.overrides.iter().find_map(rule_options))
+}
+
+fn load_projects(workspace: &Path) -> Result<Vec<NxProject>> {
+    let mut walker = ignore::WalkBuilder::new(workspace);
+    walker.hidden(false).require_git(false);
+    walker.filter_entry(|entry| !DEFAULT_IGNORED_DIRS.iter().any(|dir| entry.file_name() == *dir));
+
+    let mut projects = Vec::new();
+    for entry in walker.build().filter_map(|e| e.ok()) {
+        if entry.file_name() != "project.json" {
+            continue;
+        }
+        let path = entry.path();
+        let content = fs::read_to_string(path).map_err(|err| AnalyzerError::io(path, err))?;
+        let project: ProjectJson = serde_json::from_str(&content).map_err(|err| AnalyzerError::parse(path, err))?;
+        let root = path.parent().unwrap_or(workspace).to_path_buf();
+        projects.push(NxProject {
+            name: project.name.unwrap_or_else(|| root.file_name().unwrap_or_default().to_string_lossy().into_owned()),
+            root,
+            tags: project.tags,
+        });
+    }
+    // 入れ子のプロジェクトでは内側を優先する
+    projects.sort_by_key(|project| std::cmp::Reverse(project.root.components().count()));
+    Ok(projects)
+}
+
+/// Nxと同じく、`*`はすべて、`/.../`は正規表現、それ以外の`*`を含むタグはglobとして照合する
+fn matches_any(patterns: &[String], tags: &[String]) -> bool {
+    patterns.iter().any(|pattern| {
+        if pattern == "*" {
+            return true;
+        }
+        if pattern.len() > 1 && pattern.starts_with('/') && pattern.ends_with('/') {
+            let Ok(regex) = Regex::new(&pattern[1..pattern.len() - 1]) else {
+                return false;
+            };
+            return tags.iter().any(|tag| regex.is_match(tag));
+        }
+        if pattern.contains('*') {
+            let Ok(glob) = Glob::new(pattern).map(|glob| glob.compile_matcher()) else {
+                return false;
+            };
+            return tags.iter().any(|tag| glob.is_match(tag));
+        }
+        tags.contains(pattern)
+    })
+}
+
+fn tag_list(tags: &[String]) -> String {
+    if tags.is_empty() { "no tags".to_string() } else { format!("tags {}", tags.join(", ")) }
+}
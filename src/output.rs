@@ -0,0 +1,1187 @@
+use clap::ValueEnum;
+use serde_json::json;
+use std::collections::{ BTreeMap, HashMap, HashSet };
+use std::path::Path;
+
+use crate::components::{ ComponentGraph, Declarable, DeclarableKind, Usage };
+use crate::config::RuleLevel;
+use crate::graph::{ EdgeKind, ModuleGraph };
+use crate::i18n::{ Lang, tr };
+use crate::routes::{ Route, RouteTree };
+use crate::services::ServiceTree;
+use crate::{ AnalysisResult, AnalyzerError, ModuleInfo, ModuleType, Result, SourceLocation, ViolationType };
+
+/// レポートの出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// 違反・モジュールなどを列をそろえた表にしたコンソール出力（既定、`console`も同じ）
+    #[value(alias = "console")]
+    Table,
+    /// 一覧形式のコンソール出力
+    Plain,
+    Json,
+    Markdown,
+    Html,
+    Sarif,
+    /// GitLabのCode Quality（Code Climate互換のJSON）
+    Codeclimate,
+    /// SonarQubeの外部（generic）issueインポート形式
+    Sonar,
+    /// dependency-cruiserの`--output-type json`と同じスキーマ
+    Depcruise,
+    /// ESLintの`--format json`と同じ形式（ファイルごとのメッセージの配列）
+    EslintJson,
+    Dot,
+    Mermaid,
+}
+
+impl OutputFormat {
+    /// 出力ファイルの拡張子から形式を推測する
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            "sarif" => Some(Self::Sarif),
+            "dot" | "gv" => Some(Self::Dot),
+            "mmd" | "mermaid" => Some(Self::Mermaid),
+            _ => None,
+        }
+    }
+
+    /// 明示された形式、出力ファイルの拡張子、既定値の順に形式を決定する
+    pub fn resolve(explicit: Option<Self>, out_file: Option<&Path>, default: Self) -> Result<Self> {
+        if let Some(format) = explicit {
+            return Ok(format);
+        }
+        match out_file {
+            Some(path) =>
+                match Self::from_extension(path) {
+                    Some(format) => Ok(format),
+                    None =>
+                        Err(
+                            AnalyzerError::UnsupportedFormat(
+                                format!(
+                                    "Cannot infer output format from {:?}; use a .json, .md, .html, .sarif, .dot or .mmd extension or pass --format",
+                                    path
+                                )
+                            )
+                        ),
+                }
+            None => Ok(default),
+        }
+    }
+}
+
+/// ファイル出力向けに解析結果を指定形式の文字列へ変換する
+pub fn render(result: &AnalysisResult, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Table | OutputFormat::Plain =>
+            Err(
+                AnalyzerError::UnsupportedFormat(
+                    "Console output cannot be written to a file; choose another --format".to_string()
+                )
+            ),
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(result)? + "\n"),
+        OutputFormat::Markdown => Ok(render_markdown(result)),
+        OutputFormat::Html => Ok(render_html(result)),
+        OutputFormat::Sarif => Ok(serde_json::to_string_pretty(&render_sarif(result))? + "\n"),
+        OutputFormat::Codeclimate => Ok(serde_json::to_string_pretty(&render_codeclimate(result))? + "\n"),
+        OutputFormat::Sonar => Ok(serde_json::to_string_pretty(&render_sonar(result))? + "\n"),
+        OutputFormat::Depcruise => Ok(serde_json::to_string_pretty(&render_depcruise(result))? + "\n"),
+        OutputFormat::EslintJson => Ok(serde_json::to_string_pretty(&render_eslint(result))? + "\n"),
+        OutputFormat::Dot => Ok(render_dot(&result.modules, Externals::Hide)),
+        OutputFormat::Mermaid => Ok(render_mermaid(&result.modules, Externals::Hide)),
+    }
+}
+
+/// グラフに外部パッケージ（npm依存）をどう表示するか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Externals {
+    /// 外部パッケージを表示しない
+    #[default]
+    Hide,
+    /// パッケージスコープごとに1つの"vendor"ノードへまとめる
+    Collapse,
+    /// パッケージごとにノードを表示する
+    Show,
+}
+
+impl Externals {
+    /// モジュールが依存している外部パッケージのノード名
+    fn nodes(self, module: &ModuleInfo) -> Vec<String> {
+        let mut nodes: Vec<String> = match self {
+            Externals::Hide => Vec::new(),
+            Externals::Show => module.external_dependencies.iter().map(|dep| dep.package.to_string()).collect(),
+            Externals::Collapse =>
+                module.external_dependencies
+                    .iter()
+                    .map(|dep| package_scope(&dep.package).to_string())
+                    .collect(),
+        };
+        nodes.sort();
+        nodes.dedup();
+        nodes
+    }
+}
+
+/// `@scope/pkg/sub/path`や`pkg/sub`からパッケージ名部分を取り出す
+pub(crate) fn package_name(specifier: &str) -> &str {
+    let segments = if specifier.starts_with('@') { 2 } else { 1 };
+    match specifier.match_indices('/').nth(segments - 1) {
+        Some((i, _)) => &specifier[..i],
+        None => specifier,
+    }
+}
+
+/// スコープ付きパッケージはスコープ（`@ngrx`）、それ以外はパッケージ名
+fn package_scope(specifier: &str) -> &str {
+    if specifier.starts_with('@') {
+        specifier.split('/').next().unwrap_or(specifier)
+    } else {
+        package_name(specifier)
+    }
+}
+
+/// 外部パッケージのMermaidのノードID（モジュールのIDと重ならないよう接頭辞を付ける）
+fn mermaid_id(name: &str) -> String {
+    format!("ext_{}", module_node_id(name))
+}
+
+/// MermaidのノードIDやファイル名に使えない文字を置き換える（修飾したモジュール名の`/`・`#`など）
+pub(crate) fn module_node_id(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
+}
+
+pub fn render_dot(modules: &[ModuleInfo], externals: Externals) -> String {
+    let mut dot = String::from("digraph AngularModules {\n");
+    dot.push_str("  rankdir=TB;\n");
+    dot.push_str("  node [shape=box];\n\n");
+
+    // ノードの定義
+    for module in modules {
+        let color = match module.module_type {
+            ModuleType::Core => "lightblue",
+            ModuleType::Shared => "lightgreen",
+            ModuleType::Feature => "lightyellow",
+            ModuleType::Unknown => "lightgray",
+        };
+        dot.push_str(&format!("  \"{}\" [fillcolor={} style=filled];\n", module.name, color));
+    }
+
+    dot.push('\n');
+
+    // エッジの定義
+    for (from, to, kind) in ModuleGraph::new(modules).edges() {
+        match kind {
+            EdgeKind::Eager => dot.push_str(&format!("  \"{}\" -> \"{}\";\n", from, to)),
+            EdgeKind::Lazy => dot.push_str(&format!("  \"{}\" -> \"{}\" [style=dashed];\n", from, to)),
+        }
+    }
+
+    // 外部パッケージ
+    let mut declared = HashSet::new();
+    for module in modules {
+        for package in externals.nodes(module) {
+            if declared.insert(package.clone()) {
+                dot.push_str(&format!("  \"{}\" [shape=ellipse style=dashed color=gray];\n", package));
+            }
+            dot.push_str(&format!("  \"{}\" -> \"{}\" [color=gray];\n", module.name, package));
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// 依存グラフをノードとエッジのJSONにする（ダッシュボードなどで描画する用）
+pub fn render_graph_json(result: &AnalysisResult) -> serde_json::Value {
+    let nodes: Vec<serde_json::Value> = result.modules
+        .iter()
+        .map(|module| json!({ "id": module.name, "type": module.module_type, "path": report_path(&module.path) }))
+        .collect();
+    let edges: Vec<serde_json::Value> = ModuleGraph::new(&result.modules)
+        .edges()
+        .into_iter()
+        .map(|(from, to, kind)| {
+            let violation = result.dependency_violations
+                .iter()
+                .find(|v| *v.from_module == *from && *v.to_module == *to)
+                .map(|v| v.violation_type.code());
+            json!({
+                "from": from,
+                "to": to,
+                "lazy": kind == EdgeKind::Lazy,
+                "violation": violation,
+            })
+        })
+        .collect();
+    json!({ "nodes": nodes, "edges": edges })
+}
+
+pub fn render_mermaid(modules: &[ModuleInfo], externals: Externals) -> String {
+    let mut mermaid = String::from("graph TD\n");
+
+    for module in modules {
+        mermaid.push_str(
+            &format!(
+                "  {}[\"{}\"]:::{}\n",
+                module_node_id(&module.name),
+                module.name,
+                module.module_type.to_string().to_lowercase()
+            )
+        );
+    }
+    for (from, to, kind) in ModuleGraph::new(modules).edges() {
+        match kind {
+            EdgeKind::Eager => mermaid.push_str(&format!("  {} --> {}\n", module_node_id(from), module_node_id(to))),
+            EdgeKind::Lazy => mermaid.push_str(&format!("  {} -.-> {}\n", module_node_id(from), module_node_id(to))),
+        }
+    }
+    let mut declared = HashSet::new();
+    for module in modules {
+        for package in externals.nodes(module) {
+            let id = mermaid_id(&package);
+            if declared.insert(package.clone()) {
+                mermaid.push_str(&format!("  {}([\"{}\"]):::external\n", id, package));
+            }
+            mermaid.push_str(&format!("  {} --> {}\n", module_node_id(&module.name), id));
+        }
+    }
+
+    mermaid.push_str("  classDef core fill:#add8e6\n");
+    mermaid.push_str("  classDef shared fill:#90ee90\n");
+    mermaid.push_str("  classDef feature fill:#ffffe0\n");
+    mermaid.push_str("  classDef unknown fill:#d3d3d3\n");
+    mermaid.push_str("  classDef external fill:#fff,stroke-dasharray:3\n");
+    mermaid
+}
+
+/// 宣言しているモジュールごとのコンポーネント・ディレクティブ・パイプ（スタンドアロンは`None`）
+fn declarables_by_module(graph: &ComponentGraph) -> BTreeMap<Option<&str>, Vec<&Declarable>> {
+    let mut groups: BTreeMap<Option<&str>, Vec<&Declarable>> = BTreeMap::new();
+    for declarable in &graph.declarables {
+        groups.entry(declarable.module.as_deref()).or_default().push(declarable);
+    }
+    groups
+}
+
+/// コンポーネントの依存グラフをDOT形式にする（宣言しているモジュールごとにクラスタにまとめる）
+pub fn render_component_dot(graph: &ComponentGraph) -> String {
+    let mut dot = String::from("digraph AngularComponents {\n");
+    dot.push_str("  rankdir=TB;\n");
+    dot.push_str("  node [shape=box style=filled];\n\n");
+
+    for (module, declarables) in declarables_by_module(graph) {
+        let indent = if module.is_some() { "    " } else { "  " };
+        if let Some(module) = module {
+            dot.push_str(&format!("  subgraph \"cluster_{}\" {{\n    label=\"{}\";\n", module, module));
+        }
+        for declarable in declarables {
+            let (shape, color) = match declarable.kind {
+                DeclarableKind::Component => ("box", "lightyellow"),
+                DeclarableKind::Directive => ("ellipse", "lightblue"),
+                DeclarableKind::Pipe => ("note", "lightgreen"),
+            };
+            dot.push_str(&format!("{}\"{}\" [shape={} fillcolor={}];\n", indent, declarable.name, shape, color));
+        }
+        if module.is_some() {
+            dot.push_str("  }\n");
+        }
+    }
+
+    dot.push('\n');
+    for edge in &graph.edges {
+        match edge.usage {
+            Usage::Template => dot.push_str(&format!("  \"{}\" -> \"{}\";\n", edge.from, edge.to)),
+            Usage::Import => dot.push_str(&format!("  \"{}\" -> \"{}\" [style=dotted];\n", edge.from, edge.to)),
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// コンポーネントの依存グラフをMermaid形式にする（宣言しているモジュールごとにsubgraphにまとめる）
+pub fn render_component_mermaid(graph: &ComponentGraph) -> String {
+    let mut mermaid = String::from("graph TD\n");
+
+    for (module, declarables) in declarables_by_module(graph) {
+        let indent = if module.is_some() { "    " } else { "  " };
+        if let Some(module) = module {
+            mermaid.push_str(&format!("  subgraph {}\n", module));
+        }
+        for declarable in declarables {
+            let class = format!("{:?}", declarable.kind).to_lowercase();
+            mermaid.push_str(&format!("{}{}[\"{}\"]:::{}\n", indent, declarable.name, declarable.name, class));
+        }
+        if module.is_some() {
+            mermaid.push_str("  end\n");
+        }
+    }
+    for edge in &graph.edges {
+        match edge.usage {
+            Usage::Template => mermaid.push_str(&format!("  {} --> {}\n", edge.from, edge.to)),
+            Usage::Import => mermaid.push_str(&format!("  {} -.-> {}\n", edge.from, edge.to)),
+        }
+    }
+
+    mermaid.push_str("  classDef component fill:#ffffe0\n");
+    mermaid.push_str("  classDef directive fill:#add8e6\n");
+    mermaid.push_str("  classDef pipe fill:#90ee90\n");
+    mermaid
+}
+
+/// サービスの注入関係をDOT形式にする（提供元ごとにクラスタにまとめ、Featureの境界をまたぐ注入は赤で描く）
+pub fn render_service_dot(tree: &ServiceTree) -> String {
+    let mut dot = String::from("digraph AngularServices {\n");
+    dot.push_str("  rankdir=LR;\n");
+    dot.push_str("  node [shape=box];\n\n");
+
+    for (provider, services) in tree.by_provider() {
+        dot.push_str(&format!("  subgraph \"cluster_{}\" {{\n    label=\"{}\";\n", provider, provider));
+        for service in services {
+            dot.push_str(&format!("    \"{}\" [shape=component style=filled fillcolor=lightblue];\n", service.name));
+        }
+        dot.push_str("  }\n");
+    }
+
+    dot.push('\n');
+    // サービスどうしの注入では、サービスのノードをそのまま使う
+    let mut declared: HashSet<&String> = tree.services
+        .iter()
+        .map(|service| &service.name)
+        .collect();
+    for service in &tree.services {
+        for injection in &service.injected_by {
+            if declared.insert(&injection.class) {
+                let label = match &injection.module {
+                    Some(module) => format!("{}\\n({})", injection.class, module),
+                    None => injection.class.clone(),
+                };
+                dot.push_str(&format!("  \"{}\" [label=\"{}\"];\n", injection.class, label));
+            }
+            let style = if injection.crosses_feature { " [color=red penwidth=2]" } else { "" };
+            dot.push_str(&format!("  \"{}\" -> \"{}\"{};\n", injection.class, service.name, style));
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// ルート階層の図（DOTとMermaidで共通の組み立て）
+struct RouteDiagram {
+    mermaid: bool,
+    body: String,
+    edges: Vec<String>,
+    next_id: usize,
+}
+
+impl RouteDiagram {
+    fn id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}{}", prefix, self.next_id)
+    }
+
+    fn node(&mut self, indent: usize, id: &str, label: &str, module: bool) {
+        let indent = "  ".repeat(indent);
+        if self.mermaid {
+            let class = if module { "module" } else { "route" };
+            self.body.push_str(&format!("{}{}[\"{}\"]:::{}\n", indent, id, label.replace('"', "#quot;"), class));
+        } else {
+            let attributes = if module { "shape=component style=filled fillcolor=lightgrey" } else { "shape=box" };
+            self.body.push_str(&format!("{}{} [label=\"{}\" {}];\n", indent, id, label.replace('"', "\\\""), attributes));
+        }
+    }
+
+    fn edge(&mut self, from: &str, to: &str, lazy: bool) {
+        let edge = match (self.mermaid, lazy) {
+            (true, false) => format!("  {} --> {}\n", from, to),
+            (true, true) => format!("  {} -.-> {}\n", from, to),
+            (false, false) => format!("  {} -> {};\n", from, to),
+            (false, true) => format!("  {} -> {} [style=dashed];\n", from, to),
+        };
+        self.edges.push(edge);
+    }
+
+    fn open_cluster(&mut self, indent: usize, label: &str, lazy: bool) {
+        let id = self.id("cluster_");
+        let indent_str = "  ".repeat(indent);
+        if self.mermaid {
+            self.body.push_str(&format!("{}subgraph {}[\"{}\"]\n", indent_str, id, label));
+        } else {
+            let style = if lazy { "dashed" } else { "solid" };
+            self.body.push_str(&format!("{}subgraph {} {{\n", indent_str, id));
+            self.body.push_str(&format!("{}  label=\"{}\";\n{}  style={};\n", indent_str, label, indent_str, style));
+        }
+    }
+
+    fn close_cluster(&mut self, indent: usize) {
+        let close = if self.mermaid { "end" } else { "}" };
+        self.body.push_str(&format!("{}{}\n", "  ".repeat(indent), close));
+    }
+
+    fn modules(&mut self, indent: usize, modules: &[String]) -> HashMap<String, String> {
+        let mut ids = HashMap::new();
+        for module in modules {
+            let id = self.id("m");
+            self.node(indent, &id, module, true);
+            ids.insert(module.clone(), id);
+        }
+        ids
+    }
+
+    /// `parent`からのエッジは、遅延ロードの境界をまたぐ場合は破線にする
+    fn routes(&mut self, indent: usize, routes: &[Route], parent: Option<&str>, lazy: bool) {
+        for route in routes {
+            let id = self.id("r");
+            let label = route_label(route, if self.mermaid { "<br/>" } else { "\\n" });
+            self.node(indent, &id, &label, false);
+            if let Some(parent) = parent {
+                self.edge(parent, &id, lazy);
+            }
+            self.routes(indent, &route.children, Some(&id), false);
+
+            if let Some(boundary) = &route.lazy {
+                self.open_cluster(indent, &format!("lazy: {}", boundary.target), true);
+                let mut modules = self.modules(indent + 1, &boundary.modules);
+                if boundary.modules.is_empty() {
+                    // 読み込み先は起動時（か外側の境界）で読み込み済みなので、遅延ロードしても何も分割されない
+                    let note = self.id("m");
+                    let line_break = if self.mermaid { "<br/>" } else { "\\n" };
+                    self.node(indent + 1, &note, &format!("{}{}(already loaded)", boundary.target, line_break), true);
+                    modules.insert(boundary.target.clone(), note);
+                }
+                if boundary.routes.is_empty() {
+                    if let Some(target) = modules.get(&boundary.target) {
+                        self.edge(&id, target, true);
+                    }
+                } else {
+                    self.routes(indent + 1, &boundary.routes, Some(&id), true);
+                }
+                self.close_cluster(indent);
+            }
+        }
+    }
+}
+
+fn route_label(route: &Route, line_break: &str) -> String {
+    let mut label = format!("/{}", route.path);
+    if let Some(component) = route.component.as_ref().or(route.load_component.as_ref()) {
+        label.push_str(&format!("{}{}", line_break, component));
+    }
+    if let Some(redirect) = &route.redirect_to {
+        label.push_str(&format!("{}→ {}", line_break, redirect));
+    }
+    label
+}
+
+fn render_route_diagram(tree: &RouteTree, mermaid: bool) -> String {
+    let mut diagram = RouteDiagram { mermaid, body: String::new(), edges: Vec::new(), next_id: 0 };
+    diagram.open_cluster(1, &format!("startup: {}", tree.root), false);
+    diagram.modules(2, &tree.eager_modules);
+    diagram.routes(2, &tree.routes, None, false);
+    diagram.close_cluster(1);
+
+    let mut out = if mermaid {
+        String::from("graph TD\n")
+    } else {
+        String::from("digraph AngularRoutes {\n  rankdir=LR;\n  compound=true;\n\n")
+    };
+    out.push_str(&diagram.body);
+    out.push('\n');
+    for edge in diagram.edges {
+        out.push_str(&edge);
+    }
+    if mermaid {
+        out.push_str("  classDef module fill:#d3d3d3\n");
+        out.push_str("  classDef route fill:#ffffff\n");
+    } else {
+        out.push_str("}\n");
+    }
+    out
+}
+
+/// ルート階層をDOT形式にする（起動時に読み込まれる範囲と、遅延ロードの境界ごとに破線のクラスタで囲む）
+pub fn render_route_dot(tree: &RouteTree) -> String {
+    render_route_diagram(tree, false)
+}
+
+/// ルート階層をMermaid形式にする（遅延ロードの境界ごとにsubgraphで囲む）
+pub fn render_route_mermaid(tree: &RouteTree) -> String {
+    render_route_diagram(tree, true)
+}
+
+fn render_markdown(result: &AnalysisResult) -> String {
+    let mut md = format!("# {}\n\n", tr("Angular Module Analysis Report", "Angularモジュール解析レポート"));
+
+    md.push_str(&format!("## {}\n\n", tr("Architecture Metrics", "アーキテクチャメトリクス")));
+    md.push_str(&format!("| {} | {} |\n|---|---|\n", tr("Metric", "メトリクス"), tr("Value", "値")));
+    for (label, value) in metric_rows(result) {
+        md.push_str(&format!("| {} | {} |\n", label, value));
+    }
+    md.push('\n');
+
+    md.push_str(&format!("## {}\n\n", tr("Dependency Violations", "依存関係の違反")));
+    if result.dependency_violations.is_empty() {
+        md.push_str(&format!("{}\n\n", tr("No dependency violations found.", "依存関係の違反はありません。")));
+    } else {
+        md.push_str(
+            &format!(
+                "| {} | {} | {} |\n|---|---|---|\n",
+                tr("From", "依存元"),
+                tr("To", "依存先"),
+                tr("Description", "説明")
+            )
+        );
+        for violation in &result.dependency_violations {
+            md.push_str(
+                &format!(
+                    "| {} | {} | {} |\n",
+                    violation.from_module,
+                    violation.to_module,
+                    violation.localized_description()
+                )
+            );
+        }
+        md.push('\n');
+    }
+
+    md.push_str(&format!("## {}\n\n", tr("Circular Dependencies", "循環依存")));
+    if result.circular_dependencies.is_empty() {
+        md.push_str(&format!("{}\n\n", tr("No circular dependencies found.", "循環依存はありません。")));
+    } else {
+        for cycle in &result.circular_dependencies {
+            md.push_str(&format!("- {} -> {}\n", cycle.join(" -> "), cycle[0]));
+        }
+        md.push('\n');
+    }
+
+    if !result.plugin_findings.is_empty() {
+        md.push_str(&format!("## {}\n\n", tr("Plugin Findings", "プラグインが報告した問題")));
+        md.push_str(
+            &format!(
+                "| {} | {} | {} | {} | {} |\n|---|---|---|---|---|\n",
+                tr("Plugin", "プラグイン"),
+                tr("Rule", "ルール"),
+                tr("Severity", "重大度"),
+                tr("Module", "モジュール"),
+                tr("Message", "メッセージ")
+            )
+        );
+        for finding in &result.plugin_findings {
+            md.push_str(
+                &format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    finding.plugin,
+                    finding.rule,
+                    if finding.severity == RuleLevel::Error { "error" } else { "warn" },
+                    finding.module,
+                    finding.message
+                )
+            );
+        }
+        md.push('\n');
+    }
+
+    if !result.warnings.is_empty() {
+        md.push_str(&format!("## {}\n\n", tr("Files Not Analyzed", "解析できなかったファイル")));
+        for warning in &result.warnings {
+            md.push_str(&format!("- `{}`: {}\n", warning.file.display(), warning.cause));
+        }
+        md.push('\n');
+    }
+
+    if !result.name_collisions.is_empty() {
+        md.push_str(&format!("## {}\n\n", tr("Duplicate Module Names", "重複したモジュール名")));
+        md.push_str(
+            &format!(
+                "{}\n\n",
+                tr(
+                    "These modules share a class name and are told apart by their path.",
+                    "これらのモジュールはクラス名が同じため、パスで区別しています。"
+                )
+            )
+        );
+        for collision in &result.name_collisions {
+            let modules: Vec<String> = collision.modules
+                .iter()
+                .map(|module| format!("`{}`", module))
+                .collect();
+            md.push_str(&format!("- {}: {}\n", collision.name, modules.join(", ")));
+        }
+        md.push('\n');
+    }
+
+    if !result.suggestions.is_empty() {
+        md.push_str(&format!("## {}\n\n", tr("Suggested Refactorings", "リファクタリングの提案")));
+        md.push_str(
+            &format!(
+                "| {} | {} | {} | {} |\n|---|---|---|---|\n",
+                tr("From", "依存元"),
+                tr("To", "依存先"),
+                tr("Action", "アクション"),
+                tr("Suggestion", "提案")
+            )
+        );
+        for suggestion in &result.suggestions {
+            md.push_str(
+                &format!(
+                    "| {} | {} | `{}` | {} |\n",
+                    suggestion.from_module,
+                    suggestion.to_module,
+                    suggestion.refactoring.action(),
+                    suggestion.refactoring.localized()
+                )
+            );
+        }
+        md.push('\n');
+    }
+
+    let (test_modules, modules): (Vec<&ModuleInfo>, Vec<&ModuleInfo>) = result.modules.iter().partition(|m| m.test);
+    md.push_str(&format!("## {}\n\n", tr("Modules", "モジュール")));
+    push_module_table(&mut md, &modules);
+    if !test_modules.is_empty() {
+        md.push_str(&format!("\n## {}\n\n", tr("Test Modules", "テスト用モジュール")));
+        push_module_table(&mut md, &test_modules);
+    }
+    md
+}
+
+/// メトリクスの表の（項目名, 値）
+fn metric_rows(result: &AnalysisResult) -> [(&'static str, String); 7] {
+    let metrics = &result.metrics;
+    [
+        (tr("Total Modules", "モジュール数"), metrics.total_modules.to_string()),
+        (tr("Core Modules", "Coreモジュール"), metrics.core_modules.to_string()),
+        (tr("Shared Modules", "Sharedモジュール"), metrics.shared_modules.to_string()),
+        (tr("Feature Modules", "Featureモジュール"), metrics.feature_modules.to_string()),
+        (
+            tr("Average Dependencies per Module", "モジュールあたりの平均依存数"),
+            format!("{:.2}", metrics.average_dependencies_per_module),
+        ),
+        (tr("Coupling Factor", "結合度"), format!("{:.2}", metrics.coupling_factor)),
+        (tr("Health Score", "ヘルススコア"), format!("{:.1}", metrics.health_score)),
+    ]
+}
+
+fn push_module_table(md: &mut String, modules: &[&ModuleInfo]) {
+    md.push_str(
+        &format!(
+            "| {} | {} | {} | {} |\n|---|---|---|---|\n",
+            tr("Module", "モジュール"),
+            tr("Type", "種類"),
+            tr("Dependencies", "依存数"),
+            tr("Path", "パス")
+        )
+    );
+    for module in modules {
+        md.push_str(
+            &format!(
+                "| {} | {} | {} | `{}` |\n",
+                module.name,
+                module.module_type,
+                module.internal_dependencies().count(),
+                module.path.display()
+            )
+        );
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn render_html(result: &AnalysisResult) -> String {
+    let title = tr("Angular Module Analysis Report", "Angularモジュール解析レポート");
+    let lang = match Lang::current() {
+        Lang::En => "en",
+        Lang::Ja => "ja",
+    };
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n",
+        lang,
+        title
+    );
+    html.push_str(
+        "<style>body{font-family:sans-serif;margin:2em}table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:4px 8px;text-align:left}.violation{color:#c00}</style>\n"
+    );
+    html.push_str(&format!("</head>\n<body>\n<h1>{}</h1>\n", title));
+
+    html.push_str(&format!("<h2>{}</h2>\n<table>\n", tr("Architecture Metrics", "アーキテクチャメトリクス")));
+    for (label, value) in metric_rows(result) {
+        html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", label, value));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str(&format!("<h2>{}</h2>\n", tr("Dependency Violations", "依存関係の違反")));
+    if result.dependency_violations.is_empty() {
+        html.push_str(&format!("<p>{}</p>\n", tr("No dependency violations found.", "依存関係の違反はありません。")));
+    } else {
+        html.push_str("<ul>\n");
+        for violation in &result.dependency_violations {
+            html.push_str(
+                &format!(
+                    "<li class=\"violation\">{} &rarr; {}: {}</li>\n",
+                    escape_html(&violation.from_module),
+                    escape_html(&violation.to_module),
+                    escape_html(violation.localized_description())
+                )
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str(&format!("<h2>{}</h2>\n", tr("Circular Dependencies", "循環依存")));
+    if result.circular_dependencies.is_empty() {
+        html.push_str(&format!("<p>{}</p>\n", tr("No circular dependencies found.", "循環依存はありません。")));
+    } else {
+        html.push_str("<ul>\n");
+        for cycle in &result.circular_dependencies {
+            html.push_str(
+                &format!("<li>{} &rarr; {}</li>\n", escape_html(&cycle.join(" → ")), escape_html(&cycle[0]))
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str(
+        &format!(
+            "<h2>{}</h2>\n<table>\n<tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>\n",
+            tr("Modules", "モジュール"),
+            tr("Module", "モジュール"),
+            tr("Type", "種類"),
+            tr("Dependencies", "依存数"),
+            tr("Path", "パス")
+        )
+    );
+    for module in &result.modules {
+        html.push_str(
+            &format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>\n",
+                escape_html(&module.name),
+                module.module_type,
+                module.internal_dependencies().count(),
+                escape_html(&module.path.display().to_string())
+            )
+        );
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+/// 違反と循環依存を、出力形式によらない1件ずつの指摘にしたもの
+pub(crate) struct Finding<'r> {
+    pub(crate) rule: ViolationType,
+    /// `[rules]`で設定した重大度
+    pub(crate) severity: RuleLevel,
+    /// 依存元のモジュール（循環では最初のモジュール）
+    pub(crate) module: &'r str,
+    pub(crate) message: String,
+    /// 指摘の対象ファイル（参照箇所、分からなければ依存元モジュールのファイル）
+    pub(crate) file: Option<&'r Path>,
+    /// 参照箇所の行・列
+    pub(crate) position: Option<(usize, usize)>,
+    /// 同じ指摘を実行間で同一視するためのキー
+    pub(crate) key: String,
+}
+
+pub(crate) fn findings(result: &AnalysisResult) -> Vec<Finding<'_>> {
+    let mut findings: Vec<Finding> = result.dependency_violations
+        .iter()
+        .map(|violation| {
+            let (file, position) = locate(result, &violation.from_module, violation.location.as_ref());
+            Finding {
+                rule: violation.violation_type.clone(),
+                severity: violation.severity,
+                module: &violation.from_module,
+                message: violation.to_string(),
+                file,
+                position,
+                key: format!("{}:{}->{}", violation.violation_type.code(), violation.from_module, violation.to_module),
+            }
+        })
+        .collect();
+
+    for (i, cycle) in result.circular_dependencies.iter().enumerate() {
+        let first_edge = result.circular_dependency_locations
+            .get(i)
+            .and_then(|locations| locations.first())
+            .and_then(Option::as_ref);
+        let (file, position) = locate(result, &cycle[0], first_edge);
+        findings.push(Finding {
+            rule: ViolationType::CircularDependency,
+            severity: result.cycle_severity,
+            module: &cycle[0],
+            message: format!("Circular dependency: {} -> {}", cycle.join(" -> "), cycle[0]),
+            file,
+            position,
+            key: format!("{}:{}", ViolationType::CircularDependency.code(), cycle.join("->")),
+        });
+    }
+    findings
+}
+
+/// 参照箇所が分かればその行・列を、分からなければモジュールのファイルを指す
+fn locate<'r>(
+    result: &'r AnalysisResult,
+    name: &str,
+    location: Option<&'r SourceLocation>
+) -> (Option<&'r Path>, Option<(usize, usize)>) {
+    match location {
+        Some(location) => (Some(location.file.as_path()), Some((location.line, location.column))),
+        None =>
+            (
+                result.modules
+                    .iter()
+                    .find(|m| *m.name == *name)
+                    .map(|m| m.path.as_path()),
+                None,
+            ),
+    }
+}
+
+/// レポートに書くファイルパス（カレントディレクトリからの相対パス、区切りは`/`）
+///
+/// CIはリポジトリのルートで実行されるため、GitLabなどはこの形式でないとファイルを特定できない。
+pub(crate) fn report_path(path: &Path) -> String {
+    let relative = std::env
+        ::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path);
+    let relative = relative.strip_prefix(".").unwrap_or(relative);
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// GitLabのCode Quality形式（マージリクエストのウィジェットに新しい違反として表示される）
+fn render_codeclimate(result: &AnalysisResult) -> serde_json::Value {
+    let issues: Vec<serde_json::Value> = findings(result)
+        .into_iter()
+        .map(|finding| {
+            json!({
+                "description": finding.message,
+                "check_name": finding.rule.code(),
+                "fingerprint": blake3::hash(finding.key.as_bytes()).to_hex()[..32],
+                "severity": if finding.severity == RuleLevel::Warn { "minor" } else { "major" },
+                "location": {
+                    "path": finding.file.map(report_path).unwrap_or_default(),
+                    "lines": { "begin": finding.position.map_or(1, |(line, _)| line) },
+                },
+            })
+        })
+        .collect();
+    json!(issues)
+}
+
+/// SonarQubeの外部issue形式（`sonar.externalIssuesReportPaths`で取り込む）
+///
+/// ルールIDはルールコード（AA001など）で、行は1始まり、列は0始まりで出力する。
+fn render_sonar(result: &AnalysisResult) -> serde_json::Value {
+    let rules: Vec<serde_json::Value> = ViolationType::ALL.iter()
+        .map(|rule| {
+            json!({
+                "id": rule.code(),
+                "name": rule.to_string(),
+                "description": rule.to_string(),
+                "engineId": "angular-module-analyzer",
+                "cleanCodeAttribute": "MODULAR",
+                "impacts": [{ "softwareQuality": "MAINTAINABILITY", "severity": "MEDIUM" }],
+            })
+        })
+        .collect();
+
+    let issues: Vec<serde_json::Value> = findings(result)
+        .into_iter()
+        .filter_map(|finding| {
+            // SonarQubeはファイルに紐づかないissueを取り込めない
+            let mut location = json!({ "message": finding.message, "filePath": report_path(finding.file?) });
+            if let Some((line, column)) = finding.position {
+                location["textRange"] = json!({ "startLine": line, "startColumn": column - 1 });
+            }
+            Some(json!({ "ruleId": finding.rule.code(), "primaryLocation": location }))
+        })
+        .collect();
+
+    json!({ "rules": rules, "issues": issues })
+}
+
+/// ESLintのJSON形式（ESLintと同じく`filePath`は絶対パス、`[rules]`で`warn`にしたルールの違反はwarning）
+fn render_eslint(result: &AnalysisResult) -> serde_json::Value {
+    let mut files: BTreeMap<&Path, Vec<serde_json::Value>> = BTreeMap::new();
+    for finding in findings(result) {
+        let Some(file) = finding.file else {
+            continue;
+        };
+        let (line, column) = finding.position.unwrap_or((1, 1));
+        files
+            .entry(file)
+            .or_default()
+            .push(
+                json!({
+                "ruleId": finding.rule.code(),
+                "severity": if finding.severity == RuleLevel::Warn { 1 } else { 2 },
+                "message": finding.message,
+                "line": line,
+                "column": column,
+                "nodeType": null,
+            })
+            );
+    }
+
+    let reports: Vec<serde_json::Value> = files
+        .into_iter()
+        .map(|(file, messages)| {
+            let file_path = std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf());
+            let warnings = messages
+                .iter()
+                .filter(|message| message["severity"] == 1)
+                .count();
+            json!({
+                "filePath": file_path,
+                "messages": messages,
+                "suppressedMessages": [],
+                "errorCount": messages.len() - warnings,
+                "fatalErrorCount": 0,
+                "warningCount": warnings,
+                "fixableErrorCount": 0,
+                "fixableWarningCount": 0,
+                "usedDeprecatedRules": [],
+            })
+        })
+        .collect();
+    json!(reports)
+}
+
+/// dependency-cruiserの出力形式（`depcruise-fmt`やそのレポーターでそのまま扱える）
+///
+/// モジュールのファイルをノードとし、NgModuleの依存と遅延ロードを解決済みの依存、
+/// 外部パッケージをnpm依存として出力する。レイヤー違反と循環は`summary.violations`に入る。
+fn render_depcruise(result: &AnalysisResult) -> serde_json::Value {
+    let graph = result.graph();
+    let sources: HashMap<&str, String> = result.modules
+        .iter()
+        .map(|module| (module.name.as_ref(), report_path(&module.path)))
+        .collect();
+    let rule = |violation_type: &ViolationType, severity: RuleLevel| {
+        let severity = if severity == RuleLevel::Warn { "warn" } else { "error" };
+        json!({ "severity": severity, "name": violation_type.code() })
+    };
+    let in_cycle = |from: &str, to: &str| {
+        result.circular_dependencies.iter().any(|cycle| {
+            (0..cycle.len()).any(|i| *cycle[i] == *from && *cycle[(i + 1) % cycle.len()] == *to)
+        })
+    };
+
+    let mut modules: Vec<serde_json::Value> = Vec::new();
+    let mut packages: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    let mut dependency_count = 0;
+    for module in &result.modules {
+        let source = &sources[module.name.as_ref()];
+        let mut dependencies: Vec<serde_json::Value> = graph
+            .dependencies_of(&module.name)
+            .into_iter()
+            .map(|target| {
+                let lazy = graph.edge_kind(&module.name, target) == Some(EdgeKind::Lazy);
+                let violations: Vec<serde_json::Value> = result.dependency_violations
+                    .iter()
+                    .filter(|v| *v.from_module == *module.name && *v.to_module == *target)
+                    .map(|v| rule(&v.violation_type, v.severity))
+                    .collect();
+                let mut dependency =
+                    json!({
+                    "module": relative_specifier(source, &sources[target]),
+                    "resolved": sources[target],
+                    "moduleSystem": "es6",
+                    "dependencyTypes": if lazy { ["local", "dynamic-import"] } else { ["local", "import"] },
+                    "dynamic": lazy,
+                    "coreModule": false,
+                    "followable": true,
+                    "couldNotResolve": false,
+                    "exoticallyRequired": false,
+                    "matchesDoNotFollow": false,
+                    "circular": in_cycle(&module.name, target),
+                    "valid": violations.is_empty(),
+                });
+                if !violations.is_empty() {
+                    dependency["rules"] = json!(violations);
+                }
+                dependency
+            })
+            .collect();
+
+        let mut externals: Vec<&str> = module.external_dependencies
+            .iter()
+            .map(|dep| dep.package.as_ref())
+            .collect();
+        externals.sort();
+        externals.dedup();
+        for package in externals {
+            packages.entry(package.to_string()).or_default().push(source);
+            dependencies.push(
+                json!({
+                "module": package,
+                "resolved": package,
+                "moduleSystem": "es6",
+                "dependencyTypes": ["npm"],
+                "dynamic": false,
+                "coreModule": false,
+                "followable": false,
+                "couldNotResolve": false,
+                "exoticallyRequired": false,
+                "matchesDoNotFollow": false,
+                "circular": false,
+                "valid": true,
+            })
+            );
+        }
+        dependency_count += dependencies.len();
+
+        let dependents: Vec<&str> = graph
+            .dependents_of(&module.name)
+            .into_iter()
+            .map(|name| sources[name].as_str())
+            .collect();
+        modules.push(
+            json!({
+            "source": source,
+            "dependencies": dependencies,
+            "dependents": dependents,
+            "orphan": dependencies.is_empty() && dependents.is_empty(),
+            "valid": !result.dependency_violations.iter().any(|v| *v.from_module == *module.name),
+        })
+        );
+    }
+    for (package, dependents) in packages {
+        modules.push(
+            json!({
+            "source": package,
+            "dependencies": [],
+            "dependents": dependents,
+            "dependencyTypes": ["npm"],
+            "coreModule": false,
+            "followable": false,
+            "couldNotResolve": false,
+            "matchesDoNotFollow": false,
+            "orphan": false,
+            "valid": true,
+        })
+        );
+    }
+
+    let mut violations: Vec<serde_json::Value> = result.dependency_violations
+        .iter()
+        .filter(|v| sources.contains_key(v.to_module.as_ref()))
+        .map(|v| {
+            json!({
+                "type": "dependency",
+                "from": sources[v.from_module.as_ref()],
+                "to": sources[v.to_module.as_ref()],
+                "rule": rule(&v.violation_type, v.severity),
+            })
+        })
+        .collect();
+    for cycle in &result.circular_dependencies {
+        // dependency-cruiserは`from`の次から`from`へ戻るまでの経路を`cycle`に入れる
+        let path: Vec<serde_json::Value> = cycle
+            .iter()
+            .skip(1)
+            .chain(cycle.first())
+            .map(|name| json!({ "name": sources[name.as_ref()], "dependencyTypes": ["local", "import"] }))
+            .collect();
+        violations.push(
+            json!({
+            "type": "cycle",
+            "from": sources[cycle[0].as_ref()],
+            "to": sources[cycle[1 % cycle.len()].as_ref()],
+            "rule": rule(&ViolationType::CircularDependency, result.cycle_severity),
+            "cycle": path,
+        })
+        );
+    }
+
+    let warnings = violations
+        .iter()
+        .filter(|v| v["rule"]["severity"] == "warn")
+        .count();
+    json!({
+        "modules": modules,
+        "summary": {
+            "violations": violations,
+            "error": violations.len() - warnings,
+            "warn": warnings,
+            "info": 0,
+            "ignore": 0,
+            "totalCruised": modules.len(),
+            "totalDependenciesCruised": dependency_count,
+            "optionsUsed": { "outputType": "json" },
+        },
+    })
+}
+
+/// `from`のファイルから`to`のファイルをimportするときの相対指定子（拡張子なし）
+fn relative_specifier(from: &str, to: &str) -> String {
+    let from_dir: Vec<&str> = from.split('/').collect();
+    let from_dir = &from_dir[..from_dir.len() - 1];
+    let to_parts: Vec<&str> = to.split('/').collect();
+    let common = from_dir
+        .iter()
+        .zip(&to_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut specifier = if common == from_dir.len() { ".".to_string() } else { "..".to_string() };
+    for _ in common + 1..from_dir.len() {
+        specifier.push_str("/..");
+    }
+    for part in &to_parts[common..] {
+        specifier.push('/');
+        specifier.push_str(part);
+    }
+    specifier.strip_suffix(".ts").map(str::to_string).unwrap_or(specifier)
+}
+
+fn render_sarif(result: &AnalysisResult) -> serde_json::Value {
+    let results: Vec<serde_json::Value> = findings(result)
+        .into_iter()
+        .map(|finding| {
+            let mut sarif =
+                json!({
+                "ruleId": format!("{:?}", finding.rule),
+                "level": if finding.severity == RuleLevel::Warn { "warning" } else { "error" },
+                "message": { "text": finding.message },
+            });
+            if let Some(file) = finding.file {
+                let mut location = json!({ "artifactLocation": { "uri": file.to_string_lossy().replace('\\', "/") } });
+                if let Some((line, column)) = finding.position {
+                    location["region"] = json!({ "startLine": line, "startColumn": column });
+                }
+                sarif["locations"] = json!([{ "physicalLocation": location }]);
+            }
+            sarif
+        })
+        .collect();
+
+    let rules: Vec<serde_json::Value> = ViolationType::ALL.iter()
+        .map(|rule| json!({ "id": format!("{:?}", rule), "shortDescription": { "text": rule.to_string() } }))
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "angular-module-analyzer",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
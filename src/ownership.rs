@@ -0,0 +1,57 @@
+use std::path::{ Component, Path };
+
+use crate::ModuleInfo;
+
+/// ソースファイルがどのモジュールに属するか（モジュールファイルのあるディレクトリ以下のファイルは、そのモジュールのもの）
+///
+/// 入れ子のモジュールでは最も内側のモジュールが所有する。
+/// ビルドの統計やカバレッジのパスは、プロジェクトルートからの相対パスか絶対パスを受け付ける。
+pub struct FileOwnership<'r> {
+    root: Vec<String>,
+    /// （モジュールのディレクトリのパス要素, モジュール名）をディレクトリの深い順に
+    directories: Vec<(Vec<String>, &'r str)>,
+}
+
+impl<'r> FileOwnership<'r> {
+    pub fn new(project_path: &Path, modules: &'r [ModuleInfo]) -> Self {
+        let root = components(&project_path.canonicalize().unwrap_or_else(|_| project_path.to_path_buf()));
+        let mut directories: Vec<(Vec<String>, &'r str)> = modules
+            .iter()
+            .map(|module| {
+                let relative = module.path.strip_prefix(project_path).unwrap_or(&module.path);
+                let directory = relative.parent().map(components).unwrap_or_default();
+                (directory, module.name.as_ref())
+            })
+            .collect();
+        directories.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.1.cmp(b.1)));
+        Self { root, directories }
+    }
+
+    /// ファイルを所有するモジュール
+    ///
+    /// webpackのローダー指定（`...!./src/x.ts`）やクエリ（`?ngResource`）は取り除いてから照合する。
+    pub fn owner(&self, file: &str) -> Option<&'r str> {
+        let file = file.rsplit('!').next().unwrap_or(file);
+        let file = file.split('?').next().unwrap_or(file).replace('\\', "/");
+        let mut parts = components(Path::new(&file));
+        if Path::new(&file).is_absolute() {
+            if !parts.starts_with(&self.root) {
+                return None;
+            }
+            parts.drain(..self.root.len());
+        }
+        self.directories
+            .iter()
+            .find(|(directory, _)| parts.len() > directory.len() && parts.starts_with(directory))
+            .map(|(_, name)| *name)
+    }
+}
+
+fn components(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect()
+}
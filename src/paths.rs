@@ -0,0 +1,192 @@
+use clap::ValueEnum;
+use std::path::{ Path, PathBuf };
+
+use crate::compodoc::Reconciliation;
+use crate::deadcode::DeadCodeReport;
+use crate::impact::ImpactReport;
+use crate::services::{ MultiProviders, ProviderScopes, ServiceTree };
+use crate::standalone::MigrationPlan;
+use crate::workspaces::WorkspaceReport;
+use crate::{ AnalysisResult, ModuleInfo, SourceLocation, normalize_path };
+
+/// 結果に書くファイルパスの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum PathStyle {
+    /// プロジェクトルートからの相対パス（区切りは`/`、マシンやチェックアウト先によらず同じになる）
+    #[default]
+    Relative,
+    /// 正規化（シンボリックリンクと`..`を解決）したプロジェクトルートからの絶対パス
+    Absolute,
+}
+
+/// プロジェクトルートからの相対パスを`/`区切りにしたもの（ルートの外のパスは`..`などを解決するだけ）
+pub fn relative_path(path: &Path, project_path: &Path) -> PathBuf {
+    let path = normalize_path(path);
+    match path.strip_prefix(normalize_path(project_path)) {
+        Ok(relative) => PathBuf::from(relative.to_string_lossy().replace('\\', "/")),
+        Err(_) => path,
+    }
+}
+
+/// `style`の形式にしたパスを返す関数（`project_path`は解析したときのプロジェクトルート）
+///
+/// すでにプロジェクトルートからの相対パスになっているパスもそのまま渡せる。
+pub fn presenter(project_path: &Path, style: PathStyle) -> impl Fn(&Path) -> PathBuf + use<> {
+    let project_path = project_path.to_path_buf();
+    let root = match style {
+        PathStyle::Relative => None,
+        PathStyle::Absolute => Some(project_path.canonicalize().unwrap_or_else(|_| normalize_path(&project_path))),
+    };
+    move |path| {
+        let relative = relative_path(path, &project_path);
+        match &root {
+            Some(root) => root.join(relative),
+            // プロジェクトルート自身
+            None if relative.as_os_str().is_empty() => PathBuf::from("."),
+            None => relative,
+        }
+    }
+}
+
+/// ファイルパスを含む出力（JSONなどの機械可読な出力は、出力の直前に[`present_paths`](Self::present_paths)でそろえる）
+pub trait PresentPaths {
+    /// 含まれるファイルパスをすべて`f`で置き換える
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf);
+
+    /// 含まれるファイルパスを`style`の形式にそろえる
+    ///
+    /// 相対パスにした結果は、ファイルを読み書きするコマンド（`fix`など）にはそのまま渡せない。
+    fn present_paths(&mut self, project_path: &Path, style: PathStyle) {
+        self.map_paths(&presenter(project_path, style));
+    }
+}
+
+impl<T: PresentPaths> PresentPaths for Vec<T> {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        for item in self {
+            item.map_paths(f);
+        }
+    }
+}
+
+impl<T: PresentPaths> PresentPaths for Option<T> {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        if let Some(item) = self {
+            item.map_paths(f);
+        }
+    }
+}
+
+impl PresentPaths for SourceLocation {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        self.file = f(&self.file);
+    }
+}
+
+impl PresentPaths for AnalysisResult {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        for module in &mut self.modules {
+            module.map_paths(f);
+        }
+        for violation in &mut self.dependency_violations {
+            violation.location.map_paths(f);
+        }
+        for cycle in &mut self.circular_dependency_locations {
+            cycle.map_paths(f);
+        }
+        for suggestion in &mut self.suggestions {
+            suggestion.location.map_paths(f);
+        }
+        for finding in &mut self.plugin_findings {
+            finding.location.map_paths(f);
+        }
+        for warning in &mut self.warnings {
+            warning.file = f(&warning.file);
+        }
+        if let Some(components) = &mut self.components {
+            for declarable in &mut components.declarables {
+                declarable.file = f(&declarable.file);
+            }
+        }
+    }
+}
+
+impl PresentPaths for ImpactReport {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        for changed in &mut self.changed_files {
+            changed.file = f(&changed.file);
+        }
+    }
+}
+
+impl PresentPaths for ServiceTree {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        for service in &mut self.services {
+            service.file = f(&service.file);
+            for injection in &mut service.injected_by {
+                injection.location.map_paths(f);
+            }
+        }
+    }
+}
+
+impl PresentPaths for ProviderScopes {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        for registration in self.tokens.iter_mut().flat_map(|token| &mut token.registrations) {
+            registration.location.map_paths(f);
+        }
+    }
+}
+
+impl PresentPaths for MultiProviders {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        for registration in self.tokens.iter_mut().flat_map(|token| &mut token.registrations) {
+            registration.location.map_paths(f);
+        }
+    }
+}
+
+impl PresentPaths for WorkspaceReport {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        self.root = f(&self.root);
+        for bypass in &mut self.bypasses {
+            bypass.location.map_paths(f);
+        }
+    }
+}
+
+impl PresentPaths for DeadCodeReport {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        for item in &mut self.items {
+            item.file = f(&item.file);
+        }
+    }
+}
+
+impl PresentPaths for MigrationPlan {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        for step in &mut self.steps {
+            step.path = f(&step.path);
+        }
+    }
+}
+
+impl PresentPaths for Reconciliation {
+    fn map_paths(&mut self, f: &dyn Fn(&Path) -> PathBuf) {
+        for module in self.missing_modules.iter_mut().chain(&mut self.undocumented_modules) {
+            module.file = f(&module.file);
+        }
+    }
+}
+
+impl ModuleInfo {
+    /// モジュールのファイルと、解決した参照先のファイルのパスを置き換える
+    pub(crate) fn map_paths(&mut self, f: impl Fn(&Path) -> PathBuf) {
+        self.path = f(&self.path);
+        for dep in &mut self.module_dependencies {
+            if let Some(path) = &mut dep.path {
+                *path = f(path);
+            }
+        }
+    }
+}
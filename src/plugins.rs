@@ -0,0 +1,205 @@
+use serde::{ Deserialize, Serialize };
+use std::fs;
+use std::path::Path;
+
+use crate::config::{ PluginConfig, RuleLevel };
+use crate::graph::{ EdgeKind, ModuleGraph };
+use crate::scripts;
+use crate::{ AnalyzerError, ModuleInfo, ModuleType, Name, Result, SourceLocation };
+
+/// プラグインに渡す入力の形式のバージョン（入力JSONの`version`）
+pub const ABI_VERSION: u32 = 1;
+
+/// 1回の実行で消費できる燃料（命令数の目安。無限ループで解析が止まらないようにする）
+#[cfg(feature = "plugins")]
+const FUEL: u64 = 10_000_000_000;
+
+/// プラグインが使えるメモリの上限
+#[cfg(feature = "plugins")]
+const MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+/// プラグインが報告した問題
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginFinding {
+    /// 報告したプラグインの名前
+    pub plugin: String,
+    /// プラグインが定めたルールの識別子
+    pub rule: String,
+    pub severity: RuleLevel,
+    pub module: Name,
+    /// 依存先など、問題に関わるもう一方の名前
+    #[serde(default)]
+    pub target: Option<Name>,
+    pub message: String,
+    /// `module`のファイル内で`target`を参照している場所（`target`がなければファイルの先頭）
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
+}
+
+#[derive(Serialize)]
+struct PluginInput<'a> {
+    version: u32,
+    modules: &'a [ModuleFacts<'a>],
+    edges: &'a [EdgeFacts<'a>],
+    options: &'a toml::Table,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ModuleFacts<'a> {
+    pub name: &'a str,
+    /// プロジェクトルートからの相対パス
+    pub path: &'a Path,
+    pub module_type: &'a ModuleType,
+    pub imports: &'a [Name],
+    pub exports: &'a [Name],
+    pub providers: &'a [Name],
+    pub declarations: &'a [Name],
+    pub lazy_imports: &'a [Name],
+}
+
+#[derive(Serialize)]
+pub(crate) struct EdgeFacts<'a> {
+    pub from: &'a str,
+    pub to: &'a str,
+    pub lazy: bool,
+}
+
+/// プラグイン・スクリプトが返す問題（スクリプトでは`module`を省略すると対象のモジュールになる）
+#[derive(Deserialize)]
+pub(crate) struct RawFinding {
+    pub rule: String,
+    #[serde(default)]
+    pub module: Option<String>,
+    #[serde(default)]
+    pub target: Option<String>,
+    pub message: String,
+    #[serde(default)]
+    pub severity: Option<RuleLevel>,
+}
+
+/// 設定されたプラグインを書いた順に実行し、報告された問題を集める
+///
+/// `script`を書いたもの、`path`が`.rhai`のものはRhaiのスクリプトとして実行する。
+/// WASMプラグインとのやりとりはJSONで行う。プラグインは次をexportする。
+/// - `memory`: 入出力に使う線形メモリ
+/// - `alloc(len: i32) -> i32`: 入力を書き込む領域を確保して先頭のアドレスを返す
+/// - `analyze(ptr: i32, len: i32) -> i64`: 入力を解析し、出力の`(アドレス << 32) | 長さ`を返す
+///
+/// 入力は`{ version, modules, edges, options }`、出力は`{ rule, module, target?, message, severity? }`の配列。
+/// `severity`を省略した問題は`error`、`off`の問題は捨てる。
+pub fn run(
+    project_path: &Path,
+    plugins: &[PluginConfig],
+    modules: &[ModuleInfo],
+    graph: &ModuleGraph
+) -> Result<Vec<PluginFinding>> {
+    if plugins.is_empty() {
+        return Ok(Vec::new());
+    }
+    let facts: Vec<ModuleFacts> = modules
+        .iter()
+        .map(|m| ModuleFacts {
+            name: &m.name,
+            path: m.path.strip_prefix(project_path).unwrap_or(&m.path),
+            module_type: &m.module_type,
+            imports: &m.imports,
+            exports: &m.exports,
+            providers: &m.providers,
+            declarations: &m.declarations,
+            lazy_imports: &m.lazy_imports,
+        })
+        .collect();
+    let edges: Vec<EdgeFacts> = graph
+        .edges()
+        .into_iter()
+        .map(|(from, to, kind)| EdgeFacts { from, to, lazy: kind == EdgeKind::Lazy })
+        .collect();
+
+    let mut findings = Vec::new();
+    for plugin in plugins {
+        let name = plugin.display_name();
+        let failed = |cause: String| AnalyzerError::Plugin { plugin: name.clone(), cause };
+        let path = project_path.join(&plugin.path);
+        let raw = if let Some(script) = &plugin.script {
+            scripts::run(&name, script, &facts, &edges, &plugin.options).map_err(failed)?
+        } else if plugin.path.extension().is_some_and(|ext| ext == "rhai") {
+            let script = fs::read_to_string(&path).map_err(|err| AnalyzerError::io(&path, err))?;
+            scripts::run(&name, &script, &facts, &edges, &plugin.options).map_err(failed)?
+        } else {
+            let input = serde_json::to_vec(
+                &(PluginInput {
+                    version: ABI_VERSION,
+                    modules: &facts,
+                    edges: &edges,
+                    options: &plugin.options,
+                })
+            )?;
+            let output = execute(&path, &input).map_err(failed)?;
+            serde_json::from_slice(&output).map_err(|err| failed(format!("invalid findings: {}", err)))?
+        };
+
+        for finding in raw {
+            let severity = finding.severity.unwrap_or(RuleLevel::Error);
+            if severity == RuleLevel::Off {
+                continue;
+            }
+            let Some(module_name) = finding.module else {
+                return Err(failed(format!("finding `{}` has no `module`", finding.rule)));
+            };
+            let module = modules.iter().find(|m| *m.name == *module_name);
+            let location = module.and_then(|m| match &finding.target {
+                Some(target) => m.location_of(target),
+                None => Some(SourceLocation { file: m.path.clone(), line: 1, column: 1 }),
+            });
+            findings.push(PluginFinding {
+                plugin: name.clone(),
+                rule: finding.rule,
+                severity,
+                module: Name::from(module_name.as_str()),
+                target: finding.target.as_deref().map(Name::from),
+                message: finding.message,
+                location,
+            });
+        }
+    }
+    Ok(findings)
+}
+
+/// プラグインを読み込み、入力を渡して出力を受け取る
+#[cfg(feature = "plugins")]
+fn execute(path: &Path, input: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    use wasmtime::{ Config, Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder };
+
+    let fail = |err: wasmtime::Error| format!("{:#}", err);
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config).map_err(fail)?;
+    // `wat`機能によりテキスト形式（`.wat`）もそのまま読み込める
+    let module = Module::from_file(&engine, path).map_err(fail)?;
+    let limits = StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build();
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits: &mut StoreLimits| limits);
+    store.set_fuel(FUEL).map_err(fail)?;
+
+    // ホスト関数は提供しない（ファイルやネットワークにはアクセスさせない）
+    let instance = Instance::new(&mut store, &module, &[]).map_err(fail)?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| "the plugin does not export `memory`".to_string())?;
+    let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc").map_err(fail)?;
+    let analyze = instance.get_typed_func::<(i32, i32), i64>(&mut store, "analyze").map_err(fail)?;
+
+    let len = i32::try_from(input.len()).map_err(|_| "the input is too large".to_string())?;
+    let ptr = alloc.call(&mut store, len).map_err(fail)?;
+    memory.write(&mut store, ptr as u32 as usize, input).map_err(|err| err.to_string())?;
+    let packed = analyze.call(&mut store, (ptr, len)).map_err(fail)? as u64;
+
+    let mut output = vec![0; (packed & 0xffff_ffff) as usize];
+    memory.read(&store, (packed >> 32) as usize, &mut output).map_err(|err| err.to_string())?;
+    Ok(output)
+}
+
+#[cfg(not(feature = "plugins"))]
+fn execute(_path: &Path, _input: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    Err("this build does not support plugins (rebuild with `--features plugins`)".to_string())
+}
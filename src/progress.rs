@@ -0,0 +1,65 @@
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::{ AnalyzerError, ModuleInfo };
+
+/// 解析の段階
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Phase {
+    /// `*.module.ts`ファイルの走査
+    Discover,
+    /// モジュールファイルの解析
+    Parse,
+    /// レイヤールール違反の検出
+    Violations,
+    /// 依存グラフの構築（循環依存ルールが無効なら省略される）
+    Graph,
+    /// 循環依存の検出
+    Cycles,
+    /// メトリクスの算出
+    Metrics,
+}
+
+/// 解析の進捗を受け取るフック（[`AnalyzerBuilder::progress`](crate::AnalyzerBuilder::progress)で登録する）
+///
+/// 必要なメソッドだけを実装すればよい。CLIの進捗表示も同じトレイトで実装されている。
+pub trait ProgressHandler: Send + Sync {
+    /// 解析対象のファイルを見つけた
+    fn on_file_discovered(&self, _path: &Path) {}
+
+    /// モジュールファイルを1件解析した
+    fn on_module_parsed(&self, _module: &ModuleInfo) {}
+
+    /// ファイルを解析対象から外した（最大サイズを超えたなど）
+    fn on_file_skipped(&self, _reason: &AnalyzerError) {}
+
+    /// 段階が完了した
+    fn on_phase_complete(&self, _phase: Phase) {}
+}
+
+/// 呼び出し側で結果を参照したいハンドラーは`Arc`で共有して登録できる
+impl<T: ProgressHandler + ?Sized> ProgressHandler for Arc<T> {
+    fn on_file_discovered(&self, path: &Path) {
+        (**self).on_file_discovered(path);
+    }
+
+    fn on_module_parsed(&self, module: &ModuleInfo) {
+        (**self).on_module_parsed(module);
+    }
+
+    fn on_file_skipped(&self, reason: &AnalyzerError) {
+        (**self).on_file_skipped(reason);
+    }
+
+    fn on_phase_complete(&self, phase: Phase) {
+        (**self).on_phase_complete(phase);
+    }
+}
+
+impl fmt::Debug for dyn ProgressHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressHandler")
+    }
+}
@@ -0,0 +1,331 @@
+use std::fs;
+use std::path::Path;
+use std::time::{ SystemTime, UNIX_EPOCH };
+use tera::{ Context, Tera };
+
+use crate::graph::{ EdgeKind, ModuleGraph };
+use crate::output::{ escape_html, module_node_id };
+use crate::trend::TrendPoint;
+use crate::{ AnalysisResult, AnalyzerError, ModuleInfo, ModuleType, Result };
+
+/// レポートディレクトリに蓄積するメトリクスの履歴
+const HISTORY_FILE: &str = "history.json";
+
+const LAYERS: [(ModuleType, &str); 4] = [
+    (ModuleType::Core, "core"),
+    (ModuleType::Shared, "shared"),
+    (ModuleType::Feature, "feature"),
+    (ModuleType::Unknown, "unknown"),
+];
+
+const STYLE: &str =
+    "body{font-family:sans-serif;margin:2em;max-width:70em}table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:4px 8px;text-align:left}.violation{color:#c00}nav a{margin-right:1em}";
+
+/// 静的なHTMLレポート一式を`out_dir`に書き出す
+pub fn write_site(result: &AnalysisResult, out_dir: &Path) -> Result<()> {
+    for dir in [out_dir.join("modules"), out_dir.join("graphs")] {
+        fs::create_dir_all(&dir).map_err(|err| AnalyzerError::io(&dir, err))?;
+    }
+
+    let history = update_history(out_dir, result)?;
+    let graph = result.graph();
+
+    write_page(&out_dir.join("index.html"), "Angular Module Architecture", "", &render_index(result, &history))?;
+
+    for module in &result.modules {
+        write_page(
+            &out_dir.join("modules").join(module_file_name(module)),
+            &module.name,
+            "../",
+            &render_module(result, &graph, module)
+        )?;
+    }
+
+    for (layer, slug) in LAYERS {
+        write_page(
+            &out_dir.join("graphs").join(format!("{}.html", slug)),
+            &format!("{} layer graph", layer),
+            "../",
+            &render_layer_graph(result, &graph, &layer)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// ユーザーが用意したTeraテンプレートで解析結果を描画する
+///
+/// テンプレートには`analyze -f json`と同じ構造がトップレベルの変数（`modules`・`metrics`など）として渡る。
+/// ファイル名が`.html`・`.htm`・`.xml`（`.tera`を付けたものも含む）で終わる場合は値をエスケープする。
+pub fn render_template(result: &AnalysisResult, template: &Path) -> Result<String> {
+    let content = fs::read_to_string(template).map_err(|err| AnalyzerError::io(template, err))?;
+    let name = template.file_name().map_or_else(|| "template".to_string(), |n| n.to_string_lossy().into_owned());
+    let mut tera = Tera::default();
+    tera.autoescape_on(vec![".html", ".htm", ".xml", ".html.tera", ".htm.tera", ".xml.tera"]);
+    tera.add_raw_template(&name, &content).map_err(|err| AnalyzerError::parse(template, tera_error(&err)))?;
+    let context = Context::from_serialize(result).map_err(|err| AnalyzerError::parse(template, tera_error(&err)))?;
+    tera.render(&name, &context).map_err(|err| AnalyzerError::parse(template, tera_error(&err)))
+}
+
+/// Teraのエラーは原因（構文エラーの位置や未定義の変数など）を`source`に持つため、つなげて1つの文にする
+fn tera_error(err: &tera::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = std::error::Error::source(err);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+fn module_file_name(module: &ModuleInfo) -> String {
+    format!("{}.html", module_node_id(&module.name))
+}
+
+fn write_page(path: &Path, title: &str, root: &str, body: &str) -> Result<()> {
+    let html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<nav><a href=\"{root}index.html\">Overview</a>{layers}</nav>\n<h1>{title}</h1>\n{body}\n<script type=\"module\">import mermaid from 'https://cdn.jsdelivr.net/npm/mermaid@10/dist/mermaid.esm.min.mjs';mermaid.initialize({{startOnLoad:true}});</script>\n</body>\n</html>\n",
+        title = escape_html(title),
+        layers = LAYERS.iter()
+            .map(|(layer, slug)| format!("<a href=\"{}graphs/{}.html\">{} graph</a>", root, slug, layer))
+            .collect::<String>()
+    );
+    fs::write(path, html).map_err(|err| AnalyzerError::io(path, err))
+}
+
+fn read_history(path: &Path) -> Result<Vec<TrendPoint>> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|err| AnalyzerError::parse(path, err)),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+fn write_history(path: &Path, history: &[TrendPoint]) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(history)?).map_err(|err| AnalyzerError::io(path, err))
+}
+
+fn update_history(out_dir: &Path, result: &AnalysisResult) -> Result<Vec<TrendPoint>> {
+    let path = out_dir.join(HISTORY_FILE);
+    let mut history = read_history(&path)?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    history.push(TrendPoint::new(result, None, timestamp));
+    write_history(&path, &history)?;
+    Ok(history)
+}
+
+/// `trend`で解析した過去のリビジョンをレポートの履歴に加える（同じリビジョンは置き換え、日時順に並べる）
+pub fn record_history(out_dir: &Path, points: &[TrendPoint]) -> Result<()> {
+    fs::create_dir_all(out_dir).map_err(|err| AnalyzerError::io(out_dir, err))?;
+    let path = out_dir.join(HISTORY_FILE);
+    let mut history = read_history(&path)?;
+    history.retain(|entry| entry.revision.is_none() || !points.iter().any(|p| p.revision == entry.revision));
+    history.extend(points.iter().cloned());
+    history.sort_by_key(|entry| entry.timestamp);
+    write_history(&path, &history)
+}
+
+/// UNIXタイムスタンプを`YYYY-MM-DD HH:MM UTC`形式にする
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // Howard Hinnant's civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, seconds / 3_600, (seconds % 3_600) / 60)
+}
+
+/// ヘルススコア（0〜100）と違反数（最大値で正規化）の折れ線グラフ
+fn render_trend_chart(history: &[TrendPoint]) -> String {
+    const WIDTH: f32 = 600.0;
+    const HEIGHT: f32 = 160.0;
+    if history.len() < 2 {
+        return String::new();
+    }
+    let max_violations = history
+        .iter()
+        .map(|entry| entry.violations)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let step = WIDTH / ((history.len() - 1) as f32);
+    let line = |value: &dyn Fn(&TrendPoint) -> f32| -> String {
+        history
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| format!("{:.1},{:.1}", (i as f32) * step, HEIGHT - value(entry) * HEIGHT))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    format!(
+        "<svg width=\"{w}\" height=\"{h}\" viewBox=\"-5 -5 {vw} {vh}\" role=\"img\">\n<rect x=\"0\" y=\"0\" width=\"{WIDTH}\" height=\"{HEIGHT}\" fill=\"none\" stroke=\"#ccc\"/>\n<polyline fill=\"none\" stroke=\"#2a7\" stroke-width=\"2\" points=\"{health}\"><title>Health score</title></polyline>\n<polyline fill=\"none\" stroke=\"#c00\" stroke-width=\"2\" points=\"{violations}\"><title>Violations (max {max_violations})</title></polyline>\n</svg>\n<p><span style=\"color:#2a7\">■</span> Health score (0–100) <span style=\"color:#c00\">■</span> Violations (0–{max_violations})</p>\n",
+        w = WIDTH + 10.0,
+        h = HEIGHT + 10.0,
+        vw = WIDTH + 10.0,
+        vh = HEIGHT + 10.0,
+        health = line(&|entry| entry.health_score / 100.0),
+        violations = line(&|entry| (entry.violations as f32) / (max_violations as f32))
+    )
+}
+
+fn render_index(result: &AnalysisResult, history: &[TrendPoint]) -> String {
+    let metrics = &result.metrics;
+    let mut html = String::from("<h2>Metrics</h2>\n<table>\n");
+    for (label, value) in [
+        ("Total Modules", metrics.total_modules.to_string()),
+        ("Core Modules", metrics.core_modules.to_string()),
+        ("Shared Modules", metrics.shared_modules.to_string()),
+        ("Feature Modules", metrics.feature_modules.to_string()),
+        ("Average Dependencies per Module", format!("{:.2}", metrics.average_dependencies_per_module)),
+        ("Coupling Factor", format!("{:.2}", metrics.coupling_factor)),
+        ("Health Score", format!("{:.1}", metrics.health_score)),
+        ("Violations", result.dependency_violations.len().to_string()),
+        ("Circular Dependencies", result.circular_dependencies.len().to_string()),
+    ] {
+        html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", label, value));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Trend</h2>\n");
+    html.push_str(&render_trend_chart(history));
+    html.push_str("<table>\n<tr><th>Run</th><th>Modules</th><th>Violations</th><th>Cycles</th><th>Coupling</th><th>Health</th></tr>\n");
+    for entry in history.iter().rev() {
+        html.push_str(
+            &format!(
+                "<tr><td>{}{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.1}</td></tr>\n",
+                format_timestamp(entry.timestamp),
+                entry.revision
+                    .as_deref()
+                    .map(|revision| format!(" <code>{}</code>", escape_html(&revision[..revision.len().min(8)])))
+                    .unwrap_or_default(),
+                entry.total_modules,
+                entry.violations,
+                entry.cycles,
+                entry.coupling_factor,
+                entry.health_score
+            )
+        );
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Violations</h2>\n");
+    html.push_str(&render_violations(result, |_| true, ""));
+
+    html.push_str("<h2>Modules</h2>\n<table>\n<tr><th>Module</th><th>Type</th><th>Dependencies</th><th>Path</th></tr>\n");
+    for module in &result.modules {
+        html.push_str(
+            &format!(
+                "<tr><td><a href=\"modules/{}\">{}</a></td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>\n",
+                module_file_name(module),
+                escape_html(&module.name),
+                module.module_type,
+                module.internal_dependencies().count(),
+                escape_html(&module.path.display().to_string())
+            )
+        );
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn render_violations(result: &AnalysisResult, include: impl Fn(&str) -> bool, root: &str) -> String {
+    let items: Vec<String> = result.dependency_violations
+        .iter()
+        .filter(|v| include(&v.from_module) || include(&v.to_module))
+        .map(|v| {
+            format!(
+                "<li class=\"violation\">[{}] {} &rarr; {}: {}</li>",
+                v.violation_type.code(),
+                module_link(&v.from_module, root),
+                module_link(&v.to_module, root),
+                escape_html(&v.description)
+            )
+        })
+        .collect();
+    if items.is_empty() {
+        "<p>No violations.</p>\n".to_string()
+    } else {
+        format!("<ul>\n{}\n</ul>\n", items.join("\n"))
+    }
+}
+
+fn module_link(name: &str, root: &str) -> String {
+    format!("<a href=\"{}modules/{}.html\">{}</a>", root, module_node_id(name), escape_html(name))
+}
+
+fn render_module(result: &AnalysisResult, graph: &ModuleGraph, module: &ModuleInfo) -> String {
+    let link_list = |names: Vec<&str>| {
+        if names.is_empty() {
+            return "<p>None.</p>\n".to_string();
+        }
+        let items: Vec<String> = names
+            .iter()
+            .map(|name| {
+                let lazy = if graph.edge_kind(&module.name, name) == Some(EdgeKind::Lazy) { " (lazy)" } else { "" };
+                format!("<li>{}{}</li>", module_link(name, "../"), lazy)
+            })
+            .collect();
+        format!("<ul>\n{}\n</ul>\n", items.join("\n"))
+    };
+
+    let mut html = format!(
+        "<p>Type: <strong>{}</strong><br>Path: <code>{}</code></p>\n",
+        module.module_type,
+        escape_html(&module.path.display().to_string())
+    );
+    for (label, entries) in [
+        ("Imports", &module.imports),
+        ("Exports", &module.exports),
+        ("Declarations", &module.declarations),
+        ("Providers", &module.providers),
+    ] {
+        html.push_str(&format!("<h2>{}</h2>\n", label));
+        if entries.is_empty() {
+            html.push_str("<p>None.</p>\n");
+        } else {
+            html.push_str(&format!("<p><code>{}</code></p>\n", escape_html(&entries.join(", "))));
+        }
+    }
+    html.push_str("<h2>Dependencies</h2>\n");
+    html.push_str(&link_list(graph.dependencies_of(&module.name)));
+    html.push_str("<h2>Dependents</h2>\n");
+    html.push_str(&link_list(graph.dependents_of(&module.name)));
+    html.push_str("<h2>Violations</h2>\n");
+    html.push_str(&render_violations(result, |name| name == &*module.name, "../"));
+    html
+}
+
+fn render_layer_graph(result: &AnalysisResult, graph: &ModuleGraph, layer: &ModuleType) -> String {
+    let members: Vec<&ModuleInfo> = result.modules
+        .iter()
+        .filter(|m| &m.module_type == layer)
+        .collect();
+    if members.is_empty() {
+        return "<p>No modules in this layer.</p>".to_string();
+    }
+
+    // レイヤー内のモジュールと、その直接の依存先を描画する
+    let mut mermaid = String::from("graph TD\n");
+    for (from, to, kind) in graph.edges() {
+        if members.iter().any(|m| *m.name == *from) {
+            let arrow = if kind == EdgeKind::Lazy { "-.->" } else { "-->" };
+            mermaid.push_str(&format!("  {} {} {}\n", module_node_id(from), arrow, module_node_id(to)));
+        }
+    }
+    for module in &members {
+        let id = module_node_id(&module.name);
+        mermaid.push_str(&format!("  {}[\"{}\"]\n", id, module.name));
+        mermaid.push_str(&format!("  click {} \"../modules/{}\"\n", id, module_file_name(module)));
+    }
+
+    format!("<pre class=\"mermaid\">\n{}</pre>\n", escape_html(&mermaid))
+}
@@ -0,0 +1,269 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{ BTreeSet, HashMap };
+
+use crate::graph::{ EdgeKind, ModuleGraph };
+use crate::{ AnalysisResult, ModuleInfo, source };
+
+static ROUTER_CALL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bfor(?:Root|Child)\s*\(\s*").unwrap());
+// loadChildren: () => import('./x.module').then(m => m.XModule)、または旧形式の'./x.module#XModule'
+static LAZY_TARGET_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\.then\s*\(\s*\(?\s*\w+\s*\)?\s*=>\s*\w+\.(\w+)|#(\w+)["']"#).unwrap()
+});
+
+/// ルート定義の1件
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Route {
+    /// 親からの相対パス（`''`は空のパス）
+    pub path: String,
+    pub component: Option<String>,
+    pub redirect_to: Option<String>,
+    /// `loadComponent`で遅延ロードするスタンドアロンコンポーネント
+    pub load_component: Option<String>,
+    /// `loadChildren`による遅延ロードの境界
+    pub lazy: Option<Box<LazyBoundary>>,
+    pub children: Vec<Route>,
+}
+
+/// `loadChildren`で遅延ロードされる範囲
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LazyBoundary {
+    /// `loadChildren`の読み込み先（モジュール名、またはルート配列の名前）
+    pub target: String,
+    /// この境界で初めて読み込まれるモジュール（起動時や外側の境界で読み込み済みのものは含まない）
+    pub modules: Vec<String>,
+    /// 読み込み先のモジュールが定義しているルート
+    pub routes: Vec<Route>,
+}
+
+/// アプリケーションのルート階層
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RouteTree {
+    pub root: String,
+    /// 起動時に読み込まれるモジュール
+    pub eager_modules: Vec<String>,
+    pub routes: Vec<Route>,
+}
+
+impl RouteTree {
+    /// `root`のモジュール（と、そこから直接importしているルーティングモジュール）の`forRoot`/`forChild`からルートをたどる
+    ///
+    /// 遅延ロード先のモジュールのルートは、そのルートの`lazy`の下に入れ子にする。
+    pub fn build(result: &AnalysisResult, root: &str) -> Self {
+        let graph = result.graph();
+        let modules: HashMap<&str, &ModuleInfo> = result.modules
+            .iter()
+            .map(|m| (m.name.as_ref(), m))
+            .collect();
+        let eager_modules = graph.eager_closure(root);
+        let mut builder = TreeBuilder { graph: &graph, modules: &modules, expanding: Vec::new() };
+        let loaded: BTreeSet<&str> = eager_modules.iter().copied().collect();
+        let routes = builder.module_routes(root, &loaded);
+
+        RouteTree {
+            root: root.to_string(),
+            eager_modules: eager_modules.into_iter().map(str::to_string).collect(),
+            routes,
+        }
+    }
+}
+
+struct TreeBuilder<'g, 'a> {
+    graph: &'g ModuleGraph<'a>,
+    modules: &'g HashMap<&'a str, &'a ModuleInfo>,
+    /// 展開中の遅延ロード先（循環した遅延ロードで無限に展開しないため）
+    expanding: Vec<String>,
+}
+
+impl TreeBuilder<'_, '_> {
+    /// モジュール自身と、直接importしているモジュールのファイルで定義されたルート
+    fn module_routes(&mut self, module: &str, loaded: &BTreeSet<&str>) -> Vec<Route> {
+        let mut sources = vec![module];
+        sources.extend(
+            self.graph
+                .dependencies_of(module)
+                .into_iter()
+                .filter(|dep| self.graph.edge_kind(module, dep) == Some(EdgeKind::Eager))
+        );
+
+        let mut routes = Vec::new();
+        for source in sources {
+            let Some(info) = self.modules.get(source) else {
+                continue;
+            };
+            let Ok(content) = source::read(&info.path, Some(source::DEFAULT_MAX_FILE_SIZE)) else {
+                continue;
+            };
+            for array in route_arrays(&content) {
+                routes.extend(self.parse_routes(array, loaded));
+            }
+        }
+        routes
+    }
+
+    fn parse_routes(&mut self, array: &str, loaded: &BTreeSet<&str>) -> Vec<Route> {
+        top_level_items(array)
+            .into_iter()
+            .filter(|item| item.starts_with('{'))
+            .map(|object| {
+                let mut route = Route::default();
+                for (key, value) in fields(object) {
+                    match key {
+                        "path" => {
+                            route.path = unquote(value);
+                        }
+                        "component" => {
+                            route.component = Some(value.to_string());
+                        }
+                        "redirectTo" => {
+                            route.redirect_to = Some(unquote(value));
+                        }
+                        "loadComponent" => {
+                            route.load_component = LAZY_TARGET_REGEX.captures(value)
+                                .and_then(|cap| cap.get(1))
+                                .map(|m| m.as_str().to_string());
+                        }
+                        "loadChildren" => {
+                            if let Some(target) = LAZY_TARGET_REGEX.captures(value).and_then(|cap| cap.get(1).or(cap.get(2))) {
+                                route.lazy = Some(Box::new(self.boundary(target.as_str(), loaded)));
+                            }
+                        }
+                        "children" => {
+                            if let Some(children) = value.strip_prefix('[') {
+                                route.children = self.parse_routes(children.strip_suffix(']').unwrap_or(children), loaded);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                route
+            })
+            .collect()
+    }
+
+    fn boundary(&mut self, target: &str, loaded: &BTreeSet<&str>) -> LazyBoundary {
+        let closure = self.graph.eager_closure(target);
+        let modules: Vec<String> = closure
+            .iter()
+            .filter(|name| !loaded.contains(*name))
+            .map(|name| name.to_string())
+            .collect();
+        let routes = if self.graph.contains(target) && !self.expanding.iter().any(|t| t == target) {
+            self.expanding.push(target.to_string());
+            let mut inner = loaded.clone();
+            inner.extend(closure.iter().copied());
+            let routes = self.module_routes(target, &inner);
+            self.expanding.pop();
+            routes
+        } else {
+            Vec::new()
+        };
+        LazyBoundary { target: target.to_string(), modules, routes }
+    }
+}
+
+/// `forRoot([...])`・`forChild([...])`の配列（引数が変数なら同じファイル内のその変数の配列）の中身
+fn route_arrays(content: &str) -> Vec<&str> {
+    let mut arrays = Vec::new();
+    for call in ROUTER_CALL_REGEX.find_iter(content) {
+        let argument = &content[call.end()..];
+        if argument.starts_with('[') {
+            arrays.extend(bracketed(content, call.end()));
+            continue;
+        }
+        let name: String = argument
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+            .collect();
+        if name.is_empty() {
+            continue;
+        }
+        let declaration = Regex::new(&format!(r"\b{}\s*(?::\s*\w+(?:\[\])?\s*)?=\s*\[", regex::escape(&name))).unwrap();
+        if let Some(found) = declaration.find(content) {
+            arrays.extend(bracketed(content, found.end() - 1));
+        }
+    }
+    arrays
+}
+
+/// `open`の位置の括弧から対応する閉じ括弧までの中身（文字列リテラル内の括弧は数えない）
+fn bracketed(content: &str, open: usize) -> Option<&str> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (i, c) in content[open..].char_indices() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => {
+                quote = Some(c);
+            }
+            '[' | '{' | '(' => {
+                depth += 1;
+            }
+            ']' | '}' | ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&content[open + 1..open + i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// カンマで区切られた最上位の要素（入れ子の括弧や文字列の中のカンマでは区切らない）
+fn top_level_items(text: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => {
+                quote = Some(c);
+            }
+            '[' | '{' | '(' => {
+                depth += 1;
+            }
+            ']' | '}' | ')' => {
+                depth -= 1;
+            }
+            ',' if depth == 0 => {
+                items.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(text[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// オブジェクトリテラルの最上位のプロパティを（キー, 値）で返す
+fn fields(object: &str) -> Vec<(&str, &str)> {
+    let body = object.trim().trim_start_matches('{').trim_end_matches('}');
+    top_level_items(body)
+        .into_iter()
+        .filter_map(|item| {
+            let (key, value) = item.split_once(':')?;
+            Some((key.trim().trim_matches(|c| c == '"' || c == '\''), value.trim()))
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string()
+}
@@ -0,0 +1,154 @@
+use rhai::serde::{ from_dynamic, to_dynamic };
+use rhai::{ Array, CallFnOptions, Dynamic, Engine, Scope };
+use std::collections::{ HashMap, HashSet };
+use std::rc::Rc;
+
+use crate::plugins::{ EdgeFacts, ModuleFacts, RawFinding };
+
+/// 1回の`check`の呼び出しで実行できる操作の数（無限ループで解析が止まらないようにする）
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+/// スクリプトから使うモジュールの依存グラフ（`graph`引数）
+#[derive(Clone)]
+struct ScriptGraph(Rc<GraphData>);
+
+struct GraphData {
+    modules: Vec<Dynamic>,
+    index: HashMap<String, usize>,
+    /// 依存元 -> (依存先, 遅延ロードか)
+    dependencies: HashMap<String, Vec<(String, bool)>>,
+    /// 依存先 -> 依存元
+    dependents: HashMap<String, Vec<String>>,
+}
+
+impl ScriptGraph {
+    fn modules(&mut self) -> Array {
+        self.0.modules.clone()
+    }
+
+    fn get(&mut self, name: &str) -> Dynamic {
+        self.0.index.get(name).map_or(Dynamic::UNIT, |&i| self.0.modules[i].clone())
+    }
+
+    fn dependencies(&mut self, name: &str) -> Array {
+        self.0.dependencies
+            .get(name)
+            .map(|deps| deps.iter().map(|(to, _)| Dynamic::from(to.clone())).collect())
+            .unwrap_or_default()
+    }
+
+    fn dependents(&mut self, name: &str) -> Array {
+        self.0.dependents
+            .get(name)
+            .map(|deps| deps.iter().cloned().map(Dynamic::from).collect())
+            .unwrap_or_default()
+    }
+
+    fn is_lazy(&mut self, from: &str, to: &str) -> bool {
+        self.0.dependencies.get(from).is_some_and(|deps| deps.iter().any(|(dep, lazy)| dep == to && *lazy))
+    }
+
+    /// `from`から`to`へ（遅延ロードも含めて）依存をたどれるか
+    fn reaches(&mut self, from: &str, to: &str) -> bool {
+        let mut visited = HashSet::from([from]);
+        let mut stack = vec![from];
+        while let Some(name) = stack.pop() {
+            for (dep, _) in self.0.dependencies.get(name).into_iter().flatten() {
+                if dep == to {
+                    return true;
+                }
+                if visited.insert(dep) {
+                    stack.push(dep);
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Rhaiのスクリプトを各モジュールについて実行し、返された問題を集める
+///
+/// スクリプトは`fn check(ngmodule, graph, options)`を定義し（`module`はRhaiの予約語）、問題のマップ
+/// （`#{ rule, message, target?, severity?, module? }`）か、その配列を返す。何もなければ`()`か`[]`を返す。
+/// `print`・`debug`の出力は標準エラー出力に書く（JSONなどの出力を壊さないため）。
+pub(crate) fn run(
+    name: &str,
+    script: &str,
+    modules: &[ModuleFacts],
+    edges: &[EdgeFacts],
+    options: &toml::Table
+) -> Result<Vec<RawFinding>, String> {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(64);
+    engine.set_max_expr_depths(64, 64);
+    let prefix = name.to_string();
+    engine.on_print(move |text| eprintln!("[{}] {}", prefix, text));
+    let prefix = name.to_string();
+    engine.on_debug(move |text, _, position| eprintln!("[{}] {} {}", prefix, position, text));
+    engine
+        .register_type_with_name::<ScriptGraph>("Graph")
+        .register_fn("modules", ScriptGraph::modules)
+        .register_fn("get", ScriptGraph::get)
+        .register_fn("dependencies", ScriptGraph::dependencies)
+        .register_fn("dependents", ScriptGraph::dependents)
+        .register_fn("is_lazy", ScriptGraph::is_lazy)
+        .register_fn("reaches", ScriptGraph::reaches);
+
+    let ast = engine.compile(script).map_err(|err| err.to_string())?;
+    if !ast.iter_functions().any(|f| f.name == "check" && f.params.len() == 3) {
+        return Err("the script does not define `fn check(ngmodule, graph, options)`".to_string());
+    }
+
+    let fail = |err: Box<rhai::EvalAltResult>| err.to_string();
+    let values = modules
+        .iter()
+        .map(|m| to_dynamic(m).map_err(fail))
+        .collect::<Result<Vec<Dynamic>, String>>()?;
+    let mut dependencies: HashMap<String, Vec<(String, bool)>> = HashMap::new();
+    let mut dependents: HashMap<String, Vec<String>> = HashMap::new();
+    for edge in edges {
+        dependencies.entry(edge.from.to_string()).or_default().push((edge.to.to_string(), edge.lazy));
+        dependents.entry(edge.to.to_string()).or_default().push(edge.from.to_string());
+    }
+    let graph = ScriptGraph(
+        Rc::new(GraphData {
+            index: modules
+                .iter()
+                .enumerate()
+                .map(|(i, m)| (m.name.to_string(), i))
+                .collect(),
+            modules: values.clone(),
+            dependencies,
+            dependents,
+        })
+    );
+    let options = to_dynamic(options).map_err(fail)?;
+
+    let mut findings = Vec::new();
+    let mut scope = Scope::new();
+    for (module, value) in modules.iter().zip(values) {
+        let result: Dynamic = engine
+            .call_fn_with_options(CallFnOptions::new().eval_ast(false), &mut scope, &ast, "check", (
+                value,
+                graph.clone(),
+                options.clone(),
+            ))
+            .map_err(|err| format!("{} (while checking {})", err, module.name))?;
+        let invalid = |err: Box<rhai::EvalAltResult>| format!("invalid findings for {}: {}", module.name, err);
+        let raw: Vec<RawFinding> = if result.is_unit() {
+            Vec::new()
+        } else if result.is_array() {
+            from_dynamic(&result).map_err(invalid)?
+        } else {
+            vec![from_dynamic(&result).map_err(invalid)?]
+        };
+        findings.extend(
+            raw.into_iter().map(|mut finding| {
+                finding.module.get_or_insert_with(|| module.name.to_string());
+                finding
+            })
+        );
+    }
+    Ok(findings)
+}
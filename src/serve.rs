@@ -0,0 +1,120 @@
+use anyhow::{ Context, Result };
+use axum::extract::{ Path, State };
+use axum::http::{ StatusCode, header };
+use axum::response::{ IntoResponse, Json, Response };
+use axum::routing::get;
+use axum::Router;
+use colored::*;
+use std::net::SocketAddr;
+use std::sync::{ Arc, RwLock };
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+use angular_module_analyzer::output::{ self, Externals };
+use angular_module_analyzer::{ AnalysisResult, AngularAnalyzer, metrics };
+
+/// サーバーが保持する最新の解析結果
+#[derive(Clone)]
+struct AppState {
+    result: Arc<RwLock<Arc<AnalysisResult>>>,
+}
+
+impl AppState {
+    fn current(&self) -> Arc<AnalysisResult> {
+        self.result.read().unwrap().clone()
+    }
+}
+
+/// 解析結果をHTTPで提供する
+///
+/// 起動時に一度解析し、その後は`refresh`ごとにバックグラウンドで再解析して結果を差し替える。
+/// キャッシュを使うので、再解析では変更されたファイルだけを解析し直す。
+pub async fn run(analyzer: AngularAnalyzer, addr: SocketAddr, refresh: Duration) -> Result<()> {
+    let analyzer = Arc::new(analyzer);
+    let initial = tokio::task::block_in_place(|| analyzer.analyze())?;
+    let state = AppState { result: Arc::new(RwLock::new(Arc::new(initial))) };
+
+    let refreshed = state.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(refresh).await;
+            let analyzer = analyzer.clone();
+            match tokio::task::spawn_blocking(move || analyzer.analyze()).await {
+                Ok(Ok(result)) => {
+                    *refreshed.result.write().unwrap() = Arc::new(result);
+                }
+                // 失敗した場合は前回の結果を返し続ける
+                Ok(Err(err)) => eprintln!("{} {}", "warning:".yellow().bold(), err),
+                Err(err) => eprintln!("{} {}", "warning:".yellow().bold(), err),
+            }
+        }
+    });
+
+    let app = Router::new()
+        .route("/analysis", get(analysis))
+        .route("/modules/{name}", get(module))
+        .route("/graph.json", get(graph_json))
+        .route("/graph.svg", get(graph_svg))
+        .route("/metrics", get(prometheus))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener
+        ::bind(addr).await
+        .with_context(|| format!("Failed to listen on {}", addr))?;
+    eprintln!("Serving analysis on http://{}", listener.local_addr()?);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn analysis(State(state): State<AppState>) -> Json<Arc<AnalysisResult>> {
+    Json(state.current())
+}
+
+async fn module(State(state): State<AppState>, Path(name): Path<String>) -> Response {
+    let result = state.current();
+    let Some(module) = result.modules.iter().find(|m| *m.name == *name) else {
+        return (StatusCode::NOT_FOUND, format!("Module not found: {}", name)).into_response();
+    };
+    let graph = result.graph();
+    let violations: Vec<_> = result.dependency_violations
+        .iter()
+        .filter(|v| v.from_module == module.name || v.to_module == module.name)
+        .collect();
+    Json(
+        serde_json::json!({
+        "module": module,
+        "dependencies": graph.dependencies_of(&name),
+        "dependents": graph.dependents_of(&name),
+        "violations": violations,
+    })
+    ).into_response()
+}
+
+async fn graph_json(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(output::render_graph_json(&state.current()))
+}
+
+/// Graphvizの`dot`でDOTをSVGに変換する（`dot`がなければ503）
+async fn graph_svg(State(state): State<AppState>) -> Response {
+    let dot = output::render_dot(&state.current().modules, Externals::Hide);
+    let child = tokio::process::Command
+        ::new("dot")
+        .arg("-Tsvg")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn();
+    let Ok(mut child) = child else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "Graphviz `dot` is not installed").into_response();
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(dot.as_bytes()).await;
+    }
+    match child.wait_with_output().await {
+        Ok(svg) if svg.status.success() => ([(header::CONTENT_TYPE, "image/svg+xml")], svg.stdout).into_response(),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "Graphviz failed to render the graph").into_response(),
+    }
+}
+
+async fn prometheus(State(state): State<AppState>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], metrics::render_prometheus(&state.current()))
+}
@@ -0,0 +1,691 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{ BTreeMap, BTreeSet, HashMap, HashSet };
+use std::fmt;
+use std::path::{ Path, PathBuf };
+
+use crate::graph::reference_name;
+use crate::i18n::tr;
+use crate::ownership::FileOwnership;
+use crate::source::{ blank_comments, closing, ngmodule_decorator, object_arrays, top_level_items };
+use crate::{ AnalysisResult, ModuleInfo, ModuleType, Name, Position, SourceLocation, project_exclusions, source, source_files };
+
+static INJECTABLE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"@Injectable\s*\(\s*(\{)?").unwrap());
+static CLASS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bclass\s+(\w+)").unwrap());
+static PROVIDED_IN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"providedIn\s*:\s*(?:["'](\w+)["']|(\w+))"#).unwrap());
+static CONSTRUCTOR_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bconstructor\s*\(").unwrap());
+static PARAMETER_TYPE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r":\s*([A-Z]\w*)").unwrap());
+static INJECT_CALL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\binject\s*(?:<[^>]*>)?\(\s*([A-Z]\w*)").unwrap());
+static USE_CLASS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"use(?:Class|Existing)\s*:\s*(\w+)").unwrap());
+static COMPONENT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"@(?:Component|Directive)\s*\(\s*\{").unwrap());
+static PROVIDE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\{\s*provide\s*:\s*([\w.]+)").unwrap());
+static MULTI_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bmulti\s*:\s*true\b").unwrap());
+static USE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\buse(?:Class|Existing|Factory|Value)\s*:\s*([\w.]+)").unwrap());
+
+/// マルチプロバイダーを登録する関数と、登録先のトークン
+const MULTI_PROVIDER_FUNCTIONS: [(&str, &str); 3] = [
+    ("provideAppInitializer", "APP_INITIALIZER"),
+    ("provideEnvironmentInitializer", "ENVIRONMENT_INITIALIZER"),
+    ("providePlatformInitializer", "PLATFORM_INITIALIZER"),
+];
+
+/// アプリケーションの起動時に一度だけ使われるマルチプロバイダーのトークン
+const BOOTSTRAP_TOKENS: [&str; 3] = ["APP_INITIALIZER", "APP_BOOTSTRAP_LISTENER", "PLATFORM_INITIALIZER"];
+
+/// サービスを提供しているインジェクター
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Provider {
+    /// `providedIn: 'root'`
+    Root,
+    /// `providedIn: 'platform'`
+    Platform,
+    /// `providedIn: 'any'`（遅延ロードされたモジュールごとに別のインスタンス）
+    Any,
+    /// NgModuleの`providers`か`providedIn: SomeModule`
+    Module(Name),
+    /// どこにも提供されていない（コンポーネントの`providers`で提供しているか、注入すると実行時エラーになる）
+    Unprovided,
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Provider::Root => write!(f, "root"),
+            Provider::Platform => write!(f, "platform"),
+            Provider::Any => write!(f, "any"),
+            Provider::Module(module) => write!(f, "{}", module),
+            Provider::Unprovided => write!(f, "(not provided by a module)"),
+        }
+    }
+}
+
+/// サービスを注入しているクラス
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Injection {
+    pub class: String,
+    /// 注入しているクラスのファイルを含むモジュール
+    pub module: Option<Name>,
+    pub location: SourceLocation,
+    /// サービスが属するFeatureモジュールの外から注入している
+    pub crosses_feature: bool,
+}
+
+/// `@Injectable`を付けたクラス
+#[derive(Debug, Clone, Serialize)]
+pub struct Service {
+    pub name: String,
+    pub file: PathBuf,
+    pub provider: Provider,
+    /// サービスが属するモジュール（モジュールで提供していればそのモジュール、そうでなければファイルを含むモジュール）
+    pub module: Option<Name>,
+    pub injected_by: Vec<Injection>,
+}
+
+/// DIコンテナの構成（どのサービスがどこで提供され、どこに注入されているか）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ServiceTree {
+    /// 名前順
+    pub services: Vec<Service>,
+}
+
+impl ServiceTree {
+    /// プロジェクトのTypeScriptファイルから`@Injectable`のクラスと、コンストラクター引数・`inject()`による注入を集める
+    pub fn collect(project_path: &Path, result: &AnalysisResult) -> Self {
+        let ownership = FileOwnership::new(project_path, &result.modules);
+        let module_types: HashMap<&str, &ModuleType> = result.modules
+            .iter()
+            .map(|m| (m.name.as_ref(), &m.module_type))
+            .collect();
+        let names: HashMap<&str, &Name> = result.modules
+            .iter()
+            .map(|m| (m.name.as_ref(), &m.name))
+            .collect();
+        let owner = |file: &Path| -> Option<Name> {
+            let file = file.strip_prefix(project_path).unwrap_or(file);
+            ownership.owner(&file.to_string_lossy()).map(|name| names[name].clone())
+        };
+
+        // NgModuleの`providers`（`{ provide: X, useClass: Y }`は`Y`も）
+        let mut module_providers: HashMap<String, &Name> = HashMap::new();
+        for module in &result.modules {
+            for entry in &module.providers {
+                module_providers.insert(reference_name(entry).to_string(), &module.name);
+                for cap in USE_CLASS_REGEX.captures_iter(entry) {
+                    module_providers.insert(cap[1].to_string(), &module.name);
+                }
+            }
+        }
+
+        let files: Vec<(PathBuf, String)> = source_files(project_path, false, true, false, project_exclusions(project_path))
+            .filter_map(|path| {
+                let content = source::read(&path, Some(source::DEFAULT_MAX_FILE_SIZE)).ok()?;
+                Some((path, content.to_string()))
+            })
+            .collect();
+
+        let mut services: BTreeMap<String, Service> = BTreeMap::new();
+        for (path, content) in &files {
+            for cap in INJECTABLE_REGEX.captures_iter(content) {
+                let whole = cap.get(0).unwrap();
+                let Some(class) = CLASS_REGEX.captures(&content[whole.end()..]) else {
+                    continue;
+                };
+                let name = class[1].to_string();
+                let options = &content[whole.end()..whole.end() + class.get(0).unwrap().start()];
+                let provided_in = cap
+                    .get(1)
+                    .and(PROVIDED_IN_REGEX.captures(options))
+                    .and_then(|c| c.get(1).or(c.get(2)).map(|m| m.as_str().to_string()));
+                let provider = match provided_in.as_deref() {
+                    Some("root") => Provider::Root,
+                    Some("platform") => Provider::Platform,
+                    Some("any") => Provider::Any,
+                    Some(module) if names.contains_key(module) => Provider::Module(names[module].clone()),
+                    _ =>
+                        match module_providers.get(&name) {
+                            Some(module) => Provider::Module((*module).clone()),
+                            None => Provider::Unprovided,
+                        }
+                };
+                let module = match &provider {
+                    Provider::Module(module) => Some(module.clone()),
+                    _ => owner(path),
+                };
+                services.insert(name.clone(), Service {
+                    name,
+                    file: path.clone(),
+                    provider,
+                    module,
+                    injected_by: Vec::new(),
+                });
+            }
+        }
+
+        for (path, content) in &files {
+            let injector_module = owner(path);
+            for (class, start, end) in classes(content) {
+                let body = &content[start..end];
+                let mut injected: BTreeSet<(&str, usize)> = BTreeSet::new();
+                if
+                    let Some(constructor) = CONSTRUCTOR_REGEX.find(body) &&
+                    let Some(parameters) = parenthesized(body, constructor.end() - 1)
+                {
+                    for cap in PARAMETER_TYPE_REGEX.captures_iter(parameters) {
+                        let m = cap.get(1).unwrap();
+                        injected.insert((m.as_str(), start + constructor.end() + m.start()));
+                    }
+                }
+                for cap in INJECT_CALL_REGEX.captures_iter(body) {
+                    let m = cap.get(1).unwrap();
+                    injected.insert((m.as_str(), start + m.start()));
+                }
+
+                let mut seen = BTreeSet::new();
+                for (name, offset) in injected {
+                    if name == class || !seen.insert(name) {
+                        continue;
+                    }
+                    let Some(service) = services.get_mut(name) else {
+                        continue;
+                    };
+                    let service_is_feature = service.module
+                        .as_deref()
+                        .is_some_and(|m| module_types.get(m) == Some(&&ModuleType::Feature));
+                    let position = Position::at(content, offset);
+                    service.injected_by.push(Injection {
+                        class: class.to_string(),
+                        module: injector_module.clone(),
+                        location: SourceLocation { file: path.clone(), line: position.line, column: position.column },
+                        crosses_feature: service_is_feature && injector_module != service.module,
+                    });
+                }
+            }
+        }
+
+        let mut services: Vec<Service> = services.into_values().collect();
+        for service in &mut services {
+            service.injected_by.sort();
+        }
+        ServiceTree { services }
+    }
+
+    /// 提供元ごとのサービス（root・platform・any、モジュール名、未提供の順）
+    pub fn by_provider(&self) -> BTreeMap<&Provider, Vec<&Service>> {
+        let mut groups: BTreeMap<&Provider, Vec<&Service>> = BTreeMap::new();
+        for service in &self.services {
+            groups.entry(&service.provider).or_default().push(service);
+        }
+        groups
+    }
+
+    /// Featureの境界をまたいでいる注入の数
+    pub fn cross_feature_injections(&self) -> usize {
+        self.services
+            .iter()
+            .flat_map(|service| &service.injected_by)
+            .filter(|injection| injection.crosses_feature)
+            .count()
+    }
+}
+
+/// インジェクショントークンを登録しているインジェクター
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Scope {
+    /// `providedIn: 'platform'`
+    Platform,
+    /// `providedIn: 'root'`
+    Root,
+    /// `providedIn: 'any'`（遅延ロードされたモジュールごとに別のインスタンス）
+    Any,
+    /// 起動時に読み込まれるNgModuleの`providers`（ルートインジェクターに登録される）
+    EagerModule(Name),
+    /// 遅延ロードされるNgModuleの`providers`（そのモジュールの子インジェクターに登録される）
+    LazyModule(Name),
+    /// コンポーネント・ディレクティブの`providers`・`viewProviders`
+    Component(String),
+}
+
+impl Scope {
+    /// ルートインジェクター（とその親のプラットフォームインジェクター）に登録されるか
+    fn is_root_level(&self) -> bool {
+        matches!(self, Scope::Platform | Scope::Root | Scope::EagerModule(_))
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scope::Platform => write!(f, "platform"),
+            Scope::Root => write!(f, "root"),
+            Scope::Any => write!(f, "any"),
+            Scope::EagerModule(module) => write!(f, "{} (eager module)", module),
+            Scope::LazyModule(module) => write!(f, "{} (lazy module)", module),
+            Scope::Component(class) => write!(f, "{} (component)", class),
+        }
+    }
+}
+
+/// トークンを登録している場所1つ
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Registration {
+    pub scope: Scope,
+    pub location: SourceLocation,
+    /// `multi: true`で登録している（複数の登録が意図どおりに集まる）
+    pub multi: bool,
+    /// `useClass`・`useExisting`・`useFactory`・`useValue`に指定した識別子
+    pub using: Option<String>,
+}
+
+/// 同じトークンの登録が互いに影響し合っている問題
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScopeConflict {
+    /// ルートインジェクターに2回以上登録されている（後から登録したものが黙って前のものを置き換える）
+    Duplicate,
+    /// ルートにもあるのに遅延ロードのモジュールでも登録している（そのモジュールの中では別のインスタンスになる）
+    ShadowedByLazyModule,
+    /// モジュールやルートにもあるのにコンポーネントでも登録している（そのコンポーネントと子孫では別のインスタンスになる）
+    ShadowedByComponent,
+}
+
+impl ScopeConflict {
+    pub fn description(self) -> &'static str {
+        match self {
+            ScopeConflict::Duplicate =>
+                tr(
+                    "provided more than once in the root injector; the last registration silently wins",
+                    "ルートインジェクターに2回以上登録されている（後の登録が黙って前の登録を置き換える）"
+                ),
+            ScopeConflict::ShadowedByLazyModule =>
+                tr(
+                    "a lazy module provides its own instance, hiding the root one from everything it loads",
+                    "遅延ロードのモジュールが独自のインスタンスを提供し、そこから読み込むものからルートのインスタンスを隠している"
+                ),
+            ScopeConflict::ShadowedByComponent =>
+                tr(
+                    "a component provides its own instance, hiding the shared one from itself and its children",
+                    "コンポーネントが独自のインスタンスを提供し、自身と子孫から共有のインスタンスを隠している"
+                ),
+        }
+    }
+}
+
+/// 1つのトークンを登録しているすべての場所
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenScopes {
+    pub token: String,
+    pub registrations: Vec<Registration>,
+    pub conflicts: Vec<ScopeConflict>,
+}
+
+/// インジェクショントークンごとの登録場所（`providedIn`、NgModule・コンポーネントの`providers`）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProviderScopes {
+    /// トークン名順
+    pub tokens: Vec<TokenScopes>,
+}
+
+impl ProviderScopes {
+    /// プロジェクトのTypeScriptファイルから、トークンを登録している場所をすべて集める
+    ///
+    /// `provideHttpClient()`のような関数呼び出しやスプレッドで登録しているプロバイダーは、トークンが分からないため含めない。
+    /// NgModuleが起動時に読み込まれるか遅延ロードされるかは、どこからもimportされていないモジュールから
+    /// 遅延ロードを経由せずにたどれるかで決める。
+    pub fn collect(project_path: &Path, result: &AnalysisResult) -> Self {
+        let graph = result.graph();
+        let entry_points: Vec<&str> = result.modules
+            .iter()
+            .map(|m| m.name.as_ref())
+            .filter(|name| graph.dependents_of(name).is_empty())
+            .collect();
+        let eager: HashSet<&str> = entry_points
+            .iter()
+            .flat_map(|name| graph.eager_closure(name))
+            .collect();
+
+        let mut tokens: BTreeMap<String, Vec<Registration>> = BTreeMap::new();
+        for module in &result.modules {
+            let Ok(content) = source::read(&module.path, Some(source::DEFAULT_MAX_FILE_SIZE)) else {
+                continue;
+            };
+            let code = blank_comments(&content);
+            let Some(decorator) = ngmodule_decorator(&code) else {
+                continue;
+            };
+            let scope = if eager.contains(module.name.as_ref()) {
+                Scope::EagerModule(module.name.clone())
+            } else {
+                Scope::LazyModule(module.name.clone())
+            };
+            for (_, range) in object_arrays(&code, decorator.metadata, &["providers"]) {
+                register(&mut tokens, &module.path, &code, range.start, &code[range], &scope);
+            }
+        }
+
+        for path in source_files(project_path, false, true, false, project_exclusions(project_path)) {
+            let Ok(content) = source::read(&path, Some(source::DEFAULT_MAX_FILE_SIZE)) else {
+                continue;
+            };
+            let code = blank_comments(&content);
+            for cap in INJECTABLE_REGEX.captures_iter(&code) {
+                let whole = cap.get(0).unwrap();
+                let Some(class) = CLASS_REGEX.captures(&code[whole.end()..]) else {
+                    continue;
+                };
+                let options = &code[whole.end()..whole.end() + class.get(0).unwrap().start()];
+                let scope = match
+                    cap
+                        .get(1)
+                        .and(PROVIDED_IN_REGEX.captures(options))
+                        .and_then(|c| c.get(1).or(c.get(2)).map(|m| m.as_str()))
+                {
+                    Some("root") => Scope::Root,
+                    Some("platform") => Scope::Platform,
+                    Some("any") => Scope::Any,
+                    // `providedIn: SomeModule`はそのモジュールの`providers`と同じ
+                    Some(module) =>
+                        match result.modules.iter().find(|m| *m.name == *module) {
+                            Some(m) if eager.contains(module) => Scope::EagerModule(m.name.clone()),
+                            Some(m) => Scope::LazyModule(m.name.clone()),
+                            None => continue,
+                        }
+                    None => continue,
+                };
+                let position = Position::at(&code, whole.start());
+                tokens.entry(class[1].to_string()).or_default().push(Registration {
+                    scope,
+                    location: SourceLocation { file: path.clone(), line: position.line, column: position.column },
+                    multi: false,
+                    using: None,
+                });
+            }
+
+            for decorator in COMPONENT_REGEX.find_iter(&code) {
+                let open = decorator.end() - 1;
+                let Some(close) = closing(&code, open) else {
+                    continue;
+                };
+                let Some(class) = CLASS_REGEX.captures(&code[close..]) else {
+                    continue;
+                };
+                let scope = Scope::Component(class[1].to_string());
+                for (_, range) in object_arrays(&code, open, &["providers", "viewProviders"]) {
+                    register(&mut tokens, &path, &code, range.start, &code[range], &scope);
+                }
+            }
+        }
+
+        let tokens = tokens
+            .into_iter()
+            .map(|(token, mut registrations)| {
+                registrations.sort();
+                registrations.dedup();
+                let conflicts = conflicts(&registrations);
+                TokenScopes { token, registrations, conflicts }
+            })
+            .collect();
+        ProviderScopes { tokens }
+    }
+
+    /// 問題のあるトークン
+    pub fn conflicting(&self) -> impl Iterator<Item = &TokenScopes> {
+        self.tokens.iter().filter(|token| !token.conflicts.is_empty())
+    }
+}
+
+/// マルチプロバイダーの登録が意図どおりに働かない理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MultiIssue {
+    /// 起動時に使うトークン（`APP_INITIALIZER`など）を起動後に作られるインジェクターに登録している
+    NeverRunsAtBootstrap,
+    /// `HTTP_INTERCEPTORS`を遅延ロードのモジュール・コンポーネントに登録している
+    InterceptsOnlyLocalClient,
+    /// ルートインジェクターのリストに含まれない
+    OutsideRootList,
+}
+
+impl MultiIssue {
+    pub fn description(self) -> &'static str {
+        match self {
+            MultiIssue::NeverRunsAtBootstrap =>
+                tr(
+                    "never runs: the injector is created after the application has bootstrapped",
+                    "実行されない（インジェクターはアプリケーションの起動後に作られる）"
+                ),
+            MultiIssue::InterceptsOnlyLocalClient =>
+                tr(
+                    "only intercepts an HttpClient provided in the same injector, not the application's",
+                    "同じインジェクターで提供したHttpClientだけをインターセプトし、アプリケーションのものは対象にならない"
+                ),
+            MultiIssue::OutsideRootList =>
+                tr("not part of the list injected from the root injector", "ルートインジェクターから注入されるリストに含まれない"),
+        }
+    }
+}
+
+/// マルチプロバイダーの登録1つ
+#[derive(Debug, Clone, Serialize)]
+pub struct MultiRegistration {
+    /// ルートインジェクターのリストでの位置（1始まり。遅延ロードのモジュールとコンポーネントでは`None`）
+    pub order: Option<usize>,
+    pub scope: Scope,
+    pub using: Option<String>,
+    pub location: SourceLocation,
+    pub issue: Option<MultiIssue>,
+}
+
+/// 1つのトークンのマルチプロバイダー
+#[derive(Debug, Clone, Serialize)]
+pub struct MultiProvider {
+    pub token: String,
+    /// ルートインジェクターに登録される順（その後に遅延ロードのモジュール・コンポーネントの登録）
+    pub registrations: Vec<MultiRegistration>,
+}
+
+/// `APP_INITIALIZER`・`HTTP_INTERCEPTORS`などのマルチプロバイダーの一覧
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MultiProviders {
+    /// トークン名順
+    pub tokens: Vec<MultiProvider>,
+}
+
+impl MultiProviders {
+    /// `multi: true`の登録（と`provideAppInitializer()`など）を、ルートインジェクターに登録される順に並べる
+    ///
+    /// 起動時のモジュールの`providers`は、どこからもimportされていないモジュールから`imports`を書いた順に深さ優先でたどり、
+    /// importしたモジュールのものを先に、同じモジュールの中では書いた順に並べる（Angularがインジェクターを作るときと同じ順）。
+    pub fn collect(project_path: &Path, result: &AnalysisResult) -> Self {
+        let rank = import_order(result);
+        let scopes = ProviderScopes::collect(project_path, result);
+        let tokens = scopes.tokens
+            .into_iter()
+            .filter_map(|token| {
+                let mut registrations: Vec<Registration> = token.registrations
+                    .into_iter()
+                    .filter(|r| r.multi)
+                    .collect();
+                if registrations.is_empty() {
+                    return None;
+                }
+                let key = |r: &Registration| match &r.scope {
+                    Scope::EagerModule(module) => (0, rank.get(module.as_ref()).copied().unwrap_or(usize::MAX)),
+                    _ => (1, 0),
+                };
+                registrations.sort_by(|a, b| key(a).cmp(&key(b)).then_with(|| a.location.cmp(&b.location)));
+
+                let bootstrap = BOOTSTRAP_TOKENS.contains(&token.token.as_str());
+                let mut order = 0;
+                let registrations = registrations
+                    .into_iter()
+                    .map(|r| {
+                        let local = matches!(r.scope, Scope::LazyModule(_) | Scope::Component(_));
+                        let issue = if !local {
+                            None
+                        } else if bootstrap {
+                            Some(MultiIssue::NeverRunsAtBootstrap)
+                        } else if token.token == "HTTP_INTERCEPTORS" {
+                            Some(MultiIssue::InterceptsOnlyLocalClient)
+                        } else {
+                            Some(MultiIssue::OutsideRootList)
+                        };
+                        MultiRegistration {
+                            order: (!local).then(|| {
+                                order += 1;
+                                order
+                            }),
+                            scope: r.scope,
+                            using: r.using,
+                            location: r.location,
+                            issue,
+                        }
+                    })
+                    .collect();
+                Some(MultiProvider { token: token.token, registrations })
+            })
+            .collect();
+        MultiProviders { tokens }
+    }
+
+    /// 意図どおりに働かない登録の数
+    pub fn issues(&self) -> usize {
+        self.tokens
+            .iter()
+            .flat_map(|token| &token.registrations)
+            .filter(|r| r.issue.is_some())
+            .count()
+    }
+}
+
+/// モジュール名 -> ルートインジェクターの`providers`をまとめる順序
+fn import_order(result: &AnalysisResult) -> HashMap<&str, usize> {
+    let graph = result.graph();
+    let modules: HashMap<&str, &ModuleInfo> = result.modules
+        .iter()
+        .map(|m| (m.name.as_ref(), m))
+        .collect();
+    let mut entry_points: Vec<&str> = modules
+        .keys()
+        .copied()
+        .filter(|name| graph.dependents_of(name).is_empty())
+        .collect();
+    entry_points.sort();
+
+    fn visit<'a>(
+        name: &'a str,
+        modules: &HashMap<&'a str, &'a ModuleInfo>,
+        rank: &mut HashMap<&'a str, usize>,
+        visiting: &mut HashSet<&'a str>
+    ) {
+        if rank.contains_key(name) || !visiting.insert(name) {
+            return;
+        }
+        for entry in &modules[name].imports {
+            if let Some((imported, _)) = modules.get_key_value(reference_name(entry)) {
+                visit(imported, modules, rank, visiting);
+            }
+        }
+        let next = rank.len();
+        rank.insert(name, next);
+    }
+
+    let mut rank = HashMap::new();
+    let mut visiting = HashSet::new();
+    for name in entry_points {
+        visit(name, &modules, &mut rank, &mut visiting);
+    }
+    rank
+}
+
+/// `providers`配列の中身（`offset`はファイル内の位置）の各要素を、トークンの登録として追加する
+fn register(
+    tokens: &mut BTreeMap<String, Vec<Registration>>,
+    path: &Path,
+    code: &str,
+    offset: usize,
+    array: &str,
+    scope: &Scope
+) {
+    for item in top_level_items(array) {
+        let entry = &array[item.clone()];
+        let (token, multi) = match PROVIDE_REGEX.captures(entry) {
+            Some(cap) => (cap[1].to_string(), MULTI_REGEX.is_match(entry)),
+            None if reference_name(entry) == entry && !entry.is_empty() => (entry.to_string(), false),
+            None =>
+                match MULTI_PROVIDER_FUNCTIONS.iter().find(|(function, _)| reference_name(entry) == *function) {
+                    Some((_, token)) => (token.to_string(), true),
+                    // その他の関数呼び出しやスプレッドはトークンが分からない
+                    None => {
+                        continue;
+                    }
+                }
+        };
+        let position = Position::at(code, offset + item.start);
+        tokens.entry(token).or_default().push(Registration {
+            scope: scope.clone(),
+            location: SourceLocation { file: path.to_path_buf(), line: position.line, column: position.column },
+            multi,
+            using: USE_REGEX.captures(entry).map(|cap| cap[1].to_string()),
+        });
+    }
+}
+
+/// `multi: true`でない登録同士の問題
+fn conflicts(registrations: &[Registration]) -> Vec<ScopeConflict> {
+    let single: Vec<&Scope> = registrations
+        .iter()
+        .filter(|r| !r.multi)
+        .map(|r| &r.scope)
+        .collect();
+    let root_level = single
+        .iter()
+        .filter(|scope| scope.is_root_level())
+        .count();
+    let mut conflicts = Vec::new();
+    if root_level > 1 {
+        conflicts.push(ScopeConflict::Duplicate);
+    }
+    if root_level > 0 && single.iter().any(|scope| matches!(scope, Scope::LazyModule(_))) {
+        conflicts.push(ScopeConflict::ShadowedByLazyModule);
+    }
+    let shared = single
+        .iter()
+        .any(|scope| !matches!(scope, Scope::Component(_)));
+    if shared && single.iter().any(|scope| matches!(scope, Scope::Component(_))) {
+        conflicts.push(ScopeConflict::ShadowedByComponent);
+    }
+    conflicts
+}
+
+/// ファイル内のクラスを（クラス名, 開始位置, 次のクラスの開始位置）で返す
+fn classes(content: &str) -> Vec<(&str, usize, usize)> {
+    let starts: Vec<(&str, usize)> = CLASS_REGEX.captures_iter(content)
+        .map(|cap| (cap.get(1).unwrap().as_str(), cap.get(0).unwrap().start()))
+        .collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &(name, start))| (name, start, starts.get(i + 1).map_or(content.len(), |next| next.1)))
+        .collect()
+}
+
+/// `open`の`(`から対応する`)`までの中身（`@Inject(TOKEN)`のような入れ子の括弧を含む）
+fn parenthesized(text: &str, open: usize) -> Option<&str> {
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '(' => {
+                depth += 1;
+            }
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[open + 1..open + i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
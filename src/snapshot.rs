@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::Path;
+
+use crate::fix::unified_diff;
+use crate::graph::EdgeKind;
+use crate::{ AnalysisResult, AnalyzerError, Result };
+
+/// 既定のスナップショットファイル名（プロジェクトルートに置いてコミットする）
+pub const SNAPSHOT_FILE_NAME: &str = "architecture.snap";
+
+const HEADER: &str = "# Angular module graph snapshot; update with `snapshot save` when the change is intended";
+
+/// モジュールグラフを正規化したテキスト
+///
+/// 差分を読みやすいよう1行に1つの事実を書き、モジュールと依存は名前順に並べる。
+/// 参照位置や`declarations`の変更など、グラフの形が変わらない変更では内容は変わらない。
+pub fn render(result: &AnalysisResult, project_path: &Path) -> String {
+    let graph = result.graph();
+    let edges = graph.edges();
+    let mut modules: Vec<_> = result.modules.iter().collect();
+    modules.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path)));
+
+    let mut text = format!("{}\n", HEADER);
+    for module in modules {
+        let path = module.path.strip_prefix(project_path).unwrap_or(&module.path);
+        text.push_str(&format!("\n{}\n", module.name));
+        text.push_str(&format!("  type: {}\n", module.module_type));
+        text.push_str(&format!("  path: {}\n", path.to_string_lossy().replace('\\', "/")));
+        for (_, to, kind) in edges.iter().filter(|(from, _, _)| *from == module.name.as_ref()) {
+            match kind {
+                EdgeKind::Eager => text.push_str(&format!("  imports: {}\n", to)),
+                EdgeKind::Lazy => text.push_str(&format!("  lazy loads: {}\n", to)),
+            }
+        }
+    }
+    text
+}
+
+/// 現在のグラフをスナップショットファイルに書き出す
+pub fn save(result: &AnalysisResult, project_path: &Path, file: &Path) -> Result<()> {
+    fs::write(file, render(result, project_path)).map_err(|err| AnalyzerError::io(file, err))
+}
+
+/// スナップショットファイルと現在のグラフのunified diff（一致すれば`None`）
+pub fn verify(result: &AnalysisResult, project_path: &Path, file: &Path) -> Result<Option<String>> {
+    let saved = fs::read_to_string(file).map_err(|err| AnalyzerError::io(file, err))?;
+    // Windowsでチェックアウトすると改行がCRLFになっていることがある
+    let saved = saved.replace("\r\n", "\n");
+    let current = render(result, project_path);
+    let label = file.file_name().map_or_else(|| file.to_string_lossy(), |name| name.to_string_lossy());
+    Ok(Some(unified_diff(&label, &saved, &current)).filter(|diff| !diff.is_empty()))
+}
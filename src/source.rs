@@ -0,0 +1,357 @@
+use memchr::memmem::Finder;
+use memmap2::Mmap;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs::{ self, File };
+use std::ops::{ Deref, Range };
+use std::path::Path;
+use std::time::Duration;
+
+use crate::{ AnalyzerError, Result };
+
+/// 既定の最大ファイルサイズ（NgModuleのファイルはふつう数KBなので、これを超えるのは生成物とみなす）
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// 1ファイルの解析にかけてよい既定の時間（超えたファイルは警告にして読み飛ばす）
+pub const DEFAULT_PARSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 正規表現で読むファイルの1行の最大長（これより長い行はminifyされた生成物とみなす）
+pub const MAX_LINE_LENGTH: usize = 64 * 1024;
+
+/// `@NgModule(...)`の引数の最大長（手で書いたメタデータがこれを超えることはない）
+pub const MAX_METADATA_SIZE: usize = 256 * 1024;
+
+/// これより大きいファイルはメモリにコピーせずマップして読む
+///
+/// 既定の上限以下のファイルはコピーしても小さいので、マップ中にファイルが切り詰められてSIGBUSになる危険を負わない。
+/// マップするのは`--max-file-size`で上限を上げたか外した場合だけになる。
+const MMAP_THRESHOLD: u64 = DEFAULT_MAX_FILE_SIZE;
+
+// @NgModule( / @core.NgModule(
+static NGMODULE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"@(?:\w+\.)*NgModule\s*\(").unwrap());
+static DECORATED_CLASS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bclass\s+(\w+)").unwrap());
+static NGMODULE: Lazy<Finder<'static>> = Lazy::new(|| Finder::new("@NgModule"));
+// `import * as core from '@angular/core'`で`@core.NgModule(...)`と書いたもの
+static QUALIFIED_NGMODULE: Lazy<Finder<'static>> = Lazy::new(|| Finder::new(".NgModule("));
+static STANDALONE: Lazy<Finder<'static>> = Lazy::new(|| Finder::new("standalone:"));
+
+/// `@NgModule`（`@core.NgModule`）か`standalone:`を含むか
+///
+/// `*.module.ts`という名前でも、CSSモジュールの型定義やルート定数だけのファイルは解析しても何も得られないので、
+/// 正規表現による抽出の前にバイト列の検索だけで除外する。
+pub(crate) fn declares_module(content: &str) -> bool {
+    [&NGMODULE, &QUALIFIED_NGMODULE, &STANDALONE].iter().any(|finder| finder.find(content.as_bytes()).is_some())
+}
+
+/// 最も長い行の（1始まりの）行番号とバイト数
+pub(crate) fn longest_line(content: &str) -> Option<(usize, usize)> {
+    lines(content)
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.len()))
+        .max_by_key(|&(_, length)| length)
+}
+
+/// ソースファイルの文字コード
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    Utf8,
+    /// 先頭にBOMのあるUTF-8（内容からはBOMを除いている）
+    Utf8Bom,
+    /// BOMで判別したUTF-16（UTF-8に変換している）
+    Utf16,
+}
+
+/// 読み込んだソースファイルの内容（UTF-8として検証済み、BOMは除く）
+pub(crate) struct SourceText {
+    content: Content,
+    pub(crate) encoding: Encoding,
+}
+
+enum Content {
+    Owned(String),
+    /// BOMの後ろの位置からの内容（作成時にUTF-8であることを一度だけ確認している）
+    Mapped(Mmap, usize),
+}
+
+impl Deref for SourceText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match &self.content {
+            Content::Owned(text) => text,
+            // SAFETY: 作成時に`map[start..]`がUTF-8であることを確認している（マップの前提は`read`を参照）
+            Content::Mapped(map, start) => unsafe { std::str::from_utf8_unchecked(&map[*start..]) },
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// ファイルを読み込む（`max_size`を超える場合は`AnalyzerError::FileTooLarge`）
+///
+/// UTF-8のBOMは取り除き、BOMのあるUTF-16はUTF-8に変換する。それ以外のUTF-8でないファイルは`AnalyzerError::Parse`。
+pub(crate) fn read(path: &Path, max_size: Option<u64>) -> Result<SourceText> {
+    let size = fs::metadata(path).map_err(|err| AnalyzerError::io(path, err))?.len();
+    if let Some(limit) = max_size.filter(|limit| size > *limit) {
+        return Err(AnalyzerError::FileTooLarge { path: path.to_path_buf(), size, limit });
+    }
+    if size <= MMAP_THRESHOLD {
+        let bytes = fs::read(path).map_err(|err| AnalyzerError::io(path, err))?;
+        return decode(path, bytes);
+    }
+
+    let file = File::open(path).map_err(|err| AnalyzerError::io(path, err))?;
+    // SAFETY: マップは読み取り専用で、解析中にファイルが書き換えられないことを前提にする。
+    // 他のプロセスが書き換えると検証済みのUTF-8が壊れることがあり、切り詰めるとマップの外を読んでSIGBUSで落ちる。
+    // これを避けられないため、マップするのは既定の上限を超える大きなファイルだけにしている
+    let map = unsafe { Mmap::map(&file) }.map_err(|err| AnalyzerError::io(path, err))?;
+    let (start, encoding) = if map.starts_with(UTF8_BOM) { (UTF8_BOM.len(), Encoding::Utf8Bom) } else { (0, Encoding::Utf8) };
+    if std::str::from_utf8(&map[start..]).is_err() {
+        // UTF-16などはメモリにコピーして変換する
+        return decode(path, map.to_vec());
+    }
+    Ok(SourceText { content: Content::Mapped(map, start), encoding })
+}
+
+/// ソースファイルを文字列として読み込む（BOMとUTF-16は[`read`]と同じく扱う）
+pub fn read_to_string(path: &Path) -> Result<String> {
+    read(path, None).map(|text| text.to_string())
+}
+
+/// バイト列をBOMで判別してUTF-8の文字列にする
+pub(crate) fn decode(path: &Path, bytes: Vec<u8>) -> Result<SourceText> {
+    let utf16 = match bytes.get(..2) {
+        Some([0xFF, 0xFE]) => Some(encoding_rs::UTF_16LE),
+        Some([0xFE, 0xFF]) => Some(encoding_rs::UTF_16BE),
+        _ => None,
+    };
+    if let Some(utf16) = utf16 {
+        return match utf16.decode_without_bom_handling_and_without_replacement(&bytes[2..]) {
+            Some(text) => Ok(SourceText { content: Content::Owned(text.into_owned()), encoding: Encoding::Utf16 }),
+            None => Err(AnalyzerError::parse(path, format!("invalid {} text", utf16.name()))),
+        };
+    }
+    let (bytes, encoding) = match bytes.strip_prefix(UTF8_BOM) {
+        Some(rest) => (rest.to_vec(), Encoding::Utf8Bom),
+        None => (bytes, Encoding::Utf8),
+    };
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(SourceText { content: Content::Owned(text), encoding }),
+        Err(err) =>
+            Err(
+                AnalyzerError::parse(
+                    path,
+                    format!("not UTF-8 ({}); only UTF-8 and UTF-16 with a byte order mark are supported", err.utf8_error())
+                )
+            ),
+    }
+}
+
+/// `\r\n`・`\n`・`\r`のどれでも行を区切る（[`crate::Position`]と同じ数え方）
+pub fn lines(content: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(content);
+    std::iter::from_fn(move || {
+        let text = rest?;
+        match text.find(['\r', '\n']) {
+            Some(end) => {
+                let next = if text[end..].starts_with("\r\n") { end + 2 } else { end + 1 };
+                rest = Some(&text[next..]);
+                Some(&text[..end])
+            }
+            None => {
+                rest = None;
+                Some(text)
+            }
+        }
+    })
+}
+
+/// コメントを同じ長さの空白に置き換える（改行と文字列リテラルはそのまま）
+pub(crate) fn blank_comments(content: &str) -> String {
+    let mut code = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            code.push(c);
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                code.push(' ');
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    code.extend(std::iter::repeat_n(' ', next.len_utf8()));
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                // 開きの`*`は閉じの`*/`に数えない
+                chars.next();
+                code.push_str("  ");
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        code.push('\n');
+                    } else {
+                        code.extend(std::iter::repeat_n(' ', next.len_utf8()));
+                    }
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => {
+                if matches!(c, '"' | '\'' | '`') {
+                    quote = Some(c);
+                }
+                code.push(c);
+            }
+        }
+    }
+    code
+}
+
+/// `@NgModule(...)`デコレーターと、それを付けたクラス
+pub(crate) struct NgModuleDecorator {
+    /// メタデータのオブジェクトリテラルの`{`の位置
+    pub(crate) metadata: usize,
+    /// デコレーターの後に宣言しているクラス（他のデコレーターを挟んでいてもよい）
+    pub(crate) class_name: Option<String>,
+}
+
+/// メタデータのオブジェクトリテラルを引数に取る最初の`@NgModule(...)`（`code`はコメントを除いたもの）
+///
+/// `@CustomLog() @NgModule({ ... })`のように他のデコレーターと並んでいても、
+/// `@NgModule(withDefaults({ ... }))`のように引数を関数でラップしていてもよい（最初のオブジェクトリテラルをメタデータとする）。
+/// 他のデコレーターの引数にある`imports`などは拾わない。
+pub(crate) fn ngmodule_decorator(code: &str) -> Option<NgModuleDecorator> {
+    NGMODULE_REGEX.find_iter(code).find_map(|decorator| {
+        let open = decorator.end() - 1;
+        let close = closing(code, open)?;
+        let metadata = open + code[open..close].find('{')?;
+        let class_name = DECORATED_CLASS_REGEX.captures(&code[close..]).map(|cap| cap[1].to_string());
+        Some(NgModuleDecorator { metadata, class_name })
+    })
+}
+
+/// `open`の`{`から始まるオブジェクトの最上位にある、`fields`のプロパティの配列の中身の範囲
+pub(crate) fn object_arrays(content: &str, open: usize, fields: &[&'static str]) -> Vec<(&'static str, Range<usize>)> {
+    let Some(close) = closing(content, open) else {
+        return Vec::new();
+    };
+
+    let body = &content[open + 1..close];
+    let mut arrays = Vec::new();
+    for item in top_level_items(body) {
+        let text = &body[item.clone()];
+        let Some((key, value)) = text.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().trim_matches(|c| c == '"' || c == '\'');
+        let Some(field) = fields.iter().find(|field| **field == key) else {
+            continue;
+        };
+        let value_start = open + 1 + item.start + text.len() - value.trim_start().len();
+        if !content[value_start..].starts_with('[') {
+            continue;
+        }
+        if let Some(end) = closing(content, value_start) {
+            arrays.push((*field, value_start + 1..end));
+        }
+    }
+    arrays
+}
+
+/// `open`の括弧に対応する閉じ括弧の位置（文字列リテラル内の括弧は数えない）
+pub(crate) fn closing(content: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in content[open..].char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => {
+                quote = Some(c);
+            }
+            '[' | '{' | '(' => {
+                depth += 1;
+            }
+            ']' | '}' | ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// カンマで区切られた最上位の要素の範囲（前後の空白を除く）
+pub(crate) fn top_level_items(text: &str) -> Vec<Range<usize>> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut start = 0;
+    let mut push = |range: Range<usize>| {
+        let piece = &text[range.clone()];
+        let trimmed = piece.trim();
+        if !trimmed.is_empty() {
+            let leading = piece.len() - piece.trim_start().len();
+            items.push(range.start + leading..range.start + leading + trimmed.len());
+        }
+    };
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => {
+                quote = Some(c);
+            }
+            '[' | '{' | '(' => {
+                depth += 1;
+            }
+            ']' | '}' | ')' => {
+                depth -= 1;
+            }
+            ',' if depth == 0 => {
+                push(start..i);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    push(start..text.len());
+    items
+}
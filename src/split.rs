@@ -0,0 +1,335 @@
+use serde::Serialize;
+use std::collections::{ BTreeMap, BTreeSet, HashMap, HashSet };
+
+use crate::components::{ ComponentGraph, Declarable };
+use crate::graph::reference_name;
+use crate::{ AnalysisResult, ModuleInfo };
+
+/// 直接の依存（テンプレートやimport文での参照）の重み
+const DIRECT_WEIGHT: f64 = 2.0;
+/// 同じコンポーネントから一緒に使われていることの重み
+const CO_USAGE_WEIGHT: f64 = 1.0;
+
+/// 分割後の新しいモジュール1つ
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitGroup {
+    /// 提案するモジュール名（グループの中心になっている宣言から付ける）
+    pub name: String,
+    pub declarables: Vec<String>,
+    /// グループの外（他のグループや他のモジュール）から使われている、または元のモジュールが`exports`していた宣言
+    pub exports: Vec<String>,
+    /// 必要になるimport（他のグループと、使っている宣言を持つプロジェクト内のモジュール）
+    pub imports: Vec<String>,
+}
+
+/// 大きすぎるモジュールの分割案
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitPlan {
+    pub module: String,
+    pub declarations: usize,
+    /// コミュニティ分割のモジュラリティ（0〜1、高いほどグループ間の結びつきが弱い）
+    pub modularity: f64,
+    pub groups: Vec<SplitGroup>,
+    /// どの宣言とも関係がなく、元のモジュールに残してよい宣言
+    pub unclustered: Vec<String>,
+    /// 元のモジュールがimportしているライブラリのモジュール（`CommonModule`など、どのグループでも必要になりうる）
+    pub library_imports: Vec<String>,
+}
+
+/// `declarations`が`min_declarations`以上のモジュール（宣言の多い順）
+pub fn oversized(result: &AnalysisResult, min_declarations: usize) -> Vec<&ModuleInfo> {
+    let mut modules: Vec<&ModuleInfo> = result.modules
+        .iter()
+        .filter(|m| m.declarations.len() >= min_declarations)
+        .collect();
+    modules.sort_by(|a, b| b.declarations.len().cmp(&a.declarations.len()).then_with(|| a.name.cmp(&b.name)));
+    modules
+}
+
+/// コンポーネント単位のグラフをLouvain法でコミュニティに分け、`module`の分割案を作る
+///
+/// 直接の依存に加え、モジュールの外の同じコンポーネントから一緒に使われている宣言も結びつける。
+/// コンポーネントのグラフは[`crate::AnalyzerBuilder::components`]で集めたもの（なければすべての宣言が`unclustered`になる）。
+pub fn plan(result: &AnalysisResult, module: &ModuleInfo) -> SplitPlan {
+    let empty = ComponentGraph::default();
+    let components = result.components.as_ref().unwrap_or(&empty);
+    let names: BTreeSet<&str> = module.declarations
+        .iter()
+        .map(|d| reference_name(d))
+        .collect();
+    let nodes: Vec<&str> = names.iter().copied().collect();
+    let index: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (*name, i))
+        .collect();
+    let declarables: HashMap<&str, &Declarable> = components.declarables
+        .iter()
+        .map(|d| (d.name.as_str(), d))
+        .collect();
+
+    let mut weights: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); nodes.len()];
+    let mut link = |a: usize, b: usize, weight: f64| {
+        if a != b {
+            *weights[a].entry(b).or_insert(0.0) += weight;
+            *weights[b].entry(a).or_insert(0.0) += weight;
+        }
+    };
+    let mut used_from_outside: HashMap<&str, BTreeSet<usize>> = HashMap::new();
+    // （使っている宣言, 使われている外の宣言）
+    let mut outgoing: Vec<(usize, &str)> = Vec::new();
+    for edge in &components.edges {
+        match (index.get(edge.from.as_str()), index.get(edge.to.as_str())) {
+            (Some(&from), Some(&to)) => link(from, to, DIRECT_WEIGHT),
+            (None, Some(&to)) => {
+                used_from_outside.entry(edge.from.as_str()).or_default().insert(to);
+            }
+            (Some(&from), None) => outgoing.push((from, edge.to.as_str())),
+            (None, None) => {}
+        }
+    }
+    for used in used_from_outside.values() {
+        let used: Vec<usize> = used.iter().copied().collect();
+        for (i, &a) in used.iter().enumerate() {
+            for &b in &used[i + 1..] {
+                link(a, b, CO_USAGE_WEIGHT);
+            }
+        }
+    }
+
+    let (communities, modularity) = louvain(&weights);
+    let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    let mut unclustered = Vec::new();
+    for (node, community) in communities.iter().enumerate() {
+        if weights[node].is_empty() {
+            unclustered.push(nodes[node].to_string());
+        } else {
+            members.entry(*community).or_default().push(node);
+        }
+    }
+    // 大きいグループから並べる
+    let mut groups: Vec<Vec<usize>> = members.into_values().collect();
+    groups.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    let group_of: HashMap<usize, usize> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(g, nodes)| nodes.iter().map(move |&n| (n, g)))
+        .collect();
+
+    let mut taken: HashSet<String> = result.modules
+        .iter()
+        .map(|m| m.name.to_string())
+        .collect();
+    let group_names: Vec<String> = groups
+        .iter()
+        .map(|group| {
+            // グループ内で最も結びつきの強い宣言を中心とみなす
+            let center = group
+                .iter()
+                .copied()
+                .max_by(|&a, &b| {
+                    let inside = |n: usize| -> f64 {
+                        weights[n]
+                            .iter()
+                            .filter(|(other, _)| group_of.get(other) == group_of.get(&n))
+                            .map(|(_, w)| w)
+                            .sum()
+                    };
+                    inside(a).total_cmp(&inside(b)).then_with(|| nodes[b].cmp(nodes[a]))
+                })
+                .unwrap();
+            unique_name(module_name(nodes[center]), &mut taken)
+        })
+        .collect();
+
+    let exported: HashSet<&str> = module.exports
+        .iter()
+        .map(|e| reference_name(e))
+        .collect();
+    let owners: HashMap<&str, &str> = result.modules
+        .iter()
+        .flat_map(|m| m.declarations.iter().map(move |d| (reference_name(d), m.name.as_ref())))
+        .collect();
+    let project_modules: HashSet<&str> = result.modules
+        .iter()
+        .map(|m| m.name.as_ref())
+        .collect();
+
+    let groups = groups
+        .iter()
+        .enumerate()
+        .map(|(g, group)| {
+            let mut exports = BTreeSet::new();
+            let mut imports = BTreeSet::new();
+            for &node in group {
+                let name = nodes[node];
+                if exported.contains(name) || used_from_outside.values().any(|used| used.contains(&node)) {
+                    exports.insert(name.to_string());
+                }
+            }
+            for edge in &components.edges {
+                let (Some(&from), Some(&to)) = (index.get(edge.from.as_str()), index.get(edge.to.as_str())) else {
+                    continue;
+                };
+                let (Some(&from_group), Some(&to_group)) = (group_of.get(&from), group_of.get(&to)) else {
+                    continue;
+                };
+                if from_group == g && to_group != g {
+                    imports.insert(group_names[to_group].clone());
+                }
+                if to_group == g && from_group != g {
+                    exports.insert(nodes[to].to_string());
+                }
+            }
+            for &(from, target) in &outgoing {
+                if group_of.get(&from) != Some(&g) {
+                    continue;
+                }
+                // スタンドアロンの宣言はそれ自体をimportする
+                if let Some(owner) = owners.get(target) {
+                    imports.insert(owner.to_string());
+                } else if declarables.contains_key(target) {
+                    imports.insert(target.to_string());
+                }
+            }
+            SplitGroup {
+                name: group_names[g].clone(),
+                declarables: group.iter().map(|&n| nodes[n].to_string()).collect(),
+                exports: exports.into_iter().collect(),
+                imports: imports.into_iter().collect(),
+            }
+        })
+        .collect();
+
+    let mut library_imports: Vec<String> = module.imports
+        .iter()
+        .map(|i| reference_name(i))
+        .filter(|i| !project_modules.contains(i))
+        .map(str::to_string)
+        .collect();
+    library_imports.dedup();
+
+    SplitPlan {
+        module: module.name.to_string(),
+        declarations: nodes.len(),
+        modularity,
+        groups,
+        unclustered,
+        library_imports,
+    }
+}
+
+/// `TableHeaderComponent`から`TableHeaderModule`のような名前を作る
+fn module_name(declarable: &str) -> String {
+    let stem = ["Component", "Directive", "Pipe"]
+        .iter()
+        .find_map(|suffix| declarable.strip_suffix(suffix).filter(|stem| !stem.is_empty()))
+        .unwrap_or(declarable);
+    format!("{}Module", stem)
+}
+
+fn unique_name(name: String, taken: &mut HashSet<String>) -> String {
+    let stem = name.trim_end_matches("Module").to_string();
+    let mut candidate = name;
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}{}Module", stem, n);
+        n += 1;
+    }
+    taken.insert(candidate.clone());
+    candidate
+}
+
+/// 重み付き無向グラフをLouvain法でコミュニティに分ける（ノードごとのコミュニティ番号とモジュラリティを返す）
+///
+/// `weights[i][j]`はノードiとjの間の重み（対称、自己ループなし）。ノードの順に処理するので結果は決定的になる。
+fn louvain(weights: &[BTreeMap<usize, f64>]) -> (Vec<usize>, f64) {
+    // 集約したグラフでは自己ループがコミュニティ内の重みを表す（`adjacency[i][i]`は往復分の2倍で持つ）
+    let mut adjacency: Vec<BTreeMap<usize, f64>> = weights.to_vec();
+    let mut assignment: Vec<usize> = (0..weights.len()).collect();
+    let total: f64 = adjacency.iter().flat_map(|row| row.values()).sum();
+    if total == 0.0 {
+        return (assignment, 0.0);
+    }
+
+    loop {
+        let communities = local_moves(&adjacency, total);
+        // 番号を詰める
+        let mut renumber: BTreeMap<usize, usize> = BTreeMap::new();
+        for &c in &communities {
+            let next = renumber.len();
+            renumber.entry(c).or_insert(next);
+        }
+        if renumber.len() == adjacency.len() {
+            break;
+        }
+        for community in assignment.iter_mut() {
+            *community = renumber[&communities[*community]];
+        }
+        let mut aggregated: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); renumber.len()];
+        for (i, row) in adjacency.iter().enumerate() {
+            for (&j, &w) in row {
+                *aggregated[renumber[&communities[i]]].entry(renumber[&communities[j]]).or_insert(0.0) += w;
+            }
+        }
+        adjacency = aggregated;
+    }
+
+    (assignment, modularity(&adjacency, total))
+}
+
+/// 1段階目: 各ノードを、モジュラリティが最も増える隣のコミュニティへ動かすことを変化がなくなるまで繰り返す
+fn local_moves(adjacency: &[BTreeMap<usize, f64>], total: f64) -> Vec<usize> {
+    let degree: Vec<f64> = adjacency
+        .iter()
+        .map(|row| row.values().sum())
+        .collect();
+    let mut community: Vec<usize> = (0..adjacency.len()).collect();
+    let mut community_degree = degree.clone();
+
+    let mut moved = true;
+    while moved {
+        moved = false;
+        for node in 0..adjacency.len() {
+            let current = community[node];
+            community_degree[current] -= degree[node];
+
+            let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+            for (&neighbor, &w) in &adjacency[node] {
+                if neighbor != node {
+                    *links.entry(community[neighbor]).or_insert(0.0) += w;
+                }
+            }
+            let gain = |c: usize, w: f64| w - (community_degree[c] * degree[node]) / total;
+            let mut best = current;
+            let mut best_gain = gain(current, links.get(&current).copied().unwrap_or(0.0));
+            for (&c, &w) in &links {
+                let g = gain(c, w);
+                if g > best_gain + 1e-12 {
+                    best = c;
+                    best_gain = g;
+                }
+            }
+
+            community_degree[best] += degree[node];
+            if best != current {
+                community[node] = best;
+                moved = true;
+            }
+        }
+    }
+    community
+}
+
+/// 集約後のグラフ（各ノードが1つのコミュニティ）のモジュラリティ
+fn modularity(adjacency: &[BTreeMap<usize, f64>], total: f64) -> f64 {
+    adjacency
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let inside = row.get(&i).copied().unwrap_or(0.0);
+            let degree: f64 = row.values().sum();
+            inside / total - (degree / total).powi(2)
+        })
+        .sum()
+}
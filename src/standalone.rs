@@ -0,0 +1,207 @@
+use petgraph::algo::tarjan_scc;
+use serde::Serialize;
+use std::collections::{ BTreeSet, HashMap, HashSet };
+use std::path::{ Path, PathBuf };
+
+use crate::graph::{ EdgeKind, reference_name };
+use crate::i18n::tr;
+use crate::{ AnalysisResult, ModuleInfo };
+
+/// `forRoot()`などを使わずに`imports`に書くプロバイダー用のモジュールと、その置き換え先
+const PROVIDER_MODULES: [(&str, &str); 5] = [
+    ("HttpClientModule", "provideHttpClient()"),
+    ("HttpClientJsonpModule", "provideHttpClient(withJsonpSupport())"),
+    ("BrowserAnimationsModule", "provideAnimations()"),
+    ("NoopAnimationsModule", "provideNoopAnimations()"),
+    ("BrowserModule", "remove it (bootstrapApplication() provides it)"),
+];
+
+/// `ModuleWithProviders`を返す呼び出しと、引数をそのまま渡せる`provideX()`
+const PROVIDER_FUNCTIONS: [(&str, &str); 8] = [
+    ("RouterModule.forRoot", "provideRouter"),
+    ("StoreModule.forRoot", "provideStore"),
+    ("StoreModule.forFeature", "provideState"),
+    ("EffectsModule.forRoot", "provideEffects"),
+    ("EffectsModule.forFeature", "provideEffects"),
+    ("StoreDevtoolsModule.instrument", "provideStoreDevtools"),
+    ("ServiceWorkerModule.register", "provideServiceWorker"),
+    ("TranslateModule.forRoot", "provideTranslateService"),
+];
+
+/// スタンドアロンコンポーネントへの移行計画
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationPlan {
+    /// 実行する順（依存先のモジュールが先）
+    pub steps: Vec<MigrationStep>,
+}
+
+/// NgModule 1つを削除するまでの作業
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStep {
+    /// 1始まりの順番
+    pub order: usize,
+    /// 削除するNgModule
+    pub module: String,
+    /// プロジェクトルートからの相対パス
+    pub path: PathBuf,
+    /// `standalone: true`にするコンポーネント・ディレクティブ・パイプ
+    pub standalone: Vec<String>,
+    /// スタンドアロンにした宣言の`imports`に移すもの（ライブラリのモジュールと、移行済みのモジュールの代わりの宣言）
+    pub imports: Vec<String>,
+    /// このモジュールをimportしているモジュール
+    pub importers: Vec<String>,
+    /// 依存元がこのモジュールの代わりにimportするもの（`exports`していた宣言）
+    pub replaced_by: Vec<String>,
+    /// `provideX()`などに置き換えるプロバイダー
+    pub providers: Vec<ProviderMigration>,
+    /// `loadChildren`で遅延ロードされている（ルートを`loadComponent`か、routesの`loadChildren`に書き換える）
+    pub lazy: bool,
+    /// `BrowserModule`をimportしている起動時のモジュール（`bootstrapApplication()`に置き換える）
+    pub bootstrap: bool,
+    /// 循環している他のモジュール（先に循環を解消しないと、この順序どおりには移行できない）
+    pub cycle: Vec<String>,
+}
+
+/// モジュールが提供していたプロバイダーの移し先
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderMigration {
+    /// `imports`の`RouterModule.forRoot(routes)`や、`providers`のクラス
+    pub provider: String,
+    pub replacement: String,
+    pub target: ProviderTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderTarget {
+    /// `bootstrapApplication()`の`providers`
+    Application,
+    /// 遅延ロードするルートの`providers`
+    Route,
+}
+
+impl ProviderTarget {
+    pub fn label(self) -> &'static str {
+        match self {
+            ProviderTarget::Application => tr("application providers", "アプリケーションのproviders"),
+            ProviderTarget::Route => tr("route providers", "ルートのproviders"),
+        }
+    }
+}
+
+/// 依存グラフの葉から順に、NgModuleを1つずつ削除していく計画を作る
+///
+/// 強連結成分を依存先から順に並べるため、どのステップでも依存先のモジュールは移行済みになっている
+/// （循環しているモジュールは`cycle`に互いの名前を入れ、名前順に並べる）。
+pub fn plan(result: &AnalysisResult, project_path: &Path) -> MigrationPlan {
+    let graph = result.graph();
+    let petgraph = graph.petgraph();
+    let modules: HashMap<&str, &ModuleInfo> = result.modules
+        .iter()
+        .map(|m| (m.name.as_ref(), m))
+        .collect();
+    let mut importers: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    let mut lazy: HashSet<&str> = HashSet::new();
+    for (from, to, kind) in graph.edges() {
+        importers.entry(to).or_default().insert(from);
+        if kind == EdgeKind::Lazy {
+            lazy.insert(to);
+        }
+    }
+
+    // 移行済みのモジュール -> 依存元が代わりにimportする宣言
+    let mut replacements: HashMap<&str, Vec<String>> = HashMap::new();
+    let mut steps = Vec::new();
+    // tarjan_sccは依存先の成分から順に返す
+    for component in tarjan_scc(petgraph) {
+        let mut names: Vec<&str> = component
+            .iter()
+            .map(|&idx| petgraph[idx].name.as_ref())
+            .collect();
+        names.sort();
+        for &name in &names {
+            let module = modules[name];
+            let bootstrap = module.imports.iter().any(|entry| reference_name(entry) == "BrowserModule");
+            let is_lazy = lazy.contains(name);
+            let target = if is_lazy && !bootstrap { ProviderTarget::Route } else { ProviderTarget::Application };
+
+            let mut imports = BTreeSet::new();
+            let mut providers = Vec::new();
+            for entry in &module.imports {
+                if let Some(replacement) = provider_equivalent(entry) {
+                    providers.push(ProviderMigration { provider: entry.to_string(), replacement, target });
+                } else {
+                    imports.extend(replace(entry, &replacements));
+                }
+            }
+            for provider in &module.providers {
+                let replacement = match target {
+                    ProviderTarget::Application => format!("{} (or `providedIn: 'root'`)", provider),
+                    ProviderTarget::Route => provider.to_string(),
+                };
+                providers.push(ProviderMigration { provider: provider.to_string(), replacement, target });
+            }
+
+            let standalone: Vec<String> = module.declarations
+                .iter()
+                .map(|d| reference_name(d).to_string())
+                .collect();
+            let mut replaced_by = BTreeSet::new();
+            for entry in &module.exports {
+                replaced_by.extend(replace(entry, &replacements));
+            }
+            let replaced_by: Vec<String> = replaced_by.into_iter().collect();
+            replacements.insert(name, replaced_by.clone());
+
+            steps.push(MigrationStep {
+                order: steps.len() + 1,
+                module: name.to_string(),
+                path: module.path.strip_prefix(project_path).unwrap_or(&module.path).to_path_buf(),
+                standalone,
+                imports: imports.into_iter().collect(),
+                importers: importers
+                    .get(name)
+                    .map(|from| from.iter().map(|n| n.to_string()).collect())
+                    .unwrap_or_default(),
+                replaced_by,
+                providers,
+                lazy: is_lazy,
+                bootstrap,
+                cycle: names
+                    .iter()
+                    .filter(|&&other| other != name)
+                    .map(|n| n.to_string())
+                    .collect(),
+            });
+        }
+    }
+    MigrationPlan { steps }
+}
+
+/// `imports`・`exports`の要素を、移行後にimportするものに置き換える
+///
+/// 移行済みのプロジェクト内のモジュールはその`exports`に、それ以外（ライブラリのモジュールや宣言、循環して未移行のモジュール）はそのまま。
+fn replace(entry: &str, replacements: &HashMap<&str, Vec<String>>) -> Vec<String> {
+    match replacements.get(reference_name(entry)) {
+        Some(declarations) => declarations.clone(),
+        None => vec![reference_name(entry).to_string()],
+    }
+}
+
+/// プロバイダーを登録している`imports`の要素なら、その置き換え先
+fn provider_equivalent(entry: &str) -> Option<String> {
+    let name = reference_name(entry);
+    if let Some((_, replacement)) = PROVIDER_MODULES.iter().find(|(module, _)| *module == name) {
+        return Some(replacement.to_string());
+    }
+    let (call, rest) = entry.split_once('(')?;
+    let call = call.trim();
+    let args = rest.rsplit_once(')').map_or(rest, |(args, _)| args).trim();
+    if call == "RouterModule.forChild" {
+        return Some(format!("`loadChildren` of the lazy route returning {}", args));
+    }
+    match PROVIDER_FUNCTIONS.iter().find(|(function, _)| *function == call) {
+        Some((_, provide)) => Some(format!("{}({})", provide, args)),
+        None => Some(format!("importProvidersFrom({})", entry)),
+    }
+}
@@ -0,0 +1,198 @@
+use serde::{ Deserialize, Serialize };
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::graph::reference_name;
+use crate::i18n::Lang;
+use crate::{ DependencyViolation, ModuleInfo, ModuleType, Name, SourceLocation, ViolationType };
+
+/// 違反を解消するための機械的なリファクタリング（codemodツールがそのまま実行できる単位）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Refactoring {
+    /// `module`の`providers`から`service`を外し、`@Injectable({ providedIn: 'root' })`で提供する
+    ProvideInRoot {
+        service: Name,
+        module: Name,
+    },
+    /// `declarables`を`from`の`declarations`・`exports`から`to`へ移す
+    MoveDeclarables {
+        declarables: Vec<Name>,
+        from: Name,
+        to: Name,
+    },
+    /// `importer`の`imports`から`module`を外し、`loadChildren`で遅延ロードするルートにする
+    LazyRoute {
+        module: Name,
+        importer: Name,
+    },
+    /// `module`の`imports`から`import`を外す
+    RemoveImport {
+        module: Name,
+        import: Name,
+    },
+}
+
+impl Refactoring {
+    /// JSONの`action`と同じ名前
+    pub fn action(&self) -> &'static str {
+        match self {
+            Refactoring::ProvideInRoot { .. } => "provide_in_root",
+            Refactoring::MoveDeclarables { .. } => "move_declarables",
+            Refactoring::LazyRoute { .. } => "lazy_route",
+            Refactoring::RemoveImport { .. } => "remove_import",
+        }
+    }
+
+    /// レポートに表示する説明（[`Lang`]で選んだ言語、英語なら`Display`と同じ）
+    pub fn localized(&self) -> String {
+        if Lang::current() == Lang::En {
+            return self.to_string();
+        }
+        match self {
+            Refactoring::ProvideInRoot { service, module } => {
+                format!("{}を{}のprovidersから外し、providedIn: 'root'にする", service, module)
+            }
+            Refactoring::MoveDeclarables { declarables, from, to } => {
+                format!("{}を{}から{}へ移す", declarables.join(", "), from, to)
+            }
+            Refactoring::LazyRoute { module, importer } => {
+                format!("{}での{}の直接のimportを遅延ロードのルートに置き換える", importer, module)
+            }
+            Refactoring::RemoveImport { module, import } => format!("{}のimportsから{}を外す", module, import),
+        }
+    }
+}
+
+impl fmt::Display for Refactoring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refactoring::ProvideInRoot { service, module } => {
+                write!(f, "move {} from {} providers to providedIn: 'root'", service, module)
+            }
+            Refactoring::MoveDeclarables { declarables, from, to } => {
+                write!(f, "move {} from {} to {}", declarables.join(", "), from, to)
+            }
+            Refactoring::LazyRoute { module, importer } => {
+                write!(f, "replace direct import of {} in {} with a lazy route", module, importer)
+            }
+            Refactoring::RemoveImport { module, import } => write!(f, "remove {} from the imports of {}", import, module),
+        }
+    }
+}
+
+/// 違反1件に対する提案
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub violation_type: ViolationType,
+    pub from_module: Name,
+    pub to_module: Name,
+    #[serde(flatten)]
+    pub refactoring: Refactoring,
+    /// `refactoring`を人が読める形にしたもの
+    #[serde(default)]
+    pub description: String,
+    /// 依存元のファイル内で依存先を参照している場所
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
+}
+
+/// 依存ルールの違反と循環のそれぞれについて、解消するためのリファクタリングを提案する
+///
+/// 依存先が公開しているものから、依存を外すのに何を動かせばよいかを判断する。
+/// - `exports`している宣言があれば、依存元がSharedモジュールならそこへ、それ以外はSharedモジュールへ移す
+/// - `providers`のサービスは`providedIn: 'root'`にする
+/// - そのうえでimportを外す（何も公開していないFeatureモジュールは遅延ロードのルートにする）
+pub fn suggest(
+    modules: &[ModuleInfo],
+    violations: &[DependencyViolation],
+    cycles: &[Vec<Name>],
+    cycle_locations: &[Vec<Option<SourceLocation>>]
+) -> Vec<Suggestion> {
+    let by_name: HashMap<&str, &ModuleInfo> = modules
+        .iter()
+        .map(|m| (m.name.as_ref(), m))
+        .collect();
+    // 宣言の移動先（Sharedモジュールがなければ新しく作る想定の名前）
+    let shared = modules
+        .iter()
+        .filter(|m| m.module_type == ModuleType::Shared)
+        .map(|m| m.name.clone())
+        .min()
+        .unwrap_or_else(|| Name::from("SharedModule"));
+
+    let mut suggestions = Vec::new();
+    let mut add = |violation_type: &ViolationType, from: &str, to: &str, location: Option<&SourceLocation>| {
+        let (Some(from), Some(to)) = (by_name.get(from), by_name.get(to)) else {
+            return;
+        };
+        for refactoring in refactorings(from, to, &shared) {
+            suggestions.push(Suggestion {
+                violation_type: violation_type.clone(),
+                from_module: from.name.clone(),
+                to_module: to.name.clone(),
+                description: refactoring.to_string(),
+                refactoring,
+                location: location.cloned(),
+            });
+        }
+    };
+
+    for violation in violations {
+        add(&violation.violation_type, &violation.from_module, &violation.to_module, violation.location.as_ref());
+    }
+    for (cycle, locations) in cycles.iter().zip(cycle_locations) {
+        let index = breaking_edge(cycle, &by_name);
+        let next = &cycle[(index + 1) % cycle.len()];
+        add(&ViolationType::CircularDependency, &cycle[index], next, locations.get(index).and_then(Option::as_ref));
+    }
+    suggestions
+}
+
+fn refactorings(from: &ModuleInfo, to: &ModuleInfo, shared: &Name) -> Vec<Refactoring> {
+    let declarations: Vec<&str> = to.declarations
+        .iter()
+        .map(|d| reference_name(d))
+        .collect();
+    let declarables: Vec<Name> = to.exports
+        .iter()
+        .filter(|e| declarations.contains(&e.as_ref()))
+        .cloned()
+        .collect();
+    // `{ provide: ..., useClass: ... }`のような指定は機械的に移せない
+    let services: Vec<&Name> = to.providers
+        .iter()
+        .filter(|p| reference_name(p) == p.as_ref())
+        .collect();
+
+    let mut refactorings = Vec::new();
+    if !declarables.is_empty() {
+        let destination = if from.module_type == ModuleType::Shared { from.name.clone() } else { shared.clone() };
+        refactorings.push(Refactoring::MoveDeclarables { declarables, from: to.name.clone(), to: destination });
+    }
+    refactorings.extend(
+        services.iter().map(|service| Refactoring::ProvideInRoot { service: (*service).clone(), module: to.name.clone() })
+    );
+    if refactorings.is_empty() && to.module_type == ModuleType::Feature {
+        refactorings.push(Refactoring::LazyRoute { module: to.name.clone(), importer: from.name.clone() });
+    } else {
+        // 宣言とサービスを移せば、importする理由はなくなる
+        // `imports`にはクラス名で書かれている（同名のモジュールを修飾した名前ではない）
+        refactorings.push(Refactoring::RemoveImport { module: from.name.clone(), import: to.class_name().into() });
+    }
+    refactorings
+}
+
+/// 循環を断ち切る依存の位置（i番目からi+1番目）
+///
+/// Core・SharedからFeatureへの依存があればそれを、なければ循環を閉じる最後の依存を選ぶ。
+fn breaking_edge(cycle: &[Name], by_name: &HashMap<&str, &ModuleInfo>) -> usize {
+    let layer = |name: &Name| by_name.get(name.as_ref()).map(|m| &m.module_type);
+    (0..cycle.len())
+        .find(|&i| {
+            matches!(layer(&cycle[i]), Some(ModuleType::Core | ModuleType::Shared)) &&
+                layer(&cycle[(i + 1) % cycle.len()]) == Some(&ModuleType::Feature)
+        })
+        .unwrap_or(cycle.len() - 1)
+}
@@ -0,0 +1,92 @@
+use unicode_width::UnicodeWidthStr;
+
+/// 列の寄せ方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    /// 数値の列
+    Right,
+}
+
+/// 列をそろえたテキストの表（見出しの行と、列の間を2文字空けた行）
+///
+/// 幅は表示幅で数えるため、日本語などの全角文字を含むセルもそろう。
+/// 最後の列は詰めないので、長い説明やパスは最後の列に置く。
+///
+/// comfy-tableやtabledを使わないのは、罫線も余白もなく最後の列を詰めないこの形式にするには、
+/// それらの装飾をほぼすべて無効にする設定が必要で、残る処理は表示幅での桁そろえだけになるため。
+/// 見出しを太字にするために見出しの行を別に返す（[`lines`](Self::lines)）ことも、これらの表では難しい。
+#[derive(Debug, Clone)]
+pub struct Table {
+    columns: Vec<(String, Align)>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<S: Into<String>>(columns: impl IntoIterator<Item = (S, Align)>) -> Self {
+        Self {
+            columns: columns
+                .into_iter()
+                .map(|(header, align)| (header.into(), align))
+                .collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// 行を追加する（セルの数は列の数と同じ）
+    pub fn row(&mut self, cells: Vec<String>) {
+        debug_assert_eq!(cells.len(), self.columns.len());
+        self.rows.push(cells);
+    }
+
+    /// 見出しの行と各行（末尾の空白は除く）
+    pub fn lines(&self) -> (String, Vec<String>) {
+        let widths: Vec<usize> = self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, (header, _))| {
+                self.rows
+                    .iter()
+                    .map(|cells| cells[i].width())
+                    .chain(std::iter::once(header.width()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let format_line = |cells: &[String]| {
+            cells
+                .iter()
+                .zip(&self.columns)
+                .zip(&widths)
+                .map(|((cell, (_, align)), &width)| {
+                    let padding = " ".repeat(width - cell.width());
+                    match align {
+                        Align::Left => format!("{}{}", cell, padding),
+                        Align::Right => format!("{}{}", padding, cell),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let headers: Vec<String> = self.columns
+            .iter()
+            .map(|(header, _)| header.clone())
+            .collect();
+        (format_line(&headers), self.rows.iter().map(|cells| format_line(cells)).collect())
+    }
+
+    pub fn render(&self) -> String {
+        let (header, rows) = self.lines();
+        let mut out = header;
+        out.push('\n');
+        for row in rows {
+            out.push_str(&row);
+            out.push('\n');
+        }
+        out
+    }
+}
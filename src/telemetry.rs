@@ -0,0 +1,36 @@
+use anyhow::Result;
+use colored::*;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// 解析フェーズのspanをOTLP（HTTP）で送信するエクスポーター
+///
+/// 送信先やヘッダーは`OTEL_EXPORTER_OTLP_ENDPOINT`などの標準の環境変数で指定する。
+pub struct Telemetry {
+    provider: SdkTracerProvider,
+}
+
+impl Telemetry {
+    pub fn init() -> Result<Self> {
+        let exporter = SpanExporter::builder().with_http().build()?;
+        // バッチの送信は専用スレッドで行うので、tokioのワーカーをブロックしない
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(env!("CARGO_PKG_NAME")).build())
+            .build();
+        let tracer = provider.tracer(env!("CARGO_PKG_NAME"));
+        tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer)).try_init()?;
+        Ok(Self { provider })
+    }
+
+    /// 未送信のspanを送り切ってから終了する
+    pub fn shutdown(&self) {
+        if let Err(err) = self.provider.shutdown() {
+            eprintln!("{} failed to export traces: {}", "warning:".yellow().bold(), err);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{ Deserialize, Serialize };
+
+use crate::AnalysisResult;
+
+/// ある時点のメトリクス（`trend`の各リビジョンと、HTMLレポートの履歴で共通）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendPoint {
+    /// 解析したコミット（レポートの実行ごとの履歴では`None`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    /// UNIXタイムスタンプ（コミットの日時、またはレポートを実行した日時）
+    pub timestamp: u64,
+    pub total_modules: usize,
+    pub violations: usize,
+    pub cycles: usize,
+    pub coupling_factor: f32,
+    pub health_score: f32,
+}
+
+impl TrendPoint {
+    pub fn new(result: &AnalysisResult, revision: Option<String>, timestamp: u64) -> Self {
+        Self {
+            revision,
+            timestamp,
+            total_modules: result.metrics.total_modules,
+            violations: result.dependency_violations.len(),
+            cycles: result.circular_dependencies.len(),
+            coupling_factor: result.metrics.coupling_factor,
+            health_score: result.metrics.health_score,
+        }
+    }
+}
+
+/// `timestamp`が`every`秒以上離れるようにコミットを間引く（最新のコミットは必ず残す）
+///
+/// `commits`は（リビジョン, UNIXタイムスタンプ）で、日時順に並べ直してから間引く。
+pub fn sample(commits: &[(String, u64)], every: u64) -> Vec<(String, u64)> {
+    let mut commits = commits.to_vec();
+    commits.sort_by_key(|(_, timestamp)| *timestamp);
+    let mut sampled: Vec<(String, u64)> = Vec::new();
+    for (i, (revision, timestamp)) in commits.iter().enumerate() {
+        let due = sampled.last().is_none_or(|(_, last)| *timestamp >= last + every);
+        if due || i + 1 == commits.len() {
+            sampled.push((revision.clone(), *timestamp));
+        }
+    }
+    sampled
+}
+
+/// 時系列をCSVにする（1行目はヘッダー）
+pub fn render_csv(points: &[TrendPoint]) -> String {
+    let mut csv = String::from("revision,timestamp,total_modules,violations,cycles,coupling_factor,health_score\n");
+    for point in points {
+        csv.push_str(
+            &format!(
+                "{},{},{},{},{},{:.4},{:.2}\n",
+                point.revision.as_deref().unwrap_or(""),
+                point.timestamp,
+                point.total_modules,
+                point.violations,
+                point.cycles,
+                point.coupling_factor,
+                point.health_score
+            )
+        );
+    }
+    csv
+}
@@ -0,0 +1,369 @@
+use anyhow::Result;
+use ratatui::crossterm::event::{ self, Event, KeyCode, KeyEventKind };
+use ratatui::layout::{ Constraint, Direction, Layout, Rect };
+use ratatui::style::{ Color, Modifier, Style };
+use ratatui::text::{ Line, Span };
+use ratatui::widgets::{ Block, Borders, List, ListItem, ListState, Paragraph, Wrap };
+use ratatui::{ DefaultTerminal, Frame };
+
+use angular_module_analyzer::graph::ModuleGraph;
+use angular_module_analyzer::{ AnalysisResult, ModuleInfo, ModuleType };
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Modules,
+    Dependencies,
+    Dependents,
+}
+
+struct App<'a> {
+    result: &'a AnalysisResult,
+    graph: ModuleGraph<'a>,
+    /// 検索文字列で絞り込んだモジュール（`result.modules`のインデックス）
+    visible: Vec<usize>,
+    filter: String,
+    searching: bool,
+    focus: Focus,
+    modules_state: ListState,
+    dependencies_state: ListState,
+    dependents_state: ListState,
+    /// 依存先へジャンプする前に選択していたモジュール
+    history: Vec<usize>,
+}
+
+impl<'a> App<'a> {
+    fn new(result: &'a AnalysisResult) -> Self {
+        let mut app = Self {
+            result,
+            graph: result.graph(),
+            visible: Vec::new(),
+            filter: String::new(),
+            searching: false,
+            focus: Focus::Modules,
+            modules_state: ListState::default(),
+            dependencies_state: ListState::default(),
+            dependents_state: ListState::default(),
+            history: Vec::new(),
+        };
+        app.apply_filter();
+        app
+    }
+
+    fn apply_filter(&mut self) {
+        let filter = self.filter.to_lowercase();
+        self.visible = (0..self.result.modules.len())
+            .filter(|&i| self.result.modules[i].name.to_lowercase().contains(&filter))
+            .collect();
+        self.visible.sort_by(|&a, &b| self.result.modules[a].name.cmp(&self.result.modules[b].name));
+        self.modules_state.select(if self.visible.is_empty() { None } else { Some(0) });
+        self.reset_detail_lists();
+    }
+
+    fn reset_detail_lists(&mut self) {
+        self.dependencies_state.select(Some(0));
+        self.dependents_state.select(Some(0));
+    }
+
+    fn selected(&self) -> Option<&'a ModuleInfo> {
+        let result = self.result;
+        self.modules_state
+            .selected()
+            .and_then(|i| self.visible.get(i))
+            .map(|&i| &result.modules[i])
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        self.modules_state.selected().and_then(|i| self.visible.get(i).copied())
+    }
+
+    fn dependencies(&self) -> Vec<&'a str> {
+        self.selected().map_or_else(Vec::new, |m| self.graph.dependencies_of(&m.name))
+    }
+
+    fn dependents(&self) -> Vec<&'a str> {
+        self.selected().map_or_else(Vec::new, |m| self.graph.dependents_of(&m.name))
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let len = match self.focus {
+            Focus::Modules => self.visible.len(),
+            Focus::Dependencies => self.dependencies().len(),
+            Focus::Dependents => self.dependents().len(),
+        };
+        let state = match self.focus {
+            Focus::Modules => &mut self.modules_state,
+            Focus::Dependencies => &mut self.dependencies_state,
+            Focus::Dependents => &mut self.dependents_state,
+        };
+        if len == 0 {
+            return;
+        }
+        let current = state.selected().unwrap_or(0) as isize;
+        state.select(Some((current + delta).clamp(0, len as isize - 1) as usize));
+        if self.focus == Focus::Modules {
+            self.reset_detail_lists();
+        }
+    }
+
+    fn jump_to(&mut self, name: &str) {
+        let Some(target) = self.result.modules.iter().position(|m| *m.name == *name) else {
+            return;
+        };
+        if let Some(current) = self.selected_index() {
+            self.history.push(current);
+        }
+        self.select_module(target);
+    }
+
+    fn select_module(&mut self, target: usize) {
+        if !self.visible.contains(&target) {
+            self.filter.clear();
+            self.apply_filter();
+        }
+        let position = self.visible.iter().position(|&i| i == target);
+        self.modules_state.select(position);
+        self.focus = Focus::Modules;
+        self.reset_detail_lists();
+    }
+
+    fn follow_selected_edge(&mut self) {
+        let (names, state) = match self.focus {
+            Focus::Modules => {
+                return;
+            }
+            Focus::Dependencies => (self.dependencies(), &self.dependencies_state),
+            Focus::Dependents => (self.dependents(), &self.dependents_state),
+        };
+        if let Some(name) = state.selected().and_then(|i| names.get(i)) {
+            self.jump_to(name);
+        }
+    }
+
+    fn go_back(&mut self) {
+        if let Some(previous) = self.history.pop() {
+            self.select_module(previous);
+        }
+    }
+
+    /// キー入力を処理し、終了すべき場合はfalseを返す
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        if self.searching {
+            match code {
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.filter.clear();
+                    self.apply_filter();
+                }
+                KeyCode::Enter => {
+                    self.searching = false;
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.apply_filter();
+                }
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.apply_filter();
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                return false;
+            }
+            KeyCode::Char('/') => {
+                self.searching = true;
+                self.focus = Focus::Modules;
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Modules => Focus::Dependencies,
+                    Focus::Dependencies => Focus::Dependents,
+                    Focus::Dependents => Focus::Modules,
+                };
+            }
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => self.follow_selected_edge(),
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => self.go_back(),
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(
+            frame.area()
+        );
+        let [list_area, details_area] = Layout::horizontal([
+            Constraint::Percentage(35),
+            Constraint::Percentage(65),
+        ]).areas(main);
+
+        self.draw_module_list(frame, list_area);
+        self.draw_details(frame, details_area);
+
+        let help = if self.searching {
+            format!("Search: {}▏ (Enter: apply, Esc: clear)", self.filter)
+        } else {
+            "q: quit  /: search  j/k: move  Tab: switch pane  Enter: open  Backspace: back".to_string()
+        };
+        frame.render_widget(Paragraph::new(help).style(Style::default().fg(Color::DarkGray)), status);
+    }
+
+    fn draw_module_list(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self.visible
+            .iter()
+            .map(|&i| {
+                let module = &self.result.modules[i];
+                ListItem::new(
+                    Line::from(vec![
+                        Span::styled(type_marker(&module.module_type), type_style(&module.module_type)),
+                        Span::raw(" "),
+                        Span::raw(module.name.as_ref()),
+                    ])
+                )
+            })
+            .collect();
+        let title = if self.filter.is_empty() {
+            format!("Modules ({})", self.visible.len())
+        } else {
+            format!("Modules ({}) [/{}]", self.visible.len(), self.filter)
+        };
+        let list = List::new(items)
+            .block(pane_block(title, self.focus == Focus::Modules))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.modules_state);
+    }
+
+    fn draw_details(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(module) = self.selected() else {
+            frame.render_widget(Paragraph::new("No module selected").block(pane_block("Details".to_string(), false)), area);
+            return;
+        };
+
+        let [info_area, edges_area, violations_area] = Layout::vertical([
+            Constraint::Length(8),
+            Constraint::Min(5),
+            Constraint::Length(6),
+        ]).areas(area);
+
+        let info = vec![
+            Line::from(vec![Span::styled(module.name.as_ref(), Style::default().add_modifier(Modifier::BOLD))]),
+            Line::from(format!("Path: {}", module.path.display())),
+            Line::from(vec![
+                Span::raw("Type: "),
+                Span::styled(module.module_type.to_string(), type_style(&module.module_type)),
+            ]),
+            Line::from(format!("Imports: {}", module.imports.join(", "))),
+            Line::from(format!("Exports: {}", module.exports.join(", "))),
+            Line::from(format!("Declarations: {}", module.declarations.len())),
+        ];
+        frame.render_widget(
+            Paragraph::new(info).block(pane_block("Details".to_string(), false)).wrap(Wrap { trim: true }),
+            info_area
+        );
+
+        let [dependencies_area, dependents_area] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .areas(edges_area);
+        let dependencies = self.dependencies();
+        let dependents = self.dependents();
+        render_name_list(
+            frame,
+            dependencies_area,
+            format!("Dependencies ({})", dependencies.len()),
+            &dependencies,
+            self.focus == Focus::Dependencies,
+            &mut self.dependencies_state
+        );
+        render_name_list(
+            frame,
+            dependents_area,
+            format!("Dependents ({})", dependents.len()),
+            &dependents,
+            self.focus == Focus::Dependents,
+            &mut self.dependents_state
+        );
+
+        let violations: Vec<ListItem> = self.result.dependency_violations
+            .iter()
+            .filter(|v| v.from_module == module.name || v.to_module == module.name)
+            .map(|v| {
+                ListItem::new(v.to_string()).style(
+                    Style::default().fg(Color::Red)
+                )
+            })
+            .collect();
+        let title = format!("Violations ({})", violations.len());
+        frame.render_widget(List::new(violations).block(pane_block(title, false)), violations_area);
+    }
+}
+
+fn render_name_list(
+    frame: &mut Frame,
+    area: Rect,
+    title: String,
+    names: &[&str],
+    focused: bool,
+    state: &mut ListState
+) {
+    let items: Vec<ListItem> = names
+        .iter()
+        .map(|name| ListItem::new(name.to_string()))
+        .collect();
+    let mut list = List::new(items).block(pane_block(title, focused));
+    if focused {
+        list = list.highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    }
+    frame.render_stateful_widget(list, area, state);
+}
+
+fn pane_block(title: String, focused: bool) -> Block<'static> {
+    let style = if focused { Style::default().fg(Color::Cyan) } else { Style::default() };
+    Block::default().borders(Borders::ALL).border_style(style).title(title)
+}
+
+fn type_marker(module_type: &ModuleType) -> &'static str {
+    match module_type {
+        ModuleType::Core => "[C]",
+        ModuleType::Shared => "[S]",
+        ModuleType::Feature => "[F]",
+        _ => "[?]",
+    }
+}
+
+fn type_style(module_type: &ModuleType) -> Style {
+    let color = match module_type {
+        ModuleType::Core => Color::Blue,
+        ModuleType::Shared => Color::Green,
+        ModuleType::Feature => Color::Yellow,
+        _ => Color::Gray,
+    };
+    Style::default().fg(color)
+}
+
+/// 解析結果をインタラクティブに閲覧するTUIを起動する
+pub fn run(result: &AnalysisResult) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let outcome = event_loop(&mut terminal, App::new(result));
+    ratatui::restore();
+    outcome
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, mut app: App) -> Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if
+            let Event::Key(key) = event::read()? &&
+            key.kind == KeyEventKind::Press &&
+            !app.handle_key(key.code)
+        {
+            return Ok(());
+        }
+    }
+}
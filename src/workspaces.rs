@@ -0,0 +1,347 @@
+use globset::{ GlobBuilder, GlobSet, GlobSetBuilder };
+use serde::{ Deserialize, Serialize };
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::graph::EdgeKind;
+use crate::{
+    AnalysisResult,
+    AnalyzerError,
+    DEFAULT_IGNORED_DIRS,
+    IMPORT_REGEX,
+    ModuleInfo,
+    Name,
+    Position,
+    Result,
+    SourceLocation,
+    normalize_path,
+    source,
+};
+
+/// pnpmのワークスペース定義ファイル
+pub const PNPM_WORKSPACE_FILE_NAME: &str = "pnpm-workspace.yaml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    Pnpm,
+    Yarn,
+    Npm,
+}
+
+impl PackageManager {
+    pub fn label(self) -> &'static str {
+        match self {
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Yarn => "yarn",
+            PackageManager::Npm => "npm",
+        }
+    }
+}
+
+/// ワークスペースのパッケージ（`package.json`のあるディレクトリ）
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspacePackage {
+    pub name: String,
+    pub root: PathBuf,
+    /// `exports`で公開しているサブパス（`exports`がなければ`.`のみ）
+    pub entry_points: Vec<String>,
+}
+
+impl WorkspacePackage {
+    /// `./lib/x`のようなサブパスを`exports`が公開しているか（`./*`のようなパターンも照合する）
+    fn exposes(&self, subpath: &str) -> bool {
+        self.entry_points.iter().any(|entry| {
+            if let Some((prefix, suffix)) = entry.split_once('*') {
+                return subpath.len() >= prefix.len() + suffix.len() &&
+                    subpath.starts_with(prefix) &&
+                    subpath.ends_with(suffix);
+            }
+            // 古い形式のディレクトリ指定（`"./features/"`）
+            entry == subpath || (entry.ends_with('/') && subpath.starts_with(entry.as_str()))
+        })
+    }
+}
+
+/// pnpm・yarn・npmのワークスペース
+#[derive(Debug, Clone, Serialize)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub manager: PackageManager,
+    /// ルートが深い順
+    pub packages: Vec<WorkspacePackage>,
+}
+
+#[derive(Deserialize)]
+struct PnpmWorkspace {
+    #[serde(default)]
+    packages: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Workspaces {
+    Patterns(Vec<String>),
+    /// yarnの`{ "packages": [...], "nohoist": [...] }`
+    Object {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+#[derive(Deserialize)]
+struct PackageJson {
+    name: Option<String>,
+    workspaces: Option<Workspaces>,
+    exports: Option<serde_json::Value>,
+}
+
+impl Workspace {
+    /// `project_path`から親へたどり、`pnpm-workspace.yaml`か`workspaces`のある`package.json`を探す
+    pub fn detect(project_path: &Path) -> Result<Option<Self>> {
+        let Ok(start) = project_path.canonicalize() else {
+            return Ok(None);
+        };
+        for dir in start.ancestors() {
+            let pnpm = dir.join(PNPM_WORKSPACE_FILE_NAME);
+            if pnpm.is_file() {
+                let content = fs::read_to_string(&pnpm).map_err(|err| AnalyzerError::io(&pnpm, err))?;
+                let workspace: PnpmWorkspace = serde_yaml
+                    ::from_str(&content)
+                    .map_err(|err| AnalyzerError::parse(&pnpm, err))?;
+                return Ok(Some(Self::load(dir, PackageManager::Pnpm, &workspace.packages)?));
+            }
+
+            let package_json = dir.join("package.json");
+            if !package_json.is_file() {
+                continue;
+            }
+            let Some(workspaces) = read_package_json(&package_json)?.workspaces else {
+                continue;
+            };
+            let patterns = match workspaces {
+                Workspaces::Patterns(patterns) | Workspaces::Object { packages: patterns } => patterns,
+            };
+            let manager = if dir.join("yarn.lock").exists() { PackageManager::Yarn } else { PackageManager::Npm };
+            return Ok(Some(Self::load(dir, manager, &patterns)?));
+        }
+        Ok(None)
+    }
+
+    fn load(root: &Path, manager: PackageManager, patterns: &[String]) -> Result<Self> {
+        let glob_set = |negated: bool| -> Result<GlobSet> {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in patterns.iter().filter(|pattern| pattern.starts_with('!') == negated) {
+                let pattern = pattern.trim_start_matches('!').trim_start_matches("./").trim_end_matches('/');
+                let glob = GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|err| AnalyzerError::parse(root, err))?;
+                builder.add(glob);
+            }
+            builder.build().map_err(|err| AnalyzerError::parse(root, err))
+        };
+        let (included, excluded) = (glob_set(false)?, glob_set(true)?);
+
+        let mut walker = ignore::WalkBuilder::new(root);
+        walker.hidden(false).require_git(false);
+        walker.filter_entry(|entry| !DEFAULT_IGNORED_DIRS.iter().any(|dir| entry.file_name() == *dir));
+
+        let mut packages = Vec::new();
+        for entry in walker.build().filter_map(|e| e.ok()) {
+            if entry.file_name() != "package.json" {
+                continue;
+            }
+            let path = entry.path();
+            let Some(directory) = path.parent().filter(|dir| *dir != root) else {
+                continue;
+            };
+            let relative = directory.strip_prefix(root).unwrap_or(directory);
+            if !included.is_match(relative) || excluded.is_match(relative) {
+                continue;
+            }
+            let package = read_package_json(path)?;
+            packages.push(WorkspacePackage {
+                name: package.name.unwrap_or_else(|| relative.to_string_lossy().into_owned()),
+                root: directory.to_path_buf(),
+                entry_points: entry_points(package.exports.as_ref()),
+            });
+        }
+        // 入れ子のパッケージでは内側を優先する
+        packages.sort_by(|a, b| {
+            b.root.components().count().cmp(&a.root.components().count()).then_with(|| a.name.cmp(&b.name))
+        });
+        Ok(Self { root: root.to_path_buf(), manager, packages })
+    }
+
+    /// ファイルを含むパッケージ（入れ子の場合は内側）
+    pub fn package_of(&self, file: &Path) -> Option<&WorkspacePackage> {
+        let file = file.canonicalize().ok()?;
+        self.packages.iter().find(|package| file.starts_with(&package.root))
+    }
+
+    /// モジュールをパッケージに振り分け、パッケージ内とパッケージ間の依存、エントリーポイントを迂回するimportを集める
+    pub fn report(&self, result: &AnalysisResult) -> WorkspaceReport {
+        let package_of: BTreeMap<&str, Option<&WorkspacePackage>> = result.modules
+            .iter()
+            .map(|m| (m.name.as_ref(), self.package_of(&m.path)))
+            .collect();
+        let package_name = |module: &str| package_of[module].map(|package| package.name.clone());
+
+        let mut packages: BTreeMap<Option<String>, Vec<Name>> = self.packages
+            .iter()
+            .map(|package| (Some(package.name.clone()), Vec::new()))
+            .collect();
+        for module in &result.modules {
+            packages.entry(package_name(&module.name)).or_default().push(module.name.clone());
+        }
+        let packages = packages
+            .into_iter()
+            .map(|(package, mut modules)| {
+                modules.sort();
+                PackageModules { package, modules }
+            })
+            .collect();
+
+        let mut intra_package = Vec::new();
+        let mut cross_package = Vec::new();
+        for (from, to, kind) in result.graph().edges() {
+            let edge = PackageEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                from_package: package_name(from),
+                to_package: package_name(to),
+                lazy: kind == EdgeKind::Lazy,
+            };
+            if edge.from_package == edge.to_package {
+                intra_package.push(edge);
+            } else {
+                cross_package.push(edge);
+            }
+        }
+
+        let mut bypasses: Vec<EntryPointBypass> = result.modules
+            .iter()
+            .flat_map(|module| self.bypasses(module, package_of[module.name.as_ref()]))
+            .collect();
+        bypasses.sort_by(|a, b| a.module.cmp(&b.module).then_with(|| a.location.cmp(&b.location)));
+
+        WorkspaceReport {
+            root: self.root.clone(),
+            manager: self.manager,
+            packages,
+            intra_package,
+            cross_package,
+            bypasses,
+        }
+    }
+
+    /// 他のパッケージの内部ファイルを直接参照しているimport
+    ///
+    /// パッケージ名の後に`exports`で公開されていないサブパスを付けたものと、
+    /// 相対パスで他のパッケージのディレクトリに入り込んでいるものを対象にする。
+    fn bypasses(&self, module: &ModuleInfo, own: Option<&WorkspacePackage>) -> Vec<EntryPointBypass> {
+        let is_other = |package: &WorkspacePackage| own.is_none_or(|own| own.root != package.root);
+        let mut bypasses = Vec::new();
+
+        for specifier in &module.dependencies {
+            let deep_import = self.packages.iter().find_map(|package| {
+                let subpath = specifier.strip_prefix(package.name.as_str())?.strip_prefix('/')?;
+                Some((package, format!("./{}", subpath)))
+            });
+            if let Some((package, subpath)) = deep_import && is_other(package) && !package.exposes(&subpath) {
+                bypasses.push(EntryPointBypass {
+                    module: module.name.clone(),
+                    package: package.name.clone(),
+                    specifier: specifier.to_string(),
+                    location: module.location_of(specifier),
+                });
+            }
+        }
+
+        let Some(directory) = module.path.canonicalize().ok().and_then(|path| path.parent().map(Path::to_path_buf)) else {
+            return bypasses;
+        };
+        let Ok(content) = source::read(&module.path, Some(source::DEFAULT_MAX_FILE_SIZE)) else {
+            return bypasses;
+        };
+        for specifier in IMPORT_REGEX.captures_iter(&content).map(|cap| cap.get(1).unwrap()) {
+            if !specifier.as_str().starts_with('.') {
+                continue;
+            }
+            let target = normalize_path(&directory.join(specifier.as_str()));
+            let package = self.packages.iter().find(|package| target.starts_with(&package.root));
+            if let Some(package) = package && is_other(package) {
+                let position = Position::at(&content, specifier.start());
+                bypasses.push(EntryPointBypass {
+                    module: module.name.clone(),
+                    package: package.name.clone(),
+                    specifier: specifier.as_str().to_string(),
+                    location: Some(SourceLocation {
+                        file: module.path.clone(),
+                        line: position.line,
+                        column: position.column,
+                    }),
+                });
+            }
+        }
+        bypasses
+    }
+}
+
+fn read_package_json(path: &Path) -> Result<PackageJson> {
+    let content = fs::read_to_string(path).map_err(|err| AnalyzerError::io(path, err))?;
+    serde_json::from_str(&content).map_err(|err| AnalyzerError::parse(path, err))
+}
+
+/// `exports`のキーのうちサブパス（`.`で始まるもの）。条件だけの指定や文字列は`.`のみを公開する
+fn entry_points(exports: Option<&serde_json::Value>) -> Vec<String> {
+    let subpaths: Vec<String> = exports
+        .and_then(|exports| exports.as_object())
+        .map(|exports| {
+            exports
+                .iter()
+                .filter(|(key, target)| key.starts_with('.') && !target.is_null())
+                .map(|(key, _)| key.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    if subpaths.is_empty() { vec![".".to_string()] } else { subpaths }
+}
+
+/// パッケージに属するモジュール（`package`が`None`のものはどのパッケージにも属さない）
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageModules {
+    pub package: Option<String>,
+    pub modules: Vec<Name>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageEdge {
+    pub from: String,
+    pub to: String,
+    pub from_package: Option<String>,
+    pub to_package: Option<String>,
+    pub lazy: bool,
+}
+
+/// 公開されたエントリーポイントを通さずに他のパッケージを参照しているimport
+#[derive(Debug, Clone, Serialize)]
+pub struct EntryPointBypass {
+    pub module: Name,
+    pub package: String,
+    pub specifier: String,
+    pub location: Option<SourceLocation>,
+}
+
+/// ワークスペースのパッケージを境界にしたモジュール依存の内訳
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceReport {
+    pub root: PathBuf,
+    pub manager: PackageManager,
+    pub packages: Vec<PackageModules>,
+    pub intra_package: Vec<PackageEdge>,
+    pub cross_package: Vec<PackageEdge>,
+    pub bypasses: Vec<EntryPointBypass>,
+}
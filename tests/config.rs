@@ -0,0 +1,129 @@
+use std::path::Path;
+
+use angular_module_analyzer::AnalyzerError;
+use angular_module_analyzer::config::{ self, Config };
+
+/// 検証で見つかった問題を`行:列: メッセージ`の形式で返す
+fn issues(content: &str) -> Vec<String> {
+    config
+        ::validate(content)
+        .iter()
+        .map(|issue| issue.to_string())
+        .collect()
+}
+
+#[test]
+fn accepts_valid_config() {
+    let content =
+        r#"
+[classification]
+core = ["src/app/core/**"]
+shared = ["src/app/shared/**"]
+
+[thresholds]
+max_violations = 0
+
+[rules]
+AA003 = "warn"
+AA004 = "off"
+
+[analysis]
+jobs = 4
+exclude = ["**/legacy/**"]
+
+[[plugins]]
+script = "rules/naming.rhai"
+"#;
+    assert_eq!(issues(content), Vec::<String>::new());
+}
+
+#[test]
+fn reports_unknown_section() {
+    assert_eq!(
+        issues("[clasification]\ncore = []\n"),
+        [
+            "1:2: unknown section `clasification` (expected one of: classification, thresholds, rules, analysis, notify, plugins)",
+        ]
+    );
+}
+
+#[test]
+fn reports_unknown_key() {
+    assert_eq!(
+        issues("[thresholds]\nmax_violation = 0\n"),
+        [
+            "2:1: unknown key `max_violation` in [thresholds] (expected one of: max_coupling_factor, max_cycles, max_violations, min_health_score)",
+        ]
+    );
+}
+
+#[test]
+fn reports_unknown_rule_code() {
+    assert_eq!(
+        issues("[rules]\nAA001 = \"warn\"\nAA999 = \"off\"\n"),
+        ["3:1: unknown rule code `AA999` (known rules: AA001, AA002, AA003, AA004, AA005, AA006)"]
+    );
+}
+
+#[test]
+fn reports_unknown_rule_level() {
+    assert_eq!(
+        issues("[rules]\nAA001 = \"loud\"\n"),
+        ["2:9: unknown variant `loud`, expected one of `off`, `warn`, `error`"]
+    );
+}
+
+#[test]
+fn reports_invalid_glob() {
+    assert_eq!(
+        issues("[classification]\ncore = [\"src/**/[core\"]\n"),
+        ["2:9: invalid glob `src/**/[core`: unclosed character class; missing ']'"]
+    );
+}
+
+#[test]
+fn reports_glob_in_two_layers() {
+    assert_eq!(
+        issues("[classification]\ncore = [\"src/app/core/**\"]\nshared = [\"src/app/core/**\"]\n"),
+        ["3:11: glob `src/app/core/**` is assigned to both `core` and `shared`"]
+    );
+}
+
+#[test]
+fn reports_plugin_without_path_or_script() {
+    assert_eq!(issues("[[plugins]]\nname = \"x\"\n"), ["1:1: [[plugins]] needs either `path` or `script`"]);
+}
+
+#[test]
+fn reports_plugin_with_path_and_script() {
+    assert_eq!(
+        issues("[[plugins]]\npath = \"a.wasm\"\nscript = \"a.rhai\"\n"),
+        ["1:1: [[plugins]] cannot have both `path` and `script`"]
+    );
+}
+
+#[test]
+fn reports_syntax_error() {
+    assert_eq!(issues("[thresholds\n"), ["1:12: unclosed table, expected `]`"]);
+}
+
+#[test]
+fn reports_type_errors_after_structure() {
+    assert_eq!(issues("[analysis]\njobs = \"many\"\n"), ["2:8: invalid type: string \"many\", expected a nonzero usize"]);
+}
+
+#[test]
+fn reports_every_issue_in_file_order() {
+    let content = "[rules]\nAA999 = \"off\"\n\n[clasification]\ncore = []\n\n[thresholds]\nmax_violation = 0\n";
+    let lines: Vec<(usize, usize)> = config
+        ::validate(content)
+        .iter()
+        .map(|issue| (issue.line, issue.column))
+        .collect();
+    assert_eq!(lines, [(2, 1), (4, 2), (8, 1)]);
+
+    match Config::parse(Path::new(".angular-analyzer.toml"), content) {
+        Err(AnalyzerError::InvalidConfig { issues, .. }) => assert_eq!(issues.len(), 3),
+        other => panic!("expected InvalidConfig, got {:?}", other.map(|_| ())),
+    }
+}
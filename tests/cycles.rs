@@ -0,0 +1,101 @@
+use std::fs;
+
+use angular_module_analyzer::{ AnalysisResult, AngularAnalyzer };
+use angular_module_analyzer::graph::CycleLimits;
+
+/// `modules`（小文字のモジュール名, importするモジュール名）でプロジェクトを作って解析する
+fn analyze(name: &str, modules: &[(&str, &[&str])]) -> AnalysisResult {
+    let root = std::env::temp_dir().join(format!("angular-analyzer-cycles-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let class = |module: &str| format!("{}Module", module.to_uppercase());
+    for (module, deps) in modules {
+        let file = root.join("src/app").join(module).join(format!("{}.module.ts", module));
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        let imports: String = deps
+            .iter()
+            .map(|dep| format!("import {{ {} }} from '../{}/{}.module';\n", class(dep), dep, dep))
+            .collect();
+        let names: Vec<String> = deps
+            .iter()
+            .map(|dep| class(dep))
+            .collect();
+        fs::write(
+            file,
+            format!(
+                "import {{ NgModule }} from '@angular/core';\n{}\n@NgModule({{\n  imports: [{}],\n}})\n{}\n",
+                imports,
+                names.join(", "),
+                format_args!("export class {} {{}}", class(module))
+            )
+        ).unwrap();
+    }
+    let result = AngularAnalyzer::new(root.to_str().unwrap()).analyze().unwrap();
+    let _ = fs::remove_dir_all(&root);
+    result
+}
+
+/// A⇄B と A→B→C→A の2つの循環
+fn two_cycles() -> AnalysisResult {
+    analyze("two", &[("a", &["b"]), ("b", &["a", "c"]), ("c", &["a"])])
+}
+
+fn search(result: &AnalysisResult, max_cycles: Option<usize>, max_length: Option<usize>) -> (Vec<Vec<String>>, bool) {
+    let search = result.graph().cycles_within(CycleLimits { max_cycles, max_length });
+    let cycles = search.cycles
+        .iter()
+        .map(|cycle|
+            cycle
+                .iter()
+                .map(|name| name.to_string())
+                .collect()
+        )
+        .collect();
+    (cycles, search.truncated)
+}
+
+#[test]
+fn does_not_truncate_when_cycles_equal_the_count_limit() {
+    let result = two_cycles();
+    let (cycles, truncated) = search(&result, Some(2), None);
+    assert_eq!(cycles, [vec!["AModule", "BModule"], vec!["AModule", "BModule", "CModule"]]);
+    assert!(!truncated);
+
+    // 2つ目の循環（B⇄C）を見つけた後もC→Dを調べるが、DからはAにしか進めず新しい循環はない
+    let result = analyze("count", &[("a", &["b"]), ("b", &["c"]), ("c", &["b", "d"]), ("d", &["a"])]);
+    let (cycles, truncated) = search(&result, Some(2), None);
+    assert_eq!(cycles, [vec!["AModule", "BModule", "CModule", "DModule"], vec!["BModule", "CModule"]]);
+    assert!(!truncated);
+}
+
+#[test]
+fn truncates_when_another_cycle_exceeds_the_count_limit() {
+    let result = two_cycles();
+    let (cycles, truncated) = search(&result, Some(1), None);
+    assert_eq!(cycles.len(), 1);
+    assert!(truncated);
+}
+
+#[test]
+fn does_not_truncate_when_cycles_fit_the_length_limit() {
+    let result = two_cycles();
+    let (cycles, truncated) = search(&result, None, Some(3));
+    assert_eq!(cycles.len(), 2);
+    assert!(!truncated);
+}
+
+#[test]
+fn truncates_when_a_longer_cycle_exceeds_the_length_limit() {
+    let result = two_cycles();
+    let (cycles, truncated) = search(&result, None, Some(2));
+    assert_eq!(cycles, [vec!["AModule", "BModule"]]);
+    assert!(truncated);
+}
+
+#[test]
+fn does_not_truncate_at_the_length_limit_without_a_longer_cycle() {
+    // A⇄B と B⇄C：AからB・Cと進んでもCはBにしか戻れないので、長さ3の循環はない
+    let result = analyze("length", &[("a", &["b"]), ("b", &["a", "c"]), ("c", &["b"])]);
+    let (cycles, truncated) = search(&result, None, Some(2));
+    assert_eq!(cycles, [vec!["AModule", "BModule"], vec!["BModule", "CModule"]]);
+    assert!(!truncated);
+}
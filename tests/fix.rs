@@ -0,0 +1,230 @@
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use angular_module_analyzer::AngularAnalyzer;
+use angular_module_analyzer::fix::{ self, FileFix, FixKind, FixOptions };
+
+const SHARED_MODULE: &str = "import { NgModule } from '@angular/core';
+import { BadgeComponent } from './badge.component';
+
+@NgModule({
+  declarations: [BadgeComponent],
+  exports: [BadgeComponent],
+})
+export class SharedModule {}
+";
+
+const BADGE_COMPONENT: &str = "import { Component } from '@angular/core';
+
+@Component({ selector: 'app-badge', template: '<span>badge</span>' })
+export class BadgeComponent {}
+";
+
+const USERS_COMPONENT: &str = "import { Component } from '@angular/core';
+
+@Component({ selector: 'app-users', template: '<ul></ul>' })
+export class UsersComponent {}
+";
+
+/// `files`（プロジェクトルートからの相対パスと内容）でプロジェクトを作る
+fn write_project(name: &str, files: &[(&str, &[u8])]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("angular-analyzer-fix-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for (path, content) in files {
+        let file = root.join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, content).unwrap();
+    }
+    root
+}
+
+fn plan(root: &Path, options: FixOptions) -> Vec<FileFix> {
+    let analyzer = AngularAnalyzer::builder().path(root).components(true).build().unwrap();
+    let result = analyzer.analyze().unwrap();
+    fix::plan(&result, options).unwrap()
+}
+
+/// 機能モジュールのファイルへの修正
+fn users_fix(fixes: &[FileFix]) -> Option<&FileFix> {
+    fixes.iter().find(|file| file.path.ends_with("users.module.ts"))
+}
+
+/// SharedModuleを使っていない機能モジュールのプロジェクト
+fn project_with_users_module(name: &str, users_module: &[u8]) -> PathBuf {
+    write_project(name, &[
+        ("src/app/shared/shared.module.ts", SHARED_MODULE.as_bytes()),
+        ("src/app/shared/badge.component.ts", BADGE_COMPONENT.as_bytes()),
+        ("src/app/users/users.component.ts", USERS_COMPONENT.as_bytes()),
+        ("src/app/users/users.module.ts", users_module),
+    ])
+}
+
+#[test]
+fn removes_duplicate_entries() {
+    let root = project_with_users_module(
+        "duplicate",
+        b"import { NgModule } from '@angular/core';
+import { CommonModule } from '@angular/common';
+import { UsersComponent } from './users.component';
+
+@NgModule({
+  declarations: [UsersComponent, UsersComponent],
+  imports: [CommonModule],
+})
+export class UsersModule {}
+"
+    );
+    let fixes = plan(&root, FixOptions::default());
+    let file = users_fix(&fixes).expect("users.module.ts should be fixed");
+    assert!(file.fixed.contains("declarations: [UsersComponent],"), "{}", file.fixed);
+    assert_eq!(file.fixes.len(), 1);
+    assert_eq!(file.fixes[0].kind, FixKind::Duplicate);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn removes_unused_import_and_its_import_statement() {
+    let root = project_with_users_module(
+        "unused",
+        b"import { NgModule } from '@angular/core';
+import { SharedModule } from '../shared/shared.module';
+import { UsersComponent } from './users.component';
+
+@NgModule({
+  declarations: [UsersComponent],
+  imports: [SharedModule],
+})
+export class UsersModule {}
+"
+    );
+    let fixes = plan(&root, FixOptions::default());
+    let file = users_fix(&fixes).expect("users.module.ts should be fixed");
+    assert_eq!(
+        file.fixed,
+        "import { NgModule } from '@angular/core';
+import { UsersComponent } from './users.component';
+
+@NgModule({
+  declarations: [UsersComponent],
+  imports: [],
+})
+export class UsersModule {}
+"
+    );
+    assert_eq!(file.fixes[0].kind, FixKind::UnusedImport);
+    assert_eq!(file.fixes[0].entry.as_deref(), Some("SharedModule"));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn removes_aliased_import_statement() {
+    let root = project_with_users_module(
+        "alias",
+        b"import { NgModule } from '@angular/core';
+import { SharedModule as UiModule } from '../shared/shared.module';
+import { UsersComponent } from './users.component';
+
+@NgModule({
+  declarations: [UsersComponent],
+  imports: [UiModule],
+})
+export class UsersModule {}
+"
+    );
+    let fixes = plan(&root, FixOptions::default());
+    let file = users_fix(&fixes).expect("users.module.ts should be fixed");
+    assert!(!file.fixed.contains("UiModule"), "{}", file.fixed);
+    assert!(!file.fixed.contains("shared.module"), "{}", file.fixed);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn skips_arrays_with_comments() {
+    let root = project_with_users_module(
+        "comments",
+        b"import { NgModule } from '@angular/core';
+import { UsersComponent } from './users.component';
+
+@NgModule({
+  declarations: [
+    UsersComponent, // the list page
+    UsersComponent,
+  ],
+})
+export class UsersModule {}
+"
+    );
+    let fixes = plan(&root, FixOptions::default());
+    assert!(users_fix(&fixes).is_none());
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn keeps_byte_order_mark() {
+    let mut content = "\u{FEFF}".as_bytes().to_vec();
+    content.extend_from_slice(
+        b"import { NgModule } from '@angular/core';
+import { UsersComponent } from './users.component';
+
+@NgModule({
+  declarations: [UsersComponent, UsersComponent],
+})
+export class UsersModule {}
+"
+    );
+    let root = project_with_users_module("bom", &content);
+    let fixes = plan(&root, FixOptions::default());
+    let file = users_fix(&fixes).expect("users.module.ts should be fixed");
+    assert!(file.original.starts_with('\u{FEFF}'));
+    assert!(file.fixed.starts_with('\u{FEFF}'));
+    assert!(file.fixed.contains("declarations: [UsersComponent],"), "{}", file.fixed);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn skips_utf16_files() {
+    let text =
+        "import { NgModule } from '@angular/core';
+import { UsersComponent } from './users.component';
+
+@NgModule({
+  declarations: [UsersComponent, UsersComponent],
+})
+export class UsersModule {}
+";
+    let mut content = vec![0xff, 0xfe];
+    content.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+    let root = project_with_users_module("utf16", &content);
+    let fixes = plan(&root, FixOptions::default());
+    assert!(users_fix(&fixes).is_none());
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn sorts_only_declarations_and_exports() {
+    let root = project_with_users_module(
+        "sort",
+        b"import { NgModule } from '@angular/core';
+import { CommonModule } from '@angular/common';
+import { RouterModule } from '@angular/router';
+import { UsersComponent } from './users.component';
+import { ListComponent } from './list.component';
+
+@NgModule({
+  declarations: [UsersComponent, ListComponent],
+  imports: [RouterModule, CommonModule],
+  providers: [ZService, AService],
+  exports: [UsersComponent, ListComponent],
+})
+export class UsersModule {}
+"
+    );
+    let fixes = plan(&root, FixOptions { sort: true });
+    let file = users_fix(&fixes).expect("users.module.ts should be fixed");
+    assert!(file.fixed.contains("declarations: [ListComponent, UsersComponent],"), "{}", file.fixed);
+    assert!(file.fixed.contains("exports: [ListComponent, UsersComponent],"), "{}", file.fixed);
+    assert!(file.fixed.contains("imports: [RouterModule, CommonModule],"), "{}", file.fixed);
+    assert!(file.fixed.contains("providers: [ZService, AService],"), "{}", file.fixed);
+    assert!(file.fixes.iter().all(|fix| fix.kind == FixKind::Unsorted));
+    let _ = fs::remove_dir_all(&root);
+}
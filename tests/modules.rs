@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::PathBuf;
+
+use angular_module_analyzer::cache;
+use angular_module_analyzer::source::MAX_LINE_LENGTH;
+use angular_module_analyzer::{ AnalyzerError, AngularAnalyzer, CancellationToken };
+
+/// `src/app/a`・`b`（minifyされたファイル）・`c`の3つのモジュールファイルを持つプロジェクトを作る
+fn write_fixture(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("angular-analyzer-modules-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for (dir, class) in [("a", "AModule"), ("b", "BModule"), ("c", "CModule")] {
+        let file = root.join("src/app").join(dir).join(format!("{}.module.ts", dir));
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        let padding = if dir == "b" { "x".repeat(MAX_LINE_LENGTH + 1) } else { String::new() };
+        fs::write(
+            file,
+            format!(
+                "import {{ NgModule }} from '@angular/core';\n// {}\n@NgModule({{\n  exports: [AComponent],\n}})\n{}\n",
+                padding,
+                format_args!("export class {} {{}}", class)
+            )
+        ).unwrap();
+    }
+    root
+}
+
+#[test]
+fn modules_returns_errors_and_continues() {
+    let root = write_fixture("iter");
+    let analyzer = AngularAnalyzer::new(root.to_str().unwrap());
+    let mut results: Vec<Result<String, AnalyzerError>> = analyzer
+        .modules()
+        .map(|module| module.map(|m| m.name.to_string()))
+        .collect();
+    results.sort_by_key(|result| result.as_ref().ok().cloned());
+    assert!(matches!(results[0], Err(AnalyzerError::ExtractionLimit { .. })), "{:?}", results[0]);
+    let names: Vec<&str> = results[1..]
+        .iter()
+        .map(|result| result.as_ref().unwrap().as_str())
+        .collect();
+    assert_eq!(names, ["AModule", "CModule"]);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn modules_uses_low_memory_summaries() {
+    let root = write_fixture("low-memory");
+    let analyzer = AngularAnalyzer::builder().path(&root).low_memory(true).build().unwrap();
+    let modules: Vec<_> = analyzer.modules().flatten().collect();
+    assert_eq!(modules.len(), 2);
+    assert!(modules.iter().all(|m| m.exports.is_empty()));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn visit_modules_stops_at_the_first_error() {
+    let root = write_fixture("visit");
+    let analyzer = AngularAnalyzer::new(root.to_str().unwrap());
+    let mut visited = 0;
+    let result = analyzer.visit_modules(|_| visited += 1);
+    assert!(matches!(result, Err(AnalyzerError::ExtractionLimit { .. })), "{:?}", result);
+    assert!(visited < 2);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn visit_modules_reports_cancellation() {
+    let root = write_fixture("cancel");
+    let token = CancellationToken::new();
+    token.cancel();
+    let analyzer = AngularAnalyzer::builder().path(&root).cancellation(token).build().unwrap();
+    let mut visited = 0;
+    let result = analyzer.visit_modules(|_| visited += 1);
+    assert!(matches!(result, Err(AnalyzerError::Cancelled)), "{:?}", result);
+    assert_eq!(visited, 0);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn cache_lists_module_names_for_completion() {
+    let root = write_fixture("completion");
+    let dir = root.join(cache::DEFAULT_CACHE_DIR);
+    assert!(cache::module_names(&dir).is_empty());
+    let analyzer = AngularAnalyzer::builder().path(&root).cache_dir(&dir).build().unwrap();
+    analyzer.analyze().unwrap();
+    assert_eq!(cache::module_names(&dir), ["AModule", "CModule"]);
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use angular_module_analyzer::AngularAnalyzer;
+use angular_module_analyzer::output::{ self, OutputFormat };
+
+const MODULES: [(&str, &str); 5] = [
+    ("src/app/core/core.module.ts", "CoreModule"),
+    ("src/app/shared/shared.module.ts", "SharedModule"),
+    ("src/app/features/orders/orders.module.ts", "OrdersModule"),
+    ("src/app/features/users/users.module.ts", "UsersModule"),
+    ("src/app/features/billing/billing.module.ts", "BillingModule"),
+];
+
+/// 同じ内容のプロジェクトを、指定した順序でファイルを作成して用意する
+fn write_fixture(name: &str, order: &[usize]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("angular-analyzer-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for &i in order {
+        let (path, module) = MODULES[i];
+        let imports = match module {
+            "OrdersModule" => "SharedModule, UsersModule, BillingModule",
+            "UsersModule" => "SharedModule, OrdersModule",
+            "BillingModule" => "SharedModule, OrdersModule",
+            _ => "CommonModule",
+        };
+        let file = root.join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(
+            &file,
+            format!(
+                "import {{ NgModule }} from '@angular/core';\n\n@NgModule({{\n  imports: [{}],\n}})\nexport class {} {{}}\n",
+                imports,
+                module
+            )
+        ).unwrap();
+    }
+    root
+}
+
+fn render_all(root: &Path) -> String {
+    let result = AngularAnalyzer::new(root.to_str().unwrap()).analyze().unwrap();
+    let rendered = [OutputFormat::Json, OutputFormat::Markdown, OutputFormat::Sarif, OutputFormat::Dot]
+        .into_iter()
+        .map(|format| output::render(&result, format).unwrap())
+        .collect::<String>();
+    rendered.replace(root.to_str().unwrap(), "<root>")
+}
+
+#[test]
+fn output_does_not_depend_on_file_system_order() {
+    let forward = write_fixture("forward", &[0, 1, 2, 3, 4]);
+    let backward = write_fixture("backward", &[4, 3, 2, 1, 0]);
+
+    let first = render_all(&forward);
+    assert_eq!(first, render_all(&forward));
+    assert_eq!(first, render_all(&backward));
+
+    let result = AngularAnalyzer::new(forward.to_str().unwrap()).analyze().unwrap();
+    let paths: Vec<&Path> = result.modules
+        .iter()
+        .map(|m| m.path.as_path())
+        .collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths, sorted);
+    assert_eq!(result.circular_dependencies.len(), 2);
+
+    fs::remove_dir_all(forward).unwrap();
+    fs::remove_dir_all(backward).unwrap();
+}
@@ -0,0 +1,44 @@
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use angular_module_analyzer::impact::{ ChangedFile, ImpactReport };
+use angular_module_analyzer::paths::{ PathStyle, PresentPaths, presenter };
+
+#[test]
+fn presents_paths_relative_to_project_root() {
+    let present = presenter(Path::new("/work/app/../app"), PathStyle::Relative);
+    assert_eq!(present(Path::new("/work/app/src/app/core/core.module.ts")), Path::new("src/app/core/core.module.ts"));
+    // すでに相対パスになっているパスはそのまま
+    assert_eq!(present(Path::new("src/app/core/core.module.ts")), Path::new("src/app/core/core.module.ts"));
+    assert_eq!(present(Path::new("/work/app")), Path::new("."));
+    // プロジェクトの外のパスは`..`を解決するだけ
+    assert_eq!(present(Path::new("/work/lib/./x.ts")), Path::new("/work/lib/x.ts"));
+}
+
+#[test]
+fn presents_paths_under_canonical_root() {
+    let root = std::env::temp_dir().join(format!("angular-analyzer-paths-{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    let canonical = root.canonicalize().unwrap();
+    let present = presenter(&root.join("."), PathStyle::Absolute);
+    assert_eq!(present(&root.join("src/a.module.ts")), canonical.join("src/a.module.ts"));
+    assert_eq!(present(Path::new("src/a.module.ts")), canonical.join("src/a.module.ts"));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn presents_paths_in_reports() {
+    let mut report = ImpactReport {
+        changed_files: vec![
+            ChangedFile { file: PathBuf::from("/work/app/src/a.ts"), module: None },
+            ChangedFile { file: PathBuf::from("src/b.ts"), module: None }
+        ],
+        ..ImpactReport::default()
+    };
+    report.present_paths(Path::new("/work/app"), PathStyle::Relative);
+    let files: Vec<&Path> = report.changed_files
+        .iter()
+        .map(|changed| changed.file.as_path())
+        .collect();
+    assert_eq!(files, [Path::new("src/a.ts"), Path::new("src/b.ts")]);
+}
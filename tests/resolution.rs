@@ -0,0 +1,164 @@
+use std::fs;
+use std::path::PathBuf;
+
+use angular_module_analyzer::{ AnalysisResult, AngularAnalyzer, ModuleInfo, Resolution };
+
+/// `modules`（プロジェクトルートからの相対パス, クラス名, import文, メタデータ）でプロジェクトを作って解析する
+fn analyze(name: &str, modules: &[(&str, &str, &str, &str)]) -> (PathBuf, AnalysisResult) {
+    let root = std::env::temp_dir().join(format!("angular-analyzer-resolution-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for (path, class, imports, metadata) in modules {
+        let file = root.join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(
+            file,
+            format!(
+                "import {{ NgModule }} from '@angular/core';\n{}\n@NgModule({{\n{}\n}})\nexport class {} {{}}\n",
+                imports,
+                metadata,
+                class
+            )
+        ).unwrap();
+    }
+    let result = AngularAnalyzer::new(root.to_str().unwrap()).analyze().unwrap();
+    (root, result)
+}
+
+fn module<'r>(result: &'r AnalysisResult, name: &str) -> &'r ModuleInfo {
+    result.modules
+        .iter()
+        .find(|m| *m.name == *name)
+        .unwrap_or_else(|| panic!("{} is not in the result", name))
+}
+
+/// `module`の`dependency`への参照（解決状態と、解決できた場合のファイル）
+fn dependency(module: &ModuleInfo, dependency: &str) -> (Resolution, Option<PathBuf>) {
+    let dep = module.module_dependencies
+        .iter()
+        .find(|dep| *dep.name == *dependency)
+        .unwrap_or_else(|| panic!("{} does not reference {}", module.name, dependency));
+    (dep.resolution, dep.path.clone())
+}
+
+const SHARED_A: (&str, &str, &str, &str) = ("src/app/a/shared.module.ts", "SharedModule", "", "  exports: [],");
+const SHARED_B: (&str, &str, &str, &str) = ("src/app/b/shared.module.ts", "SharedModule", "", "  exports: [],");
+
+#[test]
+fn qualifies_duplicate_class_names_with_their_path() {
+    let (root, result) = analyze("qualify", &[SHARED_A, SHARED_B]);
+    let names: Vec<&str> = result.modules
+        .iter()
+        .map(|m| m.name.as_ref())
+        .collect();
+    assert_eq!(names, ["src/app/a/shared.module.ts#SharedModule", "src/app/b/shared.module.ts#SharedModule"]);
+    assert_eq!(result.name_collisions.len(), 1);
+    assert_eq!(&*result.name_collisions[0].name, "SharedModule");
+    assert_eq!(result.name_collisions[0].modules, result.modules.iter().map(|m| m.name.clone()).collect::<Vec<_>>());
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn resolves_relative_import_to_the_imported_file() {
+    let (root, result) = analyze("relative", &[
+        SHARED_A,
+        SHARED_B,
+        (
+            "src/app/users/users.module.ts",
+            "UsersModule",
+            "import { SharedModule } from '../b/shared.module';",
+            "  imports: [SharedModule],",
+        ),
+    ]);
+    let users = module(&result, "UsersModule");
+    assert_eq!(
+        dependency(users, "SharedModule"),
+        (Resolution::Resolved, Some(root.join("src/app/b/shared.module.ts")))
+    );
+    assert_eq!(result.graph().dependencies_of("UsersModule"), ["src/app/b/shared.module.ts#SharedModule"]);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn resolves_path_alias_by_name_only_when_unambiguous() {
+    let (root, result) = analyze("alias", &[
+        SHARED_A,
+        SHARED_B,
+        ("src/app/users/users.module.ts", "UsersModule", "", "  exports: [],"),
+        (
+            "src/app/orders/orders.module.ts",
+            "OrdersModule",
+            "import { SharedModule } from '@app/shared';\nimport { UsersModule } from '@app/users';",
+            "  imports: [SharedModule, UsersModule],",
+        ),
+    ]);
+    let orders = module(&result, "OrdersModule");
+    assert_eq!(
+        dependency(orders, "UsersModule"),
+        (Resolution::Resolved, Some(root.join("src/app/users/users.module.ts")))
+    );
+    // 同じ名前のモジュールが2つあるので、名前だけではどちらか決められない
+    assert_eq!(dependency(orders, "SharedModule").0, Resolution::External);
+    // 解決できたパスエイリアスは外部パッケージとして数えない
+    let packages: Vec<&str> = orders.external_dependencies
+        .iter()
+        .map(|dep| dep.package.as_ref())
+        .collect();
+    assert_eq!(packages, ["@app/shared"]);
+    assert_eq!(result.graph().dependencies_of("OrdersModule"), ["UsersModule"]);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn keeps_package_modules_external() {
+    let (root, result) = analyze("external", &[
+        (
+            "src/app/users/users.module.ts",
+            "UsersModule",
+            "import { CommonModule } from '@angular/common';",
+            "  imports: [CommonModule],",
+        ),
+    ]);
+    let users = module(&result, "UsersModule");
+    assert_eq!(dependency(users, "CommonModule"), (Resolution::External, None));
+    assert!(result.graph().dependencies_of("UsersModule").is_empty());
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn reports_each_violation_once() {
+    let (root, result) = analyze("dedupe", &[
+        ("src/app/users/users.module.ts", "UsersModule", "", "  exports: [],"),
+        (
+            "src/app/core/core.module.ts",
+            "CoreModule",
+            "import { UsersModule } from '../users/users.module';",
+            "  imports: [UsersModule],\n  exports: [UsersModule],",
+        ),
+    ]);
+    let violations: Vec<(&str, &str)> = result.dependency_violations
+        .iter()
+        .map(|v| (v.from_module.as_ref(), v.to_module.as_ref()))
+        .collect();
+    assert_eq!(violations, [("CoreModule", "UsersModule")]);
+    let location = result.dependency_violations[0].location.as_ref().unwrap();
+    assert_eq!(location.file, root.join("src/app/core/core.module.ts"));
+    assert_eq!((location.line, location.column), (4, 13));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn reports_lazy_loaded_module_once() {
+    let (root, result) = analyze("lazy", &[
+        ("src/app/users/users.module.ts", "UsersModule", "", "  exports: [],"),
+        (
+            "src/app/app.module.ts",
+            "AppModule",
+            "import { RouterModule } from '@angular/router';\nconst routes = [\n  { path: 'a', loadChildren: () => import('./users/users.module').then(m => m.UsersModule) },\n  { path: 'b', loadChildren: './users/users.module#UsersModule' },\n  { path: 'c', loadChildren: () => import('./users/users.module').then(m => m.UsersModule) },\n];",
+            "  imports: [RouterModule.forRoot(routes)],",
+        ),
+    ]);
+    let app = module(&result, "AppModule");
+    assert_eq!(app.lazy_imports.iter().map(|name| name.as_ref()).collect::<Vec<&str>>(), ["UsersModule"]);
+    assert_eq!(result.graph().dependencies_of("AppModule"), ["UsersModule"]);
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,70 @@
+use std::path::{ Path, PathBuf };
+
+use angular_module_analyzer::{ AnalysisResult, AngularAnalyzer };
+use angular_module_analyzer::config::Config;
+
+fn module_source(class: &str) -> String {
+    format!("import {{ NgModule }} from '@angular/core';\n\n@NgModule({{\n  imports: [],\n}})\nexport class {} {{}}\n", class)
+}
+
+/// 相対パスとクラス名からメモリ上のファイルを作って解析する
+fn analyze_sources(analyzer: AngularAnalyzer, files: &[(&str, &str)]) -> AnalysisResult {
+    analyzer
+        .analyze_sources(files.iter().map(|(path, class)| (PathBuf::from(path), module_source(class))))
+        .unwrap()
+}
+
+fn names(result: &AnalysisResult) -> Vec<&str> {
+    result.modules
+        .iter()
+        .map(|m| m.name.as_ref())
+        .collect()
+}
+
+const FILES: [(&str, &str); 4] = [
+    ("src/app/users/users.module.ts", "UsersModule"),
+    ("src/app/users/users.module.spec.ts", "UsersSpecModule"),
+    ("src/app/users/users.stories.module.ts", "UsersStoriesModule"),
+    ("src/app/testing/testing.module.ts", "TestingModule"),
+];
+
+#[test]
+fn skips_test_files_by_default() {
+    let result = analyze_sources(AngularAnalyzer::new("project"), &FILES);
+    assert_eq!(names(&result), ["UsersModule"]);
+}
+
+#[test]
+fn includes_test_files_when_requested() {
+    let analyzer = AngularAnalyzer::builder().path("project").include_tests(true).build().unwrap();
+    let result = analyze_sources(analyzer, &FILES);
+    // `*.module.spec.ts`は`*.module.ts`ではないので、テストを含めても解析しない
+    assert_eq!(names(&result), ["TestingModule", "UsersModule", "UsersStoriesModule"]);
+    let tests: Vec<bool> = result.modules
+        .iter()
+        .map(|m| m.test)
+        .collect();
+    assert_eq!(tests, [true, false, true]);
+}
+
+#[test]
+fn skips_files_matched_by_ignore_file_and_exclude() {
+    let analyzer = AngularAnalyzer::builder()
+        .path("project")
+        .ignore_file("# 生成されたモジュール\nsrc/app/generated/\n")
+        .config(
+            Config::parse(
+                Path::new(".angular-analyzer.toml"),
+                "[analysis]\nexclude = [\"src/app/legacy/*\", \"!src/app/legacy/keep.module.ts\"]\n"
+            ).unwrap()
+        )
+        .build()
+        .unwrap();
+    let result = analyze_sources(analyzer, &[
+        ("src/app/generated/api/api.module.ts", "ApiModule"),
+        ("src/app/legacy/old.module.ts", "OldModule"),
+        ("src/app/legacy/keep.module.ts", "KeepModule"),
+        ("src/app/users/users.module.ts", "UsersModule"),
+    ]);
+    assert_eq!(names(&result), ["KeepModule", "UsersModule"]);
+}
@@ -0,0 +1,53 @@
+use angular_module_analyzer::table::{ Align, Table };
+use unicode_width::UnicodeWidthStr;
+
+#[test]
+fn aligns_full_width_cells_by_display_width() {
+    let mut table = Table::new([("依存元", Align::Left), ("違反", Align::Left), ("場所", Align::Left)]);
+    table.row(vec!["CoreModule".into(), "コアが機能に依存".into(), "src/app/core/core.module.ts:4:13".into()]);
+    table.row(vec!["共有モジュール".into(), "x".into(), "src/app/shared/shared.module.ts".into()]);
+    assert_eq!(
+        table.render(),
+        "依存元          違反              場所\n\
+         CoreModule      コアが機能に依存  src/app/core/core.module.ts:4:13\n\
+         共有モジュール  x                 src/app/shared/shared.module.ts\n"
+    );
+}
+
+#[test]
+fn starts_each_column_at_the_same_display_column() {
+    let mut table = Table::new([("名前", Align::Left), ("依存数", Align::Right), ("種類", Align::Left)]);
+    table.row(vec!["UsersModule".into(), "3".into(), "機能".into()]);
+    table.row(vec!["ユーザー管理".into(), "12".into(), "共有".into()]);
+    let (header, rows) = table.lines();
+    // 最後の列の始まりの表示上の位置がすべての行で同じ
+    let starts: Vec<usize> = std::iter
+        ::once(&header)
+        .chain(&rows)
+        .map(|line| {
+            let last = line.rfind("  ").unwrap() + 2;
+            line[..last].width()
+        })
+        .collect();
+    assert_eq!(starts, [22, 22, 22]);
+    assert_eq!(rows[0], "UsersModule        3  機能");
+    assert_eq!(rows[1], "ユーザー管理      12  共有");
+}
+
+#[test]
+fn right_aligns_numbers_under_a_full_width_header() {
+    let mut table = Table::new([("違反数", Align::Right), ("モジュール", Align::Left)]);
+    table.row(vec!["7".into(), "CoreModule".into()]);
+    table.row(vec!["120".into(), "SharedModule".into()]);
+    assert_eq!(table.render(), "違反数  モジュール\n     7  CoreModule\n   120  SharedModule\n");
+}
+
+#[test]
+fn does_not_pad_the_last_column() {
+    let mut table = Table::new([("名前", Align::Left), ("説明", Align::Left)]);
+    table.row(vec!["A".into(), "".into()]);
+    table.row(vec!["B".into(), "とても長い説明文".into()]);
+    let (header, rows) = table.lines();
+    assert_eq!(header, "名前  説明");
+    assert_eq!(rows, ["A", "B     とても長い説明文"]);
+}